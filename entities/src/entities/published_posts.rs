@@ -11,6 +11,9 @@ pub struct Model {
     pub user_id: i64,
     pub backup_allowed: bool,
     pub updated_at: DateTimeUtc,
+    pub obsolete_action: Option<String>,
+    pub system_license_name: Option<String>,
+    pub license_backup_allowed: Option<bool>,
 }
 
 #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
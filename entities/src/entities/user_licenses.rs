@@ -15,6 +15,11 @@ pub struct Model {
     pub allow_backup: bool,
     pub usage_count: i32,
     pub created_at: DateTimeUtc,
+    pub allow_commercial: bool,
+    pub require_attribution: bool,
+    pub color: Option<i32>,
+    pub source: String,
+    pub sort_order: i32,
 }
 
 #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
@@ -1,12 +1,34 @@
 pub use sea_orm_migration::prelude::*;
 
 mod m20250708_000001_create_user_tables;
+mod m20250801_000001_add_allow_commercial;
+mod m20250802_000001_add_require_attribution;
+mod m20250803_000001_add_license_color;
+mod m20250810_000001_add_license_source;
+mod m20250811_000001_add_post_obsolete_action;
+mod m20250812_000001_index_published_posts_updated_at;
+mod m20250813_000001_add_post_system_license;
+mod m20250814_000001_add_license_sort_order;
+mod m20250815_000001_add_post_license_backup;
+mod m20250816_000001_unique_user_license_name;
 
 pub struct Migrator;
 
 #[async_trait::async_trait]
 impl MigratorTrait for Migrator {
     fn migrations() -> Vec<Box<dyn MigrationTrait>> {
-        vec![Box::new(m20250708_000001_create_user_tables::Migration)]
+        vec![
+            Box::new(m20250708_000001_create_user_tables::Migration),
+            Box::new(m20250801_000001_add_allow_commercial::Migration),
+            Box::new(m20250802_000001_add_require_attribution::Migration),
+            Box::new(m20250803_000001_add_license_color::Migration),
+            Box::new(m20250810_000001_add_license_source::Migration),
+            Box::new(m20250811_000001_add_post_obsolete_action::Migration),
+            Box::new(m20250812_000001_index_published_posts_updated_at::Migration),
+            Box::new(m20250813_000001_add_post_system_license::Migration),
+            Box::new(m20250814_000001_add_license_sort_order::Migration),
+            Box::new(m20250815_000001_add_post_license_backup::Migration),
+            Box::new(m20250816_000001_unique_user_license_name::Migration),
+        ]
     }
 }
@@ -0,0 +1,36 @@
+use sea_orm_migration::{prelude::*, schema::*};
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // Add allow_commercial column to user_licenses
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(UserLicenses::Table)
+                    .add_column(boolean(UserLicenses::AllowCommercial).default(false))
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(UserLicenses::Table)
+                    .drop_column(UserLicenses::AllowCommercial)
+                    .to_owned(),
+            )
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum UserLicenses {
+    Table,
+    AllowCommercial,
+}
@@ -0,0 +1,36 @@
+use sea_orm_migration::{prelude::*, schema::*};
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // Add source column to user_licenses, existing rows are marked as unknown
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(UserLicenses::Table)
+                    .add_column(string(UserLicenses::Source).default("unknown"))
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(UserLicenses::Table)
+                    .drop_column(UserLicenses::Source)
+                    .to_owned(),
+            )
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum UserLicenses {
+    Table,
+    Source,
+}
@@ -0,0 +1,36 @@
+use sea_orm_migration::{prelude::*, schema::*};
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // Add per-thread override for obsolete license messages, NULL follows the global setting
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(PublishedPosts::Table)
+                    .add_column(string_null(PublishedPosts::ObsoleteAction))
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(PublishedPosts::Table)
+                    .drop_column(PublishedPosts::ObsoleteAction)
+                    .to_owned(),
+            )
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum PublishedPosts {
+    Table,
+    ObsoleteAction,
+}
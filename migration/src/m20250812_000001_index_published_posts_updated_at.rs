@@ -0,0 +1,79 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // Create index for published_posts.updated_at, used by range and recent queries
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_published_posts_updated_at")
+                    .table(PublishedPosts::Table)
+                    .col(PublishedPosts::UpdatedAt)
+                    .to_owned(),
+            )
+            .await?;
+
+        // Replace the user_id index with (user_id, updated_at) so per-user queries
+        // can filter and order from the same index
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_published_posts_user_id_updated_at")
+                    .table(PublishedPosts::Table)
+                    .col(PublishedPosts::UserId)
+                    .col(PublishedPosts::UpdatedAt)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .drop_index(
+                Index::drop()
+                    .name("idx_published_posts_user_id")
+                    .table(PublishedPosts::Table)
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_published_posts_user_id")
+                    .table(PublishedPosts::Table)
+                    .col(PublishedPosts::UserId)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .drop_index(
+                Index::drop()
+                    .name("idx_published_posts_user_id_updated_at")
+                    .table(PublishedPosts::Table)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .drop_index(
+                Index::drop()
+                    .name("idx_published_posts_updated_at")
+                    .table(PublishedPosts::Table)
+                    .to_owned(),
+            )
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum PublishedPosts {
+    Table,
+    UserId,
+    UpdatedAt,
+}
@@ -0,0 +1,36 @@
+use sea_orm_migration::{prelude::*, schema::*};
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // Record the system license a post was published with, NULL for user licenses and older posts
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(PublishedPosts::Table)
+                    .add_column(string_null(PublishedPosts::SystemLicenseName))
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(PublishedPosts::Table)
+                    .drop_column(PublishedPosts::SystemLicenseName)
+                    .to_owned(),
+            )
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum PublishedPosts {
+    Table,
+    SystemLicenseName,
+}
@@ -0,0 +1,36 @@
+use sea_orm_migration::{prelude::*, schema::*};
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // User-defined display order of licenses, 0 until the user reorders them
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(UserLicenses::Table)
+                    .add_column(integer(UserLicenses::SortOrder).default(0))
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(UserLicenses::Table)
+                    .drop_column(UserLicenses::SortOrder)
+                    .to_owned(),
+            )
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum UserLicenses {
+    Table,
+    SortOrder,
+}
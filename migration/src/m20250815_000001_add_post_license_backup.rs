@@ -0,0 +1,36 @@
+use sea_orm_migration::{prelude::*, schema::*};
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // Backup permission declared by the published license, NULL for older posts
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(PublishedPosts::Table)
+                    .add_column(boolean_null(PublishedPosts::LicenseBackupAllowed))
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(PublishedPosts::Table)
+                    .drop_column(PublishedPosts::LicenseBackupAllowed)
+                    .to_owned(),
+            )
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum PublishedPosts {
+    Table,
+    LicenseBackupAllowed,
+}
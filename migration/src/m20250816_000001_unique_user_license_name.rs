@@ -0,0 +1,50 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // Rename existing duplicates, keeping the oldest name unchanged, so the
+        // unique index can be created
+        manager
+            .get_connection()
+            .execute_unprepared(
+                "UPDATE user_licenses SET license_name = license_name || ' (' || id || ')' \
+                 WHERE id NOT IN (SELECT MIN(id) FROM user_licenses GROUP BY user_id, license_name)",
+            )
+            .await?;
+
+        // A user cannot have two licenses with the same name
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_user_licenses_user_id_license_name")
+                    .table(UserLicenses::Table)
+                    .col(UserLicenses::UserId)
+                    .col(UserLicenses::LicenseName)
+                    .unique()
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_index(
+                Index::drop()
+                    .name("idx_user_licenses_user_id_license_name")
+                    .table(UserLicenses::Table)
+                    .to_owned(),
+            )
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum UserLicenses {
+    Table,
+    UserId,
+    LicenseName,
+}
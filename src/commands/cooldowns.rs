@@ -0,0 +1,516 @@
+use std::{
+    collections::HashMap,
+    sync::Mutex,
+    time::{Duration, Instant},
+};
+
+use poise::{CooldownConfig, CooldownContext, CreateReply, command};
+use serenity::all::*;
+use tracing::warn;
+
+use super::{Context, check_admin};
+use crate::{
+    error::BotError,
+    services::audit_log::{AuditAction, send_audit_log},
+};
+
+/// 冷却记录的作用范围，对应 [`CooldownConfig`] 中的各项
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+enum CooldownScope {
+    Global,
+    User(UserId),
+    Guild(GuildId),
+    Channel(ChannelId),
+    Member(UserId, GuildId),
+}
+
+impl CooldownScope {
+    fn user_id(self) -> Option<UserId> {
+        match self {
+            Self::User(user_id) | Self::Member(user_id, _) => Some(user_id),
+            _ => None,
+        }
+    }
+}
+
+/// 按命令保存的冷却记录，可以单独清除某个用户的冷却
+///
+/// poise 自带的冷却记录只能整体清空，因此框架启用 `manual_cooldowns`，
+/// 由全局检查读取冷却、`pre_command` 开始冷却。
+#[derive(Debug, Default)]
+pub struct CommandCooldowns {
+    invocations: Mutex<HashMap<String, HashMap<CooldownScope, Instant>>>,
+}
+
+impl CommandCooldowns {
+    /// 本次调用涉及的冷却范围及其冷却时间
+    fn scopes(config: &CooldownConfig, ctx: &CooldownContext) -> Vec<(CooldownScope, Duration)> {
+        let mut scopes = vec![];
+        if let Some(cooldown) = config.global {
+            scopes.push((CooldownScope::Global, cooldown));
+        }
+        if let Some(cooldown) = config.user {
+            scopes.push((CooldownScope::User(ctx.user_id), cooldown));
+        }
+        if let Some(cooldown) = config.channel {
+            scopes.push((CooldownScope::Channel(ctx.channel_id), cooldown));
+        }
+        if let Some(guild_id) = ctx.guild_id {
+            if let Some(cooldown) = config.guild {
+                scopes.push((CooldownScope::Guild(guild_id), cooldown));
+            }
+            if let Some(cooldown) = config.member {
+                scopes.push((CooldownScope::Member(ctx.user_id, guild_id), cooldown));
+            }
+        }
+        scopes
+    }
+
+    /// 命令在该调用上下文中的剩余冷却时间，不在冷却中时返回 `None`
+    pub fn remaining(
+        &self,
+        command: &str,
+        config: &CooldownConfig,
+        ctx: &CooldownContext,
+    ) -> Option<Duration> {
+        let invocations = self.invocations.lock().unwrap();
+        let entries = invocations.get(command)?;
+        Self::scopes(config, ctx)
+            .into_iter()
+            .filter_map(|(scope, cooldown)| cooldown.checked_sub(entries.get(&scope)?.elapsed()))
+            .max()
+    }
+
+    /// 命令执行时开始冷却，同时清理该命令已过期的记录
+    pub fn start(&self, command: &str, config: &CooldownConfig, ctx: &CooldownContext) {
+        let scopes = Self::scopes(config, ctx);
+        let Some(longest) = scopes.iter().map(|&(_, cooldown)| cooldown).max() else {
+            return;
+        };
+        let now = Instant::now();
+        let mut invocations = self.invocations.lock().unwrap();
+        let entries = invocations.entry(command.to_string()).or_default();
+        entries.retain(|_, last| now.saturating_duration_since(*last) < longest);
+        entries.extend(scopes.into_iter().map(|(scope, _)| (scope, now)));
+    }
+
+    /// 用户自身（用户级和成员级）在该命令上的剩余冷却时间
+    fn user_remaining(
+        &self,
+        command: &str,
+        config: &CooldownConfig,
+        user_id: UserId,
+    ) -> Option<Duration> {
+        let invocations = self.invocations.lock().unwrap();
+        invocations
+            .get(command)?
+            .iter()
+            .filter_map(|(scope, last)| {
+                let cooldown = match *scope {
+                    CooldownScope::User(id) if id == user_id => config.user?,
+                    CooldownScope::Member(id, _) if id == user_id => config.member?,
+                    _ => return None,
+                };
+                cooldown.checked_sub(last.elapsed())
+            })
+            .max()
+    }
+
+    /// 清除用户在该命令上的冷却，其他用户和全局冷却不受影响
+    fn reset_user(&self, command: &str, user_id: UserId) {
+        if let Some(entries) = self.invocations.lock().unwrap().get_mut(command) {
+            entries.retain(|scope, _| scope.user_id() != Some(user_id));
+        }
+    }
+
+    /// 该命令全局冷却的剩余时间
+    fn global_remaining(&self, command: &str, config: &CooldownConfig) -> Option<Duration> {
+        let invocations = self.invocations.lock().unwrap();
+        let last = invocations.get(command)?.get(&CooldownScope::Global)?;
+        config.global?.checked_sub(last.elapsed())
+    }
+
+    /// 清除该命令的全局冷却，对所有用户生效
+    fn reset_global(&self, command: &str) {
+        if let Some(entries) = self.invocations.lock().unwrap().get_mut(command) {
+            entries.remove(&CooldownScope::Global);
+        }
+    }
+}
+
+/// 找出用户仍处于冷却中的命令及剩余时间
+///
+/// `command_name` 为 `None` 时检查所有命令。
+fn user_cooldowns<'a, U, E>(
+    cooldowns: &CommandCooldowns,
+    commands: &'a [poise::Command<U, E>],
+    user_id: UserId,
+    command_name: Option<&str>,
+) -> Vec<(&'a str, Duration)> {
+    commands
+        .iter()
+        .filter(|cmd| command_name.is_none_or(|name| cmd.qualified_name == name))
+        .filter_map(|cmd| {
+            let config = cmd.cooldown_config.read().unwrap();
+            let remaining = cooldowns.user_remaining(&cmd.qualified_name, &config, user_id)?;
+            Some((cmd.qualified_name.as_str(), remaining))
+        })
+        .collect()
+}
+
+/// 找出处于全局冷却中的命令及剩余时间，全局冷却对所有用户生效
+///
+/// `command_name` 为 `None` 时检查所有命令。
+fn global_cooldowns<'a, U, E>(
+    cooldowns: &CommandCooldowns,
+    commands: &'a [poise::Command<U, E>],
+    command_name: Option<&str>,
+) -> Vec<(&'a str, Duration)> {
+    commands
+        .iter()
+        .filter(|cmd| command_name.is_none_or(|name| cmd.qualified_name == name))
+        .filter_map(|cmd| {
+            let config = cmd.cooldown_config.read().unwrap();
+            let remaining = cooldowns.global_remaining(&cmd.qualified_name, &config)?;
+            Some((cmd.qualified_name.as_str(), remaining))
+        })
+        .collect()
+}
+
+/// 冷却列表的展示行
+fn cooldown_lines(pending: &[(&str, Duration)]) -> Vec<String> {
+    pending
+        .iter()
+        .map(|(name, remaining)| format!("• `/{name}`（剩余 {} 秒）", remaining.as_secs() + 1))
+        .collect()
+}
+
+/// 设置了冷却时间的命令名自动补全
+async fn autocomplete_cooldown_command(
+    ctx: Context<'_>,
+    partial: &str,
+) -> impl Iterator<Item = AutocompleteChoice> {
+    let partial = partial.to_lowercase();
+    let names: Vec<String> = ctx
+        .framework()
+        .options()
+        .commands
+        .iter()
+        .filter(|cmd| {
+            let config = cmd.cooldown_config.read().unwrap();
+            config.global.is_some() || config.user.is_some() || config.member.is_some()
+        })
+        .map(|cmd| cmd.qualified_name.clone())
+        .filter(|name| name.to_lowercase().contains(&partial))
+        .take(25)
+        .collect();
+    names
+        .into_iter()
+        .map(|name| AutocompleteChoice::new(name.clone(), name))
+}
+
+#[command(
+    slash_command,
+    guild_only,
+    default_member_permissions = "ADMINISTRATOR",
+    check = "check_admin",
+    ephemeral,
+    name_localized("zh-CN", "重置冷却"),
+    description_localized("zh-CN", "查看并重置用户正在等待的命令冷却及命令的全局冷却")
+)]
+/// Show and reset the command cooldowns a user is waiting on, including global ones
+pub async fn reset_cooldown(
+    ctx: Context<'_>,
+    #[name_localized("zh-CN", "用户")]
+    #[description_localized("zh-CN", "要重置冷却的用户")]
+    user: User,
+    #[name_localized("zh-CN", "命令")]
+    #[description_localized("zh-CN", "只重置指定命令的冷却，不填则重置该用户所有处于冷却中的命令")]
+    #[autocomplete = "autocomplete_cooldown_command"]
+    command_name: Option<String>,
+) -> Result<(), BotError> {
+    let cooldowns = ctx.data().cooldowns();
+    let commands = &ctx.framework().options().commands;
+    let pending = user_cooldowns(cooldowns, commands, user.id, command_name.as_deref());
+    let pending_global = global_cooldowns(cooldowns, commands, command_name.as_deref());
+
+    if pending.is_empty() && pending_global.is_empty() {
+        let content = match &command_name {
+            Some(name) => format!("ℹ️ {} 的 `/{name}` 当前不在冷却中。", user.mention()),
+            None => format!("ℹ️ {} 当前没有处于冷却中的命令。", user.mention()),
+        };
+        ctx.send(CreateReply::default().content(content).ephemeral(true))
+            .await?;
+        return Ok(());
+    }
+
+    let lines = cooldown_lines(&pending);
+    let global_lines = cooldown_lines(&pending_global);
+
+    // 用户冷却和全局冷却分开确认，全局冷却的重置影响所有用户
+    let mut sections = vec![];
+    let mut buttons = vec![];
+    if !pending.is_empty() {
+        sections.push(format!(
+            "⏳ {} 正在等待以下命令的冷却：\n{}\n「确认重置」只重置该用户的这些冷却，其他用户不受影响。",
+            user.mention(),
+            lines.join("\n")
+        ));
+        buttons.push(
+            CreateButton::new("confirm_reset_cooldown")
+                .label("确认重置")
+                .style(ButtonStyle::Danger),
+        );
+    }
+    if !pending_global.is_empty() {
+        sections.push(format!(
+            "🌐 以下命令处于全局冷却中，所有用户都需要等待：\n{}\n「重置全局冷却」将清除这些命令的全局冷却，对所有用户生效。",
+            global_lines.join("\n")
+        ));
+        buttons.push(
+            CreateButton::new("confirm_reset_global_cooldown")
+                .label("重置全局冷却")
+                .style(ButtonStyle::Danger),
+        );
+    }
+    buttons.push(
+        CreateButton::new("cancel_reset_cooldown")
+            .label("❌ 取消")
+            .style(ButtonStyle::Secondary),
+    );
+
+    // 确认操作
+    let handler = ctx
+        .send(
+            CreateReply::default()
+                .content(sections.join("\n\n"))
+                .components(vec![CreateActionRow::Buttons(buttons)])
+                .ephemeral(true),
+        )
+        .await?;
+
+    let Some(interaction) = handler
+        .message()
+        .await?
+        .await_component_interaction(ctx)
+        .author_id(ctx.author().id)
+        .await
+    else {
+        warn!("Interaction timed out");
+        return Ok(());
+    };
+
+    interaction
+        .create_response(ctx, CreateInteractionResponse::Acknowledge)
+        .await?;
+
+    let content = match interaction.data.custom_id.as_str() {
+        "confirm_reset_cooldown" => {
+            for (name, _) in &pending {
+                cooldowns.reset_user(name, user.id);
+            }
+            let names: Vec<&str> = pending.iter().map(|(name, _)| *name).collect();
+            tracing::info!(
+                "Cooldowns reset by {} for user {}: {:?}",
+                ctx.author().id,
+                user.id,
+                names
+            );
+            send_audit_log(
+                ctx.http(),
+                &ctx.data().cfg().load(),
+                ctx.author().id,
+                AuditAction::SettingChanged,
+                format!(
+                    "重置了 {} 的命令冷却：{}",
+                    user.mention(),
+                    command_list(&names)
+                ),
+            )
+            .await;
+            format!(
+                "✅ 已重置 {} 的命令冷却：\n{}",
+                user.mention(),
+                lines.join("\n")
+            )
+        }
+        "confirm_reset_global_cooldown" => {
+            for (name, _) in &pending_global {
+                cooldowns.reset_global(name);
+            }
+            let names: Vec<&str> = pending_global.iter().map(|(name, _)| *name).collect();
+            tracing::info!("Global cooldowns reset by {}: {:?}", ctx.author().id, names);
+            send_audit_log(
+                ctx.http(),
+                &ctx.data().cfg().load(),
+                ctx.author().id,
+                AuditAction::SettingChanged,
+                format!("重置了命令的全局冷却：{}", command_list(&names)),
+            )
+            .await;
+            format!(
+                "✅ 已重置以下命令的全局冷却，所有用户均可立即使用：\n{}",
+                global_lines.join("\n")
+            )
+        }
+        _ => "已取消重置。".to_string(),
+    };
+
+    handler
+        .edit(
+            ctx,
+            CreateReply::default().content(content).components(vec![]),
+        )
+        .await?;
+    Ok(())
+}
+
+/// 审计日志中的命令列表
+fn command_list(names: &[&str]) -> String {
+    names
+        .iter()
+        .map(|name| format!("`/{name}`"))
+        .collect::<Vec<_>>()
+        .join("、")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn command_with_user_cooldown(name: &str, secs: Option<u64>) -> poise::Command<(), ()> {
+        let command = poise::Command {
+            name: name.to_string(),
+            qualified_name: name.to_string(),
+            ..Default::default()
+        };
+        *command.cooldown_config.write().unwrap() = CooldownConfig {
+            user: secs.map(Duration::from_secs),
+            ..Default::default()
+        };
+        command
+    }
+
+    fn cooldown_context(user_id: u64) -> CooldownContext {
+        CooldownContext {
+            user_id: UserId::new(user_id),
+            guild_id: Some(GuildId::new(100)),
+            channel_id: ChannelId::new(200),
+        }
+    }
+
+    #[test]
+    fn test_reset_user_cooldowns() {
+        let commands = vec![
+            command_with_user_cooldown("publish_license", Some(10)),
+            command_with_user_cooldown("create_license", Some(10)),
+            command_with_user_cooldown("license_help", None),
+        ];
+        let cooldowns = CommandCooldowns::default();
+        let target = cooldown_context(1);
+        let other = cooldown_context(2);
+        for command in &commands {
+            let config = command.cooldown_config.read().unwrap();
+            cooldowns.start(&command.qualified_name, &config, &target);
+            cooldowns.start(&command.qualified_name, &config, &other);
+        }
+
+        // 没有冷却设置的命令不会被列出
+        let pending = user_cooldowns(&cooldowns, &commands, target.user_id, None);
+        assert_eq!(
+            pending.iter().map(|(name, _)| *name).collect::<Vec<_>>(),
+            vec!["publish_license", "create_license"]
+        );
+        assert!(pending.iter().all(|(_, d)| *d <= Duration::from_secs(10)));
+
+        // 只重置指定用户的指定命令，其他用户的冷却保留
+        let pending = user_cooldowns(
+            &cooldowns,
+            &commands,
+            target.user_id,
+            Some("publish_license"),
+        );
+        assert_eq!(pending.len(), 1);
+        cooldowns.reset_user(pending[0].0, target.user_id);
+        let config = commands[0].cooldown_config.read().unwrap();
+        assert!(
+            cooldowns
+                .remaining("publish_license", &config, &target)
+                .is_none()
+        );
+        assert!(
+            cooldowns
+                .remaining("publish_license", &config, &other)
+                .is_some()
+        );
+        drop(config);
+
+        let pending = user_cooldowns(&cooldowns, &commands, target.user_id, None);
+        assert_eq!(
+            pending.iter().map(|(name, _)| *name).collect::<Vec<_>>(),
+            vec!["create_license"]
+        );
+    }
+
+    #[test]
+    fn test_reset_global_cooldowns() {
+        let commands = vec![
+            command_with_user_cooldown("system_info", None),
+            command_with_user_cooldown("publish_license", Some(10)),
+        ];
+        commands[0].cooldown_config.write().unwrap().global = Some(Duration::from_secs(10));
+        let cooldowns = CommandCooldowns::default();
+        let target = cooldown_context(1);
+        for command in &commands {
+            let config = command.cooldown_config.read().unwrap();
+            cooldowns.start(&command.qualified_name, &config, &target);
+        }
+
+        // 只列出处于全局冷却中的命令
+        let pending = global_cooldowns(&cooldowns, &commands, None);
+        assert_eq!(
+            pending.iter().map(|(name, _)| *name).collect::<Vec<_>>(),
+            vec!["system_info"]
+        );
+        assert!(global_cooldowns(&cooldowns, &commands, Some("publish_license")).is_empty());
+
+        // 重置后所有用户都不再受全局冷却限制，用户冷却保留
+        cooldowns.reset_global("system_info");
+        let config = commands[0].cooldown_config.read().unwrap();
+        assert!(
+            cooldowns
+                .remaining("system_info", &config, &cooldown_context(2))
+                .is_none()
+        );
+        drop(config);
+        assert!(global_cooldowns(&cooldowns, &commands, None).is_empty());
+        assert_eq!(
+            user_cooldowns(&cooldowns, &commands, target.user_id, None).len(),
+            1
+        );
+    }
+
+    #[test]
+    fn test_global_cooldown_is_not_reset_per_user() {
+        let cooldowns = CommandCooldowns::default();
+        let config = CooldownConfig {
+            global: Some(Duration::from_secs(10)),
+            ..Default::default()
+        };
+        let target = cooldown_context(1);
+        cooldowns.start("system_info", &config, &target);
+
+        // 全局冷却不属于任何用户，也对其他用户生效
+        assert!(
+            cooldowns
+                .user_remaining("system_info", &config, target.user_id)
+                .is_none()
+        );
+        cooldowns.reset_user("system_info", target.user_id);
+        assert!(
+            cooldowns
+                .remaining("system_info", &config, &cooldown_context(2))
+                .is_some()
+        );
+    }
+}
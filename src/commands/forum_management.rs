@@ -1,11 +1,29 @@
+use std::{
+    collections::HashSet,
+    time::{Duration, Instant},
+};
+
+use chrono::{DateTime, Utc};
+use futures::{StreamExt, stream};
 use poise::{CreateReply, command};
 use serenity::all::*;
 
-use super::{Context, check_admin};
-use crate::error::BotError;
+use super::{Context, Data, check_admin};
+use crate::{
+    error::BotError,
+    handlers::{BackfillMode, backfill_thread},
+};
+
+/// 回填时同时进行的自动发布流程数
+const BACKFILL_CONCURRENCY: usize = 3;
+/// 每个回填流程开始前的等待时间，避免短时间内发送大量消息
+const BACKFILL_INTERVAL: Duration = Duration::from_secs(2);
+/// 回填进度消息的最短更新间隔，避免频繁编辑消息
+const BACKFILL_PROGRESS_INTERVAL: Duration = Duration::from_secs(5);
 
 #[command(
     slash_command,
+    custom_data = "crate::commands::WriteCommand",
     default_member_permissions = "ADMINISTRATOR",
     check = "check_admin",
     ephemeral,
@@ -63,6 +81,7 @@ pub async fn add_forum(
 
 #[command(
     slash_command,
+    custom_data = "crate::commands::WriteCommand",
     default_member_permissions = "ADMINISTRATOR",
     check = "check_admin",
     ephemeral,
@@ -180,6 +199,7 @@ pub async fn list_forums(ctx: Context<'_>) -> Result<(), BotError> {
 
 #[command(
     slash_command,
+    custom_data = "crate::commands::WriteCommand",
     default_member_permissions = "ADMINISTRATOR",
     check = "check_admin",
     ephemeral,
@@ -223,3 +243,227 @@ pub async fn clear_forums(ctx: Context<'_>) -> Result<(), BotError> {
 
     Ok(())
 }
+
+/// 筛选需要回填的帖子
+///
+/// 只保留属于指定论坛、有作者、创建于bot启动前且尚未发布协议的帖子。
+/// 2022年前创建的帖子没有创建时间，同样视为旧帖子。
+fn select_backfill_threads(
+    threads: Vec<GuildChannel>,
+    forum_id: ChannelId,
+    bot_start_time: DateTime<Utc>,
+    published: &HashSet<ChannelId>,
+) -> Vec<GuildChannel> {
+    threads
+        .into_iter()
+        .filter(|thread| {
+            thread.parent_id == Some(forum_id)
+                && thread.owner_id.is_some()
+                && !published.contains(&thread.id)
+                && thread
+                    .thread_metadata
+                    .and_then(|m| m.create_timestamp)
+                    .is_none_or(|created| created.timestamp() < bot_start_time.timestamp())
+        })
+        .collect()
+}
+
+#[command(
+    slash_command,
+    custom_data = "crate::commands::WriteCommand",
+    guild_only,
+    default_member_permissions = "ADMINISTRATOR",
+    check = "check_admin",
+    ephemeral,
+    name_localized("zh-CN", "回填论坛"),
+    description_localized("zh-CN", "为Bot部署前创建、尚未发布协议的活跃帖子运行自动发布")
+)]
+/// Run auto-publish for active threads created before the bot started
+pub async fn backfill_forum(
+    ctx: Context<'_>,
+    #[name_localized("zh-CN", "论坛频道")]
+    #[description_localized("zh-CN", "要回填的论坛频道")]
+    #[channel_types("Forum")]
+    forum_channel: GuildChannel,
+
+    #[name_localized("zh-CN", "方式")]
+    #[description_localized("zh-CN", "提示帖子作者确认，或直接发布作者的默认协议")]
+    mode: BackfillMode,
+) -> Result<(), BotError> {
+    let cfg = ctx.data().cfg().load_full();
+    if !cfg.allowed_forum_channels.is_empty()
+        && !cfg.allowed_forum_channels.contains(&forum_channel.id)
+    {
+        ctx.say(format!(
+            "❌ 论坛频道 **{}** 不在Bot生效域白名单中。",
+            forum_channel.name
+        ))
+        .await?;
+        return Ok(());
+    }
+
+    ctx.defer_ephemeral().await?;
+    let threads = forum_channel
+        .guild_id
+        .get_active_threads(ctx)
+        .await?
+        .threads;
+
+    let mut published = HashSet::new();
+    for thread in threads
+        .iter()
+        .filter(|t| t.parent_id == Some(forum_channel.id))
+    {
+        if ctx
+            .data()
+            .db()
+            .published_posts()
+            .has_published_post(thread.id)
+            .await?
+        {
+            published.insert(thread.id);
+        }
+    }
+    let candidates =
+        select_backfill_threads(threads, forum_channel.id, cfg.bot_start_time, &published);
+    if candidates.is_empty() {
+        ctx.say(format!(
+            "📋 论坛 **{}** 中没有需要回填的帖子。",
+            forum_channel.name
+        ))
+        .await?;
+        return Ok(());
+    }
+
+    // 提示模式下每个流程都要等待作者操作，总耗时可能超过交互令牌的 15 分钟有效期，
+    // 因此在后台运行，并通过频道消息而不是交互回复报告进度
+    let status = ctx
+        .channel_id()
+        .send_message(
+            ctx,
+            CreateMessage::new().content(format!(
+                "⏳ {} 正在回填论坛 **{}** 中的 {} 个帖子……",
+                ctx.author().mention(),
+                forum_channel.name,
+                candidates.len()
+            )),
+        )
+        .await?;
+    ctx.say(format!(
+        "⏳ 已在后台开始回填 {} 个帖子，进度和结果将在 {} 中更新。",
+        candidates.len(),
+        ctx.channel_id().mention()
+    ))
+    .await?;
+
+    tokio::spawn(run_backfill(
+        ctx.serenity_context().clone(),
+        ctx.data().clone(),
+        candidates,
+        mode,
+        status,
+        forum_channel.name,
+    ));
+
+    Ok(())
+}
+
+/// 限制并发和速率逐个运行自动发布流程，通过编辑 `status` 消息报告进度和结果
+async fn run_backfill(
+    ctx: serenity::prelude::Context,
+    data: Data,
+    candidates: Vec<GuildChannel>,
+    mode: BackfillMode,
+    mut status: Message,
+    forum_name: String,
+) {
+    let total = candidates.len();
+    // 有意绕过旧帖子检查
+    let mut tasks = Vec::with_capacity(total);
+    for thread in &candidates {
+        let (ctx, data) = (&ctx, &data);
+        tasks.push(async move {
+            tokio::time::sleep(BACKFILL_INTERVAL).await;
+            backfill_thread(ctx, thread, data, mode).await
+        });
+    }
+    let mut results = stream::iter(tasks).buffer_unordered(BACKFILL_CONCURRENCY);
+
+    let (mut done, mut failed) = (0, 0);
+    let mut last_progress = Instant::now();
+    while let Some(result) = results.next().await {
+        done += 1;
+        if let Err(e) = result {
+            tracing::warn!("回填帖子失败: {}", e);
+            failed += 1;
+        }
+        if done < total && last_progress.elapsed() >= BACKFILL_PROGRESS_INTERVAL {
+            last_progress = Instant::now();
+            let progress = EditMessage::new().content(format!(
+                "⏳ 正在回填论坛 **{forum_name}**：已处理 {done}/{total} 个帖子，失败 {failed} 个……"
+            ));
+            if let Err(e) = status.edit(&ctx, progress).await {
+                tracing::warn!("更新回填进度失败: {}", e);
+            }
+        }
+    }
+
+    let result = EditMessage::new().content(format!(
+        "✅ 论坛 **{forum_name}** 回填完成：共处理 {total} 个帖子，失败 {failed} 个。"
+    ));
+    if let Err(e) = status.edit(&ctx, result).await {
+        tracing::warn!("发送回填结果失败: {}", e);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    fn thread(id: u64, parent: u64, owner: Option<u64>, created: Option<&str>) -> GuildChannel {
+        let mut thread = GuildChannel::default();
+        thread.id = ChannelId::new(id);
+        thread.parent_id = Some(ChannelId::new(parent));
+        thread.owner_id = owner.map(UserId::new);
+        thread.thread_metadata = Some(
+            serde_json::from_value(json!({
+                "archived": false,
+                "auto_archive_duration": 1440,
+                "archive_timestamp": "2025-01-01T00:00:00Z",
+                "locked": false,
+                "create_timestamp": created,
+            }))
+            .unwrap(),
+        );
+        thread
+    }
+
+    #[test]
+    fn test_select_backfill_threads() {
+        let forum = ChannelId::new(1);
+        let bot_start_time = "2025-06-01T00:00:00Z".parse::<DateTime<Utc>>().unwrap();
+        let threads = vec![
+            thread(10, 1, Some(100), Some("2025-05-01T00:00:00Z")),
+            // 启动后创建，由自动发布正常处理
+            thread(11, 1, Some(100), Some("2025-07-01T00:00:00Z")),
+            // 其他论坛
+            thread(12, 2, Some(100), Some("2025-05-01T00:00:00Z")),
+            // 已发布协议
+            thread(13, 1, Some(100), Some("2025-05-01T00:00:00Z")),
+            // 没有作者
+            thread(14, 1, None, Some("2025-05-01T00:00:00Z")),
+            // 没有创建时间的旧帖子
+            thread(15, 1, Some(100), None),
+        ];
+        let published = HashSet::from([ChannelId::new(13)]);
+
+        let selected = select_backfill_threads(threads, forum, bot_start_time, &published);
+
+        assert_eq!(
+            selected.iter().map(|t| t.id.get()).collect::<Vec<_>>(),
+            vec![10, 15]
+        );
+    }
+}
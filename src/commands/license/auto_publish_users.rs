@@ -0,0 +1,252 @@
+use std::time::Duration;
+
+use poise::{CreateReply, command};
+use serenity::all::*;
+
+use crate::{
+    commands::{Context, check_admin},
+    error::BotError,
+    services::user_settings::UserSettings,
+    types::license::DefaultLicenseIdentifier,
+    utils::{Paginator, cached_display_name, member_display_name_by_id},
+};
+
+/// 每页显示的用户数
+const USERS_PER_PAGE: usize = 10;
+/// 列表面板的交互超时时间（秒）
+const AUDIT_TIMEOUT_SECS: u64 = 300;
+/// 通过 API 解析用户名时两次请求之间的间隔，避免触发速率限制
+const NAME_RESOLVE_INTERVAL: Duration = Duration::from_millis(250);
+
+/// 自动发布用户列表中的一项
+#[derive(Debug, PartialEq, Eq)]
+struct AuditEntry {
+    user_id: UserId,
+    default_license: Option<DefaultLicenseIdentifier>,
+}
+
+/// 将服务返回的用户设置整理为按用户ID排序的列表
+///
+/// 默认协议的解析顺序与 `get_default_license` 一致：用户协议优先于系统协议
+fn build_audit_entries(settings: Vec<UserSettings>) -> Vec<AuditEntry> {
+    let mut entries: Vec<AuditEntry> = settings
+        .into_iter()
+        .map(|s| AuditEntry {
+            user_id: UserId::new(s.user_id as u64),
+            default_license: match (s.default_user_license_id, s.default_system_license_name) {
+                (Some(id), _) => Some(DefaultLicenseIdentifier::User(id)),
+                (None, Some(name)) => Some(DefaultLicenseIdentifier::System(name)),
+                (None, None) => None,
+            },
+        })
+        .collect();
+    entries.sort_by_key(|e| e.user_id);
+    entries
+}
+
+/// 解析用户显示名，优先使用缓存，缓存未命中时限速请求 API
+async fn resolve_display_name(ctx: Context<'_>, guild_id: GuildId, user_id: UserId) -> String {
+    let cached = ctx.guild().and_then(|g| {
+        g.members
+            .get(&user_id)
+            .map(|m| m.display_name().to_string())
+    });
+    if let Some(name) = cached {
+        return name;
+    }
+    if let Some(name) = cached_display_name(guild_id, user_id).await {
+        return name;
+    }
+    tokio::time::sleep(NAME_RESOLVE_INTERVAL).await;
+    member_display_name_by_id(ctx.http(), guild_id, user_id).await
+}
+
+/// 生成默认协议的展示文本
+async fn describe_default_license(
+    ctx: Context<'_>,
+    entry: &AuditEntry,
+) -> Result<String, BotError> {
+    Ok(match &entry.default_license {
+        Some(DefaultLicenseIdentifier::User(id)) => {
+            match ctx
+                .data()
+                .db()
+                .license()
+                .get_license(*id, entry.user_id)
+                .await?
+            {
+                Some(license) => license.license_name,
+                None => format!("个人协议 #{id}（已删除）"),
+            }
+        }
+        Some(DefaultLicenseIdentifier::System(name)) => format!("{name} (系统)"),
+        None => "未设置".to_string(),
+    })
+}
+
+async fn create_audit_embed(
+    ctx: Context<'_>,
+    entries: &[AuditEntry],
+    paginator: &Paginator,
+) -> Result<CreateEmbed, BotError> {
+    let guild_id = ctx.guild_id().ok_or(BotError::GenericError {
+        message: "此命令只能在服务器中使用".to_string(),
+        source: None,
+    })?;
+    let mut lines = Vec::new();
+    let offset = paginator.page() * USERS_PER_PAGE;
+    for (index, entry) in paginator
+        .page_items(entries, USERS_PER_PAGE)
+        .iter()
+        .enumerate()
+    {
+        let name = resolve_display_name(ctx, guild_id, entry.user_id).await;
+        let license = describe_default_license(ctx, entry).await?;
+        lines.push(format!(
+            "{}. **{name}** (`{}`) — {license}",
+            offset + index + 1,
+            entry.user_id
+        ));
+    }
+
+    let description = if lines.is_empty() {
+        "暂无用户启用自动发布。".to_string()
+    } else {
+        lines.join("\n")
+    };
+
+    Ok(CreateEmbed::new()
+        .title("📋 自动发布用户列表")
+        .description(description)
+        .colour(Colour::BLUE)
+        .footer(CreateEmbedFooter::new(format!(
+            "共 {} 位用户 | {}/{}",
+            entries.len(),
+            paginator.page() + 1,
+            paginator.total()
+        ))))
+}
+
+fn create_audit_components(paginator: &Paginator) -> Vec<CreateActionRow> {
+    if paginator.total() > 1 {
+        vec![paginator.nav_row()]
+    } else {
+        vec![]
+    }
+}
+
+#[command(
+    slash_command,
+    guild_only,
+    default_member_permissions = "ADMINISTRATOR",
+    check = "check_admin",
+    name_localized("zh-CN", "自动发布用户列表"),
+    description_localized("zh-CN", "查看已启用自动发布的用户及其默认协议"),
+    ephemeral
+)]
+/// List users who have auto-publish enabled and their default licenses
+pub async fn auto_publish_users(ctx: Context<'_>) -> Result<(), BotError> {
+    ctx.defer_ephemeral().await?;
+
+    let settings = ctx
+        .data()
+        .db()
+        .user_settings()
+        .get_auto_publish_settings()
+        .await?;
+    let entries = build_audit_entries(settings);
+    let mut paginator = Paginator::for_items(entries.len(), USERS_PER_PAGE);
+
+    let handler = ctx
+        .send(
+            CreateReply::default()
+                .embed(create_audit_embed(ctx, &entries, &paginator).await?)
+                .components(create_audit_components(&paginator))
+                .ephemeral(true),
+        )
+        .await?;
+    if paginator.total() == 1 {
+        return Ok(());
+    }
+    let message = handler.message().await?;
+
+    while let Some(interaction) = message
+        .await_component_interaction(ctx)
+        .author_id(ctx.author().id)
+        .timeout(Duration::from_secs(AUDIT_TIMEOUT_SECS))
+        .await
+    {
+        paginator.handle_action(&interaction.data.custom_id);
+        // 解析用户名可能较慢，先确认交互再更新消息
+        interaction
+            .create_response(ctx, CreateInteractionResponse::Acknowledge)
+            .await?;
+        handler
+            .edit(
+                ctx,
+                CreateReply::default()
+                    .embed(create_audit_embed(ctx, &entries, &paginator).await?)
+                    .components(create_audit_components(&paginator)),
+            )
+            .await?;
+    }
+
+    // 超时后移除翻页按钮
+    handler
+        .edit(
+            ctx,
+            CreateReply::default()
+                .embed(create_audit_embed(ctx, &entries, &paginator).await?)
+                .components(vec![]),
+        )
+        .await?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn settings(
+        user_id: i64,
+        user_license: Option<i32>,
+        system_license: Option<&str>,
+    ) -> UserSettings {
+        UserSettings {
+            user_id,
+            auto_publish_enabled: true,
+            skip_auto_publish_confirmation: false,
+            default_user_license_id: user_license,
+            default_system_license_name: system_license.map(str::to_string),
+            default_system_license_backup: None,
+        }
+    }
+
+    #[test]
+    fn test_build_audit_entries() {
+        let entries = build_audit_entries(vec![
+            settings(30, None, None),
+            settings(10, Some(5), Some("MIT")),
+            settings(20, None, Some("MIT")),
+        ]);
+
+        assert_eq!(
+            entries,
+            vec![
+                AuditEntry {
+                    user_id: UserId::new(10),
+                    default_license: Some(DefaultLicenseIdentifier::User(5)),
+                },
+                AuditEntry {
+                    user_id: UserId::new(20),
+                    default_license: Some(DefaultLicenseIdentifier::System("MIT".to_string())),
+                },
+                AuditEntry {
+                    user_id: UserId::new(30),
+                    default_license: None,
+                },
+            ]
+        );
+    }
+}
@@ -0,0 +1,174 @@
+use poise::{CreateReply, command};
+use serenity::all::*;
+use tracing::warn;
+
+use crate::{
+    commands::Context,
+    error::BotError,
+    services::license::{BulkBackupUpdate, LicensePublishService},
+};
+
+/// 生成批量修改的结果说明
+fn bulk_update_summary(summary: &BulkBackupUpdate) -> String {
+    let mut content = format!("✅ 已更新 {} 个帖子的备份权限。", summary.updated);
+    if summary.unchanged > 0 {
+        content.push_str(&format!("\n{} 个帖子的权限无需修改。", summary.unchanged));
+    }
+    if summary.failed > 0 {
+        content.push_str(&format!(
+            "\n⚠️ {} 个帖子无法访问（可能已被删除），已跳过。",
+            summary.failed
+        ));
+    }
+    content
+}
+
+#[command(
+    slash_command,
+    custom_data = "crate::commands::WriteCommand",
+    guild_only,
+    user_cooldown = 60,
+    name_localized("zh-CN", "批量设置备份"),
+    description_localized("zh-CN", "修改您所有已发布帖子的备份权限"),
+    ephemeral
+)]
+/// Update the backup permission on every thread where you have published a license
+pub async fn bulk_backup_permission(
+    ctx: Context<'_>,
+    #[name_localized("zh-CN", "允许备份")]
+    #[description_localized("zh-CN", "是否允许管理组备份您的作品")]
+    backup_allowed: bool,
+) -> Result<(), BotError> {
+    let to_change = ctx
+        .data()
+        .db()
+        .published_posts()
+        .count_user_posts_with_other_backup(ctx.author().id, backup_allowed)
+        .await?;
+    let status_text = if backup_allowed {
+        "✅ 允许"
+    } else {
+        "❌ 不允许"
+    };
+    if to_change == 0 {
+        ctx.say(format!(
+            "ℹ️ 您所有已发布帖子的备份权限已是 {status_text}，无需修改。"
+        ))
+        .await?;
+        return Ok(());
+    }
+
+    // 确认操作
+    let handler = ctx
+        .send(
+            CreateReply::default()
+                .content(format!(
+                    "⚠️ 即将把 {to_change} 个帖子的备份权限修改为 {status_text}。\n此操作会更新各帖子的置顶协议并发送备份通知。"
+                ))
+                .components(vec![CreateActionRow::Buttons(vec![
+                    CreateButton::new("confirm_bulk_backup")
+                        .label("确认修改")
+                        .style(ButtonStyle::Danger),
+                    CreateButton::new("cancel_bulk_backup")
+                        .label("❌ 取消")
+                        .style(ButtonStyle::Secondary),
+                ])]),
+        )
+        .await?;
+
+    let Some(interaction) = handler
+        .message()
+        .await?
+        .await_component_interaction(ctx)
+        .author_id(ctx.author().id)
+        .await
+    else {
+        warn!("Interaction timed out");
+        return Ok(());
+    };
+
+    interaction
+        .create_response(ctx, CreateInteractionResponse::Acknowledge)
+        .await?;
+
+    if interaction.data.custom_id != "confirm_bulk_backup" {
+        handler
+            .edit(
+                ctx,
+                CreateReply::default()
+                    .content("已取消修改备份权限。")
+                    .components(vec![]),
+            )
+            .await?;
+        return Ok(());
+    }
+
+    handler
+        .edit(
+            ctx,
+            CreateReply::default()
+                .content(format!("⏳ 正在更新 {to_change} 个帖子，请稍候……"))
+                .components(vec![]),
+        )
+        .await?;
+
+    let summary = LicensePublishService::apply_backup_permission_to_user_posts(
+        ctx.http(),
+        ctx.data(),
+        ctx.author(),
+        backup_allowed,
+        |progress| {
+            let content = format!(
+                "⏳ 正在更新 {to_change} 个帖子：已处理 {}/{to_change}，失败 {} 个……",
+                progress.processed(),
+                progress.failed
+            );
+            let handler = &handler;
+            async move {
+                if let Err(e) = handler
+                    .edit(ctx, CreateReply::default().content(content))
+                    .await
+                {
+                    warn!("更新批量设置进度失败: {}", e);
+                }
+            }
+        },
+    )
+    .await?;
+
+    handler
+        .edit(
+            ctx,
+            CreateReply::default().content(bulk_update_summary(&summary)),
+        )
+        .await?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bulk_update_summary() {
+        let summary = BulkBackupUpdate {
+            updated: 3,
+            unchanged: 0,
+            failed: 0,
+        };
+        assert_eq!(
+            bulk_update_summary(&summary),
+            "✅ 已更新 3 个帖子的备份权限。"
+        );
+
+        let summary = BulkBackupUpdate {
+            updated: 1,
+            unchanged: 2,
+            failed: 1,
+        };
+        let content = bulk_update_summary(&summary);
+        assert!(content.contains("2 个帖子的权限无需修改"));
+        assert!(content.contains("1 个帖子无法访问"));
+    }
+}
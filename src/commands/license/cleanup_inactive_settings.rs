@@ -0,0 +1,123 @@
+use poise::{CreateReply, command};
+use serenity::all::*;
+use tracing::warn;
+
+use crate::{
+    commands::{Context, check_admin},
+    error::BotError,
+    services::audit_log::{AuditAction, send_audit_log},
+};
+
+/// 未指定天数时，超过该天数未发布协议的用户视为不活跃
+const DEFAULT_INACTIVE_DAYS: u32 = 180;
+
+#[command(
+    slash_command,
+    custom_data = "crate::commands::WriteCommand",
+    guild_only,
+    default_member_permissions = "ADMINISTRATOR",
+    check = "check_admin",
+    name_localized("zh-CN", "清理非活跃设置"),
+    description_localized("zh-CN", "为长时间未发布协议的用户关闭自动发布"),
+    ephemeral
+)]
+/// Disable auto-publish for users who have not published a license recently
+pub async fn cleanup_inactive_settings(
+    ctx: Context<'_>,
+    #[name_localized("zh-CN", "天数")]
+    #[description_localized("zh-CN", "超过多少天未发布协议视为不活跃（默认 180 天）")]
+    #[min = 1]
+    // 约 100 年，过大的天数会使起始时间计算溢出
+    #[max = 36500]
+    days: Option<u32>,
+) -> Result<(), BotError> {
+    let days = days.unwrap_or(DEFAULT_INACTIVE_DAYS);
+    let since = chrono::Utc::now() - chrono::Duration::days(days.into());
+    let user_settings = ctx.data().db().user_settings();
+    let inactive = user_settings.get_inactive_auto_publish_users(since).await?;
+    let total = user_settings.get_auto_publish_count().await?;
+
+    if inactive.is_empty() {
+        ctx.say(format!(
+            "ℹ️ 已开启自动发布的 {total} 个用户在最近 {days} 天内都发布过协议，无需清理。"
+        ))
+        .await?;
+        return Ok(());
+    }
+
+    // 确认操作
+    let handler = ctx
+        .send(
+            CreateReply::default()
+                .content(format!(
+                    "⚠️ 已开启自动发布的 {total} 个用户中，有 {} 个在最近 {days} 天内没有发布过协议（包括从未发布过的用户）。\n确认后将关闭这些用户的自动发布，用户可随时在 `/自动发布设置` 中重新开启。",
+                    inactive.len()
+                ))
+                .components(vec![CreateActionRow::Buttons(vec![
+                    CreateButton::new("confirm_cleanup_inactive")
+                        .label("确认关闭")
+                        .style(ButtonStyle::Danger),
+                    CreateButton::new("cancel_cleanup_inactive")
+                        .label("❌ 取消")
+                        .style(ButtonStyle::Secondary),
+                ])]),
+        )
+        .await?;
+
+    let Some(interaction) = handler
+        .message()
+        .await?
+        .await_component_interaction(ctx)
+        .author_id(ctx.author().id)
+        .await
+    else {
+        warn!("Interaction timed out");
+        return Ok(());
+    };
+
+    interaction
+        .create_response(ctx, CreateInteractionResponse::Acknowledge)
+        .await?;
+
+    if interaction.data.custom_id != "confirm_cleanup_inactive" {
+        handler
+            .edit(
+                ctx,
+                CreateReply::default()
+                    .content("已取消清理。")
+                    .components(vec![]),
+            )
+            .await?;
+        return Ok(());
+    }
+
+    let disabled = user_settings.disable_auto_publish(&inactive).await?;
+    tracing::info!(
+        "Auto-publish disabled for {} inactive users (>{} days) by {}",
+        disabled,
+        days,
+        ctx.author().id
+    );
+    send_audit_log(
+        ctx.http(),
+        &ctx.data().cfg().load(),
+        ctx.author().id,
+        AuditAction::SettingChanged,
+        format!("关闭了 {disabled} 个超过 {days} 天未发布协议的用户的自动发布"),
+    )
+    .await;
+
+    handler
+        .edit(
+            ctx,
+            CreateReply::default()
+                .content(format!(
+                    "✅ 已关闭 {disabled} 个不活跃用户的自动发布，仍有 {} 个用户开启自动发布。",
+                    total.saturating_sub(disabled)
+                ))
+                .components(vec![]),
+        )
+        .await?;
+
+    Ok(())
+}
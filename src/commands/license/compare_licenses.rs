@@ -0,0 +1,55 @@
+use poise::{CreateReply, command};
+
+use super::publish_license::{autocomplete_license, resolve_license};
+use crate::{
+    commands::Context,
+    error::BotError,
+    utils::{LicenseEmbedBuilder, Locale},
+};
+
+#[command(
+    slash_command,
+    guild_only,
+    name_localized("zh-CN", "协议对比"),
+    description_localized("zh-CN", "并排对比两个协议的权限"),
+    ephemeral
+)]
+/// Compares the permissions of two licenses side by side
+pub async fn compare_licenses(
+    ctx: Context<'_>,
+    #[name_localized("zh-CN", "协议a")]
+    #[description_localized("zh-CN", "第一个协议")]
+    #[autocomplete = "autocomplete_license"]
+    license_a: String,
+
+    #[name_localized("zh-CN", "协议b")]
+    #[description_localized("zh-CN", "第二个协议")]
+    #[autocomplete = "autocomplete_license"]
+    license_b: String,
+) -> Result<(), BotError> {
+    let mut licenses = Vec::with_capacity(2);
+    for license_id in [&license_a, &license_b] {
+        match resolve_license(ctx, license_id).await? {
+            Ok(license) => licenses.push(license),
+            Err(message) => {
+                ctx.send(CreateReply::default().content(message).ephemeral(true))
+                    .await?;
+                return Ok(());
+            }
+        }
+    }
+
+    let locale = ctx.locale().map(Locale::from_discord).unwrap_or_default();
+    ctx.send(
+        CreateReply::default()
+            .embed(LicenseEmbedBuilder::create_license_comparison_embed(
+                &licenses[0],
+                &licenses[1],
+                locale,
+            ))
+            .ephemeral(true),
+    )
+    .await?;
+
+    Ok(())
+}
@@ -0,0 +1,168 @@
+use poise::{CreateReply, command};
+use serenity::all::*;
+use tracing::warn;
+
+use super::publish_license::{autocomplete_license, publish_error_reply, resolve_license};
+use crate::{
+    commands::Context, error::BotError, services::license::LicensePublishService,
+    utils::LicenseEmbedBuilder,
+};
+
+#[command(
+    slash_command,
+    custom_data = "crate::commands::WriteCommand",
+    guild_only,
+    name_localized("zh-CN", "对比已发布"),
+    description_localized("zh-CN", "对比帖子中已发布的协议与协议当前内容，并可重新发布"),
+    ephemeral
+)]
+/// Compare the license pinned in this thread against its current version
+pub async fn compare_published(
+    ctx: Context<'_>,
+    #[name_localized("zh-CN", "协议")]
+    #[description_localized("zh-CN", "已发布的协议")]
+    #[autocomplete = "autocomplete_license"]
+    license_id: String,
+) -> Result<(), BotError> {
+    let thread = match ctx.channel_id().to_channel(&ctx).await?.guild() {
+        Some(
+            thread @ GuildChannel {
+                kind:
+                    ChannelType::PublicThread | ChannelType::PrivateThread | ChannelType::NewsThread,
+                ..
+            },
+        ) => thread,
+        _ => {
+            ctx.say("请在您创建的帖子中使用本命令。").await?;
+            return Ok(());
+        }
+    };
+
+    if thread.owner_id != Some(ctx.author().id) {
+        ctx.say("您只能对比自己帖子中的协议。").await?;
+        return Ok(());
+    }
+
+    let license = match resolve_license(ctx, &license_id).await? {
+        Ok(license) => license,
+        Err(message) => {
+            ctx.say(message).await?;
+            return Ok(());
+        }
+    };
+
+    let Some(post) = ctx
+        .data()
+        .db()
+        .published_posts()
+        .get_by_thread(thread.id)
+        .await?
+    else {
+        ctx.say("此帖子尚未发布协议。").await?;
+        return Ok(());
+    };
+
+    let Ok(message) = ctx
+        .http()
+        .get_message(thread.id, MessageId::new(post.message_id as u64))
+        .await
+    else {
+        ctx.say("❌ 无法获取已发布的协议消息，可能已被删除。")
+            .await?;
+        return Ok(());
+    };
+    let Some(published_embed) = message.embeds.first() else {
+        ctx.say("❌ 已发布的协议消息中没有协议内容。").await?;
+        return Ok(());
+    };
+    if LicenseEmbedBuilder::is_dual_license_embed(published_embed) {
+        ctx.say("暂不支持对比双重授权协议，请使用 /发布协议 重新发布。")
+            .await?;
+        return Ok(());
+    }
+
+    let stale = LicenseEmbedBuilder::stale_fields(published_embed, &license);
+    let embed = LicenseEmbedBuilder::create_stale_fields_embed(&license.license_name, &stale);
+    if stale.is_empty() {
+        ctx.send(CreateReply::default().embed(embed).ephemeral(true))
+            .await?;
+        return Ok(());
+    }
+
+    let update_btn = CreateButton::new("update_published")
+        .label("🔄 更新")
+        .style(ButtonStyle::Success);
+    let cancel_btn = CreateButton::new("cancel_update")
+        .label("❌ 取消")
+        .style(ButtonStyle::Danger);
+    let handler = ctx
+        .send(
+            CreateReply::default()
+                .embed(embed)
+                .components(vec![CreateActionRow::Buttons(vec![update_btn, cancel_btn])])
+                .ephemeral(true),
+        )
+        .await?;
+
+    let Some(interaction) = handler
+        .message()
+        .await?
+        .await_component_interaction(ctx)
+        .author_id(ctx.author().id)
+        .await
+    else {
+        warn!("Interaction timed out");
+        return Ok(());
+    };
+    interaction
+        .create_response(ctx, CreateInteractionResponse::Acknowledge)
+        .await?;
+
+    match interaction.data.custom_id.as_str() {
+        "update_published" => {
+            // 备份权限属于帖子本身，沿用已发布时的设置
+            match LicensePublishService::publish(
+                ctx.http(),
+                ctx.data(),
+                &thread,
+                &license,
+                post.backup_allowed,
+                ctx.author().to_owned(),
+                ctx.author().id,
+            )
+            .await
+            {
+                Ok(()) => {}
+                Err(err) => {
+                    let reply = publish_error_reply(err)?;
+                    handler.edit(ctx, reply.components(vec![])).await?;
+                    return Ok(());
+                }
+            }
+
+            handler
+                .edit(
+                    ctx,
+                    CreateReply::default()
+                        .embed(LicenseEmbedBuilder::create_license_published_embed(
+                            &license.license_name,
+                        ))
+                        .components(vec![]),
+                )
+                .await?;
+        }
+        "cancel_update" => {
+            handler
+                .edit(
+                    ctx,
+                    CreateReply::default()
+                        .content("已取消更新协议。")
+                        .components(vec![]),
+                )
+                .await?;
+        }
+        _ => {}
+    }
+
+    Ok(())
+}
@@ -3,7 +3,11 @@ use serenity::all::*;
 use tracing::warn;
 
 use super::super::Context;
-use crate::{error::BotError, utils::LicenseEmbedBuilder};
+use crate::{
+    error::BotError,
+    services::license::{DUPLICATE_NAME_MESSAGE, LicenseFields, LicenseSource},
+    utils::{LicenseEmbedBuilder, Locale},
+};
 
 #[derive(Modal)]
 #[name = "限制条件"]
@@ -16,12 +20,14 @@ struct LicenseModal {
 
 #[command(
     slash_command,
+    custom_data = "crate::commands::WriteCommand",
     guild_only,
     user_cooldown = 10,
     name_localized("zh-CN", "创建协议-参数"),
     description_localized("zh-CN", "创建一个新的协议"),
     ephemeral
 )]
+#[allow(clippy::too_many_arguments)]
 pub async fn create_license(
     ctx: Context<'_>,
     #[name_localized("zh-CN", "名称")]
@@ -40,6 +46,12 @@ pub async fn create_license(
     #[name_localized("zh-CN", "备份权限")]
     #[description_localized("zh-CN", "是否允许备份(默认为否)")]
     backup: Option<bool>,
+    #[name_localized("zh-CN", "商业化使用")]
+    #[description_localized("zh-CN", "是否允许商业化使用(默认为否)")]
+    commercial: Option<bool>,
+    #[name_localized("zh-CN", "署名要求")]
+    #[description_localized("zh-CN", "二次传播时是否需要署名(默认为否)")]
+    attribution: Option<bool>,
 ) -> Result<(), BotError> {
     let Context::Application(app_ctx) = ctx else {
         return Err(BotError::GenericError {
@@ -47,20 +59,10 @@ pub async fn create_license(
             source: None,
         });
     };
-    let modal_resp = if rest == Some(true) {
-        let Some(modal_resp) = LicenseModal::execute(app_ctx).await? else {
-            warn!("Modal response is None");
-            return Ok(());
-        };
-        Some(modal_resp)
-    } else {
-        None
-    };
-
-    // 检查协议名称是否重复
+    // 检查协议名称是否重复，在填写附加限制和预览之前提示
     let name_exists = ctx
         .data()
-        .db
+        .db()
         .license()
         .license_name_exists(ctx.author().id, &name, None)
         .await?;
@@ -68,19 +70,33 @@ pub async fn create_license(
     if name_exists {
         ctx.send(
             CreateReply::default()
-                .content("❌ 您已经创建过同名协议，请使用不同的名称。")
+                .content(format!("❌ {DUPLICATE_NAME_MESSAGE}"))
                 .ephemeral(true),
         )
         .await?;
         return Ok(());
     }
 
+    let modal_resp = if rest == Some(true) {
+        let Some(modal_resp) = LicenseModal::execute(app_ctx).await? else {
+            warn!("Modal response is None");
+            return Ok(());
+        };
+        Some(modal_resp)
+    } else {
+        None
+    };
+
     let preview_license_embed = LicenseEmbedBuilder::create_license_preview_embed(
         &name,
         redis,
         modify,
         modal_resp.as_ref().map(|m| m.restrictions.as_str()),
         backup,
+        commercial.unwrap_or(false),
+        attribution.unwrap_or(false),
+        None,
+        ctx.locale().map(Locale::from_discord).unwrap_or_default(),
     );
     let save_btn = CreateButton::new("save_license")
         .label("保存协议")
@@ -103,28 +119,36 @@ pub async fn create_license(
         "save_license" => {
             let result = ctx
                 .data()
-                .db
-                .license()
-                .create(
+                .guild_license(ctx.guild_id())
+                .create_or_get(
                     ctx.author().id,
-                    name.clone(),
-                    redis,
-                    modify,
-                    modal_resp.map(|m| m.restrictions),
-                    backup.unwrap_or(false),
+                    LicenseFields {
+                        license_name: name.clone(),
+                        allow_redistribution: redis,
+                        allow_modification: modify,
+                        restrictions_note: modal_resp.map(|m| m.restrictions),
+                        allow_backup: backup.unwrap_or(false),
+                        allow_commercial: commercial.unwrap_or(false),
+                        require_attribution: attribution.unwrap_or(false),
+                        color: None,
+                    },
+                    LicenseSource::Command,
                 )
                 .await;
 
             match result {
-                Ok(_) => {
+                Ok((_, created)) => {
                     itx.create_response(ctx, CreateInteractionResponse::Acknowledge)
                         .await?;
+                    let content = if created {
+                        "✅ 协议已创建".to_string()
+                    } else {
+                        format!("❌ {DUPLICATE_NAME_MESSAGE}")
+                    };
                     reply
                         .edit(
                             ctx,
-                            CreateReply::default()
-                                .content("✅ 协议已创建")
-                                .components(vec![]),
+                            CreateReply::default().content(content).components(vec![]),
                         )
                         .await?;
                 }
@@ -5,11 +5,13 @@ use tracing::warn;
 use super::super::Context;
 use crate::{
     error::BotError,
-    utils::{LicenseEditState, LicenseEmbedBuilder, present_license_editing_panel},
+    services::license::{DUPLICATE_NAME_MESSAGE, LicenseSource},
+    utils::{LicenseEditState, LicenseEmbedBuilder, Locale, present_license_editing_panel},
 };
 
 #[command(
     slash_command,
+    custom_data = "crate::commands::WriteCommand",
     guild_only,
     user_cooldown = 10,
     name_localized("zh-CN", "创建协议"),
@@ -60,7 +62,10 @@ pub async fn create_license_interactive(ctx: Context<'_>) -> Result<(), BotError
         .await?;
     let next_number = user_licenses.len() + 1;
     let default_name = format!("我的协议{next_number}");
-    let initial_state = LicenseEditState::new(default_name);
+    let initial_state = LicenseEditState::with_defaults(
+        default_name,
+        &ctx.data().cfg().load().new_license_defaults,
+    );
 
     // 调用现有的编辑面板
     if let Ok(outcome) = present_license_editing_panel(
@@ -75,45 +80,29 @@ pub async fn create_license_interactive(ctx: Context<'_>) -> Result<(), BotError
         let followup_interaction = outcome.interaction.unwrap_or_else(|| interaction.clone());
 
         // 用户保存了协议，提取字段并创建
-        let (name, allow_redistribution, allow_modification, restrictions_note, allow_backup) =
-            final_state.to_user_license_fields();
-
-        // 检查协议名称是否重复
-        let name_exists = ctx
-            .data()
-            .db()
-            .license()
-            .license_name_exists(ctx.author().id, &name, None)
-            .await?;
-
-        if name_exists {
-            followup_interaction
-                .create_followup(
-                    ctx.http(),
-                    CreateInteractionResponseFollowup::new()
-                        .content("❌ 您已经创建过同名协议，请使用不同的名称。")
-                        .ephemeral(true),
-                )
-                .await?;
-            return Ok(());
-        }
+        let fields = final_state.to_user_license_fields();
 
         match ctx
             .data()
-            .db()
-            .license()
-            .create(
-                ctx.author().id,
-                name,
-                allow_redistribution,
-                allow_modification,
-                restrictions_note,
-                allow_backup,
-            )
+            .guild_license(ctx.guild_id())
+            .create_or_get(ctx.author().id, fields, LicenseSource::Interactive)
             .await
         {
-            Ok(license) => {
-                let success_embed = LicenseEmbedBuilder::create_license_detail_embed(&license);
+            Ok((_, false)) => {
+                followup_interaction
+                    .create_followup(
+                        ctx.http(),
+                        CreateInteractionResponseFollowup::new()
+                            .content(format!("❌ {DUPLICATE_NAME_MESSAGE}"))
+                            .ephemeral(true),
+                    )
+                    .await?;
+            }
+            Ok((license, true)) => {
+                let success_embed = LicenseEmbedBuilder::create_license_detail_embed(
+                    &license,
+                    Locale::from_discord(&followup_interaction.locale),
+                );
                 followup_interaction
                     .create_followup(
                         ctx.http(),
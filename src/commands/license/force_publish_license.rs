@@ -0,0 +1,112 @@
+use poise::{CreateReply, command};
+use serenity::all::*;
+use tracing::info;
+
+use super::publish_license::{fetch_thread_owner, publish_error_reply};
+use crate::{
+    commands::{Context, check_admin, system::autocomplete_system_license},
+    error::BotError,
+    services::license::LicensePublishService,
+    utils::LicenseEmbedBuilder,
+};
+
+#[command(
+    slash_command,
+    custom_data = "crate::commands::WriteCommand",
+    guild_only,
+    default_member_permissions = "ADMINISTRATOR",
+    check = "check_admin",
+    name_localized("zh-CN", "代发协议"),
+    description_localized("zh-CN", "管理员以帖子作者的名义在指定帖子中发布协议"),
+    ephemeral
+)]
+/// Publish a license onto another user's thread on their behalf
+pub async fn force_publish_license(
+    ctx: Context<'_>,
+    #[name_localized("zh-CN", "帖子")]
+    #[description_localized("zh-CN", "要发布协议的帖子")]
+    #[channel_types("PublicThread", "PrivateThread")]
+    thread: GuildChannel,
+
+    #[name_localized("zh-CN", "系统协议")]
+    #[description_localized("zh-CN", "要发布的系统协议（与帖子作者协议ID二选一）")]
+    #[autocomplete = "autocomplete_system_license"]
+    system_license: Option<String>,
+
+    #[name_localized("zh-CN", "作者协议id")]
+    #[description_localized("zh-CN", "帖子作者的个人协议ID（与系统协议二选一）")]
+    user_license_id: Option<i32>,
+) -> Result<(), BotError> {
+    // 缓存中缺少创建者时重新获取
+    let owner_id = match thread.owner_id {
+        Some(owner_id) => Some(owner_id),
+        None => fetch_thread_owner(ctx.http(), &thread).await,
+    };
+    let Some(owner_id) = owner_id else {
+        ctx.say("❌ 无法获取该帖子的作者。").await?;
+        return Ok(());
+    };
+
+    let license = match (system_license, user_license_id) {
+        (Some(name), None) => {
+            let Some(license) = ctx.data().system_license_cache().get_by_name(&name).await else {
+                ctx.say(format!("❌ 未找到系统协议 **{name}**。")).await?;
+                return Ok(());
+            };
+            license.to_user_license(owner_id, -1)
+        }
+        (None, Some(id)) => {
+            let Some(license) = ctx.data().db().license().get_license(id, owner_id).await? else {
+                ctx.say("❌ 帖子作者没有该协议。").await?;
+                return Ok(());
+            };
+            license
+        }
+        _ => {
+            ctx.say("❌ 请在系统协议和作者协议ID中选择且仅选择一项。")
+                .await?;
+            return Ok(());
+        }
+    };
+
+    let owner = owner_id.to_user(ctx).await?;
+    info!(
+        "管理员 {} ({}) 在帖子 {} 代发协议 {}，帖子作者 {} ({})",
+        ctx.author().name,
+        ctx.author().id,
+        thread.id,
+        license.license_name,
+        owner.name,
+        owner_id
+    );
+
+    // 以帖子作者的名义发布，发布记录的 updated_at 会随之更新
+    match LicensePublishService::publish(
+        ctx.http(),
+        ctx.data(),
+        &thread,
+        &license,
+        license.allow_backup,
+        owner,
+        ctx.author().id,
+    )
+    .await
+    {
+        Ok(()) => {}
+        Err(err) => {
+            ctx.send(publish_error_reply(err)?.ephemeral(true)).await?;
+            return Ok(());
+        }
+    }
+
+    ctx.send(
+        CreateReply::default()
+            .embed(LicenseEmbedBuilder::create_license_published_embed(
+                &license.license_name,
+            ))
+            .ephemeral(true),
+    )
+    .await?;
+
+    Ok(())
+}
@@ -0,0 +1,72 @@
+use poise::{CreateReply, command};
+use serenity::all::*;
+
+use crate::{
+    commands::{Context, check_admin},
+    error::BotError,
+    utils::{LicenseEmbedBuilder, Locale},
+};
+
+#[command(
+    slash_command,
+    guild_only,
+    name_localized("zh-CN", "协议详情"),
+    description_localized("zh-CN", "按ID查看协议详情")
+)]
+/// Shows the details of a license by its ID
+pub async fn license_detail(
+    ctx: Context<'_>,
+    #[name_localized("zh-CN", "协议id")]
+    #[description_localized("zh-CN", "要查看的协议ID")]
+    license_id: i32,
+
+    #[name_localized("zh-CN", "用户")]
+    #[description_localized("zh-CN", "协议所属用户，查看他人协议需要管理员权限（可选）")]
+    user: Option<User>,
+
+    #[name_localized("zh-CN", "公开")]
+    #[description_localized("zh-CN", "是否在频道中公开展示，便于分享（默认仅自己可见）")]
+    public: Option<bool>,
+) -> Result<(), BotError> {
+    let ephemeral = !public.unwrap_or(false);
+    let target = user.unwrap_or_else(|| ctx.author().to_owned());
+
+    // 查看他人的协议需要管理员权限
+    if target.id != ctx.author().id && !check_admin(ctx).await? {
+        ctx.send(
+            CreateReply::default()
+                .content("只有管理员可以查看其他用户的协议。")
+                .ephemeral(true),
+        )
+        .await?;
+        return Ok(());
+    }
+
+    let Some(license) = ctx
+        .data()
+        .db
+        .license()
+        .get_license(license_id, target.id)
+        .await?
+    else {
+        ctx.send(
+            CreateReply::default()
+                .content("未找到该协议。")
+                .ephemeral(true),
+        )
+        .await?;
+        return Ok(());
+    };
+
+    let locale = ctx.locale().map(Locale::from_discord).unwrap_or_default();
+    ctx.send(
+        CreateReply::default()
+            .embed(LicenseEmbedBuilder::create_license_inspection_embed(
+                &license, locale,
+            ))
+            .ephemeral(ephemeral),
+    )
+    .await?;
+
+    Ok(())
+}
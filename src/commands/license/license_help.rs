@@ -0,0 +1,240 @@
+use poise::{CreateReply, command};
+use serenity::all::*;
+
+use crate::{
+    commands::Context,
+    error::BotError,
+    utils::{Locale, Paginator},
+};
+
+/// 帮助面板的交互超时时间（秒）
+const HELP_TIMEOUT_SECS: u64 = 300;
+
+/// 章节跳转按钮的 custom_id 前缀
+const HELP_SECTION_PREFIX: &str = "help_section:";
+
+/// 帮助章节
+struct HelpSection {
+    /// 跳转按钮上显示的短标题
+    label: &'static str,
+    title: &'static str,
+    body: &'static str,
+    example: &'static str,
+}
+
+const ZH_CN_SECTIONS: [HelpSection; 5] = [
+    HelpSection {
+        label: "概览",
+        title: "📖 协议帮助",
+        body: "本机器人用于为您的作品创建并发布授权协议，说明其他人可以如何使用您的作品。\n\n\
+               使用下方按钮翻页，或直接跳转到感兴趣的功能。",
+        example: "创建协议 → 在帖子中发布 → 需要时开启自动发布",
+    },
+    HelpSection {
+        label: "创建",
+        title: "✏️ 创建协议",
+        body: "为常用的授权方式创建个人协议，可设置二次传播、二次修改、备份、商业化、署名要求以及额外的限制条件。",
+        example: "`/创建协议面板` 打开交互式编辑器\n`/创建协议` 通过参数直接创建",
+    },
+    HelpSection {
+        label: "管理",
+        title: "🗂️ 管理协议",
+        body: "查看、编辑或删除已创建的协议，也可以一次批量删除多个不再使用的协议。",
+        example: "`/协议管理` 选择协议后进行编辑或删除",
+    },
+    HelpSection {
+        label: "发布",
+        title: "📤 发布协议",
+        body: "在您创建的帖子中发布协议，协议会被置顶；重新发布时旧协议会被标记为作废。\n\
+               可以同时选择第二个协议作为双重授权，使用者可任选其一。",
+        example: "`/发布协议 协议:<选择协议>`\n`/发布协议 协议:<协议A> 第二协议:<协议B>`",
+    },
+    HelpSection {
+        label: "自动发布",
+        title: "⚙️ 自动发布设置",
+        body: "开启后，您在受支持的论坛发帖时机器人会自动为帖子发布默认协议，可选择是否在发布前确认。",
+        example: "`/自动发布设置` 选择默认协议并开启自动发布",
+    },
+];
+
+const EN_US_SECTIONS: [HelpSection; 5] = [
+    HelpSection {
+        label: "Overview",
+        title: "📖 License Help",
+        body: "This bot lets you create licenses for your works and publish them, so others know how they may use your content.\n\n\
+               Use the buttons below to flip pages or jump to a feature.",
+        example: "Create a license → publish it in a thread → enable auto-publish if needed",
+    },
+    HelpSection {
+        label: "Create",
+        title: "✏️ Creating licenses",
+        body: "Create personal licenses for the permissions you use most: redistribution, modification, backup, commercial use, attribution and extra restrictions.",
+        example: "`/create_license_interactive` opens the interactive editor\n`/create_license` creates one from command options",
+    },
+    HelpSection {
+        label: "Manage",
+        title: "🗂️ Managing licenses",
+        body: "View, edit or delete your licenses, or bulk-delete several licenses you no longer use.",
+        example: "`/license_manager` then pick a license to edit or delete",
+    },
+    HelpSection {
+        label: "Publish",
+        title: "📤 Publishing licenses",
+        body: "Publish a license in a thread you created; it will be pinned and any previous license is marked obsolete.\n\
+               You may add a second license to offer a dual license, letting users choose either.",
+        example: "`/publish_license license_id:<pick one>`\n`/publish_license license_id:<A> second_license_id:<B>`",
+    },
+    HelpSection {
+        label: "Auto-publish",
+        title: "⚙️ Auto-publish settings",
+        body: "When enabled, the bot publishes your default license whenever you post in a supported forum, optionally asking for confirmation first.",
+        example: "`/auto_publish_settings` to choose a default license and turn it on",
+    },
+];
+
+fn help_sections(locale: Locale) -> &'static [HelpSection] {
+    match locale {
+        Locale::ZhCn => &ZH_CN_SECTIONS,
+        Locale::EnUs => &EN_US_SECTIONS,
+    }
+}
+
+/// 处理帮助面板的按钮，返回该按钮是否属于帮助面板
+fn apply_help_action(paginator: &mut Paginator, custom_id: &str) -> bool {
+    if paginator.handle_action(custom_id) {
+        return true;
+    }
+    custom_id
+        .strip_prefix(HELP_SECTION_PREFIX)
+        .and_then(|index| index.parse::<usize>().ok())
+        .is_some_and(|index| paginator.go_to(index))
+}
+
+fn create_help_embed(locale: Locale, paginator: &Paginator) -> CreateEmbed {
+    let section = &help_sections(locale)[paginator.page()];
+    let example_name = match locale {
+        Locale::ZhCn => "示例",
+        Locale::EnUs => "Example",
+    };
+    CreateEmbed::new()
+        .title(section.title)
+        .description(section.body)
+        .field(example_name, section.example, false)
+        .colour(Colour::BLUE)
+        .footer(CreateEmbedFooter::new(format!(
+            "{}/{}",
+            paginator.page() + 1,
+            paginator.total()
+        )))
+}
+
+fn create_help_components(locale: Locale, paginator: &Paginator) -> Vec<CreateActionRow> {
+    let jump_buttons = help_sections(locale)
+        .iter()
+        .enumerate()
+        .map(|(index, section)| {
+            CreateButton::new(format!("{HELP_SECTION_PREFIX}{index}"))
+                .label(section.label)
+                .style(if index == paginator.page() {
+                    ButtonStyle::Primary
+                } else {
+                    ButtonStyle::Secondary
+                })
+        })
+        .collect();
+    vec![CreateActionRow::Buttons(jump_buttons), paginator.nav_row()]
+}
+
+#[command(
+    slash_command,
+    name_localized("zh-CN", "协议帮助"),
+    description_localized("zh-CN", "查看协议相关功能的使用说明"),
+    ephemeral
+)]
+/// Shows an interactive guide to the license commands
+pub async fn license_help(ctx: Context<'_>) -> Result<(), BotError> {
+    let locale = ctx.locale().map(Locale::from_discord).unwrap_or_default();
+    let mut paginator = Paginator::new(help_sections(locale).len());
+
+    let handler = ctx
+        .send(
+            CreateReply::default()
+                .embed(create_help_embed(locale, &paginator))
+                .components(create_help_components(locale, &paginator))
+                .ephemeral(true),
+        )
+        .await?;
+    let message = handler.message().await?;
+
+    while let Some(interaction) = message
+        .await_component_interaction(ctx)
+        .author_id(ctx.author().id)
+        .timeout(std::time::Duration::from_secs(HELP_TIMEOUT_SECS))
+        .await
+    {
+        if !apply_help_action(&mut paginator, &interaction.data.custom_id) {
+            interaction
+                .create_response(ctx, CreateInteractionResponse::Acknowledge)
+                .await?;
+            continue;
+        }
+
+        interaction
+            .create_response(
+                ctx,
+                CreateInteractionResponse::UpdateMessage(
+                    CreateInteractionResponseMessage::new()
+                        .embed(create_help_embed(locale, &paginator))
+                        .components(create_help_components(locale, &paginator)),
+                ),
+            )
+            .await?;
+    }
+
+    // 超时后移除按钮
+    handler
+        .edit(
+            ctx,
+            CreateReply::default()
+                .embed(create_help_embed(locale, &paginator))
+                .components(vec![]),
+        )
+        .await?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_help_navigation() {
+        let mut paginator = Paginator::new(ZH_CN_SECTIONS.len());
+
+        assert!(apply_help_action(&mut paginator, "page_next"));
+        assert_eq!(paginator.page(), 1);
+
+        assert!(apply_help_action(&mut paginator, "help_section:4"));
+        assert_eq!(paginator.page(), 4);
+
+        // 末页继续翻页保持不变
+        assert!(apply_help_action(&mut paginator, "page_next"));
+        assert_eq!(paginator.page(), 4);
+
+        // 越界或无法识别的按钮不改变状态
+        assert!(!apply_help_action(&mut paginator, "help_section:9"));
+        assert!(!apply_help_action(&mut paginator, "help_section:abc"));
+        assert!(!apply_help_action(&mut paginator, "unknown"));
+        assert_eq!(paginator.page(), 4);
+    }
+
+    #[test]
+    fn test_help_sections_localized() {
+        assert_eq!(
+            help_sections(Locale::ZhCn).len(),
+            help_sections(Locale::EnUs).len()
+        );
+        assert_eq!(help_sections(Locale::EnUs)[0].label, "Overview");
+    }
+}
@@ -5,11 +5,16 @@ use tracing::warn;
 use super::super::Context;
 use crate::{
     error::BotError,
-    utils::{LicenseEditState, LicenseEmbedBuilder, present_license_editing_panel},
+    services::{
+        audit_log::{AuditAction, send_audit_log},
+        license::{LicenseFields, UserLicense},
+    },
+    utils::{LicenseEditState, LicenseEmbedBuilder, Locale, present_license_editing_panel},
 };
 
 #[command(
     slash_command,
+    custom_data = "crate::commands::WriteCommand",
     guild_only,
     user_cooldown = 10,
     name_localized("zh-CN", "协议管理"),
@@ -31,14 +36,18 @@ pub async fn license_manager(ctx: Context<'_>) -> Result<(), BotError> {
     let embed = LicenseEmbedBuilder::create_license_manager_embed();
     // create a select menu with the user's licenses
     let options = licenses
-        .into_iter()
-        .map(|license| CreateSelectMenuOption::new(license.license_name, license.id.to_string()))
+        .iter()
+        .map(|license| CreateSelectMenuOption::new(&license.license_name, license.id.to_string()))
         .collect();
     let select_menu =
         CreateSelectMenu::new("select_license", CreateSelectMenuKind::String { options })
             .placeholder("选择要设置的协议")
             .max_values(1);
 
+    let bulk_delete_button = CreateButton::new("bulk_delete_licenses")
+        .label("批量删除")
+        .style(ButtonStyle::Danger);
+
     let cancel_button = CreateButton::new("cancel_license_selection")
         .label("❌ 取消")
         .style(ButtonStyle::Secondary);
@@ -46,7 +55,7 @@ pub async fn license_manager(ctx: Context<'_>) -> Result<(), BotError> {
     // create the reply with the select menu and cancel button
     let reply = CreateReply::default().embed(embed).components(vec![
         CreateActionRow::SelectMenu(select_menu),
-        CreateActionRow::Buttons(vec![cancel_button]),
+        CreateActionRow::Buttons(vec![bulk_delete_button, cancel_button]),
     ]);
     let reply = ctx.send(reply).await?;
     // wait for the user to select a license
@@ -66,6 +75,13 @@ pub async fn license_manager(ctx: Context<'_>) -> Result<(), BotError> {
         return Ok(());
     }
 
+    // 处理批量删除按钮
+    if itx.data.custom_id == "bulk_delete_licenses" {
+        itx.create_response(ctx, CreateInteractionResponse::Acknowledge)
+            .await?;
+        return bulk_delete_licenses(ctx, &reply, &licenses).await;
+    }
+
     // validate the interaction data
     let ComponentInteractionDataKind::StringSelect { values } = itx.data.kind.to_owned() else {
         warn!(
@@ -104,8 +120,9 @@ pub async fn license_manager(ctx: Context<'_>) -> Result<(), BotError> {
         .await?;
 
     // Create function to generate the second menu embed
+    let locale = ctx.locale().map(Locale::from_discord).unwrap_or_default();
     let create_second_menu_embed = |license: &entities::entities::user_licenses::Model| {
-        LicenseEmbedBuilder::create_license_detail_embed(license)
+        LicenseEmbedBuilder::create_license_detail_embed(license, locale)
     };
 
     // Helper function to create buttons without cloning
@@ -149,13 +166,7 @@ pub async fn license_manager(ctx: Context<'_>) -> Result<(), BotError> {
     match itx.data.custom_id.as_str() {
         "edit_license" => {
             // 创建编辑状态
-            let edit_state = LicenseEditState::from_existing(
-                license.license_name.clone(),
-                license.allow_redistribution,
-                license.allow_modification,
-                license.restrictions_note.clone(),
-                license.allow_backup,
-            );
+            let edit_state = LicenseEditState::from_existing(LicenseFields::from(&license));
 
             // 调用编辑器
             match present_license_editing_panel(
@@ -169,25 +180,11 @@ pub async fn license_manager(ctx: Context<'_>) -> Result<(), BotError> {
                 Ok(outcome) => {
                     if let Some(final_state) = outcome.state {
                         // 用户保存了编辑，更新协议
-                        let (
-                            name,
-                            allow_redistribution,
-                            allow_modification,
-                            restrictions_note,
-                            allow_backup,
-                        ) = final_state.to_user_license_fields();
+                        let fields = final_state.to_user_license_fields();
 
                         match db
                             .license()
-                            .update(
-                                license_id,
-                                ctx.author().id,
-                                name,
-                                allow_redistribution,
-                                allow_modification,
-                                restrictions_note,
-                                allow_backup,
-                            )
+                            .update(license_id, ctx.author().id, fields)
                             .await
                         {
                             Ok(Some(updated_license)) => {
@@ -199,6 +196,7 @@ pub async fn license_manager(ctx: Context<'_>) -> Result<(), BotError> {
                                             .embed(
                                                 LicenseEmbedBuilder::create_license_detail_embed(
                                                     &updated_license,
+                                                    locale,
                                                 ),
                                             )
                                             .components(vec![CreateActionRow::Buttons(
@@ -267,6 +265,14 @@ pub async fn license_manager(ctx: Context<'_>) -> Result<(), BotError> {
 
             // Delete license without confirmation
             db.license().delete(license_id, ctx.author().id).await?;
+            send_audit_log(
+                ctx.http(),
+                &ctx.data().cfg().load(),
+                ctx.author().id,
+                AuditAction::LicenseDeleted,
+                format!("协议「{}」(#{license_id})", license.license_name),
+            )
+            .await;
 
             if let Some(settings) = db.user_settings().get(ctx.author().id).await?
                 && settings.default_user_license_id == Some(license_id)
@@ -308,3 +314,155 @@ pub async fn license_manager(ctx: Context<'_>) -> Result<(), BotError> {
 
     Ok(())
 }
+
+/// 批量删除协议：多选要删除的协议，确认后统一删除
+async fn bulk_delete_licenses(
+    ctx: Context<'_>,
+    reply: &poise::ReplyHandle<'_>,
+    licenses: &[UserLicense],
+) -> Result<(), BotError> {
+    let db = ctx.data().db();
+
+    let options = licenses
+        .iter()
+        .map(|license| CreateSelectMenuOption::new(&license.license_name, license.id.to_string()))
+        .collect();
+    let select_menu = CreateSelectMenu::new(
+        "bulk_delete_select",
+        CreateSelectMenuKind::String { options },
+    )
+    .placeholder("选择要删除的协议（可多选）")
+    .min_values(1)
+    .max_values(licenses.len().min(25) as u8);
+
+    reply
+        .edit(
+            ctx,
+            CreateReply::default()
+                .embed(LicenseEmbedBuilder::create_license_manager_embed())
+                .components(vec![
+                    CreateActionRow::SelectMenu(select_menu),
+                    CreateActionRow::Buttons(vec![
+                        CreateButton::new("cancel_bulk_delete")
+                            .label("❌ 取消")
+                            .style(ButtonStyle::Secondary),
+                    ]),
+                ]),
+        )
+        .await?;
+
+    let Some(itx) = reply
+        .message()
+        .await?
+        .await_component_interaction(ctx)
+        .author_id(ctx.author().id)
+        .await
+    else {
+        warn!("Interaction timed out or was not found.");
+        return Ok(());
+    };
+
+    let ComponentInteractionDataKind::StringSelect { values } = itx.data.kind.to_owned() else {
+        itx.create_response(ctx, CreateInteractionResponse::Acknowledge)
+            .await?;
+        reply.delete(ctx).await?;
+        return Ok(());
+    };
+    let license_ids = values
+        .iter()
+        .map(|v| v.parse::<i32>())
+        .collect::<Result<Vec<_>, _>>()?;
+    let selected: Vec<&UserLicense> = licenses
+        .iter()
+        .filter(|l| license_ids.contains(&l.id))
+        .collect();
+
+    itx.create_response(ctx, CreateInteractionResponse::Acknowledge)
+        .await?;
+
+    // 确认删除
+    let names = selected
+        .iter()
+        .map(|l| format!("• {}", l.license_name))
+        .collect::<Vec<_>>()
+        .join("\n");
+    reply
+        .edit(
+            ctx,
+            CreateReply::default()
+                .embed(
+                    CreateEmbed::new()
+                        .title("⚠️ 确认批量删除")
+                        .description(format!(
+                            "将删除以下 {} 个协议，此操作无法撤销：\n{names}",
+                            selected.len()
+                        ))
+                        .colour(serenity::all::colours::branding::RED),
+                )
+                .components(vec![CreateActionRow::Buttons(vec![
+                    CreateButton::new("confirm_bulk_delete")
+                        .label("确认删除")
+                        .style(ButtonStyle::Danger),
+                    CreateButton::new("cancel_bulk_delete")
+                        .label("❌ 取消")
+                        .style(ButtonStyle::Secondary),
+                ])]),
+        )
+        .await?;
+
+    let Some(itx) = reply
+        .message()
+        .await?
+        .await_component_interaction(ctx)
+        .author_id(ctx.author().id)
+        .await
+    else {
+        warn!("Interaction timed out or was not found.");
+        return Ok(());
+    };
+    itx.create_response(ctx, CreateInteractionResponse::Acknowledge)
+        .await?;
+
+    if itx.data.custom_id != "confirm_bulk_delete" {
+        reply.delete(ctx).await?;
+        return Ok(());
+    }
+
+    let mut deleted_ids = Vec::new();
+    for license in &selected {
+        if db.license().delete(license.id, ctx.author().id).await? {
+            deleted_ids.push(license.id);
+            send_audit_log(
+                ctx.http(),
+                &ctx.data().cfg().load(),
+                ctx.author().id,
+                AuditAction::LicenseDeleted,
+                format!("协议「{}」(#{})", license.license_name, license.id),
+            )
+            .await;
+        }
+    }
+
+    if let Some(settings) = db.user_settings().get(ctx.author().id).await?
+        && settings
+            .default_user_license_id
+            .is_some_and(|id| deleted_ids.contains(&id))
+    {
+        db.user_settings()
+            .set_default_license(ctx.author().id, None, None)
+            .await?;
+    }
+
+    reply
+        .edit(
+            ctx,
+            CreateReply::default()
+                .embed(LicenseEmbedBuilder::create_licenses_bulk_deleted_embed(
+                    deleted_ids.len(),
+                ))
+                .components(vec![]),
+        )
+        .await?;
+
+    Ok(())
+}
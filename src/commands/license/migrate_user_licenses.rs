@@ -0,0 +1,169 @@
+use poise::{CreateReply, command};
+use serenity::all::*;
+use tracing::warn;
+
+use crate::{
+    commands::{Context, check_admin},
+    error::BotError,
+    services::{
+        audit_log::{AuditAction, send_audit_log},
+        license::{LicenseTransfer, UserLicense},
+    },
+};
+
+/// 以「、」连接协议名称
+fn join_names(licenses: &[UserLicense]) -> String {
+    licenses
+        .iter()
+        .map(|l| format!("「{}」", l.license_name))
+        .collect::<Vec<_>>()
+        .join("、")
+}
+
+/// 生成迁移结果的说明
+fn format_transfer_result(result: &LicenseTransfer, from: UserId, to: UserId) -> String {
+    let mut lines = vec![format!(
+        "✅ 已将 {} 个协议从 <@{from}> 迁移给 <@{to}>。",
+        result.moved.len()
+    )];
+    if !result.moved.is_empty() {
+        lines.push(format!("已迁移：{}", join_names(&result.moved)));
+    }
+    if !result.name_conflicts.is_empty() {
+        lines.push(format!(
+            "⚠️ 目标用户已有同名协议，未迁移：{}",
+            join_names(&result.name_conflicts)
+        ));
+    }
+    if !result.over_limit.is_empty() {
+        lines.push(format!(
+            "⚠️ 目标用户的协议数量已达上限，未迁移：{}",
+            join_names(&result.over_limit)
+        ));
+    }
+    lines.join("\n")
+}
+
+#[command(
+    slash_command,
+    custom_data = "crate::commands::WriteCommand",
+    guild_only,
+    default_member_permissions = "ADMINISTRATOR",
+    check = "check_admin",
+    name_localized("zh-CN", "迁移用户协议"),
+    description_localized("zh-CN", "将一位用户的所有协议迁移到另一位用户，用于更换账号"),
+    ephemeral
+)]
+/// Move all licenses from one user to another, e.g. when a user switches accounts
+pub async fn migrate_user_licenses(
+    ctx: Context<'_>,
+    #[name_localized("zh-CN", "原用户")]
+    #[description_localized("zh-CN", "协议当前所属的用户")]
+    from: User,
+    #[name_localized("zh-CN", "新用户")]
+    #[description_localized("zh-CN", "接收协议的用户")]
+    to: User,
+) -> Result<(), BotError> {
+    if from.id == to.id {
+        ctx.say("❌ 原用户和新用户不能是同一用户。").await?;
+        return Ok(());
+    }
+
+    // 目标用户的协议数量上限按当前服务器计算
+    let license_service = ctx.data().guild_license(ctx.guild_id());
+    let count = license_service.get_user_license_count(from.id).await?;
+    if count == 0 {
+        ctx.say(format!("ℹ️ {} 没有任何协议。", from.mention()))
+            .await?;
+        return Ok(());
+    }
+
+    // 确认操作
+    let handler = ctx
+        .send(
+            CreateReply::default()
+                .content(format!(
+                    "⚠️ 确认将 {} 的 {count} 个协议迁移给 {}？\n目标用户已有同名协议或协议数量达到上限时，对应的协议会保留在原用户名下。",
+                    from.mention(),
+                    to.mention()
+                ))
+                .components(vec![CreateActionRow::Buttons(vec![
+                    CreateButton::new("confirm_migrate_licenses")
+                        .label("确认迁移")
+                        .style(ButtonStyle::Danger),
+                    CreateButton::new("cancel_migrate_licenses")
+                        .label("❌ 取消")
+                        .style(ButtonStyle::Secondary),
+                ])]),
+        )
+        .await?;
+
+    let Some(interaction) = handler
+        .message()
+        .await?
+        .await_component_interaction(ctx)
+        .author_id(ctx.author().id)
+        .await
+    else {
+        warn!("Interaction timed out");
+        return Ok(());
+    };
+
+    interaction
+        .create_response(ctx, CreateInteractionResponse::Acknowledge)
+        .await?;
+
+    if interaction.data.custom_id != "confirm_migrate_licenses" {
+        handler
+            .edit(
+                ctx,
+                CreateReply::default()
+                    .content("已取消迁移。")
+                    .components(vec![]),
+            )
+            .await?;
+        return Ok(());
+    }
+
+    let result = license_service.transfer_licenses(from.id, to.id).await?;
+    // 原用户的默认协议若已被迁移走，清除该默认设置
+    ctx.data()
+        .db()
+        .user_settings()
+        .clear_stale_default_license(from.id)
+        .await?;
+
+    tracing::info!(
+        "{} licenses migrated from {} to {} by {} ({} name conflicts, {} over limit)",
+        result.moved.len(),
+        from.id,
+        to.id,
+        ctx.author().id,
+        result.name_conflicts.len(),
+        result.over_limit.len()
+    );
+    send_audit_log(
+        ctx.http(),
+        &ctx.data().cfg().load(),
+        ctx.author().id,
+        AuditAction::SettingChanged,
+        format!(
+            "将 {} 个协议从 <@{}> 迁移给 <@{}>",
+            result.moved.len(),
+            from.id,
+            to.id
+        ),
+    )
+    .await;
+
+    handler
+        .edit(
+            ctx,
+            CreateReply::default()
+                .content(format_transfer_result(&result, from.id, to.id))
+                .components(vec![]),
+        )
+        .await?;
+
+    Ok(())
+}
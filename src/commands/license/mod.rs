@@ -1,10 +1,46 @@
 mod settings;
 pub use settings::*;
+mod auto_publish_users;
+pub use auto_publish_users::*;
+mod bulk_backup_permission;
+pub use bulk_backup_permission::*;
+mod cleanup_inactive_settings;
+pub use cleanup_inactive_settings::*;
+mod compare_licenses;
+pub use compare_licenses::*;
+mod compare_published;
+pub use compare_published::*;
 mod create_license;
 pub use create_license::*;
 mod create_license_interactive;
 pub use create_license_interactive::*;
+mod force_publish_license;
+pub use force_publish_license::*;
+mod license_detail;
+pub use license_detail::*;
+mod license_help;
+pub use license_help::*;
 mod license_manager;
 pub use license_manager::*;
+mod migrate_user_licenses;
+pub use migrate_user_licenses::*;
+mod my_posts;
+pub use my_posts::*;
 mod publish_license;
 pub use publish_license::*;
+mod refresh_license_author;
+pub use refresh_license_author::*;
+mod reguide;
+pub use reguide::*;
+mod reset_usage;
+pub use reset_usage::*;
+mod search_licenses;
+pub use search_licenses::*;
+mod set_backup_permission;
+pub use set_backup_permission::*;
+mod set_obsolete_action;
+pub use set_obsolete_action::*;
+mod sort_licenses;
+pub use sort_licenses::*;
+mod transfer_license;
+pub use transfer_license::*;
@@ -0,0 +1,309 @@
+use std::{collections::HashMap, time::Duration};
+
+use poise::{CreateReply, command};
+use serenity::all::*;
+use tracing::{info, warn};
+
+use crate::{
+    commands::Context,
+    error::BotError,
+    services::{
+        post_sweeper::is_unknown_channel,
+        published_posts::{PublishedPost, is_backup_overridden},
+    },
+    utils::Paginator,
+};
+
+/// 每页显示的帖子数
+const POSTS_PER_PAGE: usize = 10;
+/// 列表面板的交互超时时间（秒）
+const MY_POSTS_TIMEOUT_SECS: u64 = 300;
+/// 通过 API 解析帖子名时两次请求之间的间隔，避免触发速率限制
+const THREAD_RESOLVE_INTERVAL: Duration = Duration::from_millis(250);
+/// 清理已删除帖子记录按钮的 custom_id
+const CLEANUP_DELETED_ID: &str = "my_posts_cleanup_deleted";
+
+/// 帖子的解析结果
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum ThreadStatus {
+    /// 帖子存在
+    Found { name: String, guild_id: GuildId },
+    /// 帖子已被删除
+    Deleted,
+    /// 暂时无法访问（如无权限、网络问题），不能确认帖子已删除
+    Unavailable,
+}
+
+/// 通过 API 获取帖子名，区分已删除和暂时无法访问的帖子
+async fn resolve_thread(http: &Http, thread_id: ChannelId) -> ThreadStatus {
+    match http.get_channel(thread_id).await {
+        Ok(Channel::Guild(thread)) => ThreadStatus::Found {
+            name: thread.name,
+            guild_id: thread.guild_id,
+        },
+        Ok(_) => ThreadStatus::Unavailable,
+        Err(e) if is_unknown_channel(&e) => ThreadStatus::Deleted,
+        Err(e) => {
+            warn!("获取帖子 {} 失败: {}", thread_id, e);
+            ThreadStatus::Unavailable
+        }
+    }
+}
+
+/// 解析当前页中尚未解析过的帖子，结果在翻页间复用
+async fn resolve_page(
+    ctx: Context<'_>,
+    posts: &[PublishedPost],
+    paginator: &Paginator,
+    statuses: &mut HashMap<i64, ThreadStatus>,
+) {
+    for post in paginator.page_items(posts, POSTS_PER_PAGE) {
+        if statuses.contains_key(&post.thread_id) {
+            continue;
+        }
+        tokio::time::sleep(THREAD_RESOLVE_INTERVAL).await;
+        let status = resolve_thread(ctx.http(), ChannelId::new(post.thread_id as u64)).await;
+        statuses.insert(post.thread_id, status);
+    }
+}
+
+/// 生成单个帖子的展示行：跳转链接、备份权限（标注是否覆盖了协议设置）和最后更新时间
+fn format_post_line(index: usize, post: &PublishedPost, status: Option<&ThreadStatus>) -> String {
+    let thread_id = post.thread_id as u64;
+    let title = match status {
+        Some(ThreadStatus::Found { name, guild_id }) => {
+            format!("[{name}](https://discord.com/channels/{guild_id}/{thread_id})")
+        }
+        Some(ThreadStatus::Deleted) => format!("~~`{thread_id}`~~（已删除）"),
+        Some(ThreadStatus::Unavailable) | None => format!("<#{thread_id}>"),
+    };
+    let backup = if post.backup_allowed {
+        "✅ 允许备份"
+    } else {
+        "❌ 不允许备份"
+    };
+    let overridden = if is_backup_overridden(post) {
+        "（已覆盖）"
+    } else {
+        ""
+    };
+    format!(
+        "{index}. {title} — {backup}{overridden} · <t:{}:R>",
+        post.updated_at.timestamp()
+    )
+}
+
+/// 已确认删除的帖子
+fn deleted_thread_ids(statuses: &HashMap<i64, ThreadStatus>) -> Vec<i64> {
+    statuses
+        .iter()
+        .filter(|(_, status)| **status == ThreadStatus::Deleted)
+        .map(|(thread_id, _)| *thread_id)
+        .collect()
+}
+
+fn create_my_posts_embed(
+    posts: &[PublishedPost],
+    statuses: &HashMap<i64, ThreadStatus>,
+    paginator: &Paginator,
+    total: u64,
+) -> CreateEmbed {
+    let offset = paginator.page() * POSTS_PER_PAGE;
+    let lines: Vec<String> = paginator
+        .page_items(posts, POSTS_PER_PAGE)
+        .iter()
+        .enumerate()
+        .map(|(index, post)| {
+            format_post_line(offset + index + 1, post, statuses.get(&post.thread_id))
+        })
+        .collect();
+
+    let description = if lines.is_empty() {
+        "您还没有在任何帖子中发布过协议。".to_string()
+    } else {
+        lines.join("\n")
+    };
+
+    CreateEmbed::new()
+        .title("📚 我的帖子")
+        .description(description)
+        .colour(Colour::BLUE)
+        .footer(CreateEmbedFooter::new(format!(
+            "共 {total} 个帖子 | {}/{}",
+            paginator.page() + 1,
+            paginator.total()
+        )))
+}
+
+fn create_my_posts_components(
+    paginator: &Paginator,
+    statuses: &HashMap<i64, ThreadStatus>,
+) -> Vec<CreateActionRow> {
+    let mut rows = Vec::new();
+    if paginator.total() > 1 {
+        rows.push(paginator.nav_row());
+    }
+    let deleted = deleted_thread_ids(statuses).len();
+    if deleted > 0 {
+        rows.push(CreateActionRow::Buttons(vec![
+            CreateButton::new(CLEANUP_DELETED_ID)
+                .label(format!("🗑️ 清理已删除帖子的记录（{deleted}）"))
+                .style(ButtonStyle::Danger),
+        ]));
+    }
+    rows
+}
+
+#[command(
+    slash_command,
+    guild_only,
+    name_localized("zh-CN", "我的帖子"),
+    description_localized("zh-CN", "查看您发布过协议的所有帖子"),
+    ephemeral
+)]
+/// List the threads where you have published a license
+pub async fn my_posts(ctx: Context<'_>) -> Result<(), BotError> {
+    ctx.defer_ephemeral().await?;
+
+    let posts_service = ctx.data().db().published_posts();
+    let mut posts = posts_service.get_user_posts(ctx.author().id).await?;
+    let mut total = posts_service.get_user_post_count(ctx.author().id).await?;
+    let mut statuses = HashMap::new();
+    let mut paginator = Paginator::for_items(posts.len(), POSTS_PER_PAGE);
+    resolve_page(ctx, &posts, &paginator, &mut statuses).await;
+
+    let components = create_my_posts_components(&paginator, &statuses);
+    let has_components = !components.is_empty();
+    let handler = ctx
+        .send(
+            CreateReply::default()
+                .embed(create_my_posts_embed(&posts, &statuses, &paginator, total))
+                .components(components)
+                .ephemeral(true),
+        )
+        .await?;
+    if !has_components {
+        return Ok(());
+    }
+    let message = handler.message().await?;
+
+    while let Some(interaction) = message
+        .await_component_interaction(ctx)
+        .author_id(ctx.author().id)
+        .timeout(Duration::from_secs(MY_POSTS_TIMEOUT_SECS))
+        .await
+    {
+        let mut content = String::new();
+        if interaction.data.custom_id == CLEANUP_DELETED_ID {
+            if ctx.data().is_maintenance_mode() {
+                content = "🛠️ 机器人维护中，暂时无法清理记录，请稍后再试。".to_string();
+            } else {
+                let mut removed = 0;
+                for thread_id in deleted_thread_ids(&statuses) {
+                    if posts_service
+                        .delete(ChannelId::new(thread_id as u64))
+                        .await?
+                    {
+                        removed += 1;
+                    }
+                    statuses.remove(&thread_id);
+                }
+                info!(
+                    "用户 {} 清理了 {} 条已删除帖子的发布记录",
+                    ctx.author().id,
+                    removed
+                );
+
+                posts = posts_service.get_user_posts(ctx.author().id).await?;
+                total = posts_service.get_user_post_count(ctx.author().id).await?;
+                let page = paginator.page();
+                paginator = Paginator::for_items(posts.len(), POSTS_PER_PAGE);
+                paginator.go_to(page.min(paginator.total() - 1));
+                content = format!("✅ 已清理 {removed} 条已删除帖子的记录。");
+            }
+        } else {
+            paginator.handle_action(&interaction.data.custom_id);
+        }
+
+        // 解析帖子名可能较慢，先确认交互再更新消息
+        interaction
+            .create_response(ctx, CreateInteractionResponse::Acknowledge)
+            .await?;
+        resolve_page(ctx, &posts, &paginator, &mut statuses).await;
+        handler
+            .edit(
+                ctx,
+                CreateReply::default()
+                    .content(content)
+                    .embed(create_my_posts_embed(&posts, &statuses, &paginator, total))
+                    .components(create_my_posts_components(&paginator, &statuses)),
+            )
+            .await?;
+    }
+
+    // 超时后移除按钮
+    handler
+        .edit(
+            ctx,
+            CreateReply::default()
+                .embed(create_my_posts_embed(&posts, &statuses, &paginator, total))
+                .components(vec![]),
+        )
+        .await?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{TimeZone, Utc};
+
+    use super::*;
+
+    fn post(thread_id: i64, backup_allowed: bool) -> PublishedPost {
+        PublishedPost {
+            thread_id,
+            message_id: thread_id + 1,
+            user_id: 1,
+            backup_allowed,
+            updated_at: Utc.timestamp_opt(1_700_000_000, 0).unwrap(),
+            obsolete_action: None,
+            system_license_name: None,
+            license_backup_allowed: None,
+        }
+    }
+
+    #[test]
+    fn test_format_post_line() {
+        let found = ThreadStatus::Found {
+            name: "我的作品".to_string(),
+            guild_id: GuildId::new(7),
+        };
+        assert_eq!(
+            format_post_line(1, &post(100, true), Some(&found)),
+            "1. [我的作品](https://discord.com/channels/7/100) — ✅ 允许备份 · <t:1700000000:R>"
+        );
+        assert_eq!(
+            format_post_line(2, &post(200, false), Some(&ThreadStatus::Deleted)),
+            "2. ~~`200`~~（已删除） — ❌ 不允许备份 · <t:1700000000:R>"
+        );
+        // 备份权限与协议声明不同时标注已覆盖
+        let overridden = PublishedPost {
+            license_backup_allowed: Some(true),
+            ..post(400, false)
+        };
+        assert_eq!(
+            format_post_line(4, &overridden, Some(&ThreadStatus::Deleted)),
+            "4. ~~`400`~~（已删除） — ❌ 不允许备份（已覆盖） · <t:1700000000:R>"
+        );
+        // 无法访问或尚未解析的帖子退回到频道提及
+        assert!(format_post_line(3, &post(300, true), None).starts_with("3. <#300> — "));
+
+        let statuses = HashMap::from([
+            (100, found),
+            (200, ThreadStatus::Deleted),
+            (300, ThreadStatus::Unavailable),
+        ]);
+        assert_eq!(deleted_thread_ids(&statuses), vec![200]);
+    }
+}
@@ -3,13 +3,93 @@ use serenity::all::*;
 use tracing::warn;
 
 use crate::{
-    commands::Context, error::BotError, services::license::LicensePublishService,
-    utils::LicenseEmbedBuilder,
+    commands::Context,
+    error::BotError,
+    services::license::{LicensePublishService, UserLicense},
+    utils::{LicenseEmbedBuilder, Locale, fuzzy_score},
 };
 
+/// 运行前检查机器人在当前帖子中是否拥有发布协议所需的权限
+///
+/// 缺少权限时直接提示缺少哪些权限，避免用户选完协议后才发布失败
+async fn check_bot_permissions(ctx: Context<'_>) -> Result<bool, BotError> {
+    // 不在服务器频道中时交由命令本身提示
+    let Some(thread) = ctx.channel_id().to_channel(&ctx).await?.guild() else {
+        return Ok(true);
+    };
+    match LicensePublishService::ensure_bot_permissions(ctx.http(), &thread).await {
+        Ok(()) => Ok(true),
+        Err(err @ BotError::AuthorizationError { .. }) => {
+            ctx.send(
+                CreateReply::default()
+                    .content(format!("❌ {}", err.user_message()))
+                    .ephemeral(true),
+            )
+            .await?;
+            Ok(false)
+        }
+        Err(err) => Err(err),
+    }
+}
+
+/// 帖子所有权的判定结果
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Ownership {
+    Owner,
+    NotOwner,
+    /// 不知道帖子创建者，需要重新获取后再判断
+    Unknown,
+}
+
+fn check_ownership(owner_id: Option<UserId>, user_id: UserId) -> Ownership {
+    match owner_id {
+        Some(owner_id) if owner_id == user_id => Ownership::Owner,
+        Some(_) => Ownership::NotOwner,
+        None => Ownership::Unknown,
+    }
+}
+
+/// 发布协议失败时给用户的回复
+///
+/// 帖子已归档、锁定或机器人缺少权限等可处理的问题直接提示，其他错误原样返回
+pub(super) fn publish_error_reply(err: BotError) -> Result<CreateReply, BotError> {
+    match err {
+        BotError::ValidationError { .. } | BotError::AuthorizationError { .. } => {
+            Ok(CreateReply::default().content(format!("❌ {}", err.user_message())))
+        }
+        err => Err(err),
+    }
+}
+
+/// 缓存中缺少创建者时获取帖子创建者
+///
+/// 先重新获取帖子，仍然没有创建者时以首条消息的作者为准
+pub(super) async fn fetch_thread_owner(http: &Http, thread: &GuildChannel) -> Option<UserId> {
+    match http.get_channel(thread.id).await {
+        Ok(Channel::Guild(fresh)) if fresh.owner_id.is_some() => return fresh.owner_id,
+        Ok(_) => {}
+        Err(e) => warn!("重新获取帖子 {} 失败: {}", thread.id, e),
+    }
+
+    // 最早的消息即帖子的首条消息
+    match thread
+        .id
+        .messages(http, GetMessages::new().after(MessageId::new(1)).limit(1))
+        .await
+    {
+        Ok(messages) => messages.first().map(|message| message.author.id),
+        Err(e) => {
+            warn!("获取帖子 {} 的首条消息失败: {}", thread.id, e);
+            None
+        }
+    }
+}
+
 #[command(
     slash_command,
+    custom_data = "crate::commands::WriteCommand",
     user_cooldown = 10,
+    check = "check_bot_permissions",
     name_localized("zh-CN", "发布协议"),
     description_localized("zh-CN", "在当前帖子发布协议"),
     ephemeral
@@ -25,9 +105,12 @@ pub async fn publish_license(
     #[name_localized("zh-CN", "备份权限")]
     #[description_localized("zh-CN", "覆盖协议中的备份权限设置（可选）")]
     backup_override: Option<bool>,
-) -> Result<(), BotError> {
-    let db = ctx.data().db.clone();
 
+    #[name_localized("zh-CN", "第二协议")]
+    #[description_localized("zh-CN", "同时提供的第二个协议，使用者可任选其一（可选）")]
+    #[autocomplete = "autocomplete_license"]
+    second_license_id: Option<String>,
+) -> Result<(), BotError> {
     // 1. 前置安全检查
     // 检查是否在帖子中
     let channel = ctx.channel_id().to_channel(&ctx).await?;
@@ -52,73 +135,59 @@ pub async fn publish_license(
     // 获取thread信息
     let thread = channel.guild().unwrap();
 
-    // 检查是否是帖子创建者
-    if thread.owner_id != Some(ctx.author().id) {
-        ctx.send(
-            CreateReply::default()
-                .content("您只能为自己创建的帖子添加授权协议。")
-                .ephemeral(true),
-        )
-        .await?;
+    // 检查是否是帖子创建者，缓存中缺少创建者时重新获取
+    let mut ownership = check_ownership(thread.owner_id, ctx.author().id);
+    if ownership == Ownership::Unknown {
+        let owner_id = fetch_thread_owner(ctx.http(), &thread).await;
+        ownership = check_ownership(owner_id, ctx.author().id);
+    }
+    if ownership != Ownership::Owner {
+        let content = if ownership == Ownership::Unknown {
+            "无法确认该帖子的创建者，请稍后再试。"
+        } else {
+            "您只能为自己创建的帖子添加授权协议。"
+        };
+        ctx.send(CreateReply::default().content(content).ephemeral(true))
+            .await?;
         return Ok(());
     }
 
     // 2. 获取选择的协议
-    let license = if let Some(user_id_str) = license_id.strip_prefix("user:") {
-        // 用户协议
-        let user_id = match user_id_str.parse::<i32>() {
-            Ok(id) => id,
-            Err(_) => {
-                ctx.send(
-                    CreateReply::default()
-                        .content("无效的协议ID格式。")
-                        .ephemeral(true),
-                )
+    let license = match resolve_license(ctx, &license_id).await? {
+        Ok(license) => license,
+        Err(message) => {
+            ctx.send(CreateReply::default().content(message).ephemeral(true))
                 .await?;
-                return Ok(());
-            }
-        };
-        let Some(license) = db.license().get_license(user_id, ctx.author().id).await? else {
-            ctx.send(
-                CreateReply::default()
-                    .content("未找到该协议。")
-                    .ephemeral(true),
-            )
-            .await?;
             return Ok(());
-        };
-        license
-    } else if let Some(system_name) = license_id.strip_prefix("system:") {
-        // 系统协议
-        let system_licenses = ctx.data().system_license_cache.get_all().await;
-        let Some(system_license) = system_licenses
-            .iter()
-            .find(|l| l.license_name == system_name)
-        else {
+        }
+    };
+
+    // 双重授权时获取第二个协议
+    let secondary = match second_license_id.as_deref() {
+        Some(second_id) if second_id == license_id => {
             ctx.send(
                 CreateReply::default()
-                    .content("未找到该系统协议。")
+                    .content("两个协议不能相同。")
                     .ephemeral(true),
             )
             .await?;
             return Ok(());
-        };
-
-        // 将系统协议转换为数据库模型格式
-        // 使用一个虚拟的ID，因为这是系统协议
-        system_license.to_user_license(ctx.author().id, -1)
-    } else {
-        ctx.send(
-            CreateReply::default()
-                .content("无效的协议格式。")
-                .ephemeral(true),
-        )
-        .await?;
-        return Ok(());
+        }
+        Some(second_id) => match resolve_license(ctx, second_id).await? {
+            Ok(license) => Some(license),
+            Err(message) => {
+                ctx.send(CreateReply::default().content(message).ephemeral(true))
+                    .await?;
+                return Ok(());
+            }
+        },
+        None => None,
     };
 
     // 应用备份权限覆盖
-    let backup_allowed = backup_override.unwrap_or(license.allow_backup);
+    // 双重授权时可任选其一，任一协议允许备份即视为允许
+    let backup_allowed = backup_override
+        .unwrap_or(license.allow_backup || secondary.as_ref().is_some_and(|l| l.allow_backup));
 
     // 3. 生成预览embed
     let display_name = ctx
@@ -126,8 +195,29 @@ pub async fn publish_license(
         .await
         .map(|m| m.display_name().to_string())
         .unwrap_or_else(|| ctx.author().name.to_string());
-    let preview_embed =
-        LicenseEmbedBuilder::create_license_embed(&license, backup_allowed, &display_name);
+    let locale = ctx.locale().map(Locale::from_discord).unwrap_or_default();
+    let cfg = ctx.data().cfg().load_full();
+    let community_rules_url = cfg.community_rules_url.as_deref();
+    let branding = cfg.branding_for(ctx.guild_id());
+    let preview_embed = match &secondary {
+        Some(secondary) => LicenseEmbedBuilder::create_dual_license_embed(
+            &license,
+            secondary,
+            backup_allowed,
+            &display_name,
+            locale,
+            community_rules_url,
+            branding,
+        ),
+        None => LicenseEmbedBuilder::create_license_embed(
+            &license,
+            backup_allowed,
+            &display_name,
+            locale,
+            community_rules_url,
+            branding,
+        ),
+    };
 
     // 创建按钮
     let publish_btn = CreateButton::new("publish_license")
@@ -166,15 +256,39 @@ pub async fn publish_license(
                 .await?;
 
             // 使用统一的发布服务
-            LicensePublishService::publish(
-                ctx.http(),
-                ctx.data(),
-                &thread,
-                &license,
-                backup_allowed,
-                ctx.author().to_owned(),
-            )
-            .await?;
+            let result = match &secondary {
+                Some(secondary) => LicensePublishService::publish_dual(
+                    ctx.http(),
+                    ctx.data(),
+                    &thread,
+                    &license,
+                    secondary,
+                    backup_allowed,
+                    ctx.author().to_owned(),
+                    ctx.author().id,
+                )
+                .await
+                .map(|_| format!("{} / {}", license.license_name, secondary.license_name)),
+                None => LicensePublishService::publish(
+                    ctx.http(),
+                    ctx.data(),
+                    &thread,
+                    &license,
+                    backup_allowed,
+                    ctx.author().to_owned(),
+                    ctx.author().id,
+                )
+                .await
+                .map(|_| license.license_name.clone()),
+            };
+            let published_name = match result {
+                Ok(name) => name,
+                Err(err) => {
+                    let reply = publish_error_reply(err)?;
+                    handler.edit(ctx, reply.components(vec![])).await?;
+                    return Ok(());
+                }
+            };
 
             // 更新回复
             handler
@@ -182,7 +296,7 @@ pub async fn publish_license(
                     ctx,
                     CreateReply::default()
                         .embed(LicenseEmbedBuilder::create_license_published_embed(
-                            &license.license_name,
+                            &published_name,
                         ))
                         .components(vec![]),
                 )
@@ -208,8 +322,54 @@ pub async fn publish_license(
     Ok(())
 }
 
+/// 根据自动补全的值获取协议，失败时返回提示文本
+pub(super) async fn resolve_license(
+    ctx: Context<'_>,
+    license_id: &str,
+) -> Result<Result<UserLicense, &'static str>, BotError> {
+    if let Some(user_id_str) = license_id.strip_prefix("user:") {
+        // 用户协议
+        let Ok(user_id) = user_id_str.parse::<i32>() else {
+            return Ok(Err("无效的协议ID格式。"));
+        };
+        Ok(ctx
+            .data()
+            .db
+            .license()
+            .get_license(user_id, ctx.author().id)
+            .await?
+            .ok_or("未找到该协议。"))
+    } else if let Some(system_name) = license_id.strip_prefix("system:") {
+        // 系统协议
+        let Some(system_license) = ctx
+            .data()
+            .system_license_cache
+            .get_by_name(system_name)
+            .await
+        else {
+            return Ok(Err("未找到该系统协议。"));
+        };
+        if !system_license.can_publish(ctx.author().id, &author_roles(ctx).await) {
+            return Ok(Err("您没有发布该系统协议的权限。"));
+        }
+        // 将系统协议转换为数据库模型格式
+        // 使用一个虚拟的ID，因为这是系统协议
+        Ok(Ok(system_license.to_user_license(ctx.author().id, -1)))
+    } else {
+        Ok(Err("无效的协议格式。"))
+    }
+}
+
+/// 获取命令使用者在当前服务器的身份组
+async fn author_roles(ctx: Context<'_>) -> Vec<RoleId> {
+    ctx.author_member()
+        .await
+        .map(|m| m.roles.clone())
+        .unwrap_or_default()
+}
+
 // 自动补全函数
-async fn autocomplete_license(
+pub(super) async fn autocomplete_license(
     ctx: Context<'_>,
     partial: &str,
 ) -> impl Iterator<Item = poise::serenity_prelude::AutocompleteChoice> {
@@ -221,22 +381,74 @@ async fn autocomplete_license(
         .get_user_licenses(ctx.author().id)
         .await
         .unwrap_or_default();
-    let system_licenses = ctx.data().system_license_cache.get_all().await;
-
-    // 组合并过滤
-    user_licenses
+    // 只列出有权发布的系统协议
+    let roles = author_roles(ctx).await;
+    let system_licenses: Vec<_> = ctx
+        .data()
+        .system_license_cache
+        .get_all()
+        .await
         .into_iter()
-        .map(|l| {
-            let name = l.license_name.clone();
-            let value = format!("user:{}", l.id);
-            (name, value)
+        .filter(|l| l.can_publish(ctx.author().id, &roles))
+        .collect();
+
+    // 按协议名模糊匹配，用户协议和系统协议按相关度混合排序
+    let mut matches: Vec<_> = user_licenses
+        .iter()
+        .filter_map(|l| {
+            Some((
+                fuzzy_score(&l.license_name, partial)?,
+                l.license_name.clone(),
+                format!("user:{}", l.id),
+            ))
         })
-        .chain(system_licenses.into_iter().map(|l| {
-            let display_name = format!("{} (系统)", l.license_name);
-            let value = format!("system:{}", l.license_name);
-            (display_name, value)
+        .chain(system_licenses.iter().filter_map(|l| {
+            Some((
+                fuzzy_score(&l.license_name, partial)?,
+                format!("{} (系统)", l.license_name),
+                format!("system:{}", l.license_name),
+            ))
         }))
-        .filter(|(name, _)| name.to_lowercase().contains(&partial.to_lowercase()))
+        .collect();
+    // 稳定排序，同分时保持用户协议在前
+    matches.sort_by_key(|(score, _, _)| std::cmp::Reverse(*score));
+
+    matches
+        .into_iter()
         .take(25)
-        .map(|(name, value)| poise::serenity_prelude::AutocompleteChoice::new(name, value))
+        .map(|(_, name, value)| poise::serenity_prelude::AutocompleteChoice::new(name, value))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_check_ownership() {
+        let user = UserId::new(1);
+        assert_eq!(check_ownership(Some(user), user), Ownership::Owner);
+        assert_eq!(
+            check_ownership(Some(UserId::new(2)), user),
+            Ownership::NotOwner
+        );
+        // 缺少创建者时不直接拒绝，而是重新获取
+        assert_eq!(check_ownership(None, user), Ownership::Unknown);
+    }
+
+    #[test]
+    fn test_publish_error_reply() {
+        let reply = publish_error_reply(BotError::ValidationError {
+            message: "帖子已锁定".to_string(),
+            loc: snafu::location!(),
+        })
+        .unwrap();
+        assert_eq!(reply.content.as_deref(), Some("❌ 帖子已锁定"));
+
+        // 其他错误交给全局错误处理
+        let result = publish_error_reply(BotError::GenericError {
+            message: "失败".to_string(),
+            source: None,
+        });
+        assert!(matches!(result, Err(BotError::GenericError { .. })));
+    }
 }
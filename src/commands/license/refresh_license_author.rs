@@ -0,0 +1,97 @@
+use poise::command;
+use serenity::all::*;
+
+use crate::{
+    commands::{Context, check_admin},
+    error::BotError,
+    utils::{LicenseEmbedBuilder, refresh_member_display_name},
+};
+
+#[command(
+    slash_command,
+    custom_data = "crate::commands::WriteCommand",
+    guild_only,
+    name_localized("zh-CN", "刷新协议署名"),
+    description_localized("zh-CN", "将当前帖子已发布协议的署名更新为作者现在的显示名"),
+    ephemeral
+)]
+/// Update the author name on the license published in the current thread
+pub async fn refresh_license_author(ctx: Context<'_>) -> Result<(), BotError> {
+    let thread = match ctx.channel_id().to_channel(&ctx).await?.guild() {
+        Some(
+            thread @ GuildChannel {
+                kind:
+                    ChannelType::PublicThread | ChannelType::PrivateThread | ChannelType::NewsThread,
+                ..
+            },
+        ) => thread,
+        _ => {
+            ctx.say("请在帖子中使用本命令。").await?;
+            return Ok(());
+        }
+    };
+
+    let Some(post) = ctx
+        .data()
+        .db()
+        .published_posts()
+        .get_by_thread(thread.id)
+        .await?
+    else {
+        ctx.say("该帖子尚未发布协议。").await?;
+        return Ok(());
+    };
+
+    let author_id = UserId::new(post.user_id as u64);
+    if author_id != ctx.author().id && !check_admin(ctx).await? {
+        ctx.say("只有协议作者或管理员可以刷新署名。").await?;
+        return Ok(());
+    }
+
+    let Ok(mut message) = ctx
+        .http()
+        .get_message(thread.id, MessageId::new(post.message_id as u64))
+        .await
+    else {
+        ctx.say("找不到已发布的协议消息，可能已被删除。").await?;
+        return Ok(());
+    };
+
+    let Some(original) = message.embeds.first() else {
+        ctx.say("无法识别协议消息的署名，请重新发布协议。").await?;
+        return Ok(());
+    };
+    let current_footer = original
+        .footer
+        .as_ref()
+        .map_or("", |footer| footer.text.as_str());
+    let display_name = refresh_member_display_name(ctx.http(), thread.guild_id, author_id).await;
+    let cfg = ctx.data().cfg().load();
+    let Some(footer) = LicenseEmbedBuilder::rename_footer_author(
+        current_footer,
+        &display_name,
+        cfg.branding_for(Some(thread.guild_id)),
+    ) else {
+        ctx.say("无法识别协议消息的署名，请重新发布协议。").await?;
+        return Ok(());
+    };
+    if footer == current_footer {
+        ctx.say(format!("ℹ️ 协议署名已是 **{display_name}**，无需更新。"))
+            .await?;
+        return Ok(());
+    }
+
+    // 只替换页脚，协议内容保持不变
+    let refreshed = CreateEmbed::from(original.clone()).footer(CreateEmbedFooter::new(footer));
+    message
+        .edit(ctx.http(), EditMessage::new().embed(refreshed))
+        .await?;
+    tracing::info!(
+        "License author name refreshed in thread {} by {}",
+        thread.id,
+        ctx.author().id
+    );
+    ctx.say(format!("✅ 已将协议署名更新为 **{display_name}**。"))
+        .await?;
+    Ok(())
+}
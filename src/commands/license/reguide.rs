@@ -0,0 +1,146 @@
+use poise::command;
+use serenity::all::*;
+
+use crate::{
+    commands::{Context, check_admin},
+    error::BotError,
+    handlers::reguide_thread,
+    services::user_settings::UserSettings,
+};
+
+/// 重新引导的判断结果
+#[derive(Debug, PartialEq, Eq)]
+enum ReguideDecision {
+    /// 可以重新显示引导
+    Eligible,
+    /// 作者已启用自动发布，无需引导
+    AlreadyEnabled,
+    /// 帖子已发布协议
+    AlreadyPublished,
+    /// 作者已主动关闭自动发布，只有作者本人可以重新引导
+    OptedOut,
+}
+
+/// 判断是否可以为帖子作者重新显示引导
+///
+/// 引导只面向尚未启用自动发布的作者；作者主动关闭过的，不由他人再次打扰。
+fn reguide_decision(
+    settings: Option<&UserSettings>,
+    has_published: bool,
+    invoked_by_owner: bool,
+) -> ReguideDecision {
+    if settings.is_some_and(|s| s.auto_publish_enabled) {
+        ReguideDecision::AlreadyEnabled
+    } else if has_published {
+        ReguideDecision::AlreadyPublished
+    } else if settings.is_some() && !invoked_by_owner {
+        ReguideDecision::OptedOut
+    } else {
+        ReguideDecision::Eligible
+    }
+}
+
+#[command(
+    slash_command,
+    custom_data = "crate::commands::WriteCommand",
+    guild_only,
+    name_localized("zh-CN", "重新引导"),
+    description_localized("zh-CN", "在帖子中重新显示自动发布引导"),
+    ephemeral
+)]
+/// Show the auto-publish guidance again for the owner of the current thread
+pub async fn reguide(ctx: Context<'_>) -> Result<(), BotError> {
+    let thread = match ctx.channel_id().to_channel(&ctx).await?.guild() {
+        Some(
+            thread @ GuildChannel {
+                kind:
+                    ChannelType::PublicThread | ChannelType::PrivateThread | ChannelType::NewsThread,
+                ..
+            },
+        ) => thread,
+        _ => {
+            ctx.say("请在帖子中使用本命令。").await?;
+            return Ok(());
+        }
+    };
+    let Some(owner_id) = thread.owner_id else {
+        ctx.say("无法确定帖子作者。").await?;
+        return Ok(());
+    };
+
+    let invoked_by_owner = owner_id == ctx.author().id;
+    if !invoked_by_owner && !check_admin(ctx).await? {
+        ctx.say("只有帖子作者或管理员可以重新引导。").await?;
+        return Ok(());
+    }
+
+    let db = ctx.data().db();
+    let settings = db.user_settings().get(owner_id).await?;
+    let has_published = db.published_posts().has_published_post(thread.id).await?;
+    let message = match reguide_decision(settings.as_ref(), has_published, invoked_by_owner) {
+        ReguideDecision::Eligible => None,
+        ReguideDecision::AlreadyEnabled => {
+            Some("帖子作者已启用自动发布，可使用 `/自动发布设置` 修改设置。")
+        }
+        ReguideDecision::AlreadyPublished => Some("此帖子已发布协议。"),
+        ReguideDecision::OptedOut => Some("帖子作者已关闭自动发布，只有作者本人可以重新引导。"),
+    };
+    if let Some(message) = message {
+        ctx.say(message).await?;
+        return Ok(());
+    }
+
+    ctx.say("✅ 已在帖子中重新发送引导。").await?;
+    reguide_thread(ctx.serenity_context(), &thread, ctx.data(), owner_id).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn settings(auto_publish_enabled: bool) -> UserSettings {
+        UserSettings {
+            user_id: 1,
+            auto_publish_enabled,
+            skip_auto_publish_confirmation: false,
+            default_user_license_id: None,
+            default_system_license_name: None,
+            default_system_license_backup: None,
+        }
+    }
+
+    #[test]
+    fn test_reguide_decision() {
+        // 错过引导的新用户，作者和管理员都可以重新引导
+        assert_eq!(
+            reguide_decision(None, false, true),
+            ReguideDecision::Eligible
+        );
+        assert_eq!(
+            reguide_decision(None, false, false),
+            ReguideDecision::Eligible
+        );
+
+        // 主动关闭的作者只能自己重新引导
+        let disabled = settings(false);
+        assert_eq!(
+            reguide_decision(Some(&disabled), false, true),
+            ReguideDecision::Eligible
+        );
+        assert_eq!(
+            reguide_decision(Some(&disabled), false, false),
+            ReguideDecision::OptedOut
+        );
+
+        // 已启用或已发布的不再引导
+        let enabled = settings(true);
+        assert_eq!(
+            reguide_decision(Some(&enabled), false, true),
+            ReguideDecision::AlreadyEnabled
+        );
+        assert_eq!(
+            reguide_decision(None, true, true),
+            ReguideDecision::AlreadyPublished
+        );
+    }
+}
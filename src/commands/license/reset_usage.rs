@@ -0,0 +1,84 @@
+use poise::{CreateReply, command};
+use serenity::all::*;
+
+use crate::{
+    commands::{Context, check_admin},
+    error::BotError,
+    services::audit_log::{AuditAction, send_audit_log},
+};
+
+#[command(
+    slash_command,
+    custom_data = "crate::commands::WriteCommand",
+    guild_only,
+    default_member_permissions = "ADMINISTRATOR",
+    check = "check_admin",
+    name_localized("zh-CN", "重置使用次数"),
+    description_localized("zh-CN", "将用户协议的使用次数清零，协议本身不受影响"),
+    ephemeral
+)]
+/// Reset the usage count of a user's licenses without deleting them
+pub async fn reset_usage(
+    ctx: Context<'_>,
+    #[name_localized("zh-CN", "用户")]
+    #[description_localized("zh-CN", "协议所属的用户")]
+    user: User,
+    #[name_localized("zh-CN", "协议名称")]
+    #[description_localized("zh-CN", "只重置该名称的协议，不填则重置该用户的所有协议")]
+    license_name: Option<String>,
+) -> Result<(), BotError> {
+    let license_service = ctx.data().db().license();
+
+    let (content, target) = match license_name.as_deref().map(str::trim) {
+        Some(name) => {
+            let licenses = license_service.get_user_licenses(user.id).await?;
+            let Some(license) = licenses.iter().find(|l| l.license_name == name) else {
+                ctx.send(
+                    CreateReply::default()
+                        .content(format!("❌ {} 没有名为「{name}」的协议。", user.mention()))
+                        .ephemeral(true),
+                )
+                .await?;
+                return Ok(());
+            };
+            license_service.reset_usage(license.id, user.id).await?;
+            (
+                format!(
+                    "✅ 已将 {} 的协议「{name}」的使用次数清零（原为 {} 次）。",
+                    user.mention(),
+                    license.usage_count
+                ),
+                format!("<@{}> 的协议「{name}」的使用次数", user.id),
+            )
+        }
+        None => {
+            let reset = license_service.reset_all_usage(user.id).await?;
+            (
+                format!(
+                    "✅ 已将 {} 的 {reset} 个协议的使用次数清零。",
+                    user.mention()
+                ),
+                format!("<@{}> 所有协议的使用次数", user.id),
+            )
+        }
+    };
+
+    tracing::info!(
+        "License usage reset by {} for user {}: {:?}",
+        ctx.author().id,
+        user.id,
+        license_name
+    );
+    send_audit_log(
+        ctx.http(),
+        &ctx.data().cfg().load(),
+        ctx.author().id,
+        AuditAction::SettingChanged,
+        format!("清零了 {target}"),
+    )
+    .await;
+
+    ctx.send(CreateReply::default().content(content).ephemeral(true))
+        .await?;
+    Ok(())
+}
@@ -0,0 +1,129 @@
+use std::time::Duration;
+
+use poise::{CreateReply, command};
+use serenity::all::*;
+
+use crate::{
+    commands::{Context, check_admin},
+    error::BotError,
+    services::license::UserLicense,
+    utils::Paginator,
+};
+
+/// 每页显示的协议数
+const LICENSES_PER_PAGE: usize = 10;
+/// 搜索结果面板的交互超时时间（秒）
+const SEARCH_TIMEOUT_SECS: u64 = 300;
+
+/// 生成单个协议的展示行：所有者、协议ID、使用次数和创建时间
+fn format_license_line(index: usize, license: &UserLicense) -> String {
+    format!(
+        "{index}. <@{}> (`{}`) — #{} · 使用 {} 次 · <t:{}:R>",
+        license.user_id,
+        license.user_id,
+        license.id,
+        license.usage_count,
+        license.created_at.timestamp()
+    )
+}
+
+fn create_search_embed(name: &str, licenses: &[UserLicense], paginator: &Paginator) -> CreateEmbed {
+    let offset = paginator.page() * LICENSES_PER_PAGE;
+    let lines: Vec<String> = paginator
+        .page_items(licenses, LICENSES_PER_PAGE)
+        .iter()
+        .enumerate()
+        .map(|(index, license)| format_license_line(offset + index + 1, license))
+        .collect();
+
+    let description = if lines.is_empty() {
+        "没有用户创建过该名称的协议。".to_string()
+    } else {
+        lines.join("\n")
+    };
+
+    CreateEmbed::new()
+        .title(format!("🔍 协议搜索：{name}"))
+        .description(description)
+        .colour(Colour::BLUE)
+        .footer(CreateEmbedFooter::new(format!(
+            "共 {} 个协议 | {}/{}",
+            licenses.len(),
+            paginator.page() + 1,
+            paginator.total()
+        )))
+}
+
+fn create_search_components(paginator: &Paginator) -> Vec<CreateActionRow> {
+    if paginator.total() > 1 {
+        vec![paginator.nav_row()]
+    } else {
+        vec![]
+    }
+}
+
+#[command(
+    slash_command,
+    guild_only,
+    default_member_permissions = "ADMINISTRATOR",
+    check = "check_admin",
+    name_localized("zh-CN", "搜索协议"),
+    description_localized("zh-CN", "查找所有创建过指定名称协议的用户"),
+    ephemeral
+)]
+/// Find every user who created a license with the given name
+pub async fn search_licenses(
+    ctx: Context<'_>,
+    #[name_localized("zh-CN", "协议名称")]
+    #[description_localized("zh-CN", "要查找的协议名称（完全匹配）")]
+    #[max_length = 50]
+    license_name: String,
+) -> Result<(), BotError> {
+    let name = license_name.trim();
+    let licenses = ctx.data().db().license().find_by_name(name).await?;
+    let mut paginator = Paginator::for_items(licenses.len(), LICENSES_PER_PAGE);
+
+    let handler = ctx
+        .send(
+            CreateReply::default()
+                .embed(create_search_embed(name, &licenses, &paginator))
+                .components(create_search_components(&paginator))
+                .ephemeral(true),
+        )
+        .await?;
+    if paginator.total() == 1 {
+        return Ok(());
+    }
+    let message = handler.message().await?;
+
+    while let Some(interaction) = message
+        .await_component_interaction(ctx)
+        .author_id(ctx.author().id)
+        .timeout(Duration::from_secs(SEARCH_TIMEOUT_SECS))
+        .await
+    {
+        paginator.handle_action(&interaction.data.custom_id);
+        interaction
+            .create_response(
+                ctx,
+                CreateInteractionResponse::UpdateMessage(
+                    CreateInteractionResponseMessage::new()
+                        .embed(create_search_embed(name, &licenses, &paginator))
+                        .components(create_search_components(&paginator)),
+                ),
+            )
+            .await?;
+    }
+
+    // 超时后移除翻页按钮
+    handler
+        .edit(
+            ctx,
+            CreateReply::default()
+                .embed(create_search_embed(name, &licenses, &paginator))
+                .components(vec![]),
+        )
+        .await?;
+
+    Ok(())
+}
@@ -0,0 +1,149 @@
+use poise::{CreateReply, command};
+use serenity::all::*;
+use tracing::warn;
+
+use crate::{
+    commands::{Context, check_admin},
+    error::BotError,
+    services::license::LicensePublishService,
+};
+
+#[command(
+    slash_command,
+    custom_data = "crate::commands::WriteCommand",
+    guild_only,
+    default_member_permissions = "ADMINISTRATOR",
+    check = "check_admin",
+    name_localized("zh-CN", "设置备份权限"),
+    description_localized("zh-CN", "管理员覆盖当前帖子已发布协议的备份权限"),
+    ephemeral
+)]
+/// Override the backup permission of the license published in the current thread
+pub async fn set_backup_permission(
+    ctx: Context<'_>,
+    #[name_localized("zh-CN", "允许备份")]
+    #[description_localized("zh-CN", "是否允许管理组备份该作品")]
+    backup_allowed: bool,
+) -> Result<(), BotError> {
+    // 检查是否在帖子中
+    let channel = ctx.channel_id().to_channel(&ctx).await?;
+    let Channel::Guild(
+        thread @ GuildChannel {
+            kind: ChannelType::PublicThread | ChannelType::PrivateThread | ChannelType::NewsThread,
+            ..
+        },
+    ) = channel
+    else {
+        ctx.send(
+            CreateReply::default()
+                .content("请在帖子中使用本命令。")
+                .ephemeral(true),
+        )
+        .await?;
+        return Ok(());
+    };
+
+    // 检查帖子是否已发布协议
+    let Some(post) = ctx
+        .data()
+        .db()
+        .published_posts()
+        .get_by_thread(thread.id)
+        .await?
+    else {
+        ctx.send(
+            CreateReply::default()
+                .content("该帖子尚未发布协议。")
+                .ephemeral(true),
+        )
+        .await?;
+        return Ok(());
+    };
+
+    let status_text = |allowed: bool| {
+        if allowed {
+            "✅ 允许"
+        } else {
+            "❌ 不允许"
+        }
+    };
+
+    // 确认操作
+    let confirm_btn = CreateButton::new("confirm_backup_override")
+        .label("确认修改")
+        .style(ButtonStyle::Danger);
+    let cancel_btn = CreateButton::new("cancel_backup_override")
+        .label("❌ 取消")
+        .style(ButtonStyle::Secondary);
+
+    let handler = ctx
+        .send(
+            CreateReply::default()
+                .content(format!(
+                    "⚠️ 即将覆盖帖子 **{}** 的备份权限：{} → {}\n此操作会更新置顶协议并发送备份通知。",
+                    thread.name,
+                    status_text(post.backup_allowed),
+                    status_text(backup_allowed),
+                ))
+                .components(vec![CreateActionRow::Buttons(vec![
+                    confirm_btn,
+                    cancel_btn,
+                ])]),
+        )
+        .await?;
+
+    let Some(interaction) = handler
+        .message()
+        .await?
+        .await_component_interaction(ctx)
+        .author_id(ctx.author().id)
+        .await
+    else {
+        warn!("Interaction timed out");
+        return Ok(());
+    };
+
+    interaction
+        .create_response(ctx, CreateInteractionResponse::Acknowledge)
+        .await?;
+
+    if interaction.data.custom_id != "confirm_backup_override" {
+        handler
+            .edit(
+                ctx,
+                CreateReply::default()
+                    .content("已取消修改备份权限。")
+                    .components(vec![]),
+            )
+            .await?;
+        return Ok(());
+    }
+
+    let content = match LicensePublishService::override_backup_permission(
+        ctx.http(),
+        ctx.data(),
+        &thread,
+        backup_allowed,
+    )
+    .await?
+    {
+        Some(true) => format!(
+            "✅ 备份权限已更新为 {}，并已发送变更通知。",
+            status_text(backup_allowed)
+        ),
+        Some(false) => format!(
+            "ℹ️ 备份权限已是 {}，无需修改。",
+            status_text(backup_allowed)
+        ),
+        None => "该帖子尚未发布协议。".to_string(),
+    };
+
+    handler
+        .edit(
+            ctx,
+            CreateReply::default().content(content).components(vec![]),
+        )
+        .await?;
+
+    Ok(())
+}
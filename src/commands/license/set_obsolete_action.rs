@@ -0,0 +1,79 @@
+use poise::{CreateReply, command};
+use serenity::all::*;
+
+use crate::{
+    commands::{Context, check_admin},
+    error::BotError,
+    services::published_posts::{ObsoleteLicenseAction, effective_obsolete_action},
+};
+
+fn action_text(action: ObsoleteLicenseAction) -> &'static str {
+    match action {
+        ObsoleteLicenseAction::Keep => "保留并标记作废",
+        ObsoleteLicenseAction::Delete => "删除",
+    }
+}
+
+#[command(
+    slash_command,
+    custom_data = "crate::commands::WriteCommand",
+    guild_only,
+    default_member_permissions = "ADMINISTRATOR",
+    check = "check_admin",
+    name_localized("zh-CN", "作废协议处理"),
+    description_localized("zh-CN", "设置当前帖子重新发布协议时旧协议消息的处理方式"),
+    ephemeral
+)]
+/// Override how obsolete license messages are handled when republishing in the current thread
+pub async fn set_obsolete_action(
+    ctx: Context<'_>,
+    #[name_localized("zh-CN", "处理方式")]
+    #[description_localized("zh-CN", "旧协议消息的处理方式，不选择则恢复全局配置")]
+    action: Option<ObsoleteLicenseAction>,
+) -> Result<(), BotError> {
+    // 检查是否在帖子中
+    let channel = ctx.channel_id().to_channel(&ctx).await?;
+    let Channel::Guild(
+        thread @ GuildChannel {
+            kind: ChannelType::PublicThread | ChannelType::PrivateThread | ChannelType::NewsThread,
+            ..
+        },
+    ) = channel
+    else {
+        ctx.send(
+            CreateReply::default()
+                .content("请在帖子中使用本命令。")
+                .ephemeral(true),
+        )
+        .await?;
+        return Ok(());
+    };
+
+    let Some(post) = ctx
+        .data()
+        .db()
+        .published_posts()
+        .set_obsolete_action(thread.id, action)
+        .await?
+    else {
+        ctx.send(
+            CreateReply::default()
+                .content("该帖子尚未发布协议。")
+                .ephemeral(true),
+        )
+        .await?;
+        return Ok(());
+    };
+
+    let global = ctx.data().cfg().load().obsolete_license_action;
+    let effective = action_text(effective_obsolete_action(&post, global));
+    let content = match action {
+        Some(_) => format!("✅ 此帖子重新发布协议时，旧协议消息将被{effective}。"),
+        None => format!("✅ 已恢复全局配置，旧协议消息将被{effective}。"),
+    };
+
+    ctx.send(CreateReply::default().content(content).ephemeral(true))
+        .await?;
+
+    Ok(())
+}
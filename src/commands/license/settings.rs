@@ -4,11 +4,133 @@ use serenity::all::*;
 
 use super::super::Context;
 use crate::{
-    error::BotError, types::license::DefaultLicenseIdentifier, utils::LicenseEmbedBuilder,
+    error::BotError,
+    services::{
+        audit_log::{AuditAction, send_audit_log},
+        license::UserLicense,
+    },
+    types::license::{DefaultLicenseIdentifier, SystemLicense, license_display_name},
+    utils::{
+        LicenseEmbedBuilder, PAGE_NEXT_ID, PAGE_PREV_ID, Paginator, SELECT_MENU_PAGE_SIZE,
+        paged_select_rows,
+    },
 };
 
+/// 系统协议备份设置下拉菜单的选项值
+const BACKUP_DEFAULT_VALUE: &str = "backup_default";
+const BACKUP_ALLOW_VALUE: &str = "backup_allow";
+const BACKUP_DENY_VALUE: &str = "backup_deny";
+
+/// 将备份设置转换为下拉菜单的选项值
+fn backup_option_value(backup: Option<bool>) -> &'static str {
+    match backup {
+        None => BACKUP_DEFAULT_VALUE,
+        Some(true) => BACKUP_ALLOW_VALUE,
+        Some(false) => BACKUP_DENY_VALUE,
+    }
+}
+
+/// 将下拉菜单的选项值解析为备份设置，无法识别时返回 `None`
+fn parse_backup_option(value: &str) -> Option<Option<bool>> {
+    match value {
+        BACKUP_DEFAULT_VALUE => Some(None),
+        BACKUP_ALLOW_VALUE => Some(Some(true)),
+        BACKUP_DENY_VALUE => Some(Some(false)),
+        _ => None,
+    }
+}
+
+/// 备份设置在下拉菜单和审计日志中显示的名称
+fn backup_option_label(backup: Option<bool>) -> &'static str {
+    match backup {
+        None => "使用系统默认",
+        Some(true) => "允许备份",
+        Some(false) => "禁止备份",
+    }
+}
+
+/// 创建系统协议备份设置的下拉菜单，当前设置为默认选中项
+fn create_backup_select(current: Option<bool>) -> CreateSelectMenu {
+    let options = [
+        (None, "沿用系统协议本身的备份设置"),
+        (Some(true), "允许管理组备份您的作品"),
+        (Some(false), "不允许管理组备份您的作品"),
+    ]
+    .into_iter()
+    .map(|(backup, description)| {
+        CreateSelectMenuOption::new(backup_option_label(backup), backup_option_value(backup))
+            .description(description)
+            .default_selection(backup == current)
+    })
+    .collect();
+
+    CreateSelectMenu::new(
+        "system_backup_select",
+        CreateSelectMenuKind::String { options },
+    )
+    .placeholder("请选择备份设置")
+    .max_values(1)
+}
+
+/// 默认协议选择菜单的全部选项
+///
+/// 受限的系统协议在自动发布时会被跳过，只列出用户有权发布的系统协议
+fn default_license_options(
+    user_licenses: &[UserLicense],
+    system_licenses: &[SystemLicense],
+    user_id: UserId,
+    roles: &[RoleId],
+) -> Vec<CreateSelectMenuOption> {
+    // 添加"无默认协议"选项
+    let mut select_options =
+        vec![CreateSelectMenuOption::new("无默认协议", "none").description("不设置默认协议")];
+
+    // 添加用户协议选项
+    for license in user_licenses {
+        select_options.push(
+            CreateSelectMenuOption::new(
+                license_display_name(&license.license_name, false, system_licenses),
+                format!("user_{}", license.id),
+            )
+            .description("用户协议"),
+        );
+    }
+
+    // 添加系统协议选项
+    for license in system_licenses
+        .iter()
+        .filter(|l| l.can_publish(user_id, roles))
+    {
+        select_options.push(
+            CreateSelectMenuOption::new(
+                license_display_name(&license.license_name, true, system_licenses),
+                format!("system_{}", license.license_name),
+            )
+            .description("系统协议"),
+        );
+    }
+
+    select_options
+}
+
+/// 构建当前页的默认协议选择菜单
+fn default_license_rows(
+    options: &[CreateSelectMenuOption],
+    paginator: &Paginator,
+) -> Vec<CreateActionRow> {
+    paged_select_rows(options, paginator, |options| {
+        CreateSelectMenu::new(
+            "set_default_license_select",
+            CreateSelectMenuKind::String { options },
+        )
+        .placeholder("请选择默认协议")
+        .max_values(1)
+    })
+}
+
 #[command(
     slash_command,
+    custom_data = "crate::commands::WriteCommand",
     user_cooldown = 10,
     name_localized("zh-CN", "自动发布设置"),
     description_localized("zh-CN", "编辑自动发布设置"),
@@ -17,7 +139,18 @@ use crate::{
 /// Fetches system information
 pub async fn auto_publish_settings(ctx: Context<'_>) -> Result<(), BotError> {
     let db = ctx.data().db.clone();
+    // 斜杠命令的成员信息随交互一起提供，私信中没有身份组
+    let author_roles = ctx
+        .author_member()
+        .await
+        .map(|m| m.roles.clone())
+        .unwrap_or_default();
     let create_embed = async || -> Result<CreateEmbed, BotError> {
+        // 其他删除途径（如 gRPC）不会清除默认协议，显示前修复失效的引用
+        let default_license_reset = db
+            .user_settings()
+            .clear_stale_default_license(ctx.author().id)
+            .await?;
         let user_settings = db.user_settings().get_or_create(ctx.author().id).await?;
         let auto_copyright = user_settings.auto_publish_enabled;
         let skip_confirmation = user_settings.skip_auto_publish_confirmation;
@@ -26,20 +159,26 @@ pub async fn auto_publish_settings(ctx: Context<'_>) -> Result<(), BotError> {
             .user_settings()
             .get_default_license(ctx.author().id)
             .await?;
+        let system_licenses = ctx.data().system_license_cache.get_all().await;
         let (name, is_system_license) = match default_license {
             Some(DefaultLicenseIdentifier::User(id)) => (
                 db.license()
                     .get_license(id, ctx.author().id)
                     .await?
-                    .map(|l| l.license_name)
+                    .map(|l| license_display_name(&l.license_name, false, &system_licenses))
                     .unwrap_or_else(|| "未设置".to_string()),
                 false,
             ),
             Some(DefaultLicenseIdentifier::System(name)) => {
                 // Verify the system license exists
-                let system_licenses = ctx.data().system_license_cache.get_all().await;
-                if system_licenses.iter().any(|l| l.license_name == name) {
-                    (format!("{name} (系统)"), true)
+                if ctx
+                    .data()
+                    .system_license_cache
+                    .get_by_name(&name)
+                    .await
+                    .is_some()
+                {
+                    (license_display_name(&name, true, &system_licenses), true)
                 } else {
                     ("未设置".to_string(), false)
                 }
@@ -52,6 +191,7 @@ pub async fn auto_publish_settings(ctx: Context<'_>) -> Result<(), BotError> {
             skip_confirmation,
             is_system_license,
             default_system_license_backup,
+            default_license_reset,
         ))
     };
     // 按钮现在在create_reply闭包中动态创建
@@ -69,7 +209,7 @@ pub async fn auto_publish_settings(ctx: Context<'_>) -> Result<(), BotError> {
         ];
         if show_system_backup {
             buttons.push(
-                CreateButton::new("toggle_system_backup")
+                CreateButton::new("set_system_backup")
                     .label("备份设置")
                     .style(ButtonStyle::Secondary),
             );
@@ -100,12 +240,30 @@ pub async fn auto_publish_settings(ctx: Context<'_>) -> Result<(), BotError> {
         .await_component_interaction(ctx)
         .author_id(ctx.author().id)
         .stream();
+    // 默认协议选择菜单的当前页
+    let mut license_page = Paginator::new(1);
     while let Some(first_interaction) = interaction_stream.next().await {
         match first_interaction.data.custom_id.as_str() {
             "toggle_auto_publish" => {
-                db.user_settings()
+                let settings = db
+                    .user_settings()
                     .toggle_auto_publish(ctx.author().id)
                     .await?;
+                send_audit_log(
+                    ctx.http(),
+                    &ctx.data().cfg().load(),
+                    ctx.author().id,
+                    AuditAction::SettingChanged,
+                    format!(
+                        "自动发布：{}",
+                        if settings.auto_publish_enabled {
+                            "开启"
+                        } else {
+                            "关闭"
+                        }
+                    ),
+                )
+                .await;
                 first_interaction
                     .create_response(ctx, CreateInteractionResponse::Acknowledge)
                     .await?;
@@ -125,50 +283,18 @@ pub async fn auto_publish_settings(ctx: Context<'_>) -> Result<(), BotError> {
                 let user_licenses = db.license().get_user_licenses(ctx.author().id).await?;
                 let system_licenses = ctx.data().system_license_cache.get_all().await;
 
-                // 创建选择菜单选项
-                let mut select_options = Vec::new();
-
-                // 添加"无默认协议"选项
-                select_options.push(
-                    CreateSelectMenuOption::new("无默认协议", "none").description("不设置默认协议"),
+                let options = default_license_options(
+                    &user_licenses,
+                    &system_licenses,
+                    ctx.author().id,
+                    &author_roles,
                 );
-
-                // 添加用户协议选项
-                for license in user_licenses {
-                    select_options.push(
-                        CreateSelectMenuOption::new(
-                            &license.license_name,
-                            format!("user_{}", license.id),
-                        )
-                        .description("用户协议"),
-                    );
-                }
-
-                // 添加系统协议选项
-                for license in system_licenses {
-                    select_options.push(
-                        CreateSelectMenuOption::new(
-                            &license.license_name,
-                            format!("system_{}", license.license_name),
-                        )
-                        .description("系统协议"),
-                    );
-                }
-
-                // 创建选择菜单
-                let select_menu = CreateSelectMenu::new(
-                    "set_default_license_select",
-                    CreateSelectMenuKind::String {
-                        options: select_options,
-                    },
-                )
-                .placeholder("请选择默认协议")
-                .max_values(1);
+                license_page = Paginator::for_items(options.len(), SELECT_MENU_PAGE_SIZE);
 
                 // 创建带有选择菜单的回复
                 let reply_with_select = CreateReply::default()
                     .embed(create_embed().await?)
-                    .components(vec![CreateActionRow::SelectMenu(select_menu)]);
+                    .components(default_license_rows(&options, &license_page));
 
                 first_interaction
                     .create_response(ctx, CreateInteractionResponse::Acknowledge)
@@ -176,6 +302,31 @@ pub async fn auto_publish_settings(ctx: Context<'_>) -> Result<(), BotError> {
 
                 handler.edit(ctx, reply_with_select).await?;
             }
+            PAGE_PREV_ID | PAGE_NEXT_ID => {
+                // 默认协议选择菜单翻页
+                license_page.handle_action(&first_interaction.data.custom_id);
+                let user_licenses = db.license().get_user_licenses(ctx.author().id).await?;
+                let system_licenses = ctx.data().system_license_cache.get_all().await;
+                let options = default_license_options(
+                    &user_licenses,
+                    &system_licenses,
+                    ctx.author().id,
+                    &author_roles,
+                );
+
+                first_interaction
+                    .create_response(ctx, CreateInteractionResponse::Acknowledge)
+                    .await?;
+
+                handler
+                    .edit(
+                        ctx,
+                        CreateReply::default()
+                            .embed(create_embed().await?)
+                            .components(default_license_rows(&options, &license_page)),
+                    )
+                    .await?;
+            }
             "set_default_license_select" => {
                 // 处理选择菜单的选择
                 if let ComponentInteractionDataKind::StringSelect { values } =
@@ -275,9 +426,25 @@ pub async fn auto_publish_settings(ctx: Context<'_>) -> Result<(), BotError> {
                 }
             }
             "toggle_skip_confirmation" => {
-                db.user_settings()
+                let settings = db
+                    .user_settings()
                     .toggle_skip_confirmation(ctx.author().id)
                     .await?;
+                send_audit_log(
+                    ctx.http(),
+                    &ctx.data().cfg().load(),
+                    ctx.author().id,
+                    AuditAction::SettingChanged,
+                    format!(
+                        "跳过发布确认：{}",
+                        if settings.skip_auto_publish_confirmation {
+                            "开启"
+                        } else {
+                            "关闭"
+                        }
+                    ),
+                )
+                .await;
                 first_interaction
                     .create_response(ctx, CreateInteractionResponse::Acknowledge)
                     .await?;
@@ -292,32 +459,55 @@ pub async fn auto_publish_settings(ctx: Context<'_>) -> Result<(), BotError> {
                     .edit(ctx, create_reply(embed, is_system_license))
                     .await?;
             }
-            "toggle_system_backup" => {
-                // 获取当前设置和默认协议
+            "set_system_backup" => {
+                // 显示备份设置下拉菜单
                 let user_settings = db.user_settings().get_or_create(ctx.author().id).await?;
-                let current_backup = user_settings.default_system_license_backup;
+                let reply_with_select = CreateReply::default()
+                    .embed(create_embed().await?)
+                    .components(vec![CreateActionRow::SelectMenu(create_backup_select(
+                        user_settings.default_system_license_backup,
+                    ))]);
+
+                first_interaction
+                    .create_response(ctx, CreateInteractionResponse::Acknowledge)
+                    .await?;
+
+                handler.edit(ctx, reply_with_select).await?;
+            }
+            "system_backup_select" => {
+                let selected = match &first_interaction.data.kind {
+                    ComponentInteractionDataKind::StringSelect { values } => {
+                        values.first().and_then(|v| parse_backup_option(v))
+                    }
+                    _ => None,
+                };
                 let default_license = db
                     .user_settings()
                     .get_default_license(ctx.author().id)
                     .await?;
 
-                // 确保当前使用的是系统协议
-                if let Some(DefaultLicenseIdentifier::System(license_name)) = default_license {
-                    // 切换备份权限设置
-                    let new_backup = match current_backup {
-                        None => Some(true),        // 未设置 -> 允许备份
-                        Some(true) => Some(false), // 允许备份 -> 不允许备份
-                        Some(false) => None,       // 不允许备份 -> 使用系统默认
-                    };
-
-                    // 更新设置，保持系统协议不变
+                // 确保当前使用的是系统协议，更新设置时保持系统协议不变
+                if let (Some(new_backup), Some(DefaultLicenseIdentifier::System(license_name))) =
+                    (selected, default_license)
+                {
                     db.user_settings()
                         .set_default_license(
                             ctx.author().id,
-                            Some(DefaultLicenseIdentifier::System(license_name)),
+                            Some(DefaultLicenseIdentifier::System(license_name.clone())),
                             new_backup,
                         )
                         .await?;
+                    send_audit_log(
+                        ctx.http(),
+                        &ctx.data().cfg().load(),
+                        ctx.author().id,
+                        AuditAction::SettingChanged,
+                        format!(
+                            "系统协议「{license_name}」备份设置：{}",
+                            backup_option_label(new_backup)
+                        ),
+                    )
+                    .await;
                 }
 
                 first_interaction
@@ -347,3 +537,22 @@ pub async fn auto_publish_settings(ctx: Context<'_>) -> Result<(), BotError> {
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_backup_option_mapping() {
+        for backup in [None, Some(true), Some(false)] {
+            assert_eq!(
+                parse_backup_option(backup_option_value(backup)),
+                Some(backup)
+            );
+        }
+        assert_eq!(parse_backup_option("backup_allow"), Some(Some(true)));
+        assert_eq!(parse_backup_option("backup_deny"), Some(Some(false)));
+        assert_eq!(parse_backup_option("backup_default"), Some(None));
+        assert_eq!(parse_backup_option("unknown"), None);
+    }
+}
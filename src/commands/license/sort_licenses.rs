@@ -0,0 +1,213 @@
+use std::time::Duration;
+
+use poise::{CreateReply, command};
+use serenity::all::*;
+
+use crate::{
+    commands::Context, error::BotError, services::license::UserLicense, utils::LicenseEmbedBuilder,
+};
+
+/// 排序面板的交互超时时间（秒）
+const SORT_TIMEOUT_SECS: u64 = 180;
+
+/// 将指定协议上移或下移一位，已在首位或末位时返回 `false`
+fn move_license(order: &mut [i32], license_id: i32, up: bool) -> bool {
+    let Some(index) = order.iter().position(|id| *id == license_id) else {
+        return false;
+    };
+    let target = if up {
+        index.checked_sub(1)
+    } else {
+        Some(index + 1).filter(|&i| i < order.len())
+    };
+    match target {
+        Some(target) => {
+            order.swap(index, target);
+            true
+        }
+        None => false,
+    }
+}
+
+fn create_sort_embed(
+    licenses: &[UserLicense],
+    order: &[i32],
+    selected: Option<i32>,
+) -> CreateEmbed {
+    let lines: Vec<String> = order
+        .iter()
+        .enumerate()
+        .filter_map(|(index, id)| {
+            let license = licenses.iter().find(|l| l.id == *id)?;
+            Some(if Some(*id) == selected {
+                format!("{}. ▶ **{}**", index + 1, license.license_name)
+            } else {
+                format!("{}. {}", index + 1, license.license_name)
+            })
+        })
+        .collect();
+
+    CreateEmbed::new()
+        .title("🔃 协议排序")
+        .description(lines.join("\n"))
+        .colour(Colour::BLUE)
+        .footer(CreateEmbedFooter::new(
+            "选择协议后使用按钮调整位置，顺序会立即保存并用于协议菜单",
+        ))
+}
+
+fn create_sort_components(
+    licenses: &[UserLicense],
+    order: &[i32],
+    selected: Option<i32>,
+) -> Vec<CreateActionRow> {
+    let options = order
+        .iter()
+        .filter_map(|id| licenses.iter().find(|l| l.id == *id))
+        .map(|license| {
+            CreateSelectMenuOption::new(&license.license_name, license.id.to_string())
+                .default_selection(Some(license.id) == selected)
+        })
+        .collect();
+    let position = selected.and_then(|id| order.iter().position(|o| *o == id));
+
+    vec![
+        CreateActionRow::SelectMenu(
+            CreateSelectMenu::new(
+                "sort_select_license",
+                CreateSelectMenuKind::String { options },
+            )
+            .placeholder("选择要移动的协议"),
+        ),
+        CreateActionRow::Buttons(vec![
+            CreateButton::new("sort_move_up")
+                .label("⬆️ 上移")
+                .style(ButtonStyle::Secondary)
+                .disabled(position.is_none_or(|p| p == 0)),
+            CreateButton::new("sort_move_down")
+                .label("⬇️ 下移")
+                .style(ButtonStyle::Secondary)
+                .disabled(position.is_none_or(|p| p + 1 == order.len())),
+            CreateButton::new("sort_done")
+                .label("✅ 完成")
+                .style(ButtonStyle::Success),
+        ]),
+    ]
+}
+
+#[command(
+    slash_command,
+    custom_data = "crate::commands::WriteCommand",
+    guild_only,
+    name_localized("zh-CN", "协议排序"),
+    description_localized("zh-CN", "调整您的协议在菜单中的显示顺序"),
+    ephemeral
+)]
+/// Change the order your licenses are shown in menus
+pub async fn sort_licenses(ctx: Context<'_>) -> Result<(), BotError> {
+    let license_service = ctx.data().db().license();
+    let licenses = license_service.get_user_licenses(ctx.author().id).await?;
+    if licenses.len() < 2 {
+        let reply = if licenses.is_empty() {
+            CreateReply::default().embed(LicenseEmbedBuilder::create_no_license_embed())
+        } else {
+            CreateReply::default().content("您只有一个协议，无需排序。")
+        };
+        ctx.send(reply.ephemeral(true)).await?;
+        return Ok(());
+    }
+
+    let mut order: Vec<i32> = licenses.iter().map(|l| l.id).collect();
+    let mut selected = None;
+    let handler = ctx
+        .send(
+            CreateReply::default()
+                .embed(create_sort_embed(&licenses, &order, selected))
+                .components(create_sort_components(&licenses, &order, selected))
+                .ephemeral(true),
+        )
+        .await?;
+    let message = handler.message().await?;
+
+    while let Some(interaction) = message
+        .await_component_interaction(ctx)
+        .author_id(ctx.author().id)
+        .timeout(Duration::from_secs(SORT_TIMEOUT_SECS))
+        .await
+    {
+        match interaction.data.custom_id.as_str() {
+            "sort_select_license" => {
+                if let ComponentInteractionDataKind::StringSelect { values } =
+                    &interaction.data.kind
+                {
+                    selected = values.first().and_then(|v| v.parse().ok());
+                }
+            }
+            "sort_move_up" | "sort_move_down" => {
+                let up = interaction.data.custom_id == "sort_move_up";
+                if let Some(id) = selected
+                    && move_license(&mut order, id, up)
+                {
+                    license_service
+                        .set_sort_order(ctx.author().id, &order)
+                        .await?;
+                }
+            }
+            _ => {
+                interaction
+                    .create_response(
+                        ctx,
+                        CreateInteractionResponse::UpdateMessage(
+                            CreateInteractionResponseMessage::new()
+                                .embed(create_sort_embed(&licenses, &order, None))
+                                .components(vec![]),
+                        ),
+                    )
+                    .await?;
+                return Ok(());
+            }
+        }
+
+        interaction
+            .create_response(
+                ctx,
+                CreateInteractionResponse::UpdateMessage(
+                    CreateInteractionResponseMessage::new()
+                        .embed(create_sort_embed(&licenses, &order, selected))
+                        .components(create_sort_components(&licenses, &order, selected)),
+                ),
+            )
+            .await?;
+    }
+
+    // 超时后移除按钮，已调整的顺序已经保存
+    handler
+        .edit(
+            ctx,
+            CreateReply::default()
+                .embed(create_sort_embed(&licenses, &order, None))
+                .components(vec![]),
+        )
+        .await?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_move_license() {
+        let mut order = vec![1, 2, 3];
+        assert!(move_license(&mut order, 3, true));
+        assert_eq!(order, vec![1, 3, 2]);
+        assert!(move_license(&mut order, 1, false));
+        assert_eq!(order, vec![3, 1, 2]);
+
+        // 首位不能上移，末位不能下移，未知协议不移动
+        assert!(!move_license(&mut order, 3, true));
+        assert!(!move_license(&mut order, 2, false));
+        assert!(!move_license(&mut order, 9, true));
+        assert_eq!(order, vec![3, 1, 2]);
+    }
+}
@@ -0,0 +1,107 @@
+use poise::{CreateReply, command};
+use serenity::all::*;
+
+use crate::{
+    commands::{Context, check_admin},
+    error::BotError,
+    services::audit_log::{AuditAction, send_audit_log},
+};
+
+#[command(
+    slash_command,
+    custom_data = "crate::commands::WriteCommand",
+    guild_only,
+    default_member_permissions = "ADMINISTRATOR",
+    check = "check_admin",
+    name_localized("zh-CN", "转移协议"),
+    description_localized("zh-CN", "将协议转移给另一位用户，例如创作者交接作品时"),
+    ephemeral
+)]
+/// Transfer a license from one user to another
+pub async fn transfer_license(
+    ctx: Context<'_>,
+    #[name_localized("zh-CN", "原所有者")]
+    #[description_localized("zh-CN", "当前拥有该协议的用户")]
+    from: User,
+    #[name_localized("zh-CN", "新所有者")]
+    #[description_localized("zh-CN", "接收该协议的用户")]
+    to: User,
+    #[name_localized("zh-CN", "协议id")]
+    #[description_localized("zh-CN", "要转移的协议ID（可在审计日志中查看）")]
+    license_id: i32,
+) -> Result<(), BotError> {
+    if from.id == to.id {
+        ctx.say("❌ 原所有者和新所有者不能是同一用户。").await?;
+        return Ok(());
+    }
+
+    // 目标用户的协议数量上限按当前服务器计算
+    let result = ctx
+        .data()
+        .guild_license(ctx.guild_id())
+        .transfer(license_id, from.id, to.id)
+        .await;
+    let license = match result {
+        Ok(Some(license)) => license,
+        Ok(None) => {
+            ctx.say(format!(
+                "❌ {} 没有 ID 为 {license_id} 的协议。",
+                from.mention()
+            ))
+            .await?;
+            return Ok(());
+        }
+        Err(BotError::LicenseLimit { .. }) => {
+            ctx.say(format!(
+                "❌ {} 的协议数量已达上限，无法接收新协议。",
+                to.mention()
+            ))
+            .await?;
+            return Ok(());
+        }
+        Err(BotError::ValidationError { message, .. }) => {
+            ctx.say(format!("❌ {message}")).await?;
+            return Ok(());
+        }
+        Err(e) => return Err(e),
+    };
+
+    // 转出的协议若是原所有者的默认协议，清除该默认设置
+    ctx.data()
+        .db()
+        .user_settings()
+        .clear_stale_default_license(from.id)
+        .await?;
+
+    tracing::info!(
+        "License {} transferred from {} to {} by {}",
+        license.id,
+        from.id,
+        to.id,
+        ctx.author().id
+    );
+    send_audit_log(
+        ctx.http(),
+        &ctx.data().cfg().load(),
+        ctx.author().id,
+        AuditAction::SettingChanged,
+        format!(
+            "将协议「{}」(#{}) 从 <@{}> 转移给 <@{}>",
+            license.license_name, license.id, from.id, to.id
+        ),
+    )
+    .await;
+
+    ctx.send(
+        CreateReply::default()
+            .content(format!(
+                "✅ 已将协议「{}」从 {} 转移给 {}。已发布的协议消息不受影响。",
+                license.license_name,
+                from.mention(),
+                to.mention()
+            ))
+            .ephemeral(true),
+    )
+    .await?;
+    Ok(())
+}
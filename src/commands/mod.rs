@@ -1,10 +1,15 @@
 // mod cookie;
+mod cooldowns;
 mod forum_management;
 mod license;
 pub mod system;
-use std::sync::Arc;
+use std::sync::{
+    Arc,
+    atomic::{AtomicBool, Ordering},
+};
 
 use arc_swap::ArcSwap;
+use cooldowns::*;
 use forum_management::*;
 use license::*;
 // use cookie::*;
@@ -12,13 +17,17 @@ use owo_colors::OwoColorize;
 use poise::command;
 use snafu::OptionExt;
 use system::*;
-use tracing::{error, info};
+use tracing::{error, info, warn};
 
 use crate::{
     config::BotCfg,
     database::BotDatabase,
     error::BotError,
-    services::{notification_service::NotificationService, system_license::SystemLicenseCache},
+    services::{
+        discord_health::DiscordHealth, license::LicenseService,
+        notification_service::NotificationService, system_license::SystemLicenseCache,
+    },
+    utils::Locale,
 };
 
 pub type Context<'a> = poise::Context<'a, Data, BotError>;
@@ -37,12 +46,17 @@ pub async fn check_admin(ctx: Context<'_>) -> Result<bool, BotError> {
         .any(|&id| ctx.data().cfg.load().admin_role_ids.contains(&id)))
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct Data {
     db: BotDatabase,
     cfg: Arc<ArcSwap<BotCfg>>,
     system_license_cache: Arc<SystemLicenseCache>,
     notification_service: Arc<NotificationService>,
+    discord_health: Arc<DiscordHealth>,
+    /// 维护模式开关，开启时拒绝会修改数据的命令；克隆的 Data 共享同一开关
+    maintenance_mode: Arc<AtomicBool>,
+    /// 命令冷却记录，可以单独清除某个用户的冷却
+    cooldowns: Arc<CommandCooldowns>,
 }
 
 impl Data {
@@ -54,6 +68,13 @@ impl Data {
         &self.cfg
     }
 
+    /// 使用指定服务器协议数量上限的协议服务
+    pub fn guild_license(&self, guild_id: Option<serenity::all::GuildId>) -> LicenseService<'_> {
+        self.db
+            .license()
+            .with_max_licenses(self.cfg.load().max_licenses_for(guild_id))
+    }
+
     pub fn system_license_cache(&self) -> &Arc<SystemLicenseCache> {
         &self.system_license_cache
     }
@@ -61,6 +82,125 @@ impl Data {
     pub fn notification_service(&self) -> &Arc<NotificationService> {
         &self.notification_service
     }
+
+    pub fn discord_health(&self) -> &Arc<DiscordHealth> {
+        &self.discord_health
+    }
+
+    pub fn is_maintenance_mode(&self) -> bool {
+        self.maintenance_mode.load(Ordering::Relaxed)
+    }
+
+    pub fn cooldowns(&self) -> &Arc<CommandCooldowns> {
+        &self.cooldowns
+    }
+
+    /// 设置维护模式，返回之前的状态
+    pub fn set_maintenance_mode(&self, enabled: bool) -> bool {
+        self.maintenance_mode.swap(enabled, Ordering::Relaxed)
+    }
+}
+
+/// 会修改数据的命令的标记，维护模式下将被拒绝
+///
+/// 在命令定义中通过 `#[command(custom_data = "crate::commands::WriteCommand")]` 声明。
+pub struct WriteCommand;
+
+/// 判断命令是否会修改数据
+fn is_write_command(command: &poise::Command<Data, BotError>) -> bool {
+    command.custom_data.downcast_ref::<WriteCommand>().is_some()
+}
+
+/// Discord 服务故障期间直接拒绝命令，避免反复请求注定失败的接口
+async fn outage_check(ctx: Context<'_>) -> Result<bool, BotError> {
+    if !ctx.data().discord_health().is_outage() {
+        return Ok(true);
+    }
+    // 故障期间回复也可能失败，失败时只记录日志
+    if let Err(e) = ctx
+        .send(
+            poise::CreateReply::default()
+                .content("⚠️ Discord 服务当前不稳定，请稍后再试。")
+                .ephemeral(true),
+        )
+        .await
+    {
+        warn!("Discord 故障期间回复命令失败: {}", e);
+    }
+    Ok(false)
+}
+
+/// 所有命令执行前的检查
+async fn command_check(ctx: Context<'_>) -> Result<bool, BotError> {
+    Ok(outage_check(ctx).await? && maintenance_check(ctx).await? && cooldown_check(ctx).await?)
+}
+
+/// 命令冷却中时提示剩余时间并拦截命令
+async fn cooldown_check(ctx: Context<'_>) -> Result<bool, BotError> {
+    let command = ctx.command();
+    let remaining = {
+        let config = command.cooldown_config.read().unwrap();
+        ctx.data()
+            .cooldowns()
+            .remaining(&command.qualified_name, &config, &ctx.cooldown_context())
+    };
+    let Some(remaining) = remaining else {
+        return Ok(true);
+    };
+    let locale = ctx.locale().map(Locale::from_discord).unwrap_or_default();
+    ctx.send(
+        poise::CreateReply::default()
+            .content(cooldown_message(locale, remaining))
+            .ephemeral(true),
+    )
+    .await?;
+    Ok(false)
+}
+
+/// 维护模式下拦截写命令，只读命令不受影响
+async fn maintenance_check(ctx: Context<'_>) -> Result<bool, BotError> {
+    if !ctx.data().is_maintenance_mode() || !is_write_command(ctx.command()) {
+        return Ok(true);
+    }
+    ctx.send(
+        poise::CreateReply::default()
+            .content("🛠️ 机器人维护中，暂时无法创建、发布或修改协议，请稍后再试。")
+            .ephemeral(true),
+    )
+    .await?;
+    Ok(false)
+}
+
+/// 命令冷却中的提示，剩余时间向上取整到秒
+fn cooldown_message(locale: Locale, remaining: std::time::Duration) -> String {
+    let secs = remaining.as_secs() + u64::from(remaining.subsec_nanos() > 0);
+    match locale {
+        Locale::ZhCn => format!("⏳ 命令冷却中，请在 {secs} 秒后再试。"),
+        Locale::EnUs => format!("⏳ This command is on cooldown, please try again in {secs}s."),
+    }
+}
+
+/// 命令出错时回复给用户的内容：友好的错误消息，附带处理建议（如有）
+fn error_reply_content(error: &BotError) -> String {
+    let mut content = format!("❌ {}", error.user_message());
+    if let Some(suggestion) = error.user_suggestion() {
+        content.push_str(&format!("\n💡 {suggestion}"));
+    }
+    content
+}
+
+/// 向用户回复命令错误，技术细节只记录在日志中
+async fn reply_command_error(ctx: Context<'_>, error: &BotError) {
+    error!("Error in command `{}`: {:?}", ctx.command().name, error);
+    if error.is_discord_outage() && ctx.data().discord_health().record_failure() {
+        warn!("连续多次请求 Discord 失败，判定为 Discord 服务故障");
+    }
+    let reply = poise::CreateReply::default()
+        .content(error_reply_content(error))
+        .ephemeral(true);
+    if let Err(e) = ctx.send(reply).await {
+        error!("Error while replying to command error: {}", e)
+    }
 }
 
 async fn on_error(error: poise::FrameworkError<'_, Data, BotError>) {
@@ -70,7 +210,14 @@ async fn on_error(error: poise::FrameworkError<'_, Data, BotError>) {
     match error {
         poise::FrameworkError::Setup { error, .. } => panic!("Failed to start bot: {error}"),
         poise::FrameworkError::Command { error, ctx, .. } => {
-            error!("Error in command `{}`: {}", ctx.command().name, error);
+            reply_command_error(ctx, &error).await;
+        }
+        poise::FrameworkError::CommandCheckFailed {
+            error: Some(error),
+            ctx,
+            ..
+        } => {
+            reply_command_error(ctx, &error).await;
         }
         error => {
             if let Err(e) = poise::builtins::on_error(error).await {
@@ -89,19 +236,52 @@ fn option(_cfg: &ArcSwap<BotCfg>) -> poise::FrameworkOptions<Data, BotError> {
     poise::FrameworkOptions {
         commands: vec![
             auto_publish_settings(),
+            reguide(),
+            bulk_backup_permission(),
+            my_posts(),
+            auto_publish_users(),
+            cleanup_inactive_settings(),
             create_license(),
             create_license_interactive(),
             register(),
             system_info(),
             setup_system_status(),
             license_manager(),
+            sort_licenses(),
+            license_detail(),
+            license_help(),
             publish_license(),
+            force_publish_license(),
+            compare_published(),
+            refresh_license_author(),
+            compare_licenses(),
             reload_licenses(),
+            import_spdx(),
+            system_license_usage(),
+            set_guild_default_license(),
+            set_backup_permission(),
+            set_obsolete_action(),
+            clear_dedup_cache(),
+            reset_cooldown(),
+            reset_usage(),
+            transfer_license(),
+            migrate_user_licenses(),
+            search_licenses(),
+            log_level(),
+            test_notification(),
+            verify_notification_endpoint(),
+            vacuum_database(),
+            storage_breakdown(),
+            maintenance_mode(),
             add_forum(),
             remove_forum(),
+            backfill_forum(),
             list_forums(),
             clear_forums(),
         ],
+        command_check: Some(|ctx| Box::pin(command_check(ctx))),
+        // 冷却由 CommandCooldowns 记录，以便单独重置某个用户的冷却
+        manual_cooldowns: true,
         on_error: |error| {
             Box::pin(async {
                 on_error(error).await;
@@ -109,6 +289,12 @@ fn option(_cfg: &ArcSwap<BotCfg>) -> poise::FrameworkOptions<Data, BotError> {
         },
         pre_command: |ctx| {
             Box::pin(async move {
+                let command = ctx.command();
+                ctx.data().cooldowns().start(
+                    &command.qualified_name,
+                    &command.cooldown_config.read().unwrap(),
+                    &ctx.cooldown_context(),
+                );
                 info!(
                     "Command: {}\tUser: {}\tGuild: {}",
                     ctx.command().name.green(),
@@ -120,6 +306,13 @@ fn option(_cfg: &ArcSwap<BotCfg>) -> poise::FrameworkOptions<Data, BotError> {
                 )
             })
         },
+        post_command: |ctx| {
+            Box::pin(async move {
+                if ctx.data().discord_health().record_success() {
+                    info!("Discord 服务已恢复");
+                }
+            })
+        },
         event_handler: |ctx, event, framework, data| {
             Box::pin(async move {
                 crate::handlers::poise_event_handler(ctx, event, framework, data).await
@@ -134,6 +327,7 @@ pub fn framework(
     cfg: Arc<ArcSwap<BotCfg>>,
     system_license_cache: Arc<SystemLicenseCache>,
     notification_service: Arc<NotificationService>,
+    discord_health: Arc<DiscordHealth>,
 ) -> poise::Framework<Data, BotError> {
     poise::Framework::builder()
         .options(option(&cfg))
@@ -146,8 +340,97 @@ pub fn framework(
                     cfg,
                     system_license_cache,
                     notification_service,
+                    discord_health,
+                    maintenance_mode: Arc::new(AtomicBool::new(false)),
+                    cooldowns: Arc::new(CommandCooldowns::default()),
                 })
             })
         })
         .build()
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_error_reply_content() {
+        let validation = BotError::ValidationError {
+            message: "协议名称不能为空".to_string(),
+            loc: snafu::location!(),
+        };
+        assert_eq!(error_reply_content(&validation), "❌ 协议名称不能为空");
+
+        let authorization = BotError::AuthorizationError {
+            message: "只有管理员可以使用该命令".to_string(),
+            loc: snafu::location!(),
+        };
+        assert_eq!(
+            error_reply_content(&authorization),
+            "❌ 只有管理员可以使用该命令\n💡 请联系管理员获取相应权限"
+        );
+
+        // 技术细节不展示给用户
+        let generic = BotError::GenericError {
+            message: "connection reset by peer".to_string(),
+            source: None,
+        };
+        assert_eq!(error_reply_content(&generic), "❌ 操作失败，请稍后再试");
+    }
+
+    #[test]
+    fn test_cooldown_message() {
+        use std::time::Duration;
+
+        assert_eq!(
+            cooldown_message(Locale::ZhCn, Duration::from_millis(7200)),
+            "⏳ 命令冷却中，请在 8 秒后再试。"
+        );
+        assert_eq!(
+            cooldown_message(Locale::EnUs, Duration::from_secs(3)),
+            "⏳ This command is on cooldown, please try again in 3s."
+        );
+    }
+
+    #[test]
+    fn test_write_command_classification() {
+        let cfg: BotCfg = toml::from_str(
+            r#"
+            time_offset = 0
+            token = "token"
+            admin_role_ids = []
+            backup_enabled = false
+            endpoint = "http://localhost"
+            extra_admins_ids = []
+            "#,
+        )
+        .unwrap();
+        let commands = option(&ArcSwap::from_pointee(cfg)).commands;
+        let is_write = |name: &str| {
+            let command = commands
+                .iter()
+                .find(|c| c.name == name)
+                .unwrap_or_else(|| panic!("{name} is not registered"));
+            is_write_command(command)
+        };
+        for name in [
+            "create_license",
+            "publish_license",
+            "license_manager",
+            "add_forum",
+            "import_spdx",
+            "set_guild_default_license",
+        ] {
+            assert!(is_write(name), "{name} should be a write command");
+        }
+        for name in [
+            "license_detail",
+            "license_help",
+            "list_forums",
+            "system_info",
+            "maintenance_mode",
+        ] {
+            assert!(!is_write(name), "{name} should be read-only");
+        }
+    }
+}
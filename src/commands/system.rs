@@ -1,3 +1,6 @@
+use std::sync::Arc;
+
+use arc_swap::ArcSwap;
 use futures::{StreamExt, stream::FuturesOrdered};
 use poise::{CreateReply, command};
 use serenity::all::{
@@ -7,7 +10,13 @@ use serenity::all::{
 use sysinfo::System;
 
 use super::{Context, check_admin};
-use crate::error::BotError;
+use crate::{
+    error::BotError,
+    services::{
+        gateway::GatewayStatus, notification_service::NotificationPayload,
+        spdx::spdx_to_system_license,
+    },
+};
 
 /// 创建系统信息 Embed
 /// 可被命令和后台服务复用
@@ -15,6 +24,7 @@ pub async fn create_system_info_embed(
     db: &crate::database::BotDatabase,
     cache: &serenity::cache::Cache,
     latency: std::time::Duration,
+    gateway_status: &GatewayStatus,
 ) -> Result<CreateEmbed, BotError> {
     use tikv_jemalloc_ctl::{epoch, stats};
     let kernel_version = System::kernel_long_version();
@@ -90,6 +100,8 @@ pub async fn create_system_info_embed(
         .field("🚀 自动发布用户", auto_publish_users.to_string(), true)
         .field("📄 使用协议作品", total_posts.to_string(), true)
         .field("💾 授权备份作品", backup_allowed_posts.to_string(), true)
+        // row 5
+        .field("🌐 gRPC 网关", gateway_status.describe(), true)
         .thumbnail(cache.current_user().avatar_url().unwrap_or_default())
         .timestamp(chrono::Utc::now())
         .footer(CreateEmbedFooter::new("系统监控"))
@@ -100,6 +112,17 @@ pub async fn create_system_info_embed(
     Ok(embed)
 }
 
+/// 从 type map 中获取网关状态，未注册时返回未启用的默认状态
+async fn gateway_status_handle(ctx: Context<'_>) -> Arc<ArcSwap<GatewayStatus>> {
+    ctx.serenity_context()
+        .data
+        .read()
+        .await
+        .get::<GatewayStatus>()
+        .cloned()
+        .unwrap_or_default()
+}
+
 #[command(
     slash_command,
     default_member_permissions = "ADMINISTRATOR",
@@ -114,7 +137,14 @@ pub async fn system_info(ctx: Context<'_>, ephemeral: Option<bool>) -> Result<()
     let ephemeral = ephemeral.unwrap_or(true);
     let latency = ctx.ping().await;
 
-    let embed = create_system_info_embed(ctx.data().db(), ctx.cache(), latency).await?;
+    let gateway_status = gateway_status_handle(ctx).await;
+    let embed = create_system_info_embed(
+        ctx.data().db(),
+        ctx.cache(),
+        latency,
+        &gateway_status.load(),
+    )
+    .await?;
 
     ctx.send(CreateReply::default().embed(embed).ephemeral(ephemeral))
         .await?;
@@ -203,6 +233,302 @@ pub async fn reload_licenses(ctx: Context<'_>) -> Result<(), BotError> {
     Ok(())
 }
 
+#[command(
+    slash_command,
+    custom_data = "crate::commands::WriteCommand",
+    default_member_permissions = "ADMINISTRATOR",
+    check = "check_admin",
+    ephemeral,
+    name_localized("zh-CN", "导入SPDX"),
+    description_localized("zh-CN", "根据SPDX标识符批量导入系统授权协议")
+)]
+/// Bulk-import system licenses from SPDX identifiers
+pub async fn import_spdx(
+    ctx: Context<'_>,
+    #[name_localized("zh-CN", "标识符")]
+    #[description_localized("zh-CN", "SPDX标识符，以空格或逗号分隔，例如 MIT, CC-BY-NC-4.0")]
+    identifiers: String,
+) -> Result<(), BotError> {
+    let mut licenses = Vec::new();
+    let mut unknown = Vec::new();
+    for identifier in identifiers
+        .split([',', '，', ' '])
+        .filter(|s| !s.trim().is_empty())
+    {
+        match spdx_to_system_license(identifier) {
+            Some(license) => licenses.push(license),
+            None => unknown.push(identifier.trim()),
+        }
+    }
+
+    let requested: Vec<String> = licenses.iter().map(|l| l.license_name.clone()).collect();
+    let added = match ctx.data().system_license_cache().import(licenses).await {
+        Ok(added) => added,
+        Err(error) => {
+            ctx.say(format!("❌ {}", error.operation_message("import_spdx")))
+                .await?;
+            return Ok(());
+        }
+    };
+    let skipped: Vec<&str> = requested
+        .iter()
+        .filter(|name| !added.contains(name))
+        .map(String::as_str)
+        .collect();
+
+    let mut lines = vec![format!("✅ 已导入 {} 个系统授权协议。", added.len())];
+    if !added.is_empty() {
+        lines.push(format!("新增: {}", added.join(", ")));
+    }
+    if !skipped.is_empty() {
+        lines.push(format!("已存在，已跳过: {}", skipped.join(", ")));
+    }
+    if !unknown.is_empty() {
+        lines.push(format!("无法识别的标识符: {}", unknown.join(", ")));
+    }
+    lines.push("💡 导入的协议默认不允许备份，如有需要请手动编辑协议文件。".to_string());
+
+    ctx.say(lines.join("\n")).await?;
+    Ok(())
+}
+
+#[command(
+    slash_command,
+    custom_data = "crate::commands::WriteCommand",
+    default_member_permissions = "ADMINISTRATOR",
+    check = "check_admin",
+    ephemeral,
+    name_localized("zh-CN", "设置社区默认协议"),
+    description_localized("zh-CN", "设置用户未选择默认协议时自动发布使用的系统协议")
+)]
+/// Set the community-wide fallback license used by auto-publish
+pub async fn set_guild_default_license(
+    ctx: Context<'_>,
+    #[name_localized("zh-CN", "协议")]
+    #[description_localized("zh-CN", "要作为社区默认协议的系统协议，不填则清除")]
+    #[autocomplete = "autocomplete_system_license"]
+    license_name: Option<String>,
+) -> Result<(), BotError> {
+    if let Some(name) = &license_name
+        && ctx
+            .data()
+            .system_license_cache()
+            .get_by_name(name)
+            .await
+            .is_none()
+    {
+        ctx.say(format!("❌ 未找到系统协议 **{name}**。")).await?;
+        return Ok(());
+    }
+
+    let mut cfg = (**ctx.data().cfg().load()).clone();
+    cfg.default_guild_license_name = license_name.clone();
+    cfg.write()?;
+    ctx.data().cfg().store(cfg.into());
+
+    let content = match license_name {
+        Some(name) => format!("✅ 已将社区默认协议设置为 **{name}**。"),
+        None => "✅ 已清除社区默认协议。".to_string(),
+    };
+    ctx.say(content).await?;
+    Ok(())
+}
+
+#[command(
+    slash_command,
+    default_member_permissions = "ADMINISTRATOR",
+    check = "check_admin",
+    ephemeral,
+    name_localized("zh-CN", "系统协议使用情况"),
+    description_localized("zh-CN", "查看各系统协议被设为默认协议的用户数和已发布的帖子数")
+)]
+/// Show how many users default to each system license and how many posts use it
+pub async fn system_license_usage(ctx: Context<'_>) -> Result<(), BotError> {
+    let db = ctx.data().db();
+    let default_counts = db.user_settings().count_default_system_licenses().await?;
+    let post_counts = db.published_posts().count_by_system_license().await?;
+    let guild_default = ctx.data().cfg().load().default_guild_license_name.clone();
+
+    let mut licenses = ctx.data().system_license_cache().get_all().await;
+    licenses.sort_by(|a, b| a.license_name.cmp(&b.license_name));
+
+    let mut lines = Vec::new();
+    let mut used = Vec::new();
+    for license in &licenses {
+        let name = &license.license_name;
+        let defaults = default_counts.get(name).copied().unwrap_or(0);
+        let posts = post_counts.get(name).copied().unwrap_or(0);
+        let is_guild_default = guild_default.as_deref() == Some(name.as_str());
+        let marker = if defaults > 0 || posts > 0 || is_guild_default {
+            used.push(name.as_str());
+            "⚠️"
+        } else {
+            "▫️"
+        };
+        let suffix = if is_guild_default {
+            "（社区默认协议）"
+        } else {
+            ""
+        };
+        lines.push(format!(
+            "{marker} **{name}**{suffix} — 默认: {defaults} 人 · 帖子: {posts} 个"
+        ));
+    }
+
+    // 设置中引用但已不在系统协议列表中的协议
+    let mut missing: Vec<&String> = default_counts
+        .keys()
+        .chain(post_counts.keys())
+        .filter(|name| !licenses.iter().any(|l| &l.license_name == *name))
+        .collect();
+    missing.sort();
+    missing.dedup();
+
+    let mut description = if lines.is_empty() {
+        "暂无系统协议。".to_string()
+    } else {
+        lines.join("\n")
+    };
+    if !missing.is_empty() {
+        description.push_str(&format!(
+            "\n\n❓ 已不存在但仍被引用的协议: {}",
+            missing
+                .iter()
+                .map(|n| n.as_str())
+                .collect::<Vec<_>>()
+                .join(", ")
+        ));
+    }
+
+    let mut embed = CreateEmbed::new()
+        .title("📊 系统协议使用情况")
+        .description(description)
+        .footer(CreateEmbedFooter::new(
+            "帖子数仅统计记录协议名称后发布的帖子",
+        ));
+    if !used.is_empty() {
+        embed = embed.colour(YELLOW).field(
+            "⚠️ 注意",
+            format!(
+                "{} 个协议仍在使用中，移除后将其设为默认协议的用户无法自动发布，请先通知相关用户。",
+                used.len()
+            ),
+            false,
+        );
+    } else {
+        embed = embed.colour(GREEN);
+    }
+
+    ctx.send(CreateReply::default().embed(embed)).await?;
+    Ok(())
+}
+
+/// 系统协议名自动补全
+pub(crate) async fn autocomplete_system_license(
+    ctx: Context<'_>,
+    partial: &str,
+) -> impl Iterator<Item = AutocompleteChoice> {
+    let partial = partial.to_lowercase();
+    ctx.data()
+        .system_license_cache()
+        .get_all()
+        .await
+        .into_iter()
+        .filter(move |l| l.license_name.to_lowercase().contains(&partial))
+        .take(25)
+        .map(|l| AutocompleteChoice::new(l.license_name.clone(), l.license_name))
+}
+
+#[command(
+    slash_command,
+    default_member_permissions = "ADMINISTRATOR",
+    owners_only,
+    name_localized("zh-CN", "整理数据库"),
+    description_localized("zh-CN", "执行VACUUM回收数据库空间，执行期间数据库会被短暂锁定"),
+    ephemeral
+)]
+/// Run VACUUM on the database to reclaim space; the database is briefly locked
+pub async fn vacuum_database(
+    ctx: Context<'_>,
+    #[name_localized("zh-CN", "优化")]
+    #[description_localized("zh-CN", "是否同时执行 PRAGMA optimize（默认是）")]
+    optimize: Option<bool>,
+) -> Result<(), BotError> {
+    ctx.defer_ephemeral().await?;
+
+    let db = ctx.data().db().clone();
+    let before = db.size().await?;
+
+    // 在独立任务中执行，整理期间其他数据库操作会等待锁释放
+    tokio::spawn({
+        let db = db.clone();
+        async move { db.vacuum(optimize.unwrap_or(true)).await }
+    })
+    .await
+    .map_err(|e| BotError::GenericError {
+        message: format!("数据库整理任务异常退出: {e}"),
+        source: None,
+    })??;
+
+    let after = db.size().await?;
+    let to_kb = |bytes: i64| bytes as f64 / 1024.0;
+    ctx.say(format!(
+        "✅ 数据库整理完成：{:.1} KB → {:.1} KB（回收 {:.1} KB）",
+        to_kb(before),
+        to_kb(after),
+        to_kb(before - after)
+    ))
+    .await?;
+    Ok(())
+}
+
+#[command(
+    slash_command,
+    default_member_permissions = "ADMINISTRATOR",
+    owners_only,
+    name_localized("zh-CN", "存储分析"),
+    description_localized("zh-CN", "查看各数据表的行数和占用空间"),
+    ephemeral
+)]
+/// Show row counts and estimated storage usage per database table
+pub async fn storage_breakdown(ctx: Context<'_>) -> Result<(), BotError> {
+    ctx.defer_ephemeral().await?;
+
+    let db = ctx.data().db();
+    let total = db.size().await?;
+    let tables = db.table_stats().await?;
+
+    let to_kb = |bytes: i64| bytes as f64 / 1024.0;
+    let lines: Vec<String> = tables
+        .iter()
+        .map(|table| {
+            let share = if total > 0 {
+                table.size as f64 / total as f64 * 100.0
+            } else {
+                0.0
+            };
+            format!(
+                "`{}` — {} 行 · {:.1} KB（{share:.1}%）",
+                table.name,
+                table.rows,
+                to_kb(table.size)
+            )
+        })
+        .collect();
+
+    let embed = CreateEmbed::new()
+        .title("💾 存储分析")
+        .description(lines.join("\n"))
+        .colour(Colour::BLUE)
+        .footer(CreateEmbedFooter::new(format!(
+            "数据库总大小 {:.1} KB，表大小包含索引，其余为空闲页和系统表",
+            to_kb(total)
+        )));
+    ctx.send(CreateReply::default().embed(embed).ephemeral(true))
+        .await?;
+    Ok(())
+}
+
 #[command(
     slash_command,
     default_member_permissions = "ADMINISTRATOR",
@@ -233,7 +559,14 @@ pub async fn setup_system_status(ctx: Context<'_>) -> Result<(), BotError> {
 
     // 创建系统信息 embed
     let latency = ctx.ping().await;
-    let embed = create_system_info_embed(ctx.data().db(), ctx.cache(), latency).await?;
+    let gateway_status = gateway_status_handle(ctx).await;
+    let embed = create_system_info_embed(
+        ctx.data().db(),
+        ctx.cache(),
+        latency,
+        &gateway_status.load(),
+    )
+    .await?;
 
     // 在当前频道发送非 ephemeral 消息
     let message = channel_id
@@ -260,6 +593,7 @@ pub async fn setup_system_status(ctx: Context<'_>) -> Result<(), BotError> {
         std::sync::Arc::new(ctx.data().db().clone()),
         ctx.data().cfg().clone(),
         ctx.serenity_context().cache.clone(),
+        gateway_status,
     )
     .await;
 
@@ -279,3 +613,204 @@ pub async fn setup_system_status(ctx: Context<'_>) -> Result<(), BotError> {
 
     Ok(())
 }
+
+#[command(
+    slash_command,
+    default_member_permissions = "ADMINISTRATOR",
+    owners_only,
+    name_localized("zh-CN", "清除去重缓存"),
+    description_localized("zh-CN", "清空帖子去重缓存，使帖子可以被重新处理"),
+    ephemeral
+)]
+/// Clear the processed-threads dedup cache
+pub async fn clear_dedup_cache(ctx: Context<'_>) -> Result<(), BotError> {
+    let cleared = crate::handlers::clear_processed_threads().await;
+
+    ctx.send(
+        CreateReply::default()
+            .content(format!("✅ 已清空帖子去重缓存（共 {cleared} 条记录）。"))
+            .ephemeral(true),
+    )
+    .await?;
+
+    Ok(())
+}
+
+#[command(
+    slash_command,
+    default_member_permissions = "ADMINISTRATOR",
+    owners_only,
+    name_localized("zh-CN", "日志级别"),
+    description_localized("zh-CN", "查看或在运行时调整日志过滤规则，无需重启"),
+    ephemeral
+)]
+/// Show or change the tracing filter at runtime
+pub async fn log_level(
+    ctx: Context<'_>,
+    #[name_localized("zh-CN", "规则")]
+    #[description_localized(
+        "zh-CN",
+        "与 RUST_LOG 语法相同，例如 debug 或 dc_bot=debug,serenity=warn；不填则查看当前规则"
+    )]
+    directives: Option<String>,
+) -> Result<(), BotError> {
+    let previous = crate::logging::current_log_filter()?;
+    let content = match directives {
+        None => format!("📝 当前日志过滤规则: `{previous}`"),
+        Some(directives) => match crate::logging::set_log_filter(directives.trim()) {
+            Ok(()) => {
+                let current = crate::logging::current_log_filter()?;
+                tracing::info!(
+                    "Log filter changed by {}: {} -> {}",
+                    ctx.author().id,
+                    previous,
+                    current
+                );
+                format!(
+                    "✅ 日志过滤规则已更新: `{previous}` → `{current}`\n💡 重启后将恢复为 RUST_LOG 的设置。"
+                )
+            }
+            Err(e @ BotError::ValidationError { .. }) => format!("❌ {}", e.user_message()),
+            Err(e) => return Err(e),
+        },
+    };
+
+    ctx.send(CreateReply::default().content(content).ephemeral(true))
+        .await?;
+    Ok(())
+}
+
+#[command(
+    slash_command,
+    default_member_permissions = "ADMINISTRATOR",
+    check = "check_admin",
+    name_localized("zh-CN", "维护模式"),
+    description_localized("zh-CN", "开启或关闭维护模式，维护期间仅允许只读命令"),
+    ephemeral
+)]
+/// Toggle maintenance mode, rejecting state-changing commands while enabled
+pub async fn maintenance_mode(
+    ctx: Context<'_>,
+    #[name_localized("zh-CN", "开启")]
+    #[description_localized("zh-CN", "是否开启维护模式（不填则切换当前状态）")]
+    enabled: Option<bool>,
+) -> Result<(), BotError> {
+    let data = ctx.data();
+    let enabled = enabled.unwrap_or(!data.is_maintenance_mode());
+    data.set_maintenance_mode(enabled);
+
+    let content = if enabled {
+        "🛠️ 已开启维护模式，创建、发布和修改协议的命令将被暂停。"
+    } else {
+        "✅ 已关闭维护模式，所有命令恢复正常。"
+    };
+    ctx.send(CreateReply::default().content(content).ephemeral(true))
+        .await?;
+    Ok(())
+}
+
+#[command(
+    slash_command,
+    guild_only,
+    default_member_permissions = "ADMINISTRATOR",
+    owners_only,
+    name_localized("zh-CN", "测试通知"),
+    description_localized("zh-CN", "向配置的通知端点发送一条测试通知，检查配置是否正确"),
+    ephemeral
+)]
+/// Send a sample notification to the configured endpoint
+pub async fn test_notification(ctx: Context<'_>) -> Result<(), BotError> {
+    ctx.defer_ephemeral().await?;
+
+    let guild_id = ctx.guild_id().unwrap_or_default();
+    let cfg = ctx.data().cfg().load();
+    let payload = NotificationPayload::test(
+        guild_id,
+        ctx.channel_id(),
+        ctx.author(),
+        cfg.notification_author_secret.as_deref(),
+    );
+    let endpoint = cfg.endpoint.clone();
+
+    let mut content = match ctx
+        .data()
+        .notification_service()
+        .send_test_notification(&payload)
+        .await
+    {
+        Ok((status, elapsed)) => {
+            let icon = if status.is_success() { "✅" } else { "⚠️" };
+            format!(
+                "{icon} 测试通知已发送到 `{endpoint}`\n状态码: `{status}`\n耗时: {} ms",
+                elapsed.as_millis()
+            )
+        }
+        Err(e @ BotError::ValidationError { .. }) => format!("❌ {}", e.user_message()),
+        Err(e) => format!("❌ 无法连接到 `{endpoint}`: {e}"),
+    };
+    if !ctx.data().cfg().load().backup_enabled {
+        content.push_str("\n💡 `backup_enabled` 当前为关闭状态，实际的备份通知不会发送。");
+    }
+
+    ctx.send(CreateReply::default().content(content).ephemeral(true))
+        .await?;
+    Ok(())
+}
+
+#[command(
+    slash_command,
+    guild_only,
+    default_member_permissions = "ADMINISTRATOR",
+    check = "check_admin",
+    name_localized("zh-CN", "验证通知端点"),
+    description_localized("zh-CN", "向通知端点发送探测请求，检查响应是否符合配置的确认格式"),
+    ephemeral
+)]
+/// Probe the notification endpoint and check its acknowledgement format
+pub async fn verify_notification_endpoint(ctx: Context<'_>) -> Result<(), BotError> {
+    ctx.defer_ephemeral().await?;
+
+    let guild_id = ctx.guild_id().unwrap_or_default();
+    let cfg = ctx.data().cfg().load();
+    let payload = NotificationPayload::probe(
+        guild_id,
+        ctx.channel_id(),
+        ctx.author(),
+        cfg.notification_author_secret.as_deref(),
+    );
+    let endpoint = cfg.endpoint.clone();
+
+    let content = match ctx
+        .data()
+        .notification_service()
+        .probe_endpoint(&payload)
+        .await
+    {
+        Ok(result) => {
+            let summary = format!(
+                "状态码: `{}`\n耗时: {} ms",
+                result.status,
+                result.elapsed.as_millis()
+            );
+            if result.mismatches.is_empty() {
+                format!("✅ 通知端点 `{endpoint}` 的响应符合确认格式\n{summary}")
+            } else {
+                let lines: Vec<String> = result
+                    .mismatches
+                    .iter()
+                    .map(|mismatch| format!("• {mismatch}"))
+                    .collect();
+                format!(
+                    "⚠️ 通知端点 `{endpoint}` 的响应不符合确认格式：\n{}\n{summary}",
+                    lines.join("\n")
+                )
+            }
+        }
+        Err(e @ BotError::ValidationError { .. }) => format!("❌ {}", e.user_message()),
+        Err(e) => format!("❌ 无法连接到 `{endpoint}`: {e}"),
+    };
+
+    ctx.send(CreateReply::default().content(content).ephemeral(true))
+        .await?;
+    Ok(())
+}
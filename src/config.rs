@@ -1,5 +1,5 @@
 use std::{
-    collections::HashSet,
+    collections::{HashMap, HashSet},
     path::{Path, PathBuf},
     sync::Arc,
 };
@@ -15,12 +15,101 @@ use reqwest::Url;
 use serde::{Deserialize, Serialize};
 use serde_with::serde_as;
 use serenity::{
-    all::{ChannelId, MessageId, RoleId, UserId},
+    all::{ChannelId, GuildId, MessageId, RoleId, UserId},
     prelude::TypeMapKey,
 };
 use snafu::ResultExt;
 
-use crate::error::BotError;
+use crate::{
+    error::BotError,
+    logging::LogFormat,
+    services::{license::MAX_LICENSES_PER_USER, published_posts::ObsoleteLicenseAction},
+    utils::Locale,
+};
+
+/// 协议数量上限的最大可配置值
+///
+/// 协议管理、批量删除、排序等菜单把用户的全部协议放在一个下拉菜单中，
+/// 而 Discord 下拉菜单最多只有 25 个选项
+pub const MAX_LICENSES_PER_USER_CAP: u64 = 25;
+
+/// 服务器专属的embed品牌设置，未配置的项沿用全局默认
+#[derive(Deserialize, Serialize, Debug, Clone, Default, PartialEq, Eq)]
+pub struct Branding {
+    /// 协议未设置颜色时使用的embed颜色（RGB）
+    #[serde(default)]
+    pub color: Option<u32>,
+    /// 附加在embed页脚末尾的文本
+    #[serde(default)]
+    pub footer_text: Option<String>,
+    /// embed缩略图链接
+    #[serde(default)]
+    pub thumbnail_url: Option<String>,
+}
+
+/// 服务器专属的数量上限，未配置的项沿用全局设置
+#[derive(Deserialize, Serialize, Debug, Clone, Default, PartialEq, Eq)]
+pub struct GuildLimits {
+    /// 每个用户最多可创建的协议数
+    #[serde(default)]
+    pub max_licenses_per_user: Option<u64>,
+}
+
+/// "创建新协议"时编辑面板的初始权限设置
+#[derive(Deserialize, Serialize, Debug, Clone, Default, PartialEq, Eq)]
+#[serde(default)]
+pub struct NewLicenseDefaults {
+    /// 是否允许二次传播
+    pub allow_redistribution: bool,
+    /// 是否允许二次修改
+    pub allow_modification: bool,
+    /// 是否允许备份
+    pub allow_backup: bool,
+    /// 默认的限制条件
+    pub restrictions_note: Option<String>,
+}
+
+/// 通知端点确认格式，`/验证通知端点` 据此检查端点的响应
+///
+/// 除 2xx 状态码外，其余各项未配置时不检查
+#[derive(Deserialize, Serialize, Debug, Clone, Default, PartialEq, Eq)]
+#[serde(default)]
+pub struct NotificationAckCfg {
+    /// 响应中必须包含的响应头
+    pub header: Option<String>,
+    /// 上述响应头的期望值（需同时配置 `header`）
+    pub header_value: Option<String>,
+    /// 响应体中必须包含的文本
+    pub body_contains: Option<String>,
+}
+
+/// 数据库连接池配置
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq, Eq)]
+#[serde(default)]
+pub struct DatabaseCfg {
+    /// 连接池的最大连接数
+    pub max_connections: u32,
+    /// 连接池保持的最小连接数
+    pub min_connections: u32,
+    /// 获取连接的超时时间（秒）
+    pub connect_timeout_secs: u64,
+    /// SQLite 数据库被锁定时的等待时间（毫秒）
+    pub busy_timeout_ms: u64,
+    /// 是否启用 WAL 日志模式
+    pub wal: bool,
+}
+
+impl Default for DatabaseCfg {
+    fn default() -> Self {
+        Self {
+            max_connections: 5,
+            min_connections: 1,
+            connect_timeout_secs: 10,
+            busy_timeout_ms: 5000,
+            wal: true,
+        }
+    }
+}
 
 #[serde_as]
 #[derive(Deserialize, Serialize, Debug, Clone)]
@@ -30,6 +119,15 @@ pub struct BotCfg {
     pub admin_role_ids: HashSet<RoleId>,
     pub backup_enabled: bool,
     pub endpoint: Url,
+    // 备份通知中帖子内容预览的最大字符数，超出时截断并追加省略号
+    #[serde(default = "default_notification_preview_chars")]
+    pub notification_preview_chars: usize,
+    // 通知载荷模板（JSON），字符串中的 {{字段路径}} 会被替换为载荷字段，不配置时发送固定结构
+    #[serde(default)]
+    pub notification_template: Option<String>,
+    // 设置后通知中的作者ID改为以此为密钥的 HMAC，并隐藏用户名，接收方只能关联而无法识别用户
+    #[serde(default)]
+    pub notification_author_secret: Option<String>,
     pub extra_admins_ids: HashSet<UserId>,
     #[serde(default)]
     pub allowed_forum_channels: HashSet<ChannelId>,
@@ -42,6 +140,78 @@ pub struct BotCfg {
     pub status_message_id: Option<MessageId>,
     #[serde(default = "default_status_update_interval")]
     pub status_update_interval_secs: u64,
+    // 审计日志频道，记录协议发布、删除和设置变更（不配置则不发送）
+    #[serde(default)]
+    pub audit_channel_id: Option<ChannelId>,
+    // 已删除帖子发布记录的清理间隔（秒），为 0 时不清理
+    #[serde(default = "default_post_sweep_interval")]
+    pub post_sweep_interval_secs: u64,
+    // 发布协议时使用的语言（非交互消息无法获取用户语言）
+    #[serde(default)]
+    pub license_locale: Locale,
+    // 附加到已发布协议的社区规则链接（不配置则不显示）
+    #[serde(default)]
+    pub community_rules_url: Option<String>,
+    // 社区默认协议（系统协议名），用户开启自动发布但未设置默认协议时使用
+    #[serde(default)]
+    pub default_guild_license_name: Option<String>,
+    // 重新发布协议时旧协议消息的处理方式，可被帖子设置覆盖
+    #[serde(default)]
+    pub obsolete_license_action: ObsoleteLicenseAction,
+    // 在已归档的帖子中发布协议时是否自动取消归档（需要管理帖子权限）
+    #[serde(default)]
+    pub auto_unarchive_threads: bool,
+    // 协议消息被取消置顶时是否自动重新置顶
+    #[serde(default)]
+    pub enforce_license_pin: bool,
+    // 各服务器的embed品牌设置（颜色、页脚、缩略图）
+    #[serde(default)]
+    pub guild_branding: HashMap<GuildId, Branding>,
+    // 是否在协议消息上添加反映备份权限的反应（💾 允许 / 🚫 不允许）
+    #[serde(default)]
+    pub backup_status_reaction: bool,
+    // 每个用户最多可创建的协议数，可被服务器设置覆盖
+    #[serde(default = "default_max_licenses_per_user")]
+    pub max_licenses_per_user: u64,
+    // 各服务器的数量上限，覆盖上面的全局设置
+    #[serde(default)]
+    pub guild_limits: HashMap<GuildId, GuildLimits>,
+    // 新用户自动发布引导的冷却时间（秒），同一用户在此期间内只会收到一次引导
+    #[serde(default = "default_guidance_cooldown_secs")]
+    pub guidance_cooldown_secs: u64,
+    // 自动发布只处理创建不超过该时长（秒）的帖子，为 0 时不检查
+    #[serde(default = "default_auto_publish_max_thread_age_secs")]
+    pub auto_publish_max_thread_age_secs: u64,
+    // 自动发布流程结束后是否清理流程中发送的临时消息
+    #[serde(default = "default_cleanup_flow_followups")]
+    pub cleanup_flow_followups: bool,
+    // 批量设置备份权限时同时处理的帖子数，至少为 1
+    #[serde(default = "default_bulk_backup_concurrency")]
+    pub bulk_backup_concurrency: usize,
+    // 系统协议文件修改后是否自动重载（无需手动执行 /重载系统授权）
+    #[serde(default)]
+    pub auto_reload_system_licenses: bool,
+    // 启动时是否自动执行数据库迁移；关闭时若存在待应用迁移则拒绝启动
+    #[serde(default = "default_auto_migrate")]
+    pub auto_migrate: bool,
+    // 启动自检存在严重问题时是否拒绝启动
+    #[serde(default)]
+    pub startup_check_strict: bool,
+    // 健康检查 HTTP 服务端口（/healthz、/readyz），未设置时不启动
+    #[serde(default)]
+    pub health_port: Option<u16>,
+    // 新建协议的默认权限设置
+    #[serde(default)]
+    pub new_license_defaults: NewLicenseDefaults,
+    // 日志输出格式：text（文本）或 json（每行一个 JSON 对象）
+    #[serde(default)]
+    pub log_format: LogFormat,
+    // 数据库连接池配置
+    #[serde(default)]
+    pub database: DatabaseCfg,
+    // 通知端点的确认格式，用于 /验证通知端点
+    #[serde(default)]
+    pub notification_ack: NotificationAckCfg,
     #[serde(skip)]
     pub path: PathBuf,
     #[serde(skip)]
@@ -52,13 +222,45 @@ fn default_status_update_interval() -> u64 {
     60 // 默认60秒更新一次
 }
 
+fn default_post_sweep_interval() -> u64 {
+    21600 // 默认6小时
+}
+
+fn default_notification_preview_chars() -> usize {
+    100
+}
+
+fn default_max_licenses_per_user() -> u64 {
+    MAX_LICENSES_PER_USER
+}
+
+fn default_guidance_cooldown_secs() -> u64 {
+    3600 // 默认1小时
+}
+
+fn default_auto_publish_max_thread_age_secs() -> u64 {
+    300 // 默认5分钟
+}
+
+fn default_cleanup_flow_followups() -> bool {
+    true
+}
+
+fn default_bulk_backup_concurrency() -> usize {
+    3
+}
+
+fn default_auto_migrate() -> bool {
+    true
+}
+
 impl TypeMapKey for BotCfg {
     type Value = Arc<ArcSwap<BotCfg>>;
 }
 
 impl BotCfg {
     pub fn read(path: impl AsRef<Path>) -> Result<Self, BotError> {
-        Ok(Self {
+        let cfg = Self {
             path: path.as_ref().to_owned(),
             bot_start_time: Utc::now(),
             ..Figment::new()
@@ -66,7 +268,52 @@ impl BotCfg {
                 .merge(Env::prefixed("DOG_BOT_"))
                 .extract_lossy()
                 .whatever_context::<&str, BotError>("Failed to read bot configuration")?
-        })
+        };
+        cfg.validate()?;
+        Ok(cfg)
+    }
+
+    /// 检查无法通过反序列化约束的配置项
+    fn validate(&self) -> Result<(), BotError> {
+        let guild_caps = self.guild_limits.iter().filter_map(|(guild_id, limits)| {
+            limits.max_licenses_per_user.map(|max| {
+                (
+                    format!("guild_limits.{guild_id}.max_licenses_per_user"),
+                    max,
+                )
+            })
+        });
+        for (key, max) in std::iter::once((
+            "max_licenses_per_user".to_string(),
+            self.max_licenses_per_user,
+        ))
+        .chain(guild_caps)
+        {
+            if max > MAX_LICENSES_PER_USER_CAP {
+                return Err(BotError::ConfigError {
+                    message: format!(
+                        "{key} 为 {max}，不能超过 {MAX_LICENSES_PER_USER_CAP}（Discord 下拉菜单的选项上限）"
+                    ),
+                    loc: snafu::location!(),
+                });
+            }
+        }
+        Ok(())
+    }
+
+    /// 获取服务器的品牌设置，未配置或不在服务器中时返回 `None`
+    pub fn branding_for(&self, guild_id: Option<GuildId>) -> Option<&Branding> {
+        guild_id.and_then(|id| self.guild_branding.get(&id))
+    }
+
+    /// 用户在指定服务器中最多可创建的协议数
+    ///
+    /// 优先使用服务器设置，其次为全局设置；没有服务器上下文（如 gRPC）时使用全局设置
+    pub fn max_licenses_for(&self, guild_id: Option<GuildId>) -> u64 {
+        guild_id
+            .and_then(|id| self.guild_limits.get(&id))
+            .and_then(|limits| limits.max_licenses_per_user)
+            .unwrap_or(self.max_licenses_per_user)
     }
 
     pub fn write(&self) -> Result<(), BotError> {
@@ -76,3 +323,49 @@ impl BotCfg {
             .whatever_context("Failed to write configuration file")
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_max_licenses_for() {
+        let mut cfg: BotCfg = toml::from_str(
+            r#"
+            time_offset = 0
+            token = "token"
+            admin_role_ids = []
+            backup_enabled = false
+            endpoint = "http://localhost"
+            extra_admins_ids = []
+
+            [guild_limits.1]
+            max_licenses_per_user = 10
+
+            [guild_limits.2]
+            "#,
+        )
+        .unwrap();
+        assert_eq!(cfg.max_licenses_for(None), MAX_LICENSES_PER_USER);
+
+        // 服务器设置优先，未设置上限的服务器和未配置的服务器沿用全局设置
+        cfg.max_licenses_per_user = 3;
+        assert_eq!(cfg.max_licenses_for(Some(GuildId::new(1))), 10);
+        assert_eq!(cfg.max_licenses_for(Some(GuildId::new(2))), 3);
+        assert_eq!(cfg.max_licenses_for(Some(GuildId::new(3))), 3);
+        assert_eq!(cfg.max_licenses_for(None), 3);
+        assert!(cfg.validate().is_ok());
+
+        // 超过下拉菜单选项上限的全局或服务器设置都会被拒绝
+        cfg.max_licenses_per_user = MAX_LICENSES_PER_USER_CAP + 1;
+        assert!(matches!(cfg.validate(), Err(BotError::ConfigError { .. })));
+        cfg.max_licenses_per_user = 3;
+        cfg.guild_limits.insert(
+            GuildId::new(4),
+            GuildLimits {
+                max_licenses_per_user: Some(MAX_LICENSES_PER_USER_CAP + 1),
+            },
+        );
+        assert!(matches!(cfg.validate(), Err(BotError::ConfigError { .. })));
+    }
+}
@@ -1,9 +1,23 @@
-use std::path::Path;
+use std::{future::Future, path::Path, pin::Pin, time::Duration};
 
-use sea_orm::{ConnectionTrait, Database, DatabaseConnection, DbBackend, Statement};
+use migration::{Migrator, MigratorTrait};
+use sea_orm::{
+    ConnectionTrait, Database, DatabaseConnection, DatabaseTransaction, DbBackend,
+    SqlxSqliteConnector, Statement, TransactionError, TransactionTrait,
+    sqlx::sqlite::{SqliteConnectOptions, SqliteJournalMode, SqlitePoolOptions},
+};
 use serenity::prelude::TypeMapKey;
 
-use crate::error::BotError;
+use crate::{config::DatabaseCfg, error::BotError};
+
+/// 单个数据表的行数和占用空间
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TableStats {
+    pub name: String,
+    pub rows: u64,
+    /// 表及其索引占用的字节数
+    pub size: i64,
+}
 
 #[derive(Debug, Clone)]
 pub struct BotDatabase {
@@ -15,13 +29,35 @@ impl TypeMapKey for BotDatabase {
 }
 
 impl BotDatabase {
-    pub async fn new(path: impl AsRef<Path>) -> Result<Self, BotError> {
-        let database_url = format!("sqlite://{}", path.as_ref().display());
-        let db = Database::connect(&database_url).await?;
+    /// 按配置建立连接池
+    ///
+    /// 启用 WAL 与忙等待超时，减少网关和事件处理并发写入时的锁冲突
+    pub async fn new(path: impl AsRef<Path>, cfg: &DatabaseCfg) -> Result<Self, BotError> {
+        let options = SqliteConnectOptions::new()
+            .filename(path)
+            .busy_timeout(Duration::from_millis(cfg.busy_timeout_ms))
+            .journal_mode(if cfg.wal {
+                SqliteJournalMode::Wal
+            } else {
+                SqliteJournalMode::Delete
+            });
+        let pool = SqlitePoolOptions::new()
+            .max_connections(cfg.max_connections.max(1))
+            .min_connections(cfg.min_connections.min(cfg.max_connections))
+            .acquire_timeout(Duration::from_secs(cfg.connect_timeout_secs))
+            .connect_with(options)
+            .await
+            .map_err(|e| BotError::DatabaseError {
+                message: format!("Failed to connect to database: {e}"),
+                loc: snafu::location!(),
+            })?;
 
-        Ok(BotDatabase { db })
+        Ok(BotDatabase {
+            db: SqlxSqliteConnector::from_sqlx_sqlite_pool(pool),
+        })
     }
 
+    /// 内存数据库，每个连接都是独立的数据库，因此只使用单个连接
     pub async fn new_memory() -> Result<Self, BotError> {
         let db = Database::connect("sqlite::memory:").await?;
         Ok(BotDatabase { db })
@@ -31,6 +67,24 @@ impl BotDatabase {
         &self.db
     }
 
+    /// 在事务中执行 `f`，返回 `Ok` 时提交，返回错误时回滚
+    ///
+    /// 服务可通过 `LicenseService::new(txn)` 等方式在事务上使用
+    pub async fn transaction<T, F>(&self, f: F) -> Result<T, BotError>
+    where
+        F: for<'c> FnOnce(
+                &'c DatabaseTransaction,
+            )
+                -> Pin<Box<dyn Future<Output = Result<T, BotError>> + Send + 'c>>
+            + Send,
+        T: Send,
+    {
+        self.db.transaction(f).await.map_err(|e| match e {
+            TransactionError::Connection(e) => e.into(),
+            TransactionError::Transaction(e) => e,
+        })
+    }
+
     pub async fn size(&self) -> Result<i64, BotError> {
         let stmt = Statement::from_string(
             DbBackend::Sqlite,
@@ -45,4 +99,220 @@ impl BotDatabase {
             Ok(0)
         }
     }
+
+    /// 统计每个数据表的行数和占用空间，按占用空间从大到小排列
+    ///
+    /// 自动包含迁移新增的表；占用空间通过 `dbstat` 虚拟表统计，包含表的索引
+    pub async fn table_stats(&self) -> Result<Vec<TableStats>, BotError> {
+        let rows = self
+            .db
+            .query_all(Statement::from_string(
+                DbBackend::Sqlite,
+                "SELECT m.name AS name, \
+                 (SELECT COALESCE(SUM(s.pgsize), 0) FROM dbstat s \
+                  JOIN sqlite_master i ON i.name = s.name \
+                  WHERE i.tbl_name = m.name) AS size \
+                 FROM sqlite_master m \
+                 WHERE m.type = 'table' AND m.name NOT LIKE 'sqlite_%'",
+            ))
+            .await?;
+
+        let mut stats = Vec::with_capacity(rows.len());
+        for row in rows {
+            let name: String = row.try_get("", "name")?;
+            let size: i64 = row.try_get("", "size")?;
+            let count = self
+                .db
+                .query_one(Statement::from_string(
+                    DbBackend::Sqlite,
+                    format!(
+                        "SELECT COUNT(*) AS count FROM \"{}\"",
+                        name.replace('"', "\"\"")
+                    ),
+                ))
+                .await?;
+            let rows = match count {
+                Some(row) => row.try_get::<i64>("", "count")? as u64,
+                None => 0,
+            };
+            stats.push(TableStats { name, rows, size });
+        }
+        stats.sort_by(|a, b| b.size.cmp(&a.size).then_with(|| a.name.cmp(&b.name)));
+        Ok(stats)
+    }
+
+    /// 执行 `VACUUM` 回收已删除数据占用的空间，可选执行 `PRAGMA optimize`
+    ///
+    /// `VACUUM` 会重建整个数据库文件，执行期间数据库会被短暂锁定。
+    pub async fn vacuum(&self, optimize: bool) -> Result<(), BotError> {
+        self.db.execute_unprepared("VACUUM").await?;
+        // WAL 模式下截断日志文件，释放磁盘空间
+        self.db
+            .execute_unprepared("PRAGMA wal_checkpoint(TRUNCATE)")
+            .await?;
+        if optimize {
+            self.db.execute_unprepared("PRAGMA optimize").await?;
+        }
+        Ok(())
+    }
+
+    /// 获取尚未应用的数据库迁移名称
+    pub async fn pending_migrations(&self) -> Result<Vec<String>, BotError> {
+        Ok(Migrator::get_pending_migrations(&self.db)
+            .await?
+            .iter()
+            .map(|m| m.name().to_string())
+            .collect())
+    }
+
+    /// 检查并执行待应用的数据库迁移，返回本次应用的迁移名称
+    ///
+    /// `auto_migrate` 为 `false` 时不执行迁移，存在待应用迁移则直接返回错误，
+    /// 避免在结构不匹配的数据库上运行。
+    pub async fn apply_migrations(&self, auto_migrate: bool) -> Result<Vec<String>, BotError> {
+        let pending = self.pending_migrations().await?;
+
+        if pending.is_empty() {
+            return Ok(pending);
+        }
+
+        if !auto_migrate {
+            return Err(BotError::DatabaseError {
+                message: format!(
+                    "存在 {} 个待应用的数据库迁移且未启用自动迁移: {}",
+                    pending.len(),
+                    pending.join(", ")
+                ),
+                loc: snafu::location!(),
+            });
+        }
+
+        Migrator::up(&self.db, None).await?;
+        Ok(pending)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::services::license::LicenseFields;
+
+    #[tokio::test]
+    async fn test_apply_migrations_fails_when_disabled() {
+        let db = BotDatabase::new_memory().await.unwrap();
+
+        let result = db.apply_migrations(false).await;
+        assert!(matches!(result, Err(BotError::DatabaseError { .. })));
+    }
+
+    #[tokio::test]
+    async fn test_apply_migrations_runs_pending() {
+        let db = BotDatabase::new_memory().await.unwrap();
+
+        let applied = db.apply_migrations(true).await.unwrap();
+        assert_eq!(applied.len(), Migrator::migrations().len());
+
+        // 已是最新，无论是否启用自动迁移都不再有待应用的迁移
+        assert!(db.apply_migrations(true).await.unwrap().is_empty());
+        assert!(db.apply_migrations(false).await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_new_applies_pool_options() {
+        let path = std::env::temp_dir().join(format!("dc-bot-test-{}.db", std::process::id()));
+        std::fs::File::create(&path).unwrap();
+
+        let db = BotDatabase::new(&path, &DatabaseCfg::default())
+            .await
+            .unwrap();
+        let row = db
+            .inner()
+            .query_one(Statement::from_string(
+                DbBackend::Sqlite,
+                "PRAGMA journal_mode",
+            ))
+            .await
+            .unwrap()
+            .unwrap();
+        let journal_mode: String = row.try_get("", "journal_mode").unwrap();
+        assert_eq!(journal_mode, "wal");
+
+        drop(db);
+        for suffix in ["", "-wal", "-shm"] {
+            let _ = std::fs::remove_file(format!("{}{suffix}", path.display()));
+        }
+    }
+
+    #[tokio::test]
+    async fn test_vacuum_reclaims_space() {
+        let db = BotDatabase::new_memory().await.unwrap();
+        db.apply_migrations(true).await.unwrap();
+
+        let service = db.license();
+        // 每个用户最多5个协议，使用多个用户写入足够的数据
+        let mut created = Vec::new();
+        for i in 0..20 {
+            let user_id = serenity::all::UserId::new(i + 1);
+            let license = service
+                .create(
+                    user_id,
+                    LicenseFields {
+                        allow_redistribution: true,
+                        allow_modification: true,
+                        restrictions_note: Some("限".repeat(2000)),
+                        ..LicenseFields::new(format!("协议{i}"))
+                    },
+                )
+                .await
+                .unwrap();
+            created.push((license.id, user_id));
+        }
+        for (id, user_id) in created {
+            service.delete(id, user_id).await.unwrap();
+        }
+
+        let before = db.size().await.unwrap();
+        db.vacuum(true).await.unwrap();
+        assert!(db.size().await.unwrap() < before);
+    }
+
+    #[tokio::test]
+    async fn test_table_stats() {
+        let db = BotDatabase::new_memory().await.unwrap();
+        db.apply_migrations(true).await.unwrap();
+
+        let user_id = serenity::all::UserId::new(1);
+        for i in 0..3 {
+            db.license()
+                .create(
+                    user_id,
+                    LicenseFields {
+                        allow_redistribution: true,
+                        allow_modification: true,
+                        ..LicenseFields::new(format!("协议{i}"))
+                    },
+                )
+                .await
+                .unwrap();
+        }
+        db.published_posts()
+            .record(
+                serenity::all::ChannelId::new(10),
+                serenity::all::MessageId::new(11),
+                user_id,
+                true,
+            )
+            .await
+            .unwrap();
+
+        let stats = db.table_stats().await.unwrap();
+        let rows = |name: &str| stats.iter().find(|t| t.name == name).unwrap().rows;
+        assert_eq!(rows("user_licenses"), 3);
+        assert_eq!(rows("published_posts"), 1);
+        assert_eq!(rows("user_settings"), 0);
+        // 迁移记录表同样会被统计，每个表至少占用一页
+        assert!(rows("seaql_migrations") > 0);
+        assert!(stats.iter().all(|t| t.size > 0));
+        assert!(stats.windows(2).all(|w| w[0].size >= w[1].size));
+    }
 }
@@ -93,6 +93,12 @@ pub enum BotError {
         #[snafu(implicit)]
         loc: Location,
     },
+    #[snafu(display("您最多只能创建{max}个协议，请先删除一些协议。"))]
+    LicenseLimit {
+        max: u64,
+        #[snafu(implicit)]
+        loc: Location,
+    },
     #[snafu(display("操作超时: {}", message))]
     TimeoutError {
         message: String,
@@ -121,14 +127,24 @@ impl BotError {
             BotError::ConfigError { .. } => "系统配置出现问题，请联系管理员".to_string(),
             BotError::IoError { .. } => "文件操作出现问题，请稍后再试".to_string(),
             BotError::NotFoundError { .. } => "未找到相关内容".to_string(),
-            BotError::AuthorizationError { .. } => "您没有权限执行此操作".to_string(),
+            BotError::AuthorizationError { message, .. } => message.clone(),
             BotError::RateLimitError { .. } => "操作太频繁，请稍后再试".to_string(),
             BotError::TimeoutError { .. } => "操作超时，请稍后再试".to_string(),
+            BotError::LicenseLimit { .. } => self.to_string(),
             BotError::GenericError { .. } => "操作失败，请稍后再试".to_string(),
+            BotError::SerenityError { .. } if self.is_discord_outage() => {
+                "Discord 服务当前不稳定，请稍后再试".to_string()
+            }
             _ => "发生未知错误，请稍后再试".to_string(),
         }
     }
 
+    /// 是否因为 Discord 服务不可用（网络错误或 5xx）而失败
+    pub fn is_discord_outage(&self) -> bool {
+        matches!(self, BotError::SerenityError { source, .. }
+            if crate::services::discord_health::is_outage_error(source))
+    }
+
     /// 返回针对特定操作的错误消息
     pub fn operation_message(&self, operation: &str) -> String {
         match (operation, self) {
@@ -138,6 +154,12 @@ impl BotError {
             ("reload_licenses", BotError::SerdeError { .. }) => {
                 "协议文件格式错误，请检查文件格式".to_string()
             }
+            ("import_spdx", BotError::IoError { .. }) => {
+                "协议文件读写失败，请检查文件是否存在且可写".to_string()
+            }
+            ("import_spdx", BotError::SerdeError { .. }) => {
+                "协议文件格式错误，无法追加导入的协议".to_string()
+            }
             _ => self.user_message(),
         }
     }
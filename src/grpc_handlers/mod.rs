@@ -3,24 +3,43 @@ pub mod user_license_handler;
 pub mod user_settings_handler;
 
 use crate::config::BotCfg;
-use crate::services::gateway::registry::ForwardRequest;
+use crate::services::gateway::{GatewayStatus, registry::ForwardRequest};
+use arc_swap::ArcSwap;
 use sea_orm::DatabaseConnection;
-use tracing::{debug, error, info};
+use tracing::{Instrument, Span, debug, error, field, info, info_span};
 
 // gRPC 方法路由器
+//
+// 每个请求都在独立的 span 中处理，处理函数的日志会嵌套在该 span 下
 pub async fn handle_grpc_request(
     request: &ForwardRequest,
     db: &DatabaseConnection,
     cfg: &BotCfg,
+    gateway_status: &ArcSwap<GatewayStatus>,
+) -> Result<Vec<u8>, Box<dyn std::error::Error + Send + Sync>> {
+    let span = info_span!(
+        "grpc_request",
+        request_id = %request.request_id,
+        method_path = %request.method_path,
+        payload_len = request.payload.len(),
+        error = field::Empty,
+    );
+
+    dispatch_grpc_request(request, db, cfg, gateway_status)
+        .instrument(span)
+        .await
+}
+
+async fn dispatch_grpc_request(
+    request: &ForwardRequest,
+    db: &DatabaseConnection,
+    cfg: &BotCfg,
+    gateway_status: &ArcSwap<GatewayStatus>,
 ) -> Result<Vec<u8>, Box<dyn std::error::Error + Send + Sync>> {
     let method_path = &request.method_path;
     let payload = &request.payload;
 
-    info!(
-        "Handling gRPC request: {} (length: {})",
-        method_path,
-        method_path.len()
-    );
+    info!("Handling gRPC request");
     debug!("Method path bytes: {:?}", method_path.as_bytes());
 
     // 移除可能的前导斜杠
@@ -32,7 +51,12 @@ pub async fn handle_grpc_request(
         // 用户许可证管理
         "LicenseManagementService.license_management/CreateUserLicense" => {
             debug!("Matched CreateUserLicense");
-            user_license_handler::handle_create_user_license(payload, db).await
+            user_license_handler::handle_create_user_license(
+                payload,
+                db,
+                cfg.max_licenses_for(None),
+            )
+            .await
         }
         "LicenseManagementService.license_management/GetUserLicenses" => {
             debug!("Matched GetUserLicenses");
@@ -50,6 +74,18 @@ pub async fn handle_grpc_request(
             debug!("Matched IncrementUsageCount");
             user_license_handler::handle_increment_usage_count(payload, db).await
         }
+        "LicenseManagementService.license_management/DecrementUsageCount" => {
+            debug!("Matched DecrementUsageCount");
+            user_license_handler::handle_decrement_usage_count(payload, db).await
+        }
+        "LicenseManagementService.license_management/SetUsageCount" => {
+            debug!("Matched SetUsageCount");
+            user_license_handler::handle_set_usage_count(payload, db).await
+        }
+        "LicenseManagementService.license_management/ResetUsageCount" => {
+            debug!("Matched ResetUsageCount");
+            user_license_handler::handle_reset_usage_count(payload, db).await
+        }
 
         // 用户设置管理
         "LicenseManagementService.license_management/GetUserSettings" => {
@@ -64,10 +100,11 @@ pub async fn handle_grpc_request(
         // 系统状态
         "LicenseManagementService.license_management/Ping" => {
             debug!("Matched Ping");
-            system_handler::handle_ping(payload, cfg).await
+            system_handler::handle_ping(payload, cfg, gateway_status).await
         }
 
         _ => {
+            Span::current().record("error", true);
             error!("Unknown gRPC method: {}", method_path);
             Err(format!("Unknown method: {}", method_path).into())
         }
@@ -1,7 +1,8 @@
-use crate::config::BotCfg;
+use crate::{config::BotCfg, services::gateway::GatewayStatus};
+use arc_swap::ArcSwap;
 use chrono::Utc;
 use prost::Message;
-use tracing::info;
+use tracing::{debug, info, instrument};
 
 // 包含生成的 protobuf 代码
 pub mod license_management {
@@ -9,19 +10,42 @@ pub mod license_management {
 }
 use license_management::*;
 
+/// 根据客户端时间戳估算往返延迟（毫秒）
+///
+/// 以单程延迟的两倍估算，依赖双方时钟同步；
+/// 时间戳为 0（旧版客户端）或时钟偏差导致结果为负时返回 `None`。
+fn estimate_rtt_ms(client_timestamp_ms: i64, server_timestamp_ms: i64) -> Option<i64> {
+    if client_timestamp_ms == 0 {
+        return None;
+    }
+    let one_way = server_timestamp_ms.checked_sub(client_timestamp_ms)?;
+    (one_way >= 0).then(|| one_way.saturating_mul(2))
+}
+
+#[instrument(skip_all)]
 pub async fn handle_ping(
     payload: &[u8],
     cfg: &BotCfg,
+    gateway_status: &ArcSwap<GatewayStatus>,
 ) -> Result<Vec<u8>, Box<dyn std::error::Error + Send + Sync>> {
-    let _request = PingRequest::decode(payload)?;
+    let request = PingRequest::decode(payload)?;
     info!("Ping request received");
 
-    let uptime_seconds = (Utc::now() - cfg.bot_start_time).num_seconds();
+    let now = Utc::now();
+    let server_timestamp_ms = now.timestamp_millis();
+    if let Some(rtt_ms) = estimate_rtt_ms(request.client_timestamp_ms, server_timestamp_ms) {
+        debug!("Estimated gateway RTT: {} ms", rtt_ms);
+        GatewayStatus::record_ping_rtt(gateway_status, rtt_ms);
+    }
+
+    let uptime_seconds = (now - cfg.bot_start_time).num_seconds();
 
     let response = PingResponse {
         status: "healthy".to_string(),
         version: env!("CARGO_PKG_VERSION").to_string(),
         uptime_seconds,
+        client_timestamp_ms: request.client_timestamp_ms,
+        server_timestamp_ms,
     };
 
     let mut buf = Vec::new();
@@ -29,3 +53,17 @@ pub async fn handle_ping(
     info!("Ping response sent");
     Ok(buf)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_estimate_rtt() {
+        assert_eq!(estimate_rtt_ms(0, 1_000), None);
+        assert_eq!(estimate_rtt_ms(1_000, 1_025), Some(50));
+        assert_eq!(estimate_rtt_ms(1_000, 1_000), Some(0));
+        // 时钟偏差导致服务端时间早于客户端
+        assert_eq!(estimate_rtt_ms(1_000, 900), None);
+    }
+}
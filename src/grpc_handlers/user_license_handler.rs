@@ -3,7 +3,7 @@ use prost::Message;
 use sea_orm::{DatabaseConnection, EntityTrait};
 use serenity::all::UserId;
 use std::io;
-use tracing::{debug, info};
+use tracing::{debug, info, instrument};
 
 // 包含生成的 protobuf 代码
 pub mod license_management {
@@ -11,11 +11,55 @@ pub mod license_management {
 }
 use license_management::*;
 
-use crate::services::license::LicenseService;
+use crate::services::license::{LicenseFields, LicenseService, LicenseSource};
+
+/// 计算协议内容的哈希（64 位 FNV-1a），结果在不同进程和版本间保持稳定
+///
+/// 只包含协议条款相关的字段，ID、所有者、使用次数和创建时间不影响哈希
+fn content_hash(model: &user_licenses::Model) -> String {
+    const OFFSET_BASIS: u64 = 0xcbf2_9ce4_8422_2325;
+    const PRIME: u64 = 0x0000_0100_0000_01b3;
+
+    // 变长字段前写入长度，可选字段前写入是否存在，避免不同字段组合拼接出相同的字节
+    let mut bytes = Vec::new();
+    let push_str = |bytes: &mut Vec<u8>, value: &str| {
+        bytes.extend_from_slice(&(value.len() as u64).to_le_bytes());
+        bytes.extend_from_slice(value.as_bytes());
+    };
+    push_str(&mut bytes, &model.license_name);
+    bytes.extend([
+        u8::from(model.allow_redistribution),
+        u8::from(model.allow_modification),
+        u8::from(model.allow_backup),
+        u8::from(model.allow_commercial),
+        u8::from(model.require_attribution),
+    ]);
+    match &model.restrictions_note {
+        Some(note) => {
+            bytes.push(1);
+            push_str(&mut bytes, note);
+        }
+        None => bytes.push(0),
+    }
+    match model.color {
+        Some(color) => {
+            bytes.push(1);
+            bytes.extend_from_slice(&color.to_le_bytes());
+        }
+        None => bytes.push(0),
+    }
+
+    let hash = bytes.iter().fold(OFFSET_BASIS, |hash, byte| {
+        (hash ^ u64::from(*byte)).wrapping_mul(PRIME)
+    });
+    format!("{hash:016x}")
+}
 
 // 辅助函数：将 SeaORM 模型转换为 Protobuf 消息
 fn to_proto_user_license(model: user_licenses::Model) -> UserLicense {
+    let content_hash = content_hash(&model);
     UserLicense {
+        content_hash,
         id: model.id,
         user_id: model.user_id,
         license_name: model.license_name,
@@ -23,6 +67,9 @@ fn to_proto_user_license(model: user_licenses::Model) -> UserLicense {
         allow_modification: model.allow_modification,
         restrictions_note: model.restrictions_note,
         allow_backup: model.allow_backup,
+        allow_commercial: model.allow_commercial,
+        require_attribution: model.require_attribution,
+        color: model.color,
         usage_count: model.usage_count,
         created_at: Some(prost_types::Timestamp {
             seconds: model.created_at.timestamp(),
@@ -31,9 +78,11 @@ fn to_proto_user_license(model: user_licenses::Model) -> UserLicense {
     }
 }
 
+#[instrument(skip_all)]
 pub async fn handle_create_user_license(
     payload: &[u8],
     db: &DatabaseConnection,
+    max_licenses: u64,
 ) -> Result<Vec<u8>, Box<dyn std::error::Error + Send + Sync>> {
     let request = CreateUserLicenseRequest::decode(payload)?;
     info!(
@@ -48,19 +97,29 @@ pub async fn handle_create_user_license(
         allow_modification,
         restrictions_note,
         allow_backup,
+        allow_commercial,
+        require_attribution,
+        color,
     } = request;
 
-    let service = LicenseService::new(db);
+    // gRPC 请求没有服务器上下文，使用全局上限
+    let service = LicenseService::new(db).with_max_licenses(max_licenses);
     let user_id = UserId::new(user_id as u64);
 
     let result = match service
-        .create(
+        .create_with_source(
             user_id,
-            license_name,
-            allow_redistribution,
-            allow_modification,
-            restrictions_note,
-            allow_backup,
+            LicenseFields {
+                license_name,
+                allow_redistribution,
+                allow_modification,
+                restrictions_note,
+                allow_backup,
+                allow_commercial,
+                require_attribution,
+                color,
+            },
+            LicenseSource::Grpc,
         )
         .await
     {
@@ -76,6 +135,7 @@ pub async fn handle_create_user_license(
     Ok(buf)
 }
 
+#[instrument(skip_all)]
 pub async fn handle_get_user_licenses(
     payload: &[u8],
     db: &DatabaseConnection,
@@ -86,19 +146,30 @@ pub async fn handle_get_user_licenses(
     let service = LicenseService::new(db);
     let user_id = UserId::new(request.user_id as u64);
 
-    let licenses = match service.get_user_licenses(user_id).await {
-        Ok(models) => models,
-        Err(e) => return Err(Box::new(e)),
+    // 未设置分页字段时保持原有行为，返回全部许可证
+    let licenses = if request.offset.is_none() && request.limit.is_none() {
+        service.get_user_licenses(user_id).await?
+    } else {
+        service
+            .get_user_licenses_page(
+                user_id,
+                request.offset.unwrap_or(0).into(),
+                request.limit.map(u64::from),
+            )
+            .await?
     };
+    let total = service.get_user_license_count(user_id).await?;
 
     info!(
-        "Found {} licenses for user {}",
+        "Found {} of {} licenses for user {}",
         licenses.len(),
+        total,
         request.user_id
     );
 
     let response = GetUserLicensesResponse {
         licenses: licenses.into_iter().map(to_proto_user_license).collect(),
+        total,
     };
 
     info!("Created response with {} licenses", response.licenses.len());
@@ -114,6 +185,7 @@ pub async fn handle_get_user_licenses(
     Ok(buf)
 }
 
+#[instrument(skip_all)]
 pub async fn handle_update_user_license(
     payload: &[u8],
     db: &DatabaseConnection,
@@ -149,16 +221,28 @@ pub async fn handle_update_user_license(
         None => existing.restrictions_note.clone(),
     };
     let new_allow_backup = request.allow_backup.unwrap_or(existing.allow_backup);
+    let new_allow_commercial = request
+        .allow_commercial
+        .unwrap_or(existing.allow_commercial);
+    let new_require_attribution = request
+        .require_attribution
+        .unwrap_or(existing.require_attribution);
+    let new_color = request.color.or(existing.color);
 
     let updated = match service
         .update(
             request.id,
             user_id,
-            new_name,
-            new_allow_redistribution,
-            new_allow_modification,
-            new_restrictions_note,
-            new_allow_backup,
+            LicenseFields {
+                license_name: new_name,
+                allow_redistribution: new_allow_redistribution,
+                allow_modification: new_allow_modification,
+                restrictions_note: new_restrictions_note,
+                allow_backup: new_allow_backup,
+                allow_commercial: new_allow_commercial,
+                require_attribution: new_require_attribution,
+                color: new_color,
+            },
         )
         .await
     {
@@ -179,6 +263,7 @@ pub async fn handle_update_user_license(
     Ok(buf)
 }
 
+#[instrument(skip_all)]
 pub async fn handle_delete_user_license(
     payload: &[u8],
     db: &DatabaseConnection,
@@ -220,22 +305,31 @@ pub async fn handle_delete_user_license(
     Ok(buf)
 }
 
-pub async fn handle_increment_usage_count(
-    payload: &[u8],
+/// 按 ID 查找许可证，不存在时返回 NotFound
+async fn find_license(
     db: &DatabaseConnection,
-) -> Result<Vec<u8>, Box<dyn std::error::Error + Send + Sync>> {
-    let request = IncrementUsageRequest::decode(payload)?;
-    info!("Incrementing usage count for license {}", request.id);
-
-    let license = user_licenses::Entity::find_by_id(request.id)
+    id: i32,
+) -> Result<user_licenses::Model, Box<dyn std::error::Error + Send + Sync>> {
+    Ok(user_licenses::Entity::find_by_id(id)
         .one(db)
         .await?
         .ok_or_else(|| {
             io::Error::new(
                 io::ErrorKind::NotFound,
-                format!("License with ID {} not found", request.id),
+                format!("License with ID {id} not found"),
             )
-        })?;
+        })?)
+}
+
+#[instrument(skip_all)]
+pub async fn handle_increment_usage_count(
+    payload: &[u8],
+    db: &DatabaseConnection,
+) -> Result<Vec<u8>, Box<dyn std::error::Error + Send + Sync>> {
+    let request = IncrementUsageRequest::decode(payload)?;
+    info!("Incrementing usage count for license {}", request.id);
+
+    let license = find_license(db, request.id).await?;
 
     let service = LicenseService::new(db);
     let user_id = UserId::new(license.user_id as u64);
@@ -253,11 +347,88 @@ pub async fn handle_increment_usage_count(
     Ok(buf)
 }
 
+#[instrument(skip_all)]
+pub async fn handle_decrement_usage_count(
+    payload: &[u8],
+    db: &DatabaseConnection,
+) -> Result<Vec<u8>, Box<dyn std::error::Error + Send + Sync>> {
+    let request = DecrementUsageRequest::decode(payload)?;
+    info!("Decrementing usage count for license {}", request.id);
+
+    let license = find_license(db, request.id).await?;
+    let service = LicenseService::new(db);
+    let user_id = UserId::new(license.user_id as u64);
+    service.decrement_usage(request.id, user_id).await?;
+
+    let response = DecrementUsageResponse {
+        new_usage_count: (license.usage_count - 1).max(0),
+    };
+    let mut buf = Vec::new();
+    response.encode(&mut buf)?;
+    Ok(buf)
+}
+
+#[instrument(skip_all)]
+pub async fn handle_set_usage_count(
+    payload: &[u8],
+    db: &DatabaseConnection,
+) -> Result<Vec<u8>, Box<dyn std::error::Error + Send + Sync>> {
+    let request = SetUsageCountRequest::decode(payload)?;
+    info!(
+        "Setting usage count for license {} to {}",
+        request.id, request.usage_count
+    );
+
+    let license = find_license(db, request.id).await?;
+    let service = LicenseService::new(db);
+    let user_id = UserId::new(license.user_id as u64);
+    service
+        .set_usage(request.id, user_id, request.usage_count)
+        .await?;
+
+    let response = SetUsageCountResponse {
+        new_usage_count: request.usage_count.max(0),
+    };
+    let mut buf = Vec::new();
+    response.encode(&mut buf)?;
+    Ok(buf)
+}
+
+#[instrument(skip_all)]
+pub async fn handle_reset_usage_count(
+    payload: &[u8],
+    db: &DatabaseConnection,
+) -> Result<Vec<u8>, Box<dyn std::error::Error + Send + Sync>> {
+    let request = ResetUsageRequest::decode(payload)?;
+    let user_id = UserId::new(request.user_id as u64);
+    let service = LicenseService::new(db);
+
+    // 只重置属于该用户的许可证
+    let reset_count = match request.id {
+        Some(id) => {
+            info!(
+                "Resetting usage count for license {} of user {}",
+                id, user_id
+            );
+            u64::from(service.reset_usage(id, user_id).await?)
+        }
+        None => {
+            info!("Resetting usage count for all licenses of user {}", user_id);
+            service.reset_all_usage(user_id).await?
+        }
+    };
+
+    let response = ResetUsageResponse { reset_count };
+    let mut buf = Vec::new();
+    response.encode(&mut buf)?;
+    Ok(buf)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use crate::database::BotDatabase;
-    use crate::services::license::LicenseService;
+    use crate::services::license::{LicenseService, MAX_LICENSES_PER_USER};
     use migration::{Migrator, MigratorTrait, SchemaManager};
     use serenity::all::UserId;
 
@@ -282,12 +453,15 @@ mod tests {
             allow_modification: false,
             restrictions_note: Some("No commercial use".to_string()),
             allow_backup: false,
+            allow_commercial: true,
+            require_attribution: true,
+            color: Some(0x5865F2),
         };
 
         let mut payload = Vec::new();
         request.encode(&mut payload).unwrap();
 
-        let response_bytes = handle_create_user_license(&payload, conn)
+        let response_bytes = handle_create_user_license(&payload, conn, MAX_LICENSES_PER_USER)
             .await
             .expect("handler should succeed");
 
@@ -296,6 +470,9 @@ mod tests {
         assert_eq!(response.license_name, "Test License");
         assert!(response.allow_redistribution);
         assert!(!response.allow_modification);
+        assert!(response.allow_commercial);
+        assert!(response.require_attribution);
+        assert_eq!(response.color, Some(0x5865F2));
         assert_eq!(
             response.restrictions_note,
             Some("No commercial use".to_string())
@@ -309,9 +486,9 @@ mod tests {
         let service = LicenseService::new(conn);
         let user_id = UserId::new(456);
 
-        for i in 0..5 {
+        for i in 0..3 {
             service
-                .create(user_id, format!("License {i}"), false, false, None, false)
+                .create(user_id, LicenseFields::new(format!("License {i}")))
                 .await
                 .unwrap();
         }
@@ -323,19 +500,246 @@ mod tests {
             allow_modification: false,
             restrictions_note: None,
             allow_backup: false,
+            allow_commercial: false,
+            require_attribution: false,
+            color: None,
         };
 
         let mut payload = Vec::new();
         overflow_request.encode(&mut payload).unwrap();
 
-        let err = handle_create_user_license(&payload, conn)
+        let err = handle_create_user_license(&payload, conn, 3)
             .await
-            .expect_err("handler should enforce license limit");
+            .expect_err("handler should enforce the configured license limit");
 
         assert!(
-            err.to_string().contains("最多只能创建5个协议"),
+            err.to_string().contains("最多只能创建3个协议"),
             "unexpected error: {}",
             err
         );
     }
+
+    #[tokio::test]
+    async fn test_handle_get_user_licenses_paging() {
+        let db = setup_db().await;
+        let conn = db.inner();
+        let service = LicenseService::new(conn);
+        let user_id = UserId::new(123);
+
+        for i in 0..4 {
+            service
+                .create(user_id, LicenseFields::new(format!("License {i}")))
+                .await
+                .unwrap();
+        }
+
+        let get = |offset: Option<u32>, limit: Option<u32>| async move {
+            let mut payload = Vec::new();
+            GetUserLicensesRequest {
+                user_id: 123,
+                offset,
+                limit,
+            }
+            .encode(&mut payload)
+            .unwrap();
+            let bytes = handle_get_user_licenses(&payload, conn).await.unwrap();
+            GetUserLicensesResponse::decode(&*bytes).unwrap()
+        };
+
+        // 不设置分页字段时返回全部许可证
+        let all = get(None, None).await;
+        assert_eq!(all.total, 4);
+        let all_ids: Vec<i32> = all.licenses.iter().map(|l| l.id).collect();
+        assert_eq!(all_ids.len(), 4);
+
+        // 分页结果按相同顺序拼接后与全部结果一致
+        let first = get(None, Some(3)).await;
+        let rest = get(Some(3), Some(3)).await;
+        assert_eq!(first.total, 4);
+        assert_eq!(first.licenses.len(), 3);
+        assert_eq!(rest.licenses.len(), 1);
+        let paged_ids: Vec<i32> = first
+            .licenses
+            .iter()
+            .chain(&rest.licenses)
+            .map(|l| l.id)
+            .collect();
+        assert_eq!(paged_ids, all_ids);
+
+        // 只设置 offset 时返回其后的全部许可证，超出范围时为空
+        assert_eq!(get(Some(1), None).await.licenses.len(), 3);
+        assert!(get(Some(10), None).await.licenses.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_handle_reset_usage_count_scoped_to_user() {
+        let db = setup_db().await;
+        let conn = db.inner();
+        let service = LicenseService::new(conn);
+        let owner = UserId::new(123);
+
+        let license = service
+            .create(owner, LicenseFields::new("Test"))
+            .await
+            .unwrap();
+        service.increment_usage(license.id, owner).await.unwrap();
+
+        let reset = |user_id: i64, id: Option<i32>| async move {
+            let mut payload = Vec::new();
+            ResetUsageRequest { user_id, id }
+                .encode(&mut payload)
+                .unwrap();
+            let bytes = handle_reset_usage_count(&payload, conn).await.unwrap();
+            ResetUsageResponse::decode(&*bytes).unwrap().reset_count
+        };
+
+        // 其他用户无法重置该许可证
+        assert_eq!(reset(456, Some(license.id)).await, 0);
+        assert_eq!(reset(456, None).await, 0);
+        let unchanged = service
+            .get_license(license.id, owner)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(unchanged.usage_count, 1);
+
+        assert_eq!(reset(123, None).await, 1);
+        let updated = service
+            .get_license(license.id, owner)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(updated.usage_count, 0);
+    }
+
+    #[tokio::test]
+    async fn test_handle_decrement_and_set_usage_count() {
+        let db = setup_db().await;
+        let conn = db.inner();
+        let service = LicenseService::new(conn);
+        let owner = UserId::new(123);
+
+        let license = service
+            .create(owner, LicenseFields::new("Test"))
+            .await
+            .unwrap();
+        let usage = || async {
+            service
+                .get_license(license.id, owner)
+                .await
+                .unwrap()
+                .unwrap()
+                .usage_count
+        };
+
+        let mut payload = Vec::new();
+        SetUsageCountRequest {
+            id: license.id,
+            usage_count: 5,
+        }
+        .encode(&mut payload)
+        .unwrap();
+        let bytes = handle_set_usage_count(&payload, conn).await.unwrap();
+        assert_eq!(
+            SetUsageCountResponse::decode(&*bytes)
+                .unwrap()
+                .new_usage_count,
+            5
+        );
+        assert_eq!(usage().await, 5);
+
+        let mut payload = Vec::new();
+        DecrementUsageRequest { id: license.id }
+            .encode(&mut payload)
+            .unwrap();
+        let bytes = handle_decrement_usage_count(&payload, conn).await.unwrap();
+        assert_eq!(
+            DecrementUsageResponse::decode(&*bytes)
+                .unwrap()
+                .new_usage_count,
+            4
+        );
+        assert_eq!(usage().await, 4);
+
+        // 负数按 0 处理，且为 0 时不会继续减少
+        let mut payload = Vec::new();
+        SetUsageCountRequest {
+            id: license.id,
+            usage_count: -3,
+        }
+        .encode(&mut payload)
+        .unwrap();
+        let bytes = handle_set_usage_count(&payload, conn).await.unwrap();
+        assert_eq!(
+            SetUsageCountResponse::decode(&*bytes)
+                .unwrap()
+                .new_usage_count,
+            0
+        );
+
+        let mut payload = Vec::new();
+        DecrementUsageRequest { id: license.id }
+            .encode(&mut payload)
+            .unwrap();
+        let bytes = handle_decrement_usage_count(&payload, conn).await.unwrap();
+        assert_eq!(
+            DecrementUsageResponse::decode(&*bytes)
+                .unwrap()
+                .new_usage_count,
+            0
+        );
+        assert_eq!(usage().await, 0);
+    }
+
+    fn license_model(id: i32, user_id: i64) -> user_licenses::Model {
+        user_licenses::Model {
+            id,
+            user_id,
+            license_name: "署名".to_string(),
+            allow_redistribution: true,
+            allow_modification: false,
+            restrictions_note: Some("请注明出处".to_string()),
+            allow_backup: true,
+            usage_count: 0,
+            created_at: chrono::Utc::now(),
+            allow_commercial: false,
+            require_attribution: true,
+            color: Some(0x5865F2),
+            source: "unknown".to_string(),
+            sort_order: 0,
+        }
+    }
+
+    #[test]
+    fn test_content_hash() {
+        let base = license_model(1, 100);
+        let hash = content_hash(&base);
+        assert_eq!(hash.len(), 16);
+
+        // 条款相同的协议哈希相同，ID、所有者、使用次数不影响哈希
+        let mut same = license_model(2, 200);
+        same.usage_count = 9;
+        same.sort_order = 3;
+        assert_eq!(content_hash(&same), hash);
+        assert_eq!(to_proto_user_license(same).content_hash, hash);
+
+        let variants: Vec<fn(&mut user_licenses::Model)> = vec![
+            |m| m.license_name = "署名2".to_string(),
+            |m| m.allow_redistribution = false,
+            |m| m.allow_modification = true,
+            |m| m.allow_backup = false,
+            |m| m.allow_commercial = true,
+            |m| m.require_attribution = false,
+            |m| m.restrictions_note = None,
+            |m| m.restrictions_note = Some(String::new()),
+            |m| m.color = None,
+            |m| m.color = Some(0),
+        ];
+        let mut hashes = std::collections::HashSet::from([hash]);
+        for change in variants {
+            let mut model = license_model(1, 100);
+            change(&mut model);
+            assert!(hashes.insert(content_hash(&model)), "{model:?}");
+        }
+    }
 }
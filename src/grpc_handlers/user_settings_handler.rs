@@ -1,7 +1,7 @@
 use entities::user_settings;
 use prost::Message;
 use sea_orm::{ActiveModelTrait, DatabaseConnection, EntityTrait, IntoActiveModel, Set};
-use tracing::info;
+use tracing::{info, instrument};
 
 // 包含生成的 protobuf 代码
 pub mod license_management {
@@ -21,6 +21,7 @@ fn to_proto_user_settings(model: user_settings::Model) -> UserSettings {
     }
 }
 
+#[instrument(skip_all)]
 pub async fn handle_get_user_settings(
     payload: &[u8],
     db: &DatabaseConnection,
@@ -39,6 +40,7 @@ pub async fn handle_get_user_settings(
     Ok(buf)
 }
 
+#[instrument(skip_all)]
 pub async fn handle_update_user_settings(
     payload: &[u8],
     db: &DatabaseConnection,
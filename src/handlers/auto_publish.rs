@@ -1,15 +1,82 @@
-use std::{sync::OnceLock, time::Duration};
+use std::{
+    sync::OnceLock,
+    time::{Duration, Instant},
+};
 
 use moka::future::Cache;
-use serenity::all::{Context, GuildChannel};
+use serenity::all::{Context, GuildChannel, UserId};
 
 use crate::{commands::Data, error::BotError};
 
-use super::auto_publish_flow::AutoPublishFlow;
+use super::auto_publish_flow::{AutoPublishFlow, BackfillMode};
 
 // 线程创建事件去重缓存，使用moka实现TTL自动清理
 static PROCESSED_THREADS: OnceLock<Cache<u64, ()>> = OnceLock::new();
 
+fn processed_threads() -> &'static Cache<u64, ()> {
+    PROCESSED_THREADS.get_or_init(|| {
+        Cache::builder()
+            .time_to_live(Duration::from_secs(300)) // 5分钟TTL
+            .max_capacity(10_000) // 限制最大条目数
+            .build()
+    })
+}
+
+// 新用户引导消息冷却缓存，记录每个用户上次收到引导的时间
+static GUIDANCE_COOLDOWNS: OnceLock<Cache<u64, Instant>> = OnceLock::new();
+
+fn guidance_cooldowns() -> &'static Cache<u64, Instant> {
+    GUIDANCE_COOLDOWNS.get_or_init(|| {
+        Cache::builder()
+            .max_capacity(10_000) // 限制最大条目数
+            .build()
+    })
+}
+
+/// 尝试为用户开始一次引导，冷却期内返回 `false`
+///
+/// 冷却时长在每次检查时读取，修改配置后立即生效
+async fn try_start_guidance_in(
+    cache: &Cache<u64, Instant>,
+    user_id: u64,
+    window: Duration,
+) -> bool {
+    if let Some(last_shown) = cache.get(&user_id).await
+        && last_shown.elapsed() < window
+    {
+        return false;
+    }
+    cache.insert(user_id, Instant::now()).await;
+    true
+}
+
+/// 检查并记录新用户引导冷却，冷却期内返回 `false`
+pub(super) async fn try_start_guidance(user_id: u64, window: Duration) -> bool {
+    try_start_guidance_in(guidance_cooldowns(), user_id, window).await
+}
+
+/// 记录用户刚显示过引导，冷却期内自动发布流程不会再次引导
+async fn record_guidance(user_id: u64) {
+    guidance_cooldowns().insert(user_id, Instant::now()).await;
+}
+
+/// 检查线程是否已被处理过
+async fn is_thread_processed(thread_id: u64) -> bool {
+    processed_threads().get(&thread_id).await.is_some()
+}
+
+/// 清空线程去重缓存，返回清除前的条目数
+///
+/// 用于测试或修复问题后让线程可以被重新处理
+pub async fn clear_processed_threads() -> u64 {
+    let cache = processed_threads();
+    cache.run_pending_tasks().await;
+    let count = cache.entry_count();
+    cache.invalidate_all();
+    cache.run_pending_tasks().await;
+    count
+}
+
 /// 检查线程中是否已有首条消息
 /// Discord的ThreadCreate事件会在帖子创建和首条消息发送时都触发
 /// 我们只想处理用户已发送首条消息的情况
@@ -34,15 +101,8 @@ pub async fn handle_thread_create(
     // 0. 去重检查 - 防止Discord事件重复触发，使用TTL缓存自动清理
     let thread_id = thread.id.get();
 
-    let cache = PROCESSED_THREADS.get_or_init(|| {
-        Cache::builder()
-            .time_to_live(Duration::from_secs(300)) // 5分钟TTL
-            .max_capacity(10_000) // 限制最大条目数
-            .build()
-    });
-
     // 检查是否已处理过
-    if cache.get(&thread_id).await.is_some() {
+    if is_thread_processed(thread_id).await {
         tracing::debug!(
             "Thread {} already processed, skipping duplicate event",
             thread_id
@@ -83,7 +143,7 @@ pub async fn handle_thread_create(
     };
 
     // 确认需要处理后，标记当前线程已处理（TTL会自动清理过期条目）
-    cache.insert(thread_id, ()).await;
+    processed_threads().insert(thread_id, ()).await;
 
     // 2. 使用新的状态机处理所有逻辑
     let flow = AutoPublishFlow::new(ctx, data, owner_id, thread);
@@ -92,12 +152,56 @@ pub async fn handle_thread_create(
     Ok(())
 }
 
+/// 为部署前创建的帖子运行自动发布流程
+///
+/// 与 `handle_thread_create` 不同，不检查首条消息和帖子创建时间，
+/// 调用方负责筛选帖子并控制处理速率。
+pub async fn backfill_thread(
+    ctx: &Context,
+    thread: &GuildChannel,
+    data: &Data,
+    mode: BackfillMode,
+) -> Result<(), BotError> {
+    let Some(owner_id) = thread.owner_id else {
+        return Ok(());
+    };
+
+    let thread_id = thread.id.get();
+    if is_thread_processed(thread_id).await {
+        return Ok(());
+    }
+    processed_threads().insert(thread_id, ()).await;
+
+    AutoPublishFlow::new(ctx, data, owner_id, thread)
+        .with_backfill(mode)
+        .run()
+        .await
+}
+
+/// 在帖子中为作者重新显示自动发布引导
+///
+/// 用于作者误关闭或错过引导后恢复，调用方负责检查是否符合重新引导的条件。
+pub async fn reguide_thread(
+    ctx: &Context,
+    thread: &GuildChannel,
+    data: &Data,
+    owner_id: UserId,
+) -> Result<(), BotError> {
+    record_guidance(owner_id.get()).await;
+    AutoPublishFlow::new(ctx, data, owner_id, thread)
+        .with_guidance()
+        .run()
+        .await
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use crate::database::BotDatabase;
+    use crate::handlers::auto_publish_flow::save_new_default_license;
+    use crate::services::license::{LicenseFields, MAX_LICENSES_PER_USER};
     use crate::types::license::DefaultLicenseIdentifier;
-    use crate::utils::LicenseEditState;
+    use crate::utils::{AutoPublishUI, LicenseEditState};
     use migration::{Migrator, MigratorTrait, SchemaManager};
     use serenity::all::UserId;
 
@@ -120,22 +224,10 @@ mod tests {
         let edit_state = LicenseEditState::new("Test License".to_string());
 
         // 测试保存协议 - 直接测试数据库层面的逻辑
-        let (name, allow_redistribution, allow_modification, restrictions_note, allow_backup) =
-            edit_state.to_user_license_fields();
+        let fields = edit_state.to_user_license_fields();
 
         // 创建协议
-        let license = db
-            .license()
-            .create(
-                user_id,
-                name,
-                allow_redistribution,
-                allow_modification,
-                restrictions_note,
-                allow_backup,
-            )
-            .await
-            .unwrap();
+        let license = db.license().create(user_id, fields).await.unwrap();
 
         assert_eq!(license.license_name, "Test License");
         assert_eq!(license.user_id, user_id.get() as i64);
@@ -163,7 +255,7 @@ mod tests {
         // 先创建5个协议（达到上限）
         for i in 0..5 {
             db.license()
-                .create(user_id, format!("License {}", i), false, false, None, false)
+                .create(user_id, LicenseFields::new(format!("License {}", i)))
                 .await
                 .unwrap();
         }
@@ -175,36 +267,102 @@ mod tests {
         // 尝试创建第6个协议，应该失败
         let result = db
             .license()
-            .create(user_id, "License 6".to_string(), false, false, None, false)
+            .create(user_id, LicenseFields::new("License 6"))
             .await;
 
         // 现在验证逻辑已经移到了 service 层，第6个协议应该被拒绝
         assert!(result.is_err());
 
-        if let Err(BotError::GenericError { message, .. }) = result {
-            assert!(message.contains("最多只能创建5个协议"));
+        if let Err(err @ BotError::LicenseLimit { .. }) = result {
+            assert!(err.user_message().contains("最多只能创建5个协议"));
         } else {
-            panic!("Expected GenericError with correct message");
+            panic!("Expected LicenseLimit error");
         }
     }
 
     #[tokio::test]
-    async fn test_license_edit_state_conversion() {
-        let edit_state = LicenseEditState::from_existing(
-            "Test License".to_string(),
-            true,
-            false,
-            Some("No commercial use".to_string()),
-            true,
-        );
+    async fn test_save_license_limit_offers_existing_licenses() {
+        let db = setup_test_db().await;
+        let user_id = UserId::new(789);
+
+        // 其他入口创建的协议已达到上限
+        for i in 0..MAX_LICENSES_PER_USER {
+            db.license()
+                .create(user_id, LicenseFields::new(format!("License {i}")))
+                .await
+                .unwrap();
+        }
 
-        let (name, allow_redistribution, allow_modification, restrictions_note, allow_backup) =
-            edit_state.to_user_license_fields();
+        // 自动发布流程的保存步骤返回上限错误，且不会修改默认协议设置
+        let err = save_new_default_license(
+            &db,
+            user_id,
+            MAX_LICENSES_PER_USER,
+            LicenseEditState::new("我的协议6".to_string()),
+        )
+        .await
+        .unwrap_err();
+        assert!(matches!(
+            err,
+            BotError::LicenseLimit {
+                max: MAX_LICENSES_PER_USER,
+                ..
+            }
+        ));
+        assert!(db.user_settings().get(user_id).await.unwrap().is_none());
+
+        // 提示中列出已有协议供用户选择
+        let licenses = db.license().get_user_licenses(user_id).await.unwrap();
+        let followup =
+            serde_json::to_value(AutoPublishUI::create_license_limit_followup(&licenses)).unwrap();
+        assert!(followup["content"].as_str().unwrap().contains("/协议管理"));
+        let options = followup["components"][0]["components"][0]["options"]
+            .as_array()
+            .unwrap();
+        assert_eq!(options.len(), licenses.len());
+        assert_eq!(options[0]["value"], licenses[0].id.to_string());
+    }
 
-        assert_eq!(name, "Test License");
-        assert!(allow_redistribution);
-        assert!(!allow_modification);
-        assert_eq!(restrictions_note, Some("No commercial use".to_string()));
-        assert!(allow_backup);
+    #[tokio::test]
+    async fn test_clear_processed_threads() {
+        let thread_id = 987_654_321;
+        processed_threads().insert(thread_id, ()).await;
+        assert!(is_thread_processed(thread_id).await);
+
+        clear_processed_threads().await;
+
+        assert!(!is_thread_processed(thread_id).await);
+    }
+
+    #[tokio::test]
+    async fn test_license_edit_state_conversion() {
+        let fields = LicenseFields {
+            license_name: "Test License".to_string(),
+            allow_redistribution: true,
+            allow_modification: false,
+            restrictions_note: Some("No commercial use".to_string()),
+            allow_backup: true,
+            allow_commercial: false,
+            require_attribution: true,
+            color: None,
+        };
+        let edit_state = LicenseEditState::from_existing(fields.clone());
+
+        assert_eq!(edit_state.to_user_license_fields(), fields);
+    }
+
+    #[tokio::test]
+    async fn test_guidance_cooldown() {
+        let cache = Cache::new(100);
+        let window = Duration::from_secs(3600);
+
+        // 首次显示引导
+        assert!(try_start_guidance_in(&cache, 1, window).await);
+        // 冷却期内跳过
+        assert!(!try_start_guidance_in(&cache, 1, window).await);
+        // 其他用户不受影响
+        assert!(try_start_guidance_in(&cache, 2, window).await);
+        // 冷却结束后可再次显示
+        assert!(try_start_guidance_in(&cache, 1, Duration::ZERO).await);
     }
 }
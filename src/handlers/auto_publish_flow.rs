@@ -1,18 +1,65 @@
 use chrono::Utc;
+use sea_orm::DatabaseTransaction;
 use serenity::all::{
-    ChannelId, ComponentInteractionDataKind, Context, CreateInteractionResponse,
-    CreateInteractionResponseFollowup, CreateInteractionResponseMessage, GuildChannel, Message,
-    UserId,
+    ChannelId, ComponentInteractionDataKind, Context, CreateActionRow, CreateInteractionResponse,
+    CreateInteractionResponseFollowup, CreateInteractionResponseMessage, CreateMessage,
+    EditInteractionResponse, GuildChannel, Http, Message, MessageId, UserId,
 };
 
+use super::auto_publish::try_start_guidance;
 use crate::{
     commands::Data,
+    database::BotDatabase,
     error::BotError,
-    services::license::LicensePublishService,
+    services::{
+        license::{LicensePublishService, LicenseService, LicenseSource},
+        user_settings::UserSettingsService,
+    },
     types::license::DefaultLicenseIdentifier,
-    utils::{AutoPublishUI, LicenseEditState, present_license_editing_panel},
+    utils::{
+        AutoPublishUI, LicenseEditState, Paginator, member_display_name_by_id,
+        present_license_editing_panel,
+    },
 };
 
+/// 回填部署前帖子时的处理方式
+#[derive(Debug, Clone, Copy, PartialEq, Eq, poise::ChoiceParameter)]
+pub enum BackfillMode {
+    /// 按用户设置运行自动发布流程（确认面板、新用户引导）
+    #[name = "Prompt owners"]
+    #[name_localized("zh-CN", "提示作者")]
+    Prompt,
+    /// 已启用自动发布的用户直接发布默认协议，其他用户跳过
+    #[name = "Apply defaults"]
+    #[name_localized("zh-CN", "直接发布默认协议")]
+    ApplyDefaults,
+}
+
+/// 解析自动发布使用的默认协议
+///
+/// 优先级：用户协议 > 用户选择的系统协议 > 社区默认协议
+fn resolve_default_license(
+    settings: &entities::entities::user_settings::Model,
+    guild_default: Option<&str>,
+) -> Option<DefaultLicenseIdentifier> {
+    if let Some(user_license_id) = settings.default_user_license_id {
+        Some(DefaultLicenseIdentifier::User(user_license_id))
+    } else if let Some(system_license_name) = &settings.default_system_license_name {
+        Some(DefaultLicenseIdentifier::System(
+            system_license_name.clone(),
+        ))
+    } else {
+        guild_default.map(|name| DefaultLicenseIdentifier::System(name.to_string()))
+    }
+}
+
+/// 判断帖子是否超过自动发布处理的最大时长
+///
+/// `max_age_secs` 为 0 时不检查时长；恰好等于上限的帖子仍会被处理
+fn is_thread_too_old(thread_age_secs: i64, max_age_secs: u64) -> bool {
+    max_age_secs > 0 && thread_age_secs > max_age_secs as i64
+}
+
 /// 自动发布流程的状态定义
 #[derive(Debug, Clone)]
 pub enum FlowState {
@@ -32,6 +79,105 @@ pub enum FlowState {
     Done,
 }
 
+/// 流程结束时仍带有交互组件的消息会被替换为此摘要
+const FLOW_ENDED_SUMMARY: &str = "⌛ 自动发布设置已结束，如需修改可使用 `/自动发布设置`。";
+
+/// 流程中发送的临时消息（交互响应或followup）
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct TrackedFollowup {
+    /// 发送该消息的交互令牌
+    token: String,
+    /// followup消息ID，`None` 表示交互的原始响应
+    message_id: Option<MessageId>,
+}
+
+/// 临时消息的清理操作，便于在测试中替换
+trait FollowupCleaner {
+    /// 删除消息
+    async fn delete(&self, followup: &TrackedFollowup);
+    /// 将消息替换为摘要文本并移除交互组件
+    async fn summarize(&self, followup: &TrackedFollowup, summary: &str);
+}
+
+impl FollowupCleaner for Http {
+    async fn delete(&self, followup: &TrackedFollowup) {
+        let result = match followup.message_id {
+            Some(message_id) => {
+                self.delete_followup_message(&followup.token, message_id)
+                    .await
+            }
+            None => {
+                self.delete_original_interaction_response(&followup.token)
+                    .await
+            }
+        };
+        if let Err(e) = result {
+            tracing::debug!("删除临时消息失败: {}", e);
+        }
+    }
+
+    async fn summarize(&self, followup: &TrackedFollowup, summary: &str) {
+        let edit = EditInteractionResponse::new()
+            .content(summary)
+            .embeds(Vec::new())
+            .components(Vec::new());
+        let result = match followup.message_id {
+            Some(message_id) => {
+                self.edit_followup_message(&followup.token, message_id, &edit, Vec::new())
+                    .await
+            }
+            None => {
+                self.edit_original_interaction_response(&followup.token, &edit, Vec::new())
+                    .await
+            }
+        };
+        if let Err(e) = result {
+            tracing::debug!("更新临时消息摘要失败: {}", e);
+        }
+    }
+}
+
+/// 统一记录流程中发送的临时消息，流程结束时集中清理
+///
+/// 中间消息会被删除；最后一条消息若尚未显示最终结果（例如等待交互时超时），
+/// 会被替换为简短摘要。
+#[derive(Debug, Default)]
+struct FollowupTracker {
+    followups: Vec<TrackedFollowup>,
+    /// 最后一条消息是否已显示最终结果
+    last_is_final: bool,
+}
+
+impl FollowupTracker {
+    /// 记录新的临时消息
+    fn track(&mut self, token: &str, message_id: Option<MessageId>) {
+        self.followups.push(TrackedFollowup {
+            token: token.to_string(),
+            message_id,
+        });
+        self.last_is_final = false;
+    }
+
+    /// 标记最后一条消息已显示最终结果，清理时保留原样
+    fn mark_final(&mut self) {
+        self.last_is_final = true;
+    }
+
+    /// 清理所有记录的消息
+    async fn cleanup(&mut self, cleaner: &impl FollowupCleaner, summary: &str) {
+        let Some(last) = self.followups.pop() else {
+            return;
+        };
+        for followup in self.followups.drain(..) {
+            cleaner.delete(&followup).await;
+        }
+        if !self.last_is_final {
+            cleaner.summarize(&last, summary).await;
+        }
+        self.last_is_final = false;
+    }
+}
+
 /// 自动发布流程状态机
 pub struct AutoPublishFlow<'a> {
     /// 当前状态
@@ -52,6 +198,10 @@ pub struct AutoPublishFlow<'a> {
     pending_interaction: Option<serenity::all::ComponentInteraction>,
     /// 编辑器交互（用于新用户流程的followup）
     editor_interaction: Option<serenity::all::ComponentInteraction>,
+    /// 流程中发送的临时消息
+    followups: FollowupTracker,
+    /// 回填模式，设置后不再跳过部署前的旧帖子
+    backfill: Option<BackfillMode>,
 }
 
 impl<'a> AutoPublishFlow<'a> {
@@ -72,9 +222,23 @@ impl<'a> AutoPublishFlow<'a> {
             system_licenses: None,
             pending_interaction: None,
             editor_interaction: None,
+            followups: FollowupTracker::default(),
+            backfill: None,
         }
     }
 
+    /// 以回填模式处理部署前创建的帖子
+    pub fn with_backfill(mut self, mode: BackfillMode) -> Self {
+        self.backfill = Some(mode);
+        self
+    }
+
+    /// 跳过用户设置检查，直接显示新用户引导
+    pub fn with_guidance(mut self) -> Self {
+        self.state = FlowState::AwaitingGuidance;
+        self
+    }
+
     /// 运行状态机主循环
     pub async fn run(mut self) -> Result<(), BotError> {
         loop {
@@ -119,6 +283,22 @@ impl<'a> AutoPublishFlow<'a> {
     /// 统一的状态错误处理
     async fn handle_state_error(&mut self, error: &BotError) {
         tracing::error!("状态机处理错误: {}", error);
+        // 直接发布时没有可以响应的交互，通过私信告知用户机器人缺少权限
+        // 回填的错误由发起回填的管理员处理，不逐个打扰用户
+        if self.backfill.is_none() && matches!(error, BotError::AuthorizationError { .. }) {
+            let content = format!(
+                "❌ 无法在 <#{}> 自动发布协议：{}",
+                self.thread.id,
+                error.user_message()
+            );
+            if let Err(e) = self
+                .owner_id
+                .direct_message(&self.ctx.http, CreateMessage::new().content(content))
+                .await
+            {
+                tracing::debug!("私信通知用户 {} 失败: {}", self.owner_id, e);
+            }
+        }
         self.cleanup().await;
     }
 
@@ -184,6 +364,38 @@ impl<'a> AutoPublishFlow<'a> {
         }
     }
 
+    /// 等待分页下拉菜单的选择，期间处理翻页按钮
+    async fn wait_for_paged_selection(
+        &self,
+        followup_message: &Message,
+        timeout_secs: u64,
+        mut paginator: Paginator,
+        build_rows: impl Fn(&Paginator) -> Vec<CreateActionRow>,
+    ) -> Result<Option<serenity::all::ComponentInteraction>, BotError> {
+        loop {
+            let Some(interaction) = self
+                .wait_for_followup_interaction_or_finish(followup_message, timeout_secs)
+                .await?
+            else {
+                return Ok(None);
+            };
+
+            if !paginator.handle_action(&interaction.data.custom_id) {
+                return Ok(Some(interaction));
+            }
+
+            // 翻页后重建当前页的菜单
+            interaction
+                .create_response(
+                    &self.ctx.http,
+                    CreateInteractionResponse::UpdateMessage(
+                        CreateInteractionResponseMessage::new().components(build_rows(&paginator)),
+                    ),
+                )
+                .await?;
+        }
+    }
+
     /// 转换到新状态
     fn transition_to(&mut self, new_state: FlowState) {
         tracing::debug!("状态转换: {:?} -> {:?}", self.state, new_state);
@@ -198,11 +410,18 @@ impl<'a> AutoPublishFlow<'a> {
             // 只删除确认类型的消息，其他消息保留作为状态记录
             let _ = message.delete(&self.ctx.http).await;
         }
+
+        // 删除中间的临时消息，并为未完成的最后一条消息留下摘要
+        if self.data.cfg().load().cleanup_flow_followups {
+            self.followups
+                .cleanup(self.ctx.http.as_ref(), FLOW_ENDED_SUMMARY)
+                .await;
+        }
     }
 
     /// 统一的成功响应方法
     async fn respond_with_success(
-        &self,
+        &mut self,
         interaction: &serenity::all::ComponentInteraction,
         message: &str,
     ) -> Result<(), BotError> {
@@ -216,16 +435,18 @@ impl<'a> AutoPublishFlow<'a> {
                 ),
             )
             .await?;
+        self.followups.track(&interaction.token, None);
+        self.followups.mark_final();
         Ok(())
     }
 
     /// 统一的错误followup方法
     async fn followup_with_error(
-        &self,
+        &mut self,
         interaction: &serenity::all::ComponentInteraction,
         message: &str,
     ) -> Result<(), BotError> {
-        interaction
+        let followup = interaction
             .create_followup(
                 &self.ctx.http,
                 CreateInteractionResponseFollowup::new()
@@ -233,6 +454,8 @@ impl<'a> AutoPublishFlow<'a> {
                     .ephemeral(true),
             )
             .await?;
+        self.followups.track(&interaction.token, Some(followup.id));
+        self.followups.mark_final();
         Ok(())
     }
 
@@ -272,11 +495,13 @@ impl<'a> AutoPublishFlow<'a> {
 
     /// 处理初始状态 - 检查用户设置并决定后续流程
     async fn handle_initial_state(&mut self) -> Result<(), BotError> {
-        // 检查帖子创建时间，防止处理bot部署前的旧帖子
-        if let Some(thread_metadata) = &self.thread.thread_metadata
+        // 检查帖子创建时间，防止处理bot部署前的旧帖子（回填时有意跳过此检查）
+        if self.backfill.is_none()
+            && let Some(thread_metadata) = &self.thread.thread_metadata
             && let Some(create_timestamp) = thread_metadata.create_timestamp
         {
-            let bot_start_time = self.data.cfg().load().bot_start_time;
+            let cfg = self.data.cfg().load();
+            let bot_start_time = cfg.bot_start_time;
 
             // 如果帖子创建时间早于bot启动时间，静默退出
             if create_timestamp.timestamp() < bot_start_time.timestamp() {
@@ -292,7 +517,7 @@ impl<'a> AutoPublishFlow<'a> {
             // 额外检查：检查首楼消息时间，确保是真正的新帖子
             let now = Utc::now();
             let thread_age_secs = now.timestamp() - create_timestamp.timestamp();
-            if thread_age_secs > 300 {
+            if is_thread_too_old(thread_age_secs, cfg.auto_publish_max_thread_age_secs) {
                 tracing::debug!(
                     "跳过过期帖子处理: 帖子创建于 {} ({} 秒前)",
                     create_timestamp,
@@ -307,9 +532,21 @@ impl<'a> AutoPublishFlow<'a> {
         let user_settings = self.data.db().user_settings().get(self.owner_id).await?;
 
         match user_settings {
+            // 回填时直接发布模式不打扰新用户
+            None if self.backfill == Some(BackfillMode::ApplyDefaults) => {
+                self.transition_to(FlowState::Done);
+            }
             // 场景一：新用户
             None => {
-                self.transition_to(FlowState::AwaitingGuidance);
+                // 冷却期内已显示过引导，避免连续发帖时重复打扰
+                let window =
+                    std::time::Duration::from_secs(self.data.cfg().load().guidance_cooldown_secs);
+                if try_start_guidance(self.owner_id.get(), window).await {
+                    self.transition_to(FlowState::AwaitingGuidance);
+                } else {
+                    tracing::debug!("用户 {} 处于引导冷却期，跳过引导", self.owner_id);
+                    self.transition_to(FlowState::Done);
+                }
             }
             // 用户已存在
             Some(settings) => {
@@ -320,14 +557,11 @@ impl<'a> AutoPublishFlow<'a> {
                 }
 
                 // 场景二：已启用功能的用户
-                let default_license_id = if let Some(user_license_id) =
-                    settings.default_user_license_id
-                {
-                    DefaultLicenseIdentifier::User(user_license_id)
-                } else if let Some(ref system_license_name) = settings.default_system_license_name {
-                    DefaultLicenseIdentifier::System(system_license_name.clone())
-                } else {
-                    // 用户启用了功能但未设置默认协议，静默退出
+                let guild_default = self.data.cfg().load().default_guild_license_name.clone();
+                let Some(default_license_id) =
+                    resolve_default_license(&settings, guild_default.as_deref())
+                else {
+                    // 用户启用了功能但未设置默认协议，且没有社区默认协议，静默退出
                     self.transition_to(FlowState::Done);
                     return Ok(());
                 };
@@ -338,8 +572,10 @@ impl<'a> AutoPublishFlow<'a> {
                     .await?;
 
                 if let Some(license) = license_model {
-                    // 检查是否跳过确认
-                    if settings.skip_auto_publish_confirmation {
+                    // 检查是否跳过确认，回填的直接发布模式总是跳过
+                    if settings.skip_auto_publish_confirmation
+                        || self.backfill == Some(BackfillMode::ApplyDefaults)
+                    {
                         // 直接发布协议
                         self.publish_license_directly(&license).await?;
                         self.transition_to(FlowState::Done);
@@ -372,17 +608,31 @@ impl<'a> AutoPublishFlow<'a> {
                 .get_license(*id, self.owner_id)
                 .await?),
             DefaultLicenseIdentifier::System(name) => {
-                let Some(sys_license) = self
-                    .data
-                    .system_license_cache()
-                    .get_all()
-                    .await
-                    .into_iter()
-                    .find(|l| l.license_name == *name)
+                let Some(sys_license) = self.data.system_license_cache().get_by_name(name).await
                 else {
                     return Ok(None);
                 };
 
+                // 受限的系统协议只有发布名单中的用户可以自动发布；
+                // 获取身份组失败时返回错误，而不是按没有身份组处理而静默跳过
+                let roles = if sys_license.is_restricted() {
+                    self.thread
+                        .guild_id
+                        .member(&self.ctx.http, self.owner_id)
+                        .await?
+                        .roles
+                } else {
+                    Vec::new()
+                };
+                if !sys_license.can_publish(self.owner_id, &roles) {
+                    tracing::debug!(
+                        "用户 {} 无权发布系统协议 {}，跳过自动发布",
+                        self.owner_id,
+                        name
+                    );
+                    return Ok(None);
+                }
+
                 let mut license = sys_license.to_user_license(self.owner_id, -1);
                 // 如果用户设置了系统协议的备份权限覆盖，使用用户的设置
                 if let Some(backup_override) = settings.default_system_license_backup {
@@ -405,6 +655,7 @@ impl<'a> AutoPublishFlow<'a> {
             license,
             license.allow_backup,
             self.owner_id.to_user(self.ctx).await?,
+            self.owner_id,
         )
         .await
     }
@@ -414,15 +665,25 @@ impl<'a> AutoPublishFlow<'a> {
         &mut self,
         license: &crate::services::license::UserLicense,
     ) -> Result<(), BotError> {
-        let display_name = self
-            .thread
-            .guild_id
-            .member(&self.ctx.http, self.owner_id)
-            .await
-            .map(|m| m.display_name().to_string())?;
+        let display_name =
+            member_display_name_by_id(&self.ctx.http, self.thread.guild_id, self.owner_id).await;
 
         // 使用UI构建器创建确认面板
-        let message = AutoPublishUI::build_auto_publish_confirmation(license, &display_name);
+        // 确认面板发送在帖子中，使用配置中的语言
+        let locale = self.data.cfg().load().license_locale;
+        let replaces_existing = self
+            .data
+            .db()
+            .published_posts()
+            .has_published_post(self.thread.id)
+            .await?;
+        let message = AutoPublishUI::build_auto_publish_confirmation(
+            license,
+            &display_name,
+            locale,
+            self.thread,
+            replaces_existing,
+        );
 
         let sent_message = ChannelId::new(self.thread.id.get())
             .send_message(&self.ctx.http, message)
@@ -473,17 +734,20 @@ impl<'a> AutoPublishFlow<'a> {
         self.system_licenses = Some(system_licenses.clone());
 
         // 使用UI构建器创建选择菜单
-        let select_menu = AutoPublishUI::build_license_selection_menu(&system_licenses);
+        let paginator = AutoPublishUI::license_selection_paginator(&system_licenses, false);
+        let selection_rows =
+            AutoPublishUI::build_license_selection_menu(&system_licenses, &paginator);
 
         // 立即确认交互并附加选择菜单 - 全部 ephemeral
         interaction
             .create_response(
                 &self.ctx.http,
                 CreateInteractionResponse::Message(AutoPublishUI::create_enable_response(
-                    select_menu,
+                    selection_rows,
                 )),
             )
             .await?;
+        self.followups.track(&interaction.token, None);
 
         // 删除旧的引导消息
         if let Some(message) = &self.current_message {
@@ -506,8 +770,11 @@ impl<'a> AutoPublishFlow<'a> {
     ) -> Result<(), BotError> {
         // 等待用户选择协议
         let followup_message = interaction.get_response(&self.ctx.http).await?;
+        let paginator = AutoPublishUI::license_selection_paginator(&system_licenses, false);
         let Some(select_interaction) = self
-            .wait_for_followup_interaction_or_finish(&followup_message, 120)
+            .wait_for_paged_selection(&followup_message, 120, paginator, |paginator| {
+                AutoPublishUI::build_license_selection_menu(&system_licenses, paginator)
+            })
             .await?
         else {
             self.transition_to(FlowState::Done);
@@ -542,16 +809,26 @@ impl<'a> AutoPublishFlow<'a> {
         system_licenses: &[crate::types::license::SystemLicense],
     ) -> Result<LicenseEditState, BotError> {
         if selected == "new_license" {
-            // 使用智能命名策略，避免重名协议
-            let user_licenses = self
-                .data
-                .db()
-                .license()
-                .get_user_licenses(self.owner_id)
-                .await?;
-            let next_number = user_licenses.len() + 1;
-            let default_name = format!("我的协议{next_number}");
-            Ok(LicenseEditState::new(default_name))
+            // 从协议数量+1开始编号，跳过已被占用的名称（用户删除过协议时编号可能重复）
+            let license_service = self.data.db().license();
+            let mut next_number = license_service
+                .get_user_license_count(self.owner_id)
+                .await?
+                + 1;
+            let default_name = loop {
+                let name = format!("我的协议{next_number}");
+                if !license_service
+                    .license_name_exists(self.owner_id, &name, None)
+                    .await?
+                {
+                    break name;
+                }
+                next_number += 1;
+            };
+            Ok(LicenseEditState::with_defaults(
+                default_name,
+                &self.data.cfg().load().new_license_defaults,
+            ))
         } else if let Some(system_name) = selected.strip_prefix("system_") {
             if let Some(system_license) = system_licenses
                 .iter()
@@ -591,6 +868,8 @@ impl<'a> AutoPublishFlow<'a> {
                 CreateInteractionResponse::Message(AutoPublishUI::create_disable_response()),
             )
             .await?;
+        self.followups.track(&interaction.token, None);
+        self.followups.mark_final();
 
         self.transition_to(FlowState::Done);
         Ok(())
@@ -627,6 +906,27 @@ impl<'a> AutoPublishFlow<'a> {
                         Ok(license) => {
                             self.transition_to(FlowState::ConfirmingSave(license));
                         }
+                        // 协议数量已达上限，引导用户改用已有协议，而不是直接结束
+                        Err(BotError::LicenseLimit { .. }) => {
+                            self.handle_license_limit(&latest_interaction).await?;
+                        }
+                        // 协议名重复等可修正的问题，提示原因后让用户重新选择
+                        Err(BotError::ValidationError { message, .. }) => {
+                            let followup = latest_interaction
+                                .create_followup(
+                                    &self.ctx.http,
+                                    CreateInteractionResponseFollowup::new()
+                                        .content(format!("❌ {message}"))
+                                        .ephemeral(true),
+                                )
+                                .await?;
+                            self.followups
+                                .track(&latest_interaction.token, Some(followup.id));
+                            let system_licenses = self.system_licenses.clone().unwrap_or_default();
+                            self.transition_to(FlowState::AwaitingLicenseReselection(
+                                system_licenses,
+                            ));
+                        }
                         Err(e) => {
                             tracing::error!("保存协议失败: {}", e);
                             // 发送错误消息
@@ -658,6 +958,70 @@ impl<'a> AutoPublishFlow<'a> {
         Ok(())
     }
 
+    /// 协议数量达到上限时，让用户从已有协议中选择一个作为默认协议
+    ///
+    /// 选择后继续发布确认流程；超时或没有可选协议时结束流程，提示中已说明如何删除协议。
+    async fn handle_license_limit(
+        &mut self,
+        interaction: &serenity::all::ComponentInteraction,
+    ) -> Result<(), BotError> {
+        let licenses = self
+            .data
+            .db()
+            .license()
+            .get_user_licenses(self.owner_id)
+            .await?;
+        let followup_message = interaction
+            .create_followup(
+                &self.ctx.http,
+                AutoPublishUI::create_license_limit_followup(&licenses),
+            )
+            .await?;
+        self.followups
+            .track(&interaction.token, Some(followup_message.id));
+        if licenses.is_empty() {
+            self.followups.mark_final();
+            self.transition_to(FlowState::Done);
+            return Ok(());
+        }
+
+        let Some(select_interaction) = self
+            .wait_for_followup_interaction_or_finish(&followup_message, 120)
+            .await?
+        else {
+            self.transition_to(FlowState::Done);
+            return Ok(());
+        };
+        let selected = match &select_interaction.data.kind {
+            ComponentInteractionDataKind::StringSelect { values } => values
+                .first()
+                .and_then(|value| value.parse::<i32>().ok())
+                .and_then(|id| licenses.iter().find(|l| l.id == id)),
+            _ => None,
+        };
+        let Some(license) = selected.cloned() else {
+            self.transition_to(FlowState::Done);
+            return Ok(());
+        };
+
+        self.set_default_and_enable(license.id).await?;
+        select_interaction
+            .create_response(
+                &self.ctx.http,
+                CreateInteractionResponse::UpdateMessage(
+                    CreateInteractionResponseMessage::new()
+                        .content(format!("✅ 已选择已有协议「{}」。", license.license_name))
+                        .components(Vec::new()),
+                ),
+            )
+            .await?;
+
+        // 用选择交互继续后续的发布确认
+        self.editor_interaction = Some(select_interaction);
+        self.transition_to(FlowState::ConfirmingSave(license));
+        Ok(())
+    }
+
     /// 处理等待重新选择协议状态
     async fn handle_awaiting_license_reselection(
         &mut self,
@@ -673,16 +1037,21 @@ impl<'a> AutoPublishFlow<'a> {
                 })?;
 
         // 显示重新选择菜单
+        let paginator = AutoPublishUI::license_selection_paginator(&system_licenses, true);
         let followup_message = editor_interaction
             .create_followup(
                 &self.ctx.http,
-                AutoPublishUI::build_license_reselection_menu(&system_licenses),
+                AutoPublishUI::build_license_reselection_menu(&system_licenses, &paginator),
             )
             .await?;
+        self.followups
+            .track(&editor_interaction.token, Some(followup_message.id));
 
         // 等待用户重新选择
         let Some(reselect_interaction) = self
-            .wait_for_followup_interaction_or_finish(&followup_message, 120)
+            .wait_for_paged_selection(&followup_message, 120, paginator, |paginator| {
+                AutoPublishUI::build_license_reselection_rows(&system_licenses, paginator)
+            })
             .await?
         else {
             self.transition_to(FlowState::Done);
@@ -763,12 +1132,19 @@ impl<'a> AutoPublishFlow<'a> {
 
         match interaction.data.custom_id.as_str() {
             "confirm_auto_publish" => {
-                // 确认发布
-                self.publish_license_directly(&license).await?;
+                // 确认发布，机器人缺少权限时提示用户而不是静默结束
+                let content = match self.publish_license_directly(&license).await {
+                    Ok(()) => "✅ 协议已成功发布！".to_string(),
+                    Err(e @ BotError::AuthorizationError { .. }) => {
+                        tracing::warn!("自动发布协议失败: {}", e);
+                        format!("❌ {}", e.user_message())
+                    }
+                    Err(e) => return Err(e),
+                };
                 self.cleanup_message_and_respond(
                     &interaction,
                     CreateInteractionResponseMessage::new()
-                        .content("✅ 协议已成功发布！")
+                        .content(content)
                         .ephemeral(true),
                 )
                 .await?;
@@ -803,6 +1179,8 @@ impl<'a> AutoPublishFlow<'a> {
         let followup_message = self
             .show_new_user_publish_confirmation(&license, &editor_interaction)
             .await?;
+        self.followups
+            .track(&editor_interaction.token, Some(followup_message.id));
 
         // 等待用户交互 - 从followup消息等待
         let Some(interaction) = self
@@ -816,9 +1194,11 @@ impl<'a> AutoPublishFlow<'a> {
             "confirm_publish_new_license" => {
                 self.publish_and_respond_success(&interaction, &license)
                     .await?;
+                self.followups.mark_final();
             }
             "skip_publish_new_license" => {
                 self.respond_skip_publish(&interaction).await?;
+                self.followups.mark_final();
             }
             _ => {}
         }
@@ -832,8 +1212,15 @@ impl<'a> AutoPublishFlow<'a> {
         interaction: &serenity::all::ComponentInteraction,
         license: &crate::services::license::UserLicense,
     ) -> Result<(), BotError> {
-        // 发布协议
-        self.publish_license_directly(license).await?;
+        // 发布协议，机器人缺少权限时提示用户而不是静默结束
+        let content = match self.publish_license_directly(license).await {
+            Ok(()) => "✅ 协议已设置为默认协议，并发布到当前帖子！".to_string(),
+            Err(e @ BotError::AuthorizationError { .. }) => {
+                tracing::warn!("自动发布协议失败: {}", e);
+                format!("✅ 协议已设置为默认协议，但发布失败：{}", e.user_message())
+            }
+            Err(e) => return Err(e),
+        };
 
         // 直接编辑确认消息为最终状态，并响应interaction
         interaction
@@ -841,7 +1228,7 @@ impl<'a> AutoPublishFlow<'a> {
                 &self.ctx.http,
                 CreateInteractionResponse::UpdateMessage(
                     serenity::all::CreateInteractionResponseMessage::new()
-                        .content("✅ 协议已创建、设置为默认协议，并发布到当前帖子！")
+                        .content(content)
                         .components(Vec::new()),
                 ),
             )
@@ -861,7 +1248,7 @@ impl<'a> AutoPublishFlow<'a> {
                 &self.ctx.http,
                 CreateInteractionResponse::UpdateMessage(
                     serenity::all::CreateInteractionResponseMessage::new()
-                        .content("✅ 协议已创建并设置为默认协议！你可以稍后使用 `/发布协议` 或在新帖子中自动发布。")
+                        .content("✅ 协议已设置为默认协议！你可以稍后使用 `/发布协议` 或在新帖子中自动发布。")
                         .components(Vec::new()),
                 ),
             )
@@ -891,41 +1278,237 @@ impl<'a> AutoPublishFlow<'a> {
         &self,
         final_state: LicenseEditState,
     ) -> Result<crate::services::license::UserLicense, BotError> {
-        let (name, allow_redistribution, allow_modification, restrictions_note, allow_backup) =
-            final_state.to_user_license_fields();
-
-        // 创建协议
-        let license = self
+        let max_licenses = self
             .data
-            .db()
-            .license()
-            .create(
-                self.owner_id,
-                name,
-                allow_redistribution,
-                allow_modification,
-                restrictions_note,
-                allow_backup,
-            )
-            .await?;
+            .cfg()
+            .load()
+            .max_licenses_for(Some(self.thread.guild_id));
+        save_new_default_license(self.data.db(), self.owner_id, max_licenses, final_state).await
+    }
 
-        // 设置为默认协议
+    /// 将用户协议设置为默认协议并启用自动发布
+    async fn set_default_and_enable(&self, license_id: i32) -> Result<(), BotError> {
+        let owner_id = self.owner_id;
         self.data
             .db()
-            .user_settings()
-            .set_default_license(
-                self.owner_id,
-                Some(DefaultLicenseIdentifier::User(license.id)),
-                None,
+            .transaction(|txn| Box::pin(set_default_and_enable(txn, owner_id, license_id)))
+            .await
+    }
+}
+
+/// 保存自动发布流程中新建的协议，设为默认协议并启用自动发布
+///
+/// 三步在同一事务中完成，避免只完成其中一部分；协议数量已达上限时返回 `BotError::LicenseLimit`。
+pub async fn save_new_default_license(
+    db: &BotDatabase,
+    owner_id: UserId,
+    max_licenses: u64,
+    final_state: LicenseEditState,
+) -> Result<crate::services::license::UserLicense, BotError> {
+    let fields = final_state.to_user_license_fields();
+
+    db.transaction(|txn| {
+        Box::pin(async move {
+            let license = LicenseService::new(txn)
+                .with_max_licenses(max_licenses)
+                .create_with_source(owner_id, fields, LicenseSource::AutoPublish)
+                .await?;
+            set_default_and_enable(txn, owner_id, license.id).await?;
+            Ok(license)
+        })
+    })
+    .await
+}
+
+/// 将用户协议设置为默认协议并启用自动发布，应在事务中调用
+async fn set_default_and_enable(
+    txn: &DatabaseTransaction,
+    owner_id: UserId,
+    license_id: i32,
+) -> Result<(), BotError> {
+    let user_settings = UserSettingsService::new(txn);
+    user_settings
+        .set_default_license(
+            owner_id,
+            Some(DefaultLicenseIdentifier::User(license_id)),
+            None,
+        )
+        .await?;
+    user_settings.set_auto_publish(owner_id, true).await?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    use super::*;
+    use crate::services::license::MAX_LICENSES_PER_USER;
+
+    #[tokio::test]
+    async fn test_save_license_rolls_back_on_failure() {
+        use sea_orm::ConnectionTrait;
+
+        let db = BotDatabase::new_memory().await.unwrap();
+        db.apply_migrations(true).await.unwrap();
+        let owner_id = UserId::new(42);
+
+        // 创建协议、设置默认协议后，在启用自动发布这一步失败
+        db.inner()
+            .execute_unprepared(
+                "CREATE TRIGGER reject_auto_publish BEFORE UPDATE OF auto_publish_enabled \
+                 ON user_settings BEGIN SELECT RAISE(ABORT, '模拟失败'); END",
             )
-            .await?;
+            .await
+            .unwrap();
+        let result = save_new_default_license(
+            &db,
+            owner_id,
+            MAX_LICENSES_PER_USER,
+            LicenseEditState::new("新协议".to_string()),
+        )
+        .await;
+        assert!(matches!(result, Err(BotError::SeaOrmError { .. })));
+
+        // 协议和设置都已回滚
+        assert!(
+            db.license()
+                .get_user_licenses(owner_id)
+                .await
+                .unwrap()
+                .is_empty()
+        );
+        assert!(db.user_settings().get(owner_id).await.unwrap().is_none());
+
+        // 成功时一并提交
+        db.inner()
+            .execute_unprepared("DROP TRIGGER reject_auto_publish")
+            .await
+            .unwrap();
+        let license = save_new_default_license(
+            &db,
+            owner_id,
+            MAX_LICENSES_PER_USER,
+            LicenseEditState::new("新协议".to_string()),
+        )
+        .await
+        .unwrap();
+        let settings = db.user_settings().get(owner_id).await.unwrap().unwrap();
+        assert_eq!(settings.default_user_license_id, Some(license.id));
+        assert!(settings.auto_publish_enabled);
+    }
 
-        self.data
-            .db()
-            .user_settings()
-            .set_auto_publish(self.owner_id, true)
-            .await?;
+    /// 记录清理操作次数的模拟清理器
+    #[derive(Default)]
+    struct CountingCleaner {
+        deleted: AtomicUsize,
+        summarized: AtomicUsize,
+    }
+
+    impl FollowupCleaner for CountingCleaner {
+        async fn delete(&self, _followup: &TrackedFollowup) {
+            self.deleted.fetch_add(1, Ordering::SeqCst);
+        }
+
+        async fn summarize(&self, _followup: &TrackedFollowup, _summary: &str) {
+            self.summarized.fetch_add(1, Ordering::SeqCst);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_cleanup_deletes_intermediate_and_summarizes_last() {
+        let mut tracker = FollowupTracker::default();
+        tracker.track("token-a", None);
+        tracker.track("token-b", Some(MessageId::new(1)));
+        tracker.track("token-b", Some(MessageId::new(2)));
+
+        let cleaner = CountingCleaner::default();
+        tracker.cleanup(&cleaner, FLOW_ENDED_SUMMARY).await;
+
+        assert_eq!(cleaner.deleted.load(Ordering::SeqCst), 2);
+        assert_eq!(cleaner.summarized.load(Ordering::SeqCst), 1);
+
+        // 清理后不再重复处理
+        tracker.cleanup(&cleaner, FLOW_ENDED_SUMMARY).await;
+        assert_eq!(cleaner.deleted.load(Ordering::SeqCst), 2);
+        assert_eq!(cleaner.summarized.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn test_cleanup_keeps_final_message() {
+        let mut tracker = FollowupTracker::default();
+        tracker.track("token-a", None);
+        tracker.track("token-b", Some(MessageId::new(1)));
+        tracker.mark_final();
+
+        let cleaner = CountingCleaner::default();
+        tracker.cleanup(&cleaner, FLOW_ENDED_SUMMARY).await;
+
+        assert_eq!(cleaner.deleted.load(Ordering::SeqCst), 1);
+        assert_eq!(cleaner.summarized.load(Ordering::SeqCst), 0);
+    }
+
+    #[tokio::test]
+    async fn test_tracking_after_final_resets_state() {
+        let mut tracker = FollowupTracker::default();
+        tracker.track("token-a", None);
+        tracker.mark_final();
+        tracker.track("token-b", Some(MessageId::new(1)));
+
+        let cleaner = CountingCleaner::default();
+        tracker.cleanup(&cleaner, FLOW_ENDED_SUMMARY).await;
+
+        assert_eq!(cleaner.deleted.load(Ordering::SeqCst), 1);
+        assert_eq!(cleaner.summarized.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn test_cleanup_without_followups() {
+        let mut tracker = FollowupTracker::default();
+        let cleaner = CountingCleaner::default();
+        tracker.cleanup(&cleaner, FLOW_ENDED_SUMMARY).await;
+
+        assert_eq!(cleaner.deleted.load(Ordering::SeqCst), 0);
+        assert_eq!(cleaner.summarized.load(Ordering::SeqCst), 0);
+    }
+
+    #[test]
+    fn test_resolve_default_license_order() {
+        let mut settings = entities::entities::user_settings::Model {
+            user_id: 1,
+            auto_publish_enabled: true,
+            skip_auto_publish_confirmation: false,
+            default_user_license_id: Some(3),
+            default_system_license_name: Some("MIT".to_string()),
+            default_system_license_backup: None,
+        };
+
+        assert_eq!(
+            resolve_default_license(&settings, Some("社区协议")),
+            Some(DefaultLicenseIdentifier::User(3))
+        );
+
+        settings.default_user_license_id = None;
+        assert_eq!(
+            resolve_default_license(&settings, Some("社区协议")),
+            Some(DefaultLicenseIdentifier::System("MIT".to_string()))
+        );
+
+        settings.default_system_license_name = None;
+        assert_eq!(
+            resolve_default_license(&settings, Some("社区协议")),
+            Some(DefaultLicenseIdentifier::System("社区协议".to_string()))
+        );
+        assert_eq!(resolve_default_license(&settings, None), None);
+    }
+
+    #[test]
+    fn test_thread_age_threshold_boundary() {
+        assert!(!is_thread_too_old(299, 300));
+        assert!(!is_thread_too_old(300, 300));
+        assert!(is_thread_too_old(301, 300));
 
-        Ok(license)
+        // 0 表示不检查帖子时长
+        assert!(!is_thread_too_old(i64::MAX, 0));
     }
 }
@@ -0,0 +1,67 @@
+use std::time::Duration;
+
+use serenity::all::{ChannelId, Context, MessageId};
+use tracing::{debug, info};
+
+use crate::{commands::Data, error::BotError};
+
+/// Discord 的 "Unknown Message" 错误码
+const UNKNOWN_MESSAGE_CODE: isize = 10008;
+/// 置顶变化后等待的时间，避免与重新发布协议时的取消置顶/置顶冲突
+const PIN_CHECK_DELAY: Duration = Duration::from_secs(5);
+
+/// 帖子置顶变化时，确保已发布的协议消息仍处于置顶状态
+pub async fn handle_pins_update(
+    ctx: &Context,
+    channel_id: ChannelId,
+    data: &Data,
+) -> Result<(), BotError> {
+    if data
+        .db()
+        .published_posts()
+        .get_by_thread(channel_id)
+        .await?
+        .is_none()
+    {
+        return Ok(());
+    }
+
+    // 重新发布时会先取消旧消息的置顶，等待发布记录更新后再读取
+    tokio::time::sleep(PIN_CHECK_DELAY).await;
+    let Some(post) = data
+        .db()
+        .published_posts()
+        .get_by_thread(channel_id)
+        .await?
+    else {
+        return Ok(());
+    };
+    let message_id = MessageId::new(post.message_id as u64);
+
+    let message = match ctx.http.get_message(channel_id, message_id).await {
+        Ok(message) => message,
+        Err(err) => {
+            if let serenity::Error::Http(http_err) = &err
+                && let serenity::http::HttpError::UnsuccessfulRequest(resp) = http_err
+                && resp.error.code == UNKNOWN_MESSAGE_CODE
+            {
+                debug!(
+                    thread_id = %channel_id,
+                    "License message already deleted, skipping re-pin"
+                );
+                return Ok(());
+            }
+            return Err(err.into());
+        }
+    };
+
+    if !message.pinned {
+        message.pin(&ctx.http).await?;
+        info!(
+            "Re-pinned license message {} in thread {}",
+            message_id, channel_id
+        );
+    }
+
+    Ok(())
+}
@@ -1,7 +1,10 @@
 mod auto_publish;
 mod auto_publish_flow;
+mod license_pin;
 mod ping;
 
+pub use auto_publish::{backfill_thread, clear_processed_threads, reguide_thread};
+pub use auto_publish_flow::BackfillMode;
 pub use ping::PingHandler;
 use serenity::all::{Channel, ChannelType, Context, FullEvent};
 
@@ -13,6 +16,27 @@ pub async fn poise_event_handler(
     _framework: poise::FrameworkContext<'_, Data, BotError>,
     data: &Data,
 ) -> Result<(), BotError> {
+    if let FullEvent::ThreadDelete { thread, .. } = event {
+        // 帖子被删除后清理发布记录，避免统计数据虚高
+        match data.db().published_posts().delete(thread.id).await {
+            Ok(true) => tracing::info!(
+                "Removed published post record for deleted thread {}",
+                thread.id
+            ),
+            Ok(false) => {}
+            Err(e) => tracing::error!(
+                "Failed to remove published post for thread {}: {}",
+                thread.id,
+                e
+            ),
+        }
+    }
+    if let FullEvent::ChannelPinsUpdate { pin } = event
+        && data.cfg().load().enforce_license_pin
+        && let Err(e) = license_pin::handle_pins_update(ctx, pin.channel_id, data).await
+    {
+        tracing::error!("Failed to enforce license pin in {}: {}", pin.channel_id, e);
+    }
     if let FullEvent::ThreadCreate { thread } = event {
         // 检查是否是论坛类型频道中的线程
         if let Ok(Channel::Guild(guild_channel)) = thread
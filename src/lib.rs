@@ -8,6 +8,7 @@ pub mod database;
 pub mod error;
 pub mod grpc_handlers;
 pub mod handlers;
+pub mod logging;
 pub mod services;
 pub mod types;
 pub mod utils;
@@ -0,0 +1,201 @@
+use std::sync::OnceLock;
+
+use chrono::{FixedOffset, Utc};
+use serde::{Deserialize, Serialize};
+use tracing::Subscriber;
+use tracing_subscriber::{
+    EnvFilter, Layer, Registry,
+    fmt::{MakeWriter, format::Writer, time::FormatTime},
+    layer::SubscriberExt,
+    reload,
+    util::SubscriberInitExt,
+};
+
+use crate::error::BotError;
+
+/// 运行时调整日志过滤规则的句柄
+pub type LogFilterHandle = reload::Handle<EnvFilter, Registry>;
+
+// 全局日志过滤句柄，在初始化日志时设置
+static LOG_FILTER: OnceLock<LogFilterHandle> = OnceLock::new();
+
+/// 日志输出格式
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum LogFormat {
+    /// 便于阅读的文本格式
+    #[default]
+    Text,
+    /// 每行一个 JSON 对象，便于日志收集系统解析
+    Json,
+}
+
+/// 按配置的时区偏移输出日志时间
+struct TimeFormatter {
+    offset: i32,
+}
+
+impl FormatTime for TimeFormatter {
+    fn format_time(&self, w: &mut Writer<'_>) -> std::fmt::Result {
+        let offset = self.offset;
+        let now = Utc::now().with_timezone(
+            &FixedOffset::east_opt(offset)
+                .expect("Failed to create FixedOffset with the configured time offset"),
+        );
+        write!(w, "{}", now.format("%Y-%m-%d %H:%M:%S%.3f %Z"))
+    }
+}
+
+/// 构建日志订阅器，`time_offset` 为时区偏移（秒）
+///
+/// 过滤规则放在可重载的层中，返回的句柄用于运行时替换规则
+fn build_subscriber<W>(
+    format: LogFormat,
+    time_offset: i32,
+    filter: EnvFilter,
+    writer: W,
+) -> (Box<dyn Subscriber + Send + Sync>, LogFilterHandle)
+where
+    W: for<'a> MakeWriter<'a> + Send + Sync + 'static,
+{
+    let (filter, handle) = reload::Layer::new(filter);
+    let fmt_layer = tracing_subscriber::fmt::layer()
+        .with_timer(TimeFormatter {
+            offset: time_offset,
+        })
+        .with_writer(writer);
+    let fmt_layer = match format {
+        LogFormat::Text => fmt_layer.with_ansi(true).boxed(),
+        LogFormat::Json => fmt_layer.json().with_ansi(false).boxed(),
+    };
+    let subscriber = tracing_subscriber::registry().with(filter).with(fmt_layer);
+    (Box::new(subscriber), handle)
+}
+
+/// 初始化全局日志，初始日志级别由 `RUST_LOG` 环境变量控制
+pub fn init(format: LogFormat, time_offset: i32) {
+    let (subscriber, handle) = build_subscriber(
+        format,
+        time_offset,
+        EnvFilter::from_default_env(),
+        std::io::stdout,
+    );
+    subscriber.init();
+    let _ = LOG_FILTER.set(handle);
+}
+
+/// 用新的过滤规则（与 `RUST_LOG` 语法相同）替换当前规则
+fn apply_filter(handle: &LogFilterHandle, directives: &str) -> Result<(), BotError> {
+    let filter = EnvFilter::try_new(directives).map_err(|e| BotError::ValidationError {
+        message: format!("无效的日志过滤规则: {e}"),
+        loc: snafu::location!(),
+    })?;
+    handle.reload(filter).map_err(|e| BotError::GenericError {
+        message: format!("更新日志过滤规则失败: {e}"),
+        source: None,
+    })
+}
+
+/// 获取日志过滤句柄，日志未初始化时返回错误
+fn log_filter() -> Result<&'static LogFilterHandle, BotError> {
+    LOG_FILTER.get().ok_or(BotError::GenericError {
+        message: "日志尚未初始化".to_string(),
+        source: None,
+    })
+}
+
+/// 获取当前的日志过滤规则
+pub fn current_log_filter() -> Result<String, BotError> {
+    log_filter()?
+        .with_current(|filter| filter.to_string())
+        .map_err(|e| BotError::GenericError {
+            message: format!("读取日志过滤规则失败: {e}"),
+            source: None,
+        })
+}
+
+/// 在运行时替换全局日志过滤规则
+pub fn set_log_filter(directives: &str) -> Result<(), BotError> {
+    apply_filter(log_filter()?, directives)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        io,
+        sync::{Arc, Mutex},
+    };
+
+    use super::*;
+
+    #[derive(Clone, Default)]
+    struct SharedBuf(Arc<Mutex<Vec<u8>>>);
+
+    impl io::Write for SharedBuf {
+        fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+            self.0.lock().unwrap().extend_from_slice(buf);
+            Ok(buf.len())
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_json_log_format() {
+        let buf = SharedBuf::default();
+        let writer = buf.clone();
+        let (subscriber, _) =
+            build_subscriber(LogFormat::Json, 7200, EnvFilter::new("info"), move || {
+                writer.clone()
+            });
+
+        tracing::subscriber::with_default(subscriber, || {
+            tracing::info!(thread_id = 42, "协议已发布");
+        });
+
+        let output = String::from_utf8(buf.0.lock().unwrap().clone()).unwrap();
+        let line: serde_json::Value = serde_json::from_str(output.trim()).unwrap();
+        assert_eq!(line["level"], "INFO");
+        assert_eq!(line["fields"]["message"], "协议已发布");
+        assert_eq!(line["fields"]["thread_id"], 42);
+        // 时间使用配置的时区偏移
+        assert!(line["timestamp"].as_str().unwrap().ends_with("+02:00"));
+    }
+
+    #[test]
+    fn test_reload_log_filter() {
+        let buf = SharedBuf::default();
+        let writer = buf.clone();
+        let (subscriber, handle) =
+            build_subscriber(LogFormat::Json, 0, EnvFilter::new("info"), move || {
+                writer.clone()
+            });
+        let line_count = || {
+            buf.0
+                .lock()
+                .unwrap()
+                .iter()
+                .filter(|&&b| b == b'\n')
+                .count()
+        };
+
+        tracing::subscriber::with_default(subscriber, || {
+            tracing::debug!("调整前");
+            assert_eq!(line_count(), 0);
+
+            apply_filter(&handle, "debug").unwrap();
+            tracing::debug!("调整后");
+            assert_eq!(line_count(), 1);
+            assert_eq!(handle.with_current(|f| f.to_string()).unwrap(), "debug");
+
+            // 无效规则被拒绝，原规则保持不变
+            assert!(matches!(
+                apply_filter(&handle, "dc_bot=verbose"),
+                Err(BotError::ValidationError { .. })
+            ));
+            assert_eq!(handle.with_current(|f| f.to_string()).unwrap(), "debug");
+        });
+    }
+}
@@ -1,7 +1,6 @@
 use std::sync::Arc;
 
 use arc_swap::ArcSwap;
-use chrono::{FixedOffset, Utc};
 use clap::Parser;
 use dc_bot::{
     Args,
@@ -9,50 +8,101 @@ use dc_bot::{
     config::BotCfg,
     database::BotDatabase,
     error::BotError,
+    logging,
     services::{
-        gateway, notification_service::NotificationService, system_license::SystemLicenseCache,
+        discord_health::{self, DiscordHealth},
+        gateway::{self, GatewayStatus},
+        health_server::{self, Readiness, ReadinessHandler},
+        notification_service::NotificationService,
+        self_check::{
+            CheckLevel, CheckResult, SelfCheckReport, check_config, check_forum_permissions,
+            check_migrations, check_system_licenses, forum_permissions,
+        },
+        system_license::SystemLicenseCache,
     },
 };
-use serenity::{Client, all::GatewayIntents};
-use tracing_subscriber::{
-    EnvFilter,
-    fmt::{format::Writer, time::FormatTime},
+use serenity::{
+    Client,
+    all::{GatewayIntents, Http},
 };
 
 #[cfg(not(target_env = "msvc"))]
 #[global_allocator]
 static GLOBAL: tikv_jemallocator::Jemalloc = tikv_jemallocator::Jemalloc;
 
-struct TimeFormatter {
-    offset: i32,
-}
+/// 启动前检查数据库、系统协议、配置和论坛权限，输出就绪状态
+///
+/// 启用 `startup_check_strict` 时，存在严重问题则拒绝启动。
+async fn startup_self_check(
+    http: &Http,
+    db: &BotDatabase,
+    cfg: &BotCfg,
+    system_license_cache: &SystemLicenseCache,
+) -> Result<(), BotError> {
+    let mut report = SelfCheckReport::default();
+
+    report.push(check_migrations(&db.pending_migrations().await?));
 
-impl FormatTime for TimeFormatter {
-    fn format_time(&self, w: &mut Writer<'_>) -> std::fmt::Result {
-        let offset = self.offset;
-        let now = Utc::now().with_timezone(
-            &FixedOffset::east_opt(offset)
-                .expect("Failed to create FixedOffset with the configured time offset"),
-        );
-        write!(w, "{}", now.format("%Y-%m-%d %H:%M:%S%.3f %Z"))
+    let system_licenses = system_license_cache.get_all().await;
+    report.push(check_system_licenses(system_licenses.len()));
+    let license_names: Vec<String> = system_licenses
+        .into_iter()
+        .map(|l| l.license_name)
+        .collect();
+    report.push(check_config(cfg, &license_names));
+
+    match http.get_current_user().await {
+        Ok(bot) => {
+            report.push(CheckResult {
+                name: "Discord 认证",
+                level: CheckLevel::Ok,
+                message: format!("已登录为 {}", bot.name),
+            });
+            for forum_id in &cfg.allowed_forum_channels {
+                report.push(match forum_permissions(http, *forum_id).await {
+                    Ok(permissions) => check_forum_permissions(*forum_id, permissions),
+                    Err(e) => CheckResult {
+                        name: "论坛权限",
+                        level: CheckLevel::Warning,
+                        message: format!("无法访问 <#{forum_id}>: {e}"),
+                    },
+                });
+            }
+        }
+        Err(e) => report.push(CheckResult {
+            name: "Discord 认证",
+            level: CheckLevel::Critical,
+            message: format!("无法获取机器人信息: {e}"),
+        }),
+    }
+
+    report.log_summary();
+    if report.level() == CheckLevel::Critical && cfg.startup_check_strict {
+        return Err(BotError::GenericError {
+            message: "启动自检存在严重问题，已拒绝启动".to_string(),
+            source: None,
+        });
     }
+    Ok(())
 }
 
 #[tokio::main]
 async fn main() -> Result<(), BotError> {
     let args = Args::parse();
     let cfg = BotCfg::read(&args.config)?;
-    tracing_subscriber::fmt()
-        .with_env_filter(EnvFilter::from_default_env())
-        .with_ansi(true)
-        .with_timer(TimeFormatter {
-            offset: cfg.time_offset,
-        })
-        .init();
+    logging::init(cfg.log_format, cfg.time_offset);
 
     let intents = GatewayIntents::non_privileged() | GatewayIntents::privileged();
 
-    let db = BotDatabase::new(&args.db).await?;
+    let db = BotDatabase::new(&args.db, &cfg.database).await?;
+    let applied_migrations = db.apply_migrations(cfg.auto_migrate).await?;
+    if applied_migrations.is_empty() {
+        tracing::info!("Database schema is up to date");
+    } else {
+        for name in &applied_migrations {
+            tracing::info!("Applied migration: {}", name);
+        }
+    }
     let cfg = Arc::new(ArcSwap::from_pointee(cfg));
 
     // Initialize system license cache
@@ -60,17 +110,27 @@ async fn main() -> Result<(), BotError> {
 
     // Initialize notification service
     let notification_service = Arc::new(NotificationService::new(cfg.clone()));
+    let discord_health = Arc::new(DiscordHealth::default());
 
     // Start GRPC gateway client if configured
-    if cfg.load().gateway_enabled.unwrap_or(false)
+    let gateway_enabled = cfg.load().gateway_enabled.unwrap_or(false)
         && cfg.load().gateway_address.is_some()
-        && cfg.load().gateway_api_key.is_some()
-    {
+        && cfg.load().gateway_api_key.is_some();
+    let gateway_status = Arc::new(ArcSwap::from_pointee(GatewayStatus {
+        enabled: gateway_enabled,
+        ..Default::default()
+    }));
+    if gateway_enabled {
         let db_for_gateway = Arc::new(db.clone());
         let cfg_for_gateway = cfg.clone();
+        let status_for_gateway = gateway_status.clone();
         tokio::spawn(async move {
-            if let Err(e) =
-                gateway::start_gateway_client_with_retry(db_for_gateway, cfg_for_gateway).await
+            if let Err(e) = gateway::start_gateway_client_with_retry(
+                db_for_gateway,
+                cfg_for_gateway,
+                status_for_gateway,
+            )
+            .await
             {
                 tracing::error!("Gateway client failed: {}", e);
             }
@@ -80,6 +140,11 @@ async fn main() -> Result<(), BotError> {
         tracing::warn!("GRPC gateway not configured, skipping gateway client");
     }
 
+    let readiness = Arc::new(Readiness::default());
+    if let Some(port) = cfg.load().health_port {
+        health_server::start_health_server(port, db.clone(), readiness.clone()).await?;
+    }
+
     let mut client = Client::builder(&cfg.load().token, intents)
         .cache_settings({
             let mut s = serenity::cache::Settings::default();
@@ -91,15 +156,28 @@ async fn main() -> Result<(), BotError> {
         })
         .type_map_insert::<BotDatabase>(db.to_owned())
         .type_map_insert::<BotCfg>(cfg.to_owned())
+        .type_map_insert::<GatewayStatus>(gateway_status.clone())
+        .event_handler(ReadinessHandler(readiness))
         .framework(framework(
             db.clone(),
             cfg.clone(),
-            system_license_cache,
+            system_license_cache.clone(),
             notification_service,
+            discord_health.clone(),
         ))
         .await?;
 
-    // Start status monitor after client is created
+    startup_self_check(&client.http, &db, &cfg.load(), &system_license_cache).await?;
+
+    // Start background tasks after client is created
+    dc_bot::services::system_license::start_auto_reload(system_license_cache.clone(), cfg.clone());
+    discord_health::start_health_probe(client.http.clone(), discord_health.clone());
+    dc_bot::services::post_sweeper::start_post_sweeper(
+        client.http.clone(),
+        Arc::new(db.clone()),
+        cfg.clone(),
+        discord_health,
+    );
     let db_for_monitor = Arc::new(db);
     let cfg_for_monitor = cfg;
     let http_for_monitor = client.http.clone();
@@ -111,6 +189,7 @@ async fn main() -> Result<(), BotError> {
             db_for_monitor,
             cfg_for_monitor,
             cache_for_monitor,
+            gateway_status,
         )
         .await;
     });
@@ -0,0 +1,83 @@
+use serenity::all::{Colour, CreateEmbed, CreateMessage, Http, Timestamp, UserId};
+use tracing::warn;
+
+use crate::config::BotCfg;
+
+/// 审计日志记录的操作类型
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AuditAction {
+    /// 发布协议
+    LicensePublished,
+    /// 删除协议
+    LicenseDeleted,
+    /// 修改用户设置
+    SettingChanged,
+}
+
+impl AuditAction {
+    fn label(self) -> &'static str {
+        match self {
+            AuditAction::LicensePublished => "📢 发布协议",
+            AuditAction::LicenseDeleted => "🗑️ 删除协议",
+            AuditAction::SettingChanged => "⚙️ 修改设置",
+        }
+    }
+
+    fn colour(self) -> Colour {
+        match self {
+            AuditAction::LicensePublished => Colour::DARK_GREEN,
+            AuditAction::LicenseDeleted => Colour::RED,
+            AuditAction::SettingChanged => Colour::BLUE,
+        }
+    }
+}
+
+/// 生成审计日志的embed
+fn create_audit_log_embed(actor: UserId, action: AuditAction, target: &str) -> CreateEmbed {
+    CreateEmbed::new()
+        .title(action.label())
+        .field("操作者", format!("<@{actor}> (`{actor}`)"), true)
+        .field("对象", target, true)
+        .colour(action.colour())
+        .timestamp(Timestamp::now())
+}
+
+/// 向审计频道发送操作记录
+///
+/// 未配置审计频道时不发送。发送失败只记录警告，不影响调用方的操作。
+pub async fn send_audit_log(
+    http: &Http,
+    cfg: &BotCfg,
+    actor: UserId,
+    action: AuditAction,
+    target: impl AsRef<str>,
+) {
+    let Some(channel_id) = cfg.audit_channel_id else {
+        return;
+    };
+
+    let embed = create_audit_log_embed(actor, action, target.as_ref());
+    if let Err(e) = channel_id
+        .send_message(http, CreateMessage::new().embed(embed))
+        .await
+    {
+        warn!("发送审计日志失败: {}", e);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_create_audit_log_embed() {
+        let embed =
+            create_audit_log_embed(UserId::new(42), AuditAction::LicenseDeleted, "协议「MIT」");
+        let json = serde_json::to_value(&embed).unwrap();
+
+        assert_eq!(json["title"], "🗑️ 删除协议");
+        assert_eq!(json["fields"][0]["value"], "<@42> (`42`)");
+        assert_eq!(json["fields"][1]["value"], "协议「MIT」");
+        assert_eq!(json["color"], Colour::RED.0);
+    }
+}
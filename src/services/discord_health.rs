@@ -0,0 +1,109 @@
+use std::{
+    sync::{
+        Arc,
+        atomic::{AtomicU32, Ordering},
+    },
+    time::Duration,
+};
+
+use serenity::all::{Http, HttpError};
+use tokio::time;
+use tracing::{info, warn};
+
+/// 连续多少次 Discord 请求失败后判定为服务故障
+const OUTAGE_THRESHOLD: u32 = 3;
+/// 故障期间探测 Discord 是否恢复的间隔
+const PROBE_INTERVAL: Duration = Duration::from_secs(30);
+
+/// 判断 Discord 请求失败是否说明 Discord 服务本身不可用
+///
+/// 只有网络错误和 5xx 响应计入，4xx（如帖子不存在、无权限）属于请求本身的问题
+pub fn is_outage_error(err: &serenity::Error) -> bool {
+    match err {
+        serenity::Error::Http(HttpError::UnsuccessfulRequest(response)) => {
+            response.status_code.is_server_error()
+        }
+        serenity::Error::Http(HttpError::Request(_)) => true,
+        _ => false,
+    }
+}
+
+/// Discord 服务的健康状态
+///
+/// 记录连续失败的次数，达到阈值后视为故障，任意一次成功即恢复
+#[derive(Debug, Default)]
+pub struct DiscordHealth {
+    consecutive_failures: AtomicU32,
+}
+
+impl DiscordHealth {
+    /// 当前是否处于故障状态
+    pub fn is_outage(&self) -> bool {
+        self.consecutive_failures.load(Ordering::Relaxed) >= OUTAGE_THRESHOLD
+    }
+
+    /// 记录一次失败，返回本次失败是否使状态变为故障
+    pub fn record_failure(&self) -> bool {
+        self.consecutive_failures.fetch_add(1, Ordering::Relaxed) + 1 == OUTAGE_THRESHOLD
+    }
+
+    /// 记录一次成功，返回是否从故障中恢复
+    pub fn record_success(&self) -> bool {
+        self.consecutive_failures.swap(0, Ordering::Relaxed) >= OUTAGE_THRESHOLD
+    }
+}
+
+/// 启动 Discord 健康探测后台任务
+///
+/// 只在故障期间定期请求当前机器人用户，请求成功即恢复，
+/// 避免故障期间由命令反复请求注定失败的接口
+pub fn start_health_probe(http: Arc<Http>, health: Arc<DiscordHealth>) {
+    tokio::spawn(async move {
+        let mut interval = time::interval(PROBE_INTERVAL);
+        loop {
+            interval.tick().await;
+            if !health.is_outage() {
+                continue;
+            }
+            match http.get_current_user().await {
+                Ok(_) => {
+                    if health.record_success() {
+                        info!("Discord 服务已恢复");
+                    }
+                }
+                Err(e) => warn!("Discord 服务仍不可用: {}", e),
+            }
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_outage_state_transitions() {
+        let health = DiscordHealth::default();
+        assert!(!health.is_outage());
+
+        // 未达到阈值前不视为故障，成功会清零计数
+        assert!(!health.record_failure());
+        assert!(!health.record_failure());
+        assert!(!health.is_outage());
+        assert!(!health.record_success());
+        assert!(!health.record_failure());
+        assert!(!health.record_failure());
+        assert!(!health.is_outage());
+
+        // 连续失败达到阈值时进入故障，之后的失败不再重复报告
+        assert!(health.record_failure());
+        assert!(health.is_outage());
+        assert!(!health.record_failure());
+        assert!(health.is_outage());
+
+        // 一次成功即恢复
+        assert!(health.record_success());
+        assert!(!health.is_outage());
+        assert!(!health.record_success());
+    }
+}
@@ -15,9 +15,76 @@ use registry::{
 use crate::config::BotCfg;
 use crate::database::BotDatabase;
 use arc_swap::ArcSwap;
+use chrono::{DateTime, Utc};
+use serenity::prelude::TypeMapKey;
 use std::convert::TryFrom;
 use std::sync::Arc;
 
+/// 网关连接状态，由网关客户端在连接/断开时更新
+#[derive(Debug, Clone, Default)]
+pub struct GatewayStatus {
+    /// 是否启用了网关客户端
+    pub enabled: bool,
+    /// 当前是否已连接
+    pub connected: bool,
+    /// 最近一次建立连接的时间
+    pub last_connected_at: Option<DateTime<Utc>>,
+    /// 最近一次 Ping 估算的往返延迟（毫秒）
+    pub last_ping_rtt_ms: Option<i64>,
+}
+
+impl TypeMapKey for GatewayStatus {
+    type Value = Arc<ArcSwap<GatewayStatus>>;
+}
+
+impl GatewayStatus {
+    /// 标记为已连接并记录连接时间
+    pub fn mark_connected(status: &ArcSwap<GatewayStatus>) {
+        let now = Utc::now();
+        status.rcu(|current| GatewayStatus {
+            connected: true,
+            last_connected_at: Some(now),
+            ..GatewayStatus::clone(current)
+        });
+    }
+
+    /// 标记为已断开，保留上次连接时间
+    pub fn mark_disconnected(status: &ArcSwap<GatewayStatus>) {
+        status.rcu(|current| GatewayStatus {
+            connected: false,
+            ..GatewayStatus::clone(current)
+        });
+    }
+
+    /// 记录最近一次 Ping 测得的往返延迟
+    pub fn record_ping_rtt(status: &ArcSwap<GatewayStatus>, rtt_ms: i64) {
+        status.rcu(|current| GatewayStatus {
+            last_ping_rtt_ms: Some(rtt_ms),
+            ..GatewayStatus::clone(current)
+        });
+    }
+
+    /// 用于系统信息展示的状态文本
+    pub fn describe(&self) -> String {
+        if !self.enabled {
+            return "⚪ 未启用".to_string();
+        }
+        let state = if self.connected {
+            "🟢 已连接"
+        } else {
+            "🔴 未连接"
+        };
+        let mut text = match self.last_connected_at {
+            Some(at) => format!("{state}\n上次连接: <t:{}:R>", at.timestamp()),
+            None => format!("{state}\n从未连接"),
+        };
+        if let Some(rtt_ms) = self.last_ping_rtt_ms {
+            text.push_str(&format!("\nPing 往返: {rtt_ms} ms"));
+        }
+        text
+    }
+}
+
 /// 智能检测协议并构建连接 URL
 fn build_gateway_url(address: &str) -> String {
     if address.starts_with("http://") || address.starts_with("https://") {
@@ -37,9 +104,22 @@ fn build_gateway_url(address: &str) -> String {
 }
 
 /// 启动反向连接模式客户端
+///
+/// 连接结束（无论正常关闭还是出错）后会将网关状态标记为已断开
 pub async fn start_gateway_client(
     db: Arc<BotDatabase>,
     cfg: Arc<ArcSwap<BotCfg>>,
+    status: Arc<ArcSwap<GatewayStatus>>,
+) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    let result = run_gateway_client(db, cfg, &status).await;
+    GatewayStatus::mark_disconnected(&status);
+    result
+}
+
+async fn run_gateway_client(
+    db: Arc<BotDatabase>,
+    cfg: Arc<ArcSwap<BotCfg>>,
+    status: &ArcSwap<GatewayStatus>,
 ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
     let config = cfg.load();
     let gateway_address = config
@@ -134,6 +214,7 @@ pub async fn start_gateway_client(
                         &forward_req,
                         db_conn,
                         &current_cfg,
+                        status,
                     )
                     .await
                     {
@@ -197,28 +278,31 @@ pub async fn start_gateway_client(
                 connection_message::MessageType::Response(response) => {
                     info!("Received Response: status {}", response.status_code);
                 }
-                connection_message::MessageType::Status(status) => {
-                    match registry::connection_status::StatusType::try_from(status.status) {
+                connection_message::MessageType::Status(conn_status) => {
+                    match registry::connection_status::StatusType::try_from(conn_status.status) {
                         Ok(registry::connection_status::StatusType::Connected) => {
-                            if status.connection_id.is_empty() {
+                            if conn_status.connection_id.is_empty() {
                                 warn!("Received connected status without connection_id");
                             } else {
-                                info!(connection_id = %status.connection_id, "Gateway reported connection established");
-                                if let Err(e) = conn_id_tx.send(Some(status.connection_id.clone()))
+                                info!(connection_id = %conn_status.connection_id, "Gateway reported connection established");
+                                GatewayStatus::mark_connected(status);
+                                if let Err(e) =
+                                    conn_id_tx.send(Some(conn_status.connection_id.clone()))
                                 {
                                     warn!("Failed to update connection_id for heartbeat: {}", e);
                                 }
                             }
                         }
                         Ok(registry::connection_status::StatusType::Disconnected) => {
-                            warn!(connection_id = %status.connection_id, message = %status.message, "Gateway reported disconnection");
+                            warn!(connection_id = %conn_status.connection_id, message = %conn_status.message, "Gateway reported disconnection");
+                            GatewayStatus::mark_disconnected(status);
                             let _ = conn_id_tx.send(None);
                         }
                         Ok(registry::connection_status::StatusType::Error) => {
-                            warn!(connection_id = %status.connection_id, message = %status.message, "Gateway reported connection error");
+                            warn!(connection_id = %conn_status.connection_id, message = %conn_status.message, "Gateway reported connection error");
                         }
                         Err(_) => {
-                            warn!(status = %status.status, "Received unknown connection status type");
+                            warn!(status = %conn_status.status, "Received unknown connection status type");
                         }
                     }
                 }
@@ -236,13 +320,14 @@ pub async fn start_gateway_client(
 pub async fn start_gateway_client_with_retry(
     db: Arc<BotDatabase>,
     cfg: Arc<ArcSwap<BotCfg>>,
+    status: Arc<ArcSwap<GatewayStatus>>,
 ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
     let mut retry_count = 0;
     let max_retries = 10;
     let mut backoff_duration = Duration::from_secs(1);
 
     loop {
-        match start_gateway_client(db.clone(), cfg.clone()).await {
+        match start_gateway_client(db.clone(), cfg.clone(), status.clone()).await {
             Ok(_) => {
                 info!("Gateway connection established successfully");
                 break;
@@ -276,3 +361,39 @@ pub async fn start_gateway_client_with_retry(
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_gateway_status_transitions() {
+        let status = ArcSwap::from_pointee(GatewayStatus {
+            enabled: true,
+            ..Default::default()
+        });
+        assert!(status.load().describe().contains("从未连接"));
+
+        GatewayStatus::mark_connected(&status);
+        let connected = status.load_full();
+        assert!(connected.connected);
+        assert!(connected.last_connected_at.is_some());
+        assert!(connected.describe().starts_with("🟢 已连接"));
+
+        GatewayStatus::mark_disconnected(&status);
+        let disconnected = status.load_full();
+        assert!(!disconnected.connected);
+        assert_eq!(disconnected.last_connected_at, connected.last_connected_at);
+        assert!(disconnected.describe().starts_with("🔴 未连接"));
+        assert!(!disconnected.describe().contains("Ping"));
+
+        GatewayStatus::record_ping_rtt(&status, 42);
+        assert_eq!(status.load().last_ping_rtt_ms, Some(42));
+        assert!(status.load().describe().contains("Ping 往返: 42 ms"));
+    }
+
+    #[test]
+    fn test_gateway_status_disabled() {
+        assert_eq!(GatewayStatus::default().describe(), "⚪ 未启用");
+    }
+}
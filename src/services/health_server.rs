@@ -0,0 +1,165 @@
+use std::{
+    collections::HashSet,
+    net::{Ipv4Addr, SocketAddr},
+    sync::{Arc, Mutex},
+};
+
+use axum::{Router, extract::State, http::StatusCode, routing::get};
+use serenity::{
+    all::{ConnectionStage, Context, EventHandler, Ready, ShardId, ShardStageUpdateEvent},
+    async_trait,
+};
+use snafu::ResultExt;
+use tokio::net::TcpListener;
+use tracing::{error, info, warn};
+
+use crate::{database::BotDatabase, error::BotError};
+
+/// Discord 客户端各分片是否已连接，供 `/readyz` 使用
+///
+/// 分片总数在收到 Ready 事件后才知道，所有分片都连接后才视为就绪
+#[derive(Debug, Default)]
+pub struct Readiness(Mutex<ShardStates>);
+
+#[derive(Debug, Default)]
+struct ShardStates {
+    total: u32,
+    connected: HashSet<ShardId>,
+}
+
+impl Readiness {
+    pub fn is_ready(&self) -> bool {
+        let shards = self.0.lock().unwrap();
+        shards.total > 0 && shards.connected.len() as u32 >= shards.total
+    }
+
+    /// 记录分片总数
+    pub fn set_shard_total(&self, total: u32) {
+        self.0.lock().unwrap().total = total;
+    }
+
+    /// 更新单个分片的连接状态
+    pub fn set_shard_connected(&self, shard_id: ShardId, connected: bool) {
+        let mut shards = self.0.lock().unwrap();
+        if connected {
+            shards.connected.insert(shard_id);
+        } else {
+            shards.connected.remove(&shard_id);
+        }
+    }
+}
+
+/// 根据网关连接事件更新各分片的就绪状态
+///
+/// 分片收到 Ready 事件后视为已连接，断线重连期间视为未连接
+pub struct ReadinessHandler(pub Arc<Readiness>);
+
+#[async_trait]
+impl EventHandler for ReadinessHandler {
+    async fn ready(&self, ctx: Context, ready: Ready) {
+        // 未分片时 Ready 事件不带分片信息，只有一个分片
+        self.0
+            .set_shard_total(ready.shard.map_or(1, |shard| shard.total));
+        self.0.set_shard_connected(ctx.shard_id, true);
+    }
+
+    async fn shard_stage_update(&self, _ctx: Context, event: ShardStageUpdateEvent) {
+        self.0
+            .set_shard_connected(event.shard_id, event.new == ConnectionStage::Connected);
+    }
+}
+
+#[derive(Clone)]
+struct HealthState {
+    db: BotDatabase,
+    readiness: Arc<Readiness>,
+}
+
+/// 存活检查：数据库能响应简单查询即返回 200，不依赖 Discord
+async fn healthz(State(state): State<HealthState>) -> StatusCode {
+    match state.db.inner().ping().await {
+        Ok(()) => StatusCode::OK,
+        Err(e) => {
+            warn!("健康检查时数据库无响应: {}", e);
+            StatusCode::SERVICE_UNAVAILABLE
+        }
+    }
+}
+
+/// 就绪检查：Discord 客户端连接后才返回 200
+async fn readyz(State(state): State<HealthState>) -> StatusCode {
+    if state.readiness.is_ready() {
+        StatusCode::OK
+    } else {
+        StatusCode::SERVICE_UNAVAILABLE
+    }
+}
+
+/// 在指定端口启动健康检查 HTTP 服务，提供 `/healthz` 和 `/readyz`
+///
+/// 端口在返回前完成监听，端口被占用等问题会作为错误返回，由调用方决定是否继续启动
+pub async fn start_health_server(
+    port: u16,
+    db: BotDatabase,
+    readiness: Arc<Readiness>,
+) -> Result<(), BotError> {
+    let app = Router::new()
+        .route("/healthz", get(healthz))
+        .route("/readyz", get(readyz))
+        .with_state(HealthState { db, readiness });
+
+    let addr = SocketAddr::from((Ipv4Addr::UNSPECIFIED, port));
+    let listener = TcpListener::bind(addr)
+        .await
+        .with_whatever_context::<_, _, BotError>(|_| format!("健康检查服务无法监听 {addr}"))?;
+    info!("健康检查服务已启动: {}", addr);
+
+    tokio::spawn(async move {
+        if let Err(e) = axum::serve(listener, app).await {
+            error!("健康检查服务异常退出: {}", e);
+        }
+    });
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_health_endpoints() {
+        let state = HealthState {
+            db: BotDatabase::new_memory().await.unwrap(),
+            readiness: Arc::new(Readiness::default()),
+        };
+
+        // 存活检查只依赖数据库，客户端未连接时也返回 200
+        assert_eq!(healthz(State(state.clone())).await, StatusCode::OK);
+        assert_eq!(
+            readyz(State(state.clone())).await,
+            StatusCode::SERVICE_UNAVAILABLE
+        );
+
+        // 所有分片都连接后才就绪，任一分片断线即未就绪
+        state.readiness.set_shard_total(2);
+        state.readiness.set_shard_connected(ShardId(0), true);
+        assert_eq!(
+            readyz(State(state.clone())).await,
+            StatusCode::SERVICE_UNAVAILABLE
+        );
+        state.readiness.set_shard_connected(ShardId(1), true);
+        assert_eq!(readyz(State(state.clone())).await, StatusCode::OK);
+        state.readiness.set_shard_connected(ShardId(1), false);
+        assert_eq!(readyz(State(state)).await, StatusCode::SERVICE_UNAVAILABLE);
+    }
+
+    #[tokio::test]
+    async fn test_start_health_server_reports_bind_error() {
+        let db = BotDatabase::new_memory().await.unwrap();
+        let occupied = std::net::TcpListener::bind((Ipv4Addr::UNSPECIFIED, 0)).unwrap();
+        let port = occupied.local_addr().unwrap().port();
+
+        let result = start_health_server(port, db, Arc::new(Readiness::default())).await;
+        assert!(result.is_err());
+    }
+}
@@ -4,6 +4,6 @@ pub mod service;
 mod tests;
 pub mod types;
 
-pub use publish_service::LicensePublishService;
-pub use service::LicenseService;
-pub use types::UserLicense;
+pub use publish_service::{BulkBackupUpdate, LicensePublishService};
+pub use service::{DUPLICATE_NAME_MESSAGE, LicenseService, LicenseTransfer, MAX_LICENSES_PER_USER};
+pub use types::{LicenseFields, LicenseSource, UserLicense};
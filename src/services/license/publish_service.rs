@@ -1,17 +1,258 @@
-use serenity::all::{ChannelId, CreateMessage, EditMessage, GuildChannel, Http, MessageId, User};
-use tracing::{error, info};
+use std::{
+    path::Path,
+    time::{Duration, Instant},
+};
+
+use futures::{Stream, StreamExt, stream};
+use reqwest::Url;
+use serenity::all::{
+    ChannelId, CreateActionRow, CreateAttachment, CreateButton, CreateEmbed, CreateMessage,
+    EditMessage, EditThread, GuildChannel, Http, HttpError, MessageId, Permissions, ReactionType,
+    ThreadMetadata, User, UserId,
+};
+use tracing::{error, info, warn};
 
 use crate::{
-    commands::Data, error::BotError, services::notification_service::NotificationPayload,
-    utils::LicenseEmbedBuilder,
+    commands::Data,
+    config::Branding,
+    error::BotError,
+    services::{
+        audit_log::{AuditAction, send_audit_log},
+        notification_service::NotificationPayload,
+        published_posts::{
+            ObsoleteLicenseAction, PublishedPost, USER_POSTS_PAGE_SIZE, UserPostsCursor,
+            effective_obsolete_action,
+        },
+        self_check::{FORUM_PERMISSIONS, cached_forum_permissions},
+    },
+    types::license::SystemLicense,
+    utils::{LicenseEmbedBuilder, Locale, member_display_name},
 };
 
+/// 随协议消息一起发布的完整协议文本
+enum FullText {
+    /// 以链接按钮的形式附在消息下方
+    Link { license_name: String, url: String },
+    /// 作为附件上传
+    Attachment(CreateAttachment),
+}
+
+/// 加载系统协议配置的完整协议文本，无效或缺失的引用记录警告后跳过
+async fn resolve_full_texts(system: &SystemLicense, base_dir: &Path) -> Vec<FullText> {
+    let mut full_texts = Vec::new();
+
+    if let Some(url) = &system.full_text_url {
+        match Url::parse(url) {
+            Ok(parsed) if matches!(parsed.scheme(), "http" | "https") => {
+                full_texts.push(FullText::Link {
+                    license_name: system.license_name.clone(),
+                    url: url.clone(),
+                });
+            }
+            _ => warn!(
+                "系统协议 {} 的完整文本链接无效，已跳过: {}",
+                system.license_name, url
+            ),
+        }
+    }
+
+    if let Some(file) = &system.full_text_file {
+        let path = base_dir.join(file);
+        match CreateAttachment::path(&path).await {
+            Ok(attachment) => full_texts.push(FullText::Attachment(attachment)),
+            Err(e) => warn!(
+                "无法读取系统协议 {} 的完整文本文件 {}，已跳过: {}",
+                system.license_name,
+                path.display(),
+                e
+            ),
+        }
+    }
+
+    full_texts
+}
+
+/// 组装协议消息，完整协议文本以附件或链接按钮的形式附在embed旁边
+fn build_license_message(embed: CreateEmbed, full_texts: Vec<FullText>) -> CreateMessage {
+    let mut message = CreateMessage::new().embed(embed);
+    let mut buttons = Vec::new();
+    for full_text in full_texts {
+        match full_text {
+            FullText::Link { license_name, url } => buttons
+                .push(CreateButton::new_link(url).label(format!("📄 {license_name} 完整文本"))),
+            FullText::Attachment(attachment) => message = message.add_file(attachment),
+        }
+    }
+    if !buttons.is_empty() {
+        message = message.components(vec![CreateActionRow::Buttons(buttons)]);
+    }
+    message
+}
+
+/// Discord 的 "Thread is archived" 错误码
+const THREAD_ARCHIVED_CODE: isize = 50083;
+/// Discord 的 "Thread is locked" 错误码
+const THREAD_LOCKED_CODE: isize = 160005;
+
+/// 帖子无法发布协议的原因
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ThreadUnavailable {
+    Archived,
+    Locked,
+}
+
+impl ThreadUnavailable {
+    /// 根据帖子元数据判断帖子是否可以发布协议
+    fn from_metadata(metadata: Option<&ThreadMetadata>) -> Option<Self> {
+        match metadata {
+            Some(m) if m.locked => Some(Self::Locked),
+            Some(m) if m.archived => Some(Self::Archived),
+            _ => None,
+        }
+    }
+
+    /// 识别发送消息失败时 Discord 返回的归档/锁定错误
+    fn from_error(err: &serenity::Error) -> Option<Self> {
+        if let serenity::Error::Http(HttpError::UnsuccessfulRequest(resp)) = err {
+            Self::from_error_code(resp.error.code)
+        } else {
+            None
+        }
+    }
+
+    fn from_error_code(code: isize) -> Option<Self> {
+        match code {
+            THREAD_ARCHIVED_CODE => Some(Self::Archived),
+            THREAD_LOCKED_CODE => Some(Self::Locked),
+            _ => None,
+        }
+    }
+
+    /// 转换为带有处理建议的错误
+    fn into_error(self) -> BotError {
+        let message = match self {
+            Self::Archived => "帖子已归档，无法发布协议。请先取消归档帖子后再试。",
+            Self::Locked => "帖子已锁定，无法发布协议。请联系管理员解锁帖子后再试。",
+        };
+        BotError::ValidationError {
+            message: message.to_string(),
+            loc: snafu::location!(),
+        }
+    }
+}
+
+/// 检查机器人是否拥有发布协议所需的权限，缺少时返回列出缺失权限的错误
+fn check_publish_permissions(permissions: Permissions) -> Result<(), BotError> {
+    let missing: Vec<&str> = FORUM_PERMISSIONS
+        .iter()
+        .filter(|(required, _)| !permissions.contains(*required))
+        .map(|(_, name)| *name)
+        .collect();
+    if missing.is_empty() {
+        return Ok(());
+    }
+    Err(BotError::AuthorizationError {
+        message: format!(
+            "机器人在此论坛缺少以下权限，无法发布协议：{}。请联系管理员为机器人授予权限后再试。",
+            missing.join("、")
+        ),
+        loc: snafu::location!(),
+    })
+}
+
+/// 批量修改备份权限时，两次修改协议消息之间的间隔
+const BULK_BACKUP_EDIT_INTERVAL: Duration = Duration::from_millis(1000);
+/// 批量修改备份权限时，进度回调的最短间隔
+const BULK_BACKUP_PROGRESS_INTERVAL: Duration = Duration::from_secs(5);
+
+/// 以至多 `concurrency` 个任务同时运行，按完成顺序产出结果
+fn bounded<F: Future>(
+    tasks: impl IntoIterator<Item = F>,
+    concurrency: usize,
+) -> impl Stream<Item = F::Output> {
+    stream::iter(tasks).buffer_unordered(concurrency.max(1))
+}
+
+/// 允许备份时添加到协议消息的反应
+const BACKUP_ALLOWED_REACTION: char = '💾';
+/// 不允许备份时添加到协议消息的反应
+const BACKUP_DENIED_REACTION: char = '🚫';
+
+/// 根据备份权限选择要添加和要移除的反应
+fn backup_reactions(backup_allowed: bool) -> (char, char) {
+    if backup_allowed {
+        (BACKUP_ALLOWED_REACTION, BACKUP_DENIED_REACTION)
+    } else {
+        (BACKUP_DENIED_REACTION, BACKUP_ALLOWED_REACTION)
+    }
+}
+
+/// 批量修改备份权限的结果
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct BulkBackupUpdate {
+    /// 已更新的帖子数
+    pub updated: usize,
+    /// 权限本就相同、无需修改的帖子数
+    pub unchanged: usize,
+    /// 帖子无法访问而跳过的数量
+    pub failed: usize,
+}
+
+/// 批量修改中单个帖子的处理结果
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum BulkBackupOutcome {
+    Updated,
+    Unchanged,
+    Failed,
+}
+
+impl BulkBackupUpdate {
+    /// 已处理的帖子总数
+    pub fn processed(&self) -> usize {
+        self.updated + self.unchanged + self.failed
+    }
+
+    fn record(&mut self, outcome: BulkBackupOutcome) {
+        match outcome {
+            BulkBackupOutcome::Updated => self.updated += 1,
+            BulkBackupOutcome::Unchanged => self.unchanged += 1,
+            BulkBackupOutcome::Failed => self.failed += 1,
+        }
+    }
+
+    /// 以至多 `concurrency` 个任务同时处理，按完成顺序汇总结果
+    ///
+    /// 距离上次回调超过 `BULK_BACKUP_PROGRESS_INTERVAL` 时调用 `on_progress`。
+    async fn run<F, P>(
+        &mut self,
+        tasks: Vec<F>,
+        concurrency: usize,
+        last_progress: &mut Instant,
+        on_progress: &mut impl FnMut(BulkBackupUpdate) -> P,
+    ) -> Result<(), BotError>
+    where
+        F: Future<Output = Result<BulkBackupOutcome, BotError>>,
+        P: Future<Output = ()>,
+    {
+        let mut results = bounded(tasks, concurrency);
+        while let Some(outcome) = results.next().await {
+            self.record(outcome?);
+            if last_progress.elapsed() >= BULK_BACKUP_PROGRESS_INTERVAL {
+                *last_progress = Instant::now();
+                on_progress(*self).await;
+            }
+        }
+        Ok(())
+    }
+}
+
 pub struct LicensePublishService;
 
 impl LicensePublishService {
     /// 发布协议到指定线程
     ///
-    /// 此方法作为协调者，调用各个专门的函数完成协议发布流程
+    /// 此方法作为协调者，调用各个专门的函数完成协议发布流程。
+    /// `actor` 为执行发布的用户，记录在审计日志中；代发时与协议作者 `author` 不同。
     pub async fn publish(
         http: &Http,
         data: &Data,
@@ -19,27 +260,118 @@ impl LicensePublishService {
         license: &entities::user_licenses::Model,
         backup_allowed: bool,
         author: User,
+        actor: UserId,
+    ) -> Result<(), BotError> {
+        Self::publish_licenses(
+            http,
+            data,
+            thread,
+            license,
+            None,
+            backup_allowed,
+            author,
+            actor,
+        )
+        .await
+    }
+
+    /// 以双重授权的形式发布两个协议到指定线程
+    ///
+    /// 两个协议合并为一条embed消息，帖子记录与单协议发布相同
+    #[allow(clippy::too_many_arguments)]
+    pub async fn publish_dual(
+        http: &Http,
+        data: &Data,
+        thread: &GuildChannel,
+        primary: &entities::user_licenses::Model,
+        secondary: &entities::user_licenses::Model,
+        backup_allowed: bool,
+        author: User,
+        actor: UserId,
+    ) -> Result<(), BotError> {
+        Self::publish_licenses(
+            http,
+            data,
+            thread,
+            primary,
+            Some(secondary),
+            backup_allowed,
+            author,
+            actor,
+        )
+        .await
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    async fn publish_licenses(
+        http: &Http,
+        data: &Data,
+        thread: &GuildChannel,
+        license: &entities::user_licenses::Model,
+        secondary: Option<&entities::user_licenses::Model>,
+        backup_allowed: bool,
+        author: User,
+        actor: UserId,
     ) -> Result<(), BotError> {
+        // 0. 确认机器人有权限且帖子可以发布消息
+        Self::ensure_bot_permissions(http, thread).await?;
+        Self::ensure_thread_writable(http, data, thread).await?;
+
         // 1. 处理已有协议
         Self::handle_existing_license(http, data, thread).await?;
 
         // 2. 发布新协议消息
-        let new_msg =
-            Self::publish_new_message(http, thread, license, backup_allowed, &author).await?;
+        // 发布消息不属于交互，使用配置中的语言
+        let full_texts = Self::load_full_texts(data, license, secondary).await;
+        let cfg = data.cfg().load_full();
+        let new_msg = Self::publish_new_message(
+            http,
+            thread,
+            license,
+            secondary,
+            backup_allowed,
+            &author,
+            cfg.license_locale,
+            cfg.community_rules_url.as_deref(),
+            cfg.branding_for(Some(thread.guild_id)),
+            full_texts,
+        )
+        .await?;
+
+        Self::sync_backup_reaction(http, data, thread.id, new_msg.id, backup_allowed).await;
 
         // 3. 更新数据库记录
-        let backup_changed =
-            Self::update_database_records(data, thread, new_msg.id, author.id, backup_allowed)
-                .await?;
+        // 系统协议使用负数ID，双协议时记录其中第一个系统协议
+        let system_license = std::iter::once(license)
+            .chain(secondary)
+            .find(|l| l.id < 0)
+            .map(|l| l.license_name.as_str());
+        // 协议声明的备份权限，双重授权时任一协议允许即视为允许
+        let license_backup_allowed =
+            license.allow_backup || secondary.is_some_and(|l| l.allow_backup);
+        let backup_changed = Self::update_database_records(
+            data,
+            thread,
+            new_msg.id,
+            author.id,
+            backup_allowed,
+            license_backup_allowed,
+            system_license,
+        )
+        .await?;
 
         // 4. 发送备份通知（如果需要）
+        let license_type = match secondary {
+            Some(secondary) => format!("{} / {}", license.license_name, secondary.license_name),
+            None => license.license_name.clone(),
+        };
         Self::send_backup_notification_if_needed(
             http,
             data,
             thread,
             new_msg.id,
             &author,
-            license,
+            license_type.clone(),
             backup_allowed,
             backup_changed,
         )
@@ -47,11 +379,277 @@ impl LicensePublishService {
 
         // 5. 增加使用计数
         Self::increment_usage_count(data, license.id, author.id).await?;
+        if let Some(secondary) = secondary {
+            Self::increment_usage_count(data, secondary.id, author.id).await?;
+        }
+
+        // 6. 记录审计日志
+        send_audit_log(
+            http,
+            &cfg,
+            actor,
+            AuditAction::LicensePublished,
+            format!("<#{}>：{license_type}", thread.id),
+        )
+        .await;
 
         Ok(())
     }
 
-    /// 处理已有协议（标记为作废并取消置顶）
+    /// 管理员覆盖帖子的备份权限
+    ///
+    /// 返回 `None` 表示帖子没有已发布的协议；`Some(changed)` 表示是否实际发生变更。
+    /// 仅在变更时重新渲染置顶协议并发送备份通知。
+    pub async fn override_backup_permission(
+        http: &Http,
+        data: &Data,
+        thread: &GuildChannel,
+        backup_allowed: bool,
+    ) -> Result<Option<bool>, BotError> {
+        let Some((post, changed)) = data
+            .db()
+            .published_posts()
+            .override_backup_permission(thread.id, backup_allowed)
+            .await?
+        else {
+            return Ok(None);
+        };
+
+        if !changed {
+            return Ok(Some(false));
+        }
+
+        let author = UserId::new(post.user_id as u64).to_user(http).await?;
+        Self::rerender_backup_permission(
+            http,
+            data,
+            thread,
+            &post,
+            &author,
+            backup_allowed,
+            "管理员手动设置".to_string(),
+        )
+        .await?;
+
+        Ok(Some(true))
+    }
+
+    /// 将作者所有已发布帖子的备份权限修改为同一设置
+    ///
+    /// 只处理权限实际变化的帖子：更新记录、重新渲染置顶协议并发送备份通知。
+    /// 同时处理至多 `bulk_backup_concurrency` 个帖子，每个帖子更新后等待
+    /// `BULK_BACKUP_EDIT_INTERVAL`，避免触发速率限制；处理过程中定期调用 `on_progress`。
+    pub async fn apply_backup_permission_to_user_posts<P>(
+        http: &Http,
+        data: &Data,
+        author: &User,
+        backup_allowed: bool,
+        mut on_progress: impl FnMut(BulkBackupUpdate) -> P,
+    ) -> Result<BulkBackupUpdate, BotError>
+    where
+        P: Future<Output = ()>,
+    {
+        let posts_service = data.db().published_posts();
+        let concurrency = data.cfg().load().bulk_backup_concurrency;
+        let mut summary = BulkBackupUpdate::default();
+        let mut last_progress = Instant::now();
+
+        // 分页读取，避免一次性加载发帖很多的用户的全部帖子
+        let mut cursor = None;
+        loop {
+            let page = posts_service
+                .get_user_posts_page(author.id, cursor, USER_POSTS_PAGE_SIZE)
+                .await?;
+            let Some(last) = page.last() else {
+                break;
+            };
+            cursor = Some(UserPostsCursor::from(last));
+
+            let tasks = page
+                .into_iter()
+                .map(|post| {
+                    Self::apply_backup_permission_to_post(http, data, author, post, backup_allowed)
+                })
+                .collect();
+            summary
+                .run(tasks, concurrency, &mut last_progress, &mut on_progress)
+                .await?;
+        }
+
+        Ok(summary)
+    }
+
+    /// 批量修改中处理单个帖子，帖子已被删除或无权访问时跳过但不中断其他帖子
+    async fn apply_backup_permission_to_post(
+        http: &Http,
+        data: &Data,
+        author: &User,
+        post: PublishedPost,
+        backup_allowed: bool,
+    ) -> Result<BulkBackupOutcome, BotError> {
+        let posts_service = data.db().published_posts();
+        let thread_id = ChannelId::new(post.thread_id as u64);
+        if !posts_service
+            .has_backup_permission_changed(thread_id, backup_allowed)
+            .await?
+        {
+            return Ok(BulkBackupOutcome::Unchanged);
+        }
+
+        let thread = match thread_id.to_channel(http).await.map(|c| c.guild()) {
+            Ok(Some(thread)) => thread,
+            Ok(None) => return Ok(BulkBackupOutcome::Failed),
+            Err(e) => {
+                warn!("获取帖子 {} 失败: {}", thread_id, e);
+                return Ok(BulkBackupOutcome::Failed);
+            }
+        };
+
+        let Some(post) = posts_service
+            .update_backup_permission(thread_id, backup_allowed)
+            .await?
+        else {
+            return Ok(BulkBackupOutcome::Failed);
+        };
+        Self::rerender_backup_permission(
+            http,
+            data,
+            &thread,
+            &post,
+            author,
+            backup_allowed,
+            "作者批量设置".to_string(),
+        )
+        .await?;
+
+        tokio::time::sleep(BULK_BACKUP_EDIT_INTERVAL).await;
+        Ok(BulkBackupOutcome::Updated)
+    }
+
+    /// 重新渲染置顶协议的备份字段，并发送备份权限变更通知
+    #[allow(clippy::too_many_arguments)]
+    async fn rerender_backup_permission(
+        http: &Http,
+        data: &Data,
+        thread: &GuildChannel,
+        post: &PublishedPost,
+        author: &User,
+        backup_allowed: bool,
+        license_type: String,
+    ) -> Result<(), BotError> {
+        let message_id = MessageId::new(post.message_id as u64);
+
+        // 重新渲染置顶的协议消息
+        if let Ok(mut msg) = http.get_message(thread.id, message_id).await
+            && let Some(original_embed) = msg.embeds.first()
+        {
+            let updated_embed =
+                LicenseEmbedBuilder::create_backup_overridden_embed(original_embed, backup_allowed);
+            let _ = msg
+                .edit(http, EditMessage::new().embed(updated_embed))
+                .await;
+        }
+
+        Self::sync_backup_reaction(http, data, thread.id, message_id, backup_allowed).await;
+
+        Self::send_backup_notification_if_needed(
+            http,
+            data,
+            thread,
+            message_id,
+            author,
+            license_type,
+            backup_allowed,
+            true,
+        )
+        .await
+    }
+
+    /// 在协议消息上添加反映备份权限的反应，并移除相反的反应
+    ///
+    /// 仅在启用 `backup_status_reaction` 时生效。缺少添加反应权限等失败只记录警告，
+    /// 不影响发布或权限修改本身。
+    async fn sync_backup_reaction(
+        http: &Http,
+        data: &Data,
+        channel_id: ChannelId,
+        message_id: MessageId,
+        backup_allowed: bool,
+    ) {
+        if !data.cfg().load().backup_status_reaction {
+            return;
+        }
+
+        let (add, remove) = backup_reactions(backup_allowed);
+        // 机器人没有添加过该反应时移除会直接成功，无需先检查
+        if let Err(e) = http
+            .delete_reaction_me(
+                channel_id,
+                message_id,
+                &ReactionType::Unicode(remove.into()),
+            )
+            .await
+        {
+            warn!("移除协议消息 {} 的备份状态反应失败: {}", message_id, e);
+        }
+        if let Err(e) = http
+            .create_reaction(channel_id, message_id, &ReactionType::Unicode(add.into()))
+            .await
+        {
+            warn!("为协议消息 {} 添加备份状态反应失败: {}", message_id, e);
+        }
+    }
+
+    /// 发布前检查机器人在帖子所属论坛中的权限
+    ///
+    /// 帖子的权限继承自所属论坛，论坛权限会被短暂缓存，
+    /// 发布命令的预检查和发布本身不会重复请求。无法获取权限时只记录警告，交由后续发布步骤报错。
+    pub async fn ensure_bot_permissions(
+        http: &Http,
+        thread: &GuildChannel,
+    ) -> Result<(), BotError> {
+        let Some(forum_id) = thread.parent_id else {
+            return Ok(());
+        };
+        match cached_forum_permissions(http, forum_id).await {
+            Ok(permissions) => check_publish_permissions(permissions),
+            Err(e) => {
+                warn!("获取机器人在论坛 {} 的权限失败: {}", forum_id, e);
+                Ok(())
+            }
+        }
+    }
+
+    /// 检查帖子是否已归档或锁定
+    ///
+    /// 配置允许时自动取消归档（锁定的帖子不会自动解锁），否则返回提示先取消归档的错误。
+    async fn ensure_thread_writable(
+        http: &Http,
+        data: &Data,
+        thread: &GuildChannel,
+    ) -> Result<(), BotError> {
+        let Some(reason) = ThreadUnavailable::from_metadata(thread.thread_metadata.as_ref()) else {
+            return Ok(());
+        };
+
+        if reason == ThreadUnavailable::Archived && data.cfg().load().auto_unarchive_threads {
+            match thread
+                .id
+                .edit_thread(http, EditThread::new().archived(false))
+                .await
+            {
+                Ok(_) => {
+                    info!("已自动取消归档帖子 {}", thread.id);
+                    return Ok(());
+                }
+                Err(e) => warn!("自动取消归档帖子 {} 失败: {}", thread.id, e),
+            }
+        }
+
+        Err(reason.into_error())
+    }
+
+    /// 处理已有协议（标记为作废并取消置顶，或按设置删除）
     async fn handle_existing_license(
         http: &Http,
         data: &Data,
@@ -60,11 +658,20 @@ impl LicensePublishService {
         let existing_post = data.db().published_posts().get_by_thread(thread.id).await?;
 
         if let Some(existing) = existing_post {
-            // 编辑旧协议消息为作废
+            let action =
+                effective_obsolete_action(&existing, data.cfg().load().obsolete_license_action);
             if let Ok(mut old_msg) = http
                 .get_message(thread.id, MessageId::new(existing.message_id as u64))
                 .await
             {
+                if action == ObsoleteLicenseAction::Delete {
+                    if let Err(e) = old_msg.delete(http).await {
+                        warn!("删除旧协议消息失败: {}", e);
+                    }
+                    return Ok(());
+                }
+
+                // 编辑旧协议消息为作废
                 // 获取原有的 embed
                 if let Some(original_embed) = old_msg.embeds.first() {
                     let fields: Vec<(String, String, bool)> = original_embed
@@ -95,26 +702,69 @@ impl LicensePublishService {
         Ok(())
     }
 
+    /// 加载所发布的系统协议的完整协议文本
+    ///
+    /// 系统协议转换为数据库模型时使用负数ID，以此区分同名的用户协议
+    async fn load_full_texts(
+        data: &Data,
+        license: &entities::user_licenses::Model,
+        secondary: Option<&entities::user_licenses::Model>,
+    ) -> Vec<FullText> {
+        let cache = data.system_license_cache();
+        let mut full_texts = Vec::new();
+        for license in std::iter::once(license).chain(secondary) {
+            if license.id >= 0 {
+                continue;
+            }
+            if let Some(system) = cache.get_by_name(&license.license_name).await {
+                full_texts.extend(resolve_full_texts(&system, cache.base_dir()).await);
+            }
+        }
+        full_texts
+    }
+
     /// 发布新协议消息并置顶
+    #[allow(clippy::too_many_arguments)]
     async fn publish_new_message(
         http: &Http,
         thread: &GuildChannel,
         license: &entities::user_licenses::Model,
+        secondary: Option<&entities::user_licenses::Model>,
         backup_allowed: bool,
         author: &User,
+        locale: Locale,
+        community_rules_url: Option<&str>,
+        branding: Option<&Branding>,
+        full_texts: Vec<FullText>,
     ) -> Result<serenity::all::Message, BotError> {
-        let display_name = thread
-            .guild_id
-            .member(http, author.id)
-            .await
-            .map(|m| m.display_name().to_string())
-            .unwrap_or_else(|_| author.display_name().to_string());
+        let display_name = member_display_name(http, thread.guild_id, author).await;
 
-        let license_embed =
-            LicenseEmbedBuilder::create_license_embed(license, backup_allowed, &display_name);
+        let license_embed = match secondary {
+            Some(secondary) => LicenseEmbedBuilder::create_dual_license_embed(
+                license,
+                secondary,
+                backup_allowed,
+                &display_name,
+                locale,
+                community_rules_url,
+                branding,
+            ),
+            None => LicenseEmbedBuilder::create_license_embed(
+                license,
+                backup_allowed,
+                &display_name,
+                locale,
+                community_rules_url,
+                branding,
+            ),
+        };
         let new_msg = ChannelId::new(thread.id.get())
-            .send_message(http, CreateMessage::new().embed(license_embed))
-            .await?;
+            .send_message(http, build_license_message(license_embed, full_texts))
+            .await
+            .map_err(|err| match ThreadUnavailable::from_error(&err) {
+                Some(reason) => reason.into_error(),
+                None => err.into(),
+            })?;
 
         // Pin新消息
         let _ = new_msg.pin(http).await;
@@ -129,6 +779,8 @@ impl LicensePublishService {
         message_id: MessageId,
         author_id: serenity::all::UserId,
         backup_allowed: bool,
+        license_backup_allowed: bool,
+        system_license: Option<&str>,
     ) -> Result<bool, BotError> {
         // 检查备份权限是否变更
         let backup_changed = data
@@ -137,10 +789,17 @@ impl LicensePublishService {
             .has_backup_permission_changed(thread.id, backup_allowed)
             .await?;
 
-        // 更新数据库
+        // 更新数据库，协议声明的备份权限单独记录，便于审计发布时的覆盖
         data.db()
             .published_posts()
-            .record_or_update(thread.id, message_id, author_id, backup_allowed)
+            .record_or_update(
+                thread.id,
+                message_id,
+                author_id,
+                backup_allowed,
+                license_backup_allowed,
+                system_license,
+            )
             .await?;
 
         Ok(backup_changed)
@@ -154,7 +813,7 @@ impl LicensePublishService {
         thread: &GuildChannel,
         message_id: MessageId,
         author: &User,
-        license: &entities::user_licenses::Model,
+        license_type: String,
         backup_allowed: bool,
         backup_changed: bool,
     ) -> Result<(), BotError> {
@@ -166,13 +825,16 @@ impl LicensePublishService {
                 .await
                 .unwrap_or_else(|_| "无法获取内容预览".to_string());
 
+            let cfg = data.cfg().load();
             let notification_payload = NotificationPayload::from_discord_context(
                 thread,
                 message_id,
                 author.clone(),
                 content_preview,
-                license.license_name.clone(),
+                cfg.notification_preview_chars,
+                license_type,
                 backup_allowed,
+                cfg.notification_author_secret.as_deref(),
             )
             .await;
 
@@ -218,3 +880,189 @@ impl LicensePublishService {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_backup_reactions() {
+        assert_eq!(backup_reactions(true), ('💾', '🚫'));
+        assert_eq!(backup_reactions(false), ('🚫', '💾'));
+    }
+
+    fn thread_metadata(archived: bool, locked: bool) -> ThreadMetadata {
+        serde_json::from_value(serde_json::json!({
+            "archived": archived,
+            "auto_archive_duration": 1440,
+            "archive_timestamp": null,
+            "locked": locked,
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_thread_unavailable_classification() {
+        // 锁定优先于归档，锁定的帖子不会被自动取消归档
+        assert_eq!(ThreadUnavailable::from_metadata(None), None);
+        assert_eq!(
+            ThreadUnavailable::from_metadata(Some(&thread_metadata(false, false))),
+            None
+        );
+        assert_eq!(
+            ThreadUnavailable::from_metadata(Some(&thread_metadata(true, false))),
+            Some(ThreadUnavailable::Archived)
+        );
+        assert_eq!(
+            ThreadUnavailable::from_metadata(Some(&thread_metadata(true, true))),
+            Some(ThreadUnavailable::Locked)
+        );
+
+        // 发送失败时按错误码识别
+        assert_eq!(
+            ThreadUnavailable::from_error_code(THREAD_LOCKED_CODE),
+            Some(ThreadUnavailable::Locked)
+        );
+        assert_eq!(
+            ThreadUnavailable::from_error_code(THREAD_ARCHIVED_CODE),
+            Some(ThreadUnavailable::Archived)
+        );
+        assert_eq!(ThreadUnavailable::from_error_code(50013), None);
+
+        let err = ThreadUnavailable::Locked.into_error();
+        assert!(matches!(err, BotError::ValidationError { .. }));
+        assert!(err.user_message().contains("帖子已锁定"));
+    }
+
+    #[test]
+    fn test_check_publish_permissions() {
+        use crate::services::self_check::REQUIRED_FORUM_PERMISSIONS;
+
+        // 与启动自检使用同一组权限
+        let all = REQUIRED_FORUM_PERMISSIONS;
+        assert!(check_publish_permissions(all | Permissions::ADD_REACTIONS).is_ok());
+        assert!(check_publish_permissions(Permissions::ADMINISTRATOR.union(all)).is_ok());
+
+        // 错误信息指出缺少的具体权限
+        let err = check_publish_permissions(
+            all - Permissions::EMBED_LINKS - Permissions::MANAGE_MESSAGES,
+        )
+        .unwrap_err();
+        assert!(matches!(err, BotError::AuthorizationError { .. }));
+        assert_eq!(
+            err.user_message(),
+            "机器人在此论坛缺少以下权限，无法发布协议：嵌入链接（用于显示协议卡片）、管理消息（用于置顶协议）。请联系管理员为机器人授予权限后再试。"
+        );
+
+        let message = check_publish_permissions(all - Permissions::ATTACH_FILES)
+            .unwrap_err()
+            .user_message();
+        assert!(message.contains("附加文件（用于上传完整协议文本）"));
+        assert!(!message.contains("嵌入链接"));
+    }
+
+    fn system_license(full_text_url: Option<&str>, full_text_file: Option<&str>) -> SystemLicense {
+        SystemLicense {
+            license_name: "CC-BY-4.0".to_string(),
+            allow_redistribution: true,
+            allow_modification: true,
+            restrictions_note: None,
+            allow_backup: false,
+            allow_commercial: true,
+            require_attribution: true,
+            color: None,
+            allowed_publisher_ids: Vec::new(),
+            allowed_publisher_roles: Vec::new(),
+            full_text_url: full_text_url.map(str::to_string),
+            full_text_file: full_text_file.map(Into::into),
+            category: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_license_message_includes_full_text() {
+        let base_dir =
+            std::env::temp_dir().join(format!("dc-bot-full-text-{}", std::process::id()));
+        tokio::fs::create_dir_all(&base_dir).await.unwrap();
+        tokio::fs::write(base_dir.join("cc-by.md"), "# CC BY 4.0")
+            .await
+            .unwrap();
+
+        let license = system_license(Some("https://example.com/cc-by"), Some("cc-by.md"));
+        let full_texts = resolve_full_texts(&license, &base_dir).await;
+        let message = build_license_message(CreateEmbed::new().title("协议"), full_texts);
+        let message = serde_json::to_value(&message).unwrap();
+
+        assert_eq!(message["attachments"][0]["filename"], "cc-by.md");
+        let button = &message["components"][0]["components"][0];
+        assert_eq!(button["url"], "https://example.com/cc-by");
+        assert_eq!(button["label"], "📄 CC-BY-4.0 完整文本");
+
+        // 无效链接和缺失的文件被跳过，协议仍可正常发布
+        let broken = system_license(Some("not a url"), Some("missing.pdf"));
+        let full_texts = resolve_full_texts(&broken, &base_dir).await;
+        assert!(full_texts.is_empty());
+        let message = build_license_message(CreateEmbed::new().title("协议"), full_texts);
+        let message = serde_json::to_value(&message).unwrap();
+        assert!(message.get("components").is_none());
+        assert_eq!(message["embeds"][0]["title"], "协议");
+
+        tokio::fs::remove_dir_all(&base_dir).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_bulk_backup_respects_concurrency() {
+        use std::sync::atomic::{AtomicUsize, Ordering};
+
+        let running = AtomicUsize::new(0);
+        let peak = AtomicUsize::new(0);
+        let outcomes = [
+            BulkBackupOutcome::Updated,
+            BulkBackupOutcome::Unchanged,
+            BulkBackupOutcome::Failed,
+        ];
+        let tasks = (0..10)
+            .map(|i| {
+                let (running, peak) = (&running, &peak);
+                async move {
+                    let now = running.fetch_add(1, Ordering::SeqCst) + 1;
+                    peak.fetch_max(now, Ordering::SeqCst);
+                    tokio::time::sleep(Duration::from_millis(10)).await;
+                    running.fetch_sub(1, Ordering::SeqCst);
+                    Ok(outcomes[i % 3])
+                }
+            })
+            .collect();
+
+        let mut summary = BulkBackupUpdate::default();
+        let mut last_progress = Instant::now() - BULK_BACKUP_PROGRESS_INTERVAL;
+        let mut reported = Vec::new();
+        summary
+            .run(tasks, 3, &mut last_progress, &mut |p: BulkBackupUpdate| {
+                reported.push(p.processed());
+                async {}
+            })
+            .await
+            .unwrap();
+        assert_eq!(peak.load(Ordering::SeqCst), 3);
+        assert_eq!(
+            summary,
+            BulkBackupUpdate {
+                updated: 4,
+                unchanged: 3,
+                failed: 3,
+            }
+        );
+        // 进度回调受最短间隔限制，只在第一次完成时触发
+        assert_eq!(reported, vec![1]);
+
+        // 并发数为 0 时按 1 处理，而不是永远挂起
+        let mut summary = BulkBackupUpdate::default();
+        let tasks = vec![async { Ok(BulkBackupOutcome::Updated) }];
+        summary
+            .run(tasks, 0, &mut Instant::now(), &mut |_| async {})
+            .await
+            .unwrap();
+        assert_eq!(summary.updated, 1);
+    }
+}
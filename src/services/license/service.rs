@@ -1,69 +1,312 @@
 use chrono::Utc;
 use entities::user_licenses::*;
-use sea_orm::{QueryOrder, QuerySelect, Set, prelude::*, sea_query::Expr};
+use sea_orm::{
+    QueryOrder, QuerySelect, Set, SqlErr, TransactionTrait, prelude::*, sea_query::Expr,
+};
 use serenity::all::*;
 
-use super::types::UserLicense;
+use super::types::{LicenseFields, LicenseSource, UserLicense};
 use crate::{database::BotDatabase, error::BotError};
 
-pub struct LicenseService<'a>(&'a DatabaseConnection);
+/// 每个用户最多可创建的协议数（未配置时的默认值）
+pub const MAX_LICENSES_PER_USER: u64 = 5;
+
+/// 协议颜色允许的最大值（24 位 RGB）
+const MAX_LICENSE_COLOR: i32 = 0xFFFFFF;
+
+/// 检查协议颜色是否为 24 位 RGB 值，超出范围的颜色会导致 Discord 拒绝协议嵌入
+fn validate_color(color: Option<i32>) -> Result<(), BotError> {
+    match color {
+        Some(color) if !(0..=MAX_LICENSE_COLOR).contains(&color) => {
+            Err(BotError::ValidationError {
+                message: format!("协议颜色 {color} 无效，必须在 0 到 0xFFFFFF 之间。"),
+                loc: snafu::location!(),
+            })
+        }
+        _ => Ok(()),
+    }
+}
+
+/// 同名协议已存在时的提示
+pub const DUPLICATE_NAME_MESSAGE: &str = "您已经创建过同名协议，请使用不同的名称。";
+
+/// 将违反 (user_id, license_name) 唯一索引的数据库错误转换为验证错误
+fn name_conflict_error(err: DbErr) -> BotError {
+    if matches!(err.sql_err(), Some(SqlErr::UniqueConstraintViolation(_))) {
+        BotError::ValidationError {
+            message: DUPLICATE_NAME_MESSAGE.to_string(),
+            loc: snafu::location!(),
+        }
+    } else {
+        err.into()
+    }
+}
+
+/// 批量转移协议的结果
+#[derive(Debug, Default, PartialEq)]
+pub struct LicenseTransfer {
+    /// 已转移给目标用户的协议
+    pub moved: Vec<UserLicense>,
+    /// 目标用户已有同名协议而保留在原用户名下的协议
+    pub name_conflicts: Vec<UserLicense>,
+    /// 目标用户协议数量已达上限而保留在原用户名下的协议
+    pub over_limit: Vec<UserLicense>,
+}
+
+/// 用户协议服务，可在数据库连接或事务上使用
+pub struct LicenseService<'a, C = DatabaseConnection> {
+    conn: &'a C,
+    max_licenses: u64,
+}
 
 impl BotDatabase {
     /// Get a reference to the license service
     pub fn license(&self) -> LicenseService<'_> {
-        LicenseService(self.inner())
+        LicenseService::new(self.inner())
     }
 }
 
-impl<'a> LicenseService<'a> {
-    pub fn new(conn: &'a DatabaseConnection) -> LicenseService<'a> {
-        LicenseService(conn)
+impl<'a, C: ConnectionTrait + TransactionTrait> LicenseService<'a, C> {
+    pub fn new(conn: &'a C) -> Self {
+        LicenseService {
+            conn,
+            max_licenses: MAX_LICENSES_PER_USER,
+        }
+    }
+
+    /// Use a different per-user license limit when creating licenses
+    pub fn with_max_licenses(mut self, max_licenses: u64) -> Self {
+        self.max_licenses = max_licenses;
+        self
     }
 
-    /// Create a new user license
+    /// Create a new user license with an unknown source
     pub async fn create(
         &self,
         user_id: UserId,
-        license_name: String,
-        allow_redistribution: bool,
-        allow_modification: bool,
-        restrictions_note: Option<String>,
-        allow_backup: bool,
+        fields: LicenseFields,
+    ) -> Result<UserLicense, BotError> {
+        self.create_with_source(user_id, fields, LicenseSource::Unknown)
+            .await
+    }
+
+    /// Create a new user license, recording where it was created from
+    pub async fn create_with_source(
+        &self,
+        user_id: UserId,
+        fields: LicenseFields,
+        source: LicenseSource,
+    ) -> Result<UserLicense, BotError> {
+        self.insert_license(self.conn, user_id, fields, source)
+            .await
+    }
+
+    /// Create a license, or return the user's existing license with the same name
+    ///
+    /// The name check and insert run in one transaction, and the unique index on
+    /// `(user_id, license_name)` rejects a concurrent insert that slips past the
+    /// check. Returns the license and whether it was newly created.
+    pub async fn create_or_get(
+        &self,
+        user_id: UserId,
+        fields: LicenseFields,
+        source: LicenseSource,
+    ) -> Result<(UserLicense, bool), BotError> {
+        let txn = self.conn.begin().await?;
+
+        let license_name = fields.license_name.clone();
+        if let Some(existing) =
+            Self::find_user_license_by_name(&txn, user_id, &license_name).await?
+        {
+            txn.commit().await?;
+            return Ok((existing, false));
+        }
+
+        let result = self.insert_license(&txn, user_id, fields, source).await;
+        let license = match result {
+            Ok(license) => license,
+            // 并发提交的同名协议已先写入，返回该协议
+            Err(e @ BotError::ValidationError { .. }) => {
+                txn.rollback().await?;
+                return match Self::find_user_license_by_name(self.conn, user_id, &license_name)
+                    .await?
+                {
+                    Some(existing) => Ok((existing, false)),
+                    None => Err(e),
+                };
+            }
+            Err(e) => return Err(e),
+        };
+        txn.commit().await?;
+        Ok((license, true))
+    }
+
+    /// Find a user's license by exact name
+    async fn find_user_license_by_name<T: ConnectionTrait>(
+        conn: &T,
+        user_id: UserId,
+        license_name: &str,
+    ) -> Result<Option<UserLicense>, BotError> {
+        Ok(Entity::find()
+            .filter(
+                Column::UserId
+                    .eq(user_id.get() as i64)
+                    .and(Column::LicenseName.eq(license_name)),
+            )
+            .one(conn)
+            .await?)
+    }
+
+    /// Insert a license after checking the per-user limit
+    async fn insert_license<T: ConnectionTrait>(
+        &self,
+        conn: &T,
+        user_id: UserId,
+        fields: LicenseFields,
+        source: LicenseSource,
     ) -> Result<UserLicense, BotError> {
+        validate_color(fields.color)?;
+
         // 检查用户协议数量是否超过上限
-        let current_count = self.get_user_license_count(user_id).await?;
-        if current_count >= 5 {
-            return Err(BotError::GenericError {
-                message: "您最多只能创建5个协议，请先删除一些协议。".to_string(),
-                source: None,
+        let current_count = Entity::find()
+            .filter(Column::UserId.eq(user_id.get() as i64))
+            .count(conn)
+            .await?;
+        if current_count >= self.max_licenses {
+            return Err(BotError::LicenseLimit {
+                max: self.max_licenses,
+                loc: snafu::location!(),
             });
         }
 
         let license = ActiveModel {
             user_id: Set(user_id.get() as i64),
-            license_name: Set(license_name),
-            allow_redistribution: Set(allow_redistribution),
-            allow_modification: Set(allow_modification),
-            restrictions_note: Set(restrictions_note),
-            allow_backup: Set(allow_backup),
+            license_name: Set(fields.license_name),
+            allow_redistribution: Set(fields.allow_redistribution),
+            allow_modification: Set(fields.allow_modification),
+            restrictions_note: Set(fields.restrictions_note),
+            allow_backup: Set(fields.allow_backup),
+            allow_commercial: Set(fields.allow_commercial),
+            require_attribution: Set(fields.require_attribution),
+            color: Set(fields.color),
+            source: Set(source.as_str().to_string()),
             usage_count: Set(0),
+            sort_order: Set(Self::next_sort_order(conn, user_id).await?),
             created_at: Set(Utc::now()),
             ..Default::default()
         };
 
-        let result = license.insert(self.0).await?;
-        Ok(result)
+        license.insert(conn).await.map_err(name_conflict_error)
+    }
+
+    /// Sort order for a license newly added to a user
+    ///
+    /// Users who never reordered their licenses keep sort order 0 (newest first);
+    /// otherwise the new license goes after the ones already ordered.
+    async fn next_sort_order<T: ConnectionTrait>(
+        conn: &T,
+        user_id: UserId,
+    ) -> Result<i32, BotError> {
+        let max: Option<i32> = Entity::find()
+            .select_only()
+            .column_as(Column::SortOrder.max(), "max")
+            .filter(Column::UserId.eq(user_id.get() as i64))
+            .into_tuple::<Option<i32>>()
+            .one(conn)
+            .await?
+            .flatten();
+        Ok(max.filter(|&max| max > 0).map_or(0, |max| max + 1))
+    }
+
+    /// Count licenses grouped by creation source, most common first
+    pub async fn get_source_counts(&self) -> Result<Vec<(LicenseSource, u64)>, BotError> {
+        let rows: Vec<(String, i64)> = Entity::find()
+            .select_only()
+            .column(Column::Source)
+            .column_as(Column::Id.count(), "count")
+            .group_by(Column::Source)
+            .into_tuple()
+            .all(self.conn)
+            .await?;
+
+        // 无法识别的值合并到未知来源
+        let mut counts: Vec<(LicenseSource, u64)> = Vec::new();
+        for (source, count) in rows {
+            let source = LicenseSource::from_db(&source);
+            match counts.iter_mut().find(|(s, _)| *s == source) {
+                Some((_, total)) => *total += count as u64,
+                None => counts.push((source, count as u64)),
+            }
+        }
+        counts.sort_by_key(|(_, count)| std::cmp::Reverse(*count));
+        Ok(counts)
     }
 
     /// Get all licenses for a user
     pub async fn get_user_licenses(&self, user_id: UserId) -> Result<Vec<UserLicense>, BotError> {
         Ok(Entity::find()
             .filter(Column::UserId.eq(user_id.get() as i64))
+            .order_by_asc(Column::SortOrder)
             .order_by_desc(Column::CreatedAt)
-            .all(self.0)
+            .all(self.conn)
             .await?)
     }
 
+    /// Get a page of a user's licenses, in the same order as `get_user_licenses`
+    ///
+    /// `limit` of `None` returns every license after `offset`.
+    pub async fn get_user_licenses_page(
+        &self,
+        user_id: UserId,
+        offset: u64,
+        limit: Option<u64>,
+    ) -> Result<Vec<UserLicense>, BotError> {
+        Ok(Entity::find()
+            .filter(Column::UserId.eq(user_id.get() as i64))
+            .order_by_asc(Column::SortOrder)
+            .order_by_desc(Column::CreatedAt)
+            .order_by_asc(Column::Id)
+            .offset(offset)
+            // SQLite 的 OFFSET 必须搭配 LIMIT，不限制条数时使用最大值
+            .limit(limit.unwrap_or(i64::MAX as u64))
+            .all(self.conn)
+            .await?)
+    }
+
+    /// Find licenses with the given name across all users
+    ///
+    /// Not scoped to a user, so only admin-facing callers should use it.
+    pub async fn find_by_name(&self, license_name: &str) -> Result<Vec<UserLicense>, BotError> {
+        Ok(Entity::find()
+            .filter(Column::LicenseName.eq(license_name))
+            .order_by_asc(Column::UserId)
+            .order_by_asc(Column::CreatedAt)
+            .all(self.conn)
+            .await?)
+    }
+
+    /// Set the display order of a user's licenses
+    ///
+    /// `license_ids` lists the licenses from first to last. IDs that don't belong to the
+    /// user are ignored; licenses not listed keep their current order.
+    pub async fn set_sort_order(
+        &self,
+        user_id: UserId,
+        license_ids: &[i32],
+    ) -> Result<(), BotError> {
+        let txn = self.conn.begin().await?;
+        for (position, license_id) in license_ids.iter().enumerate() {
+            Entity::update_many()
+                .col_expr(Column::SortOrder, Expr::value(position as i32 + 1))
+                .filter(Column::Id.eq(*license_id))
+                .filter(Column::UserId.eq(user_id.get() as i64))
+                .exec(&txn)
+                .await?;
+        }
+        txn.commit().await?;
+        Ok(())
+    }
+
     /// Get a specific license by ID and user ID
     pub async fn get_license(
         &self,
@@ -76,39 +319,52 @@ impl<'a> LicenseService<'a> {
                     .eq(license_id)
                     .and(Column::UserId.eq(user_id.get() as i64)),
             )
-            .one(self.0)
+            .one(self.conn)
             .await?)
     }
 
     /// Update a user license (atomic operation)
-    #[allow(clippy::too_many_arguments)]
     pub async fn update(
         &self,
         license_id: i32,
         user_id: UserId,
-        license_name: String,
-        allow_redistribution: bool,
-        allow_modification: bool,
-        restrictions_note: Option<String>,
-        allow_backup: bool,
+        fields: LicenseFields,
     ) -> Result<Option<UserLicense>, BotError> {
+        validate_color(fields.color)?;
+
         // 执行原子更新
         let update_result = Entity::update_many()
-            .col_expr(Column::LicenseName, Expr::value(license_name))
+            .col_expr(Column::LicenseName, Expr::value(fields.license_name))
             .col_expr(
                 Column::AllowRedistribution,
-                Expr::value(allow_redistribution),
+                Expr::value(fields.allow_redistribution),
             )
-            .col_expr(Column::AllowModification, Expr::value(allow_modification))
-            .col_expr(Column::RestrictionsNote, Expr::value(restrictions_note))
-            .col_expr(Column::AllowBackup, Expr::value(allow_backup))
+            .col_expr(
+                Column::AllowModification,
+                Expr::value(fields.allow_modification),
+            )
+            .col_expr(
+                Column::RestrictionsNote,
+                Expr::value(fields.restrictions_note),
+            )
+            .col_expr(Column::AllowBackup, Expr::value(fields.allow_backup))
+            .col_expr(
+                Column::AllowCommercial,
+                Expr::value(fields.allow_commercial),
+            )
+            .col_expr(
+                Column::RequireAttribution,
+                Expr::value(fields.require_attribution),
+            )
+            .col_expr(Column::Color, Expr::value(fields.color))
             .filter(
                 Column::Id
                     .eq(license_id)
                     .and(Column::UserId.eq(user_id.get() as i64)),
             )
-            .exec(self.0)
-            .await?;
+            .exec(self.conn)
+            .await
+            .map_err(name_conflict_error)?;
 
         // 如果更新成功，获取更新后的记录
         if update_result.rows_affected > 0 {
@@ -126,17 +382,127 @@ impl<'a> LicenseService<'a> {
                     .eq(license_id)
                     .and(Column::UserId.eq(user_id.get() as i64)),
             )
-            .exec(self.0)
+            .exec(self.conn)
             .await?;
 
         Ok(result.rows_affected > 0)
     }
 
+    /// Transfer a license to another user
+    ///
+    /// Ownership, the destination's license limit and name conflicts are checked
+    /// in the same transaction as the update. Returns `None` when `from` does not
+    /// own the license.
+    pub async fn transfer(
+        &self,
+        license_id: i32,
+        from: UserId,
+        to: UserId,
+    ) -> Result<Option<UserLicense>, BotError> {
+        let txn = self.conn.begin().await?;
+
+        let Some(license) = Entity::find()
+            .filter(
+                Column::Id
+                    .eq(license_id)
+                    .and(Column::UserId.eq(from.get() as i64)),
+            )
+            .one(&txn)
+            .await?
+        else {
+            return Ok(None);
+        };
+        if from == to {
+            return Ok(Some(license));
+        }
+
+        let destination = Entity::find().filter(Column::UserId.eq(to.get() as i64));
+        if destination.clone().count(&txn).await? >= self.max_licenses {
+            return Err(BotError::LicenseLimit {
+                max: self.max_licenses,
+                loc: snafu::location!(),
+            });
+        }
+        if destination
+            .filter(Column::LicenseName.eq(license.license_name.as_str()))
+            .one(&txn)
+            .await?
+            .is_some()
+        {
+            return Err(BotError::ValidationError {
+                message: format!("目标用户已有名为「{}」的协议。", license.license_name),
+                loc: snafu::location!(),
+            });
+        }
+
+        let mut active: ActiveModel = license.into();
+        active.user_id = Set(to.get() as i64);
+        active.sort_order = Set(Self::next_sort_order(&txn, to).await?);
+        let license = active.update(&txn).await?;
+        txn.commit().await?;
+        Ok(Some(license))
+    }
+
+    /// Move all of a user's licenses to another user
+    ///
+    /// Licenses are moved in display order until the destination reaches its
+    /// license limit. Licenses whose name the destination already uses, or that
+    /// no longer fit, stay with `from` and are reported in the result. Runs in a
+    /// single transaction.
+    pub async fn transfer_licenses(
+        &self,
+        from: UserId,
+        to: UserId,
+    ) -> Result<LicenseTransfer, BotError> {
+        let mut result = LicenseTransfer::default();
+        if from == to {
+            return Ok(result);
+        }
+
+        let txn = self.conn.begin().await?;
+        let licenses = Entity::find()
+            .filter(Column::UserId.eq(from.get() as i64))
+            .order_by_asc(Column::SortOrder)
+            .order_by_desc(Column::CreatedAt)
+            .all(&txn)
+            .await?;
+        let existing = Entity::find()
+            .filter(Column::UserId.eq(to.get() as i64))
+            .all(&txn)
+            .await?;
+        let mut names: Vec<String> = existing.into_iter().map(|l| l.license_name).collect();
+        let mut sort_order = Self::next_sort_order(&txn, to).await?;
+
+        for license in licenses {
+            if names.contains(&license.license_name) {
+                result.name_conflicts.push(license);
+                continue;
+            }
+            if names.len() as u64 >= self.max_licenses {
+                result.over_limit.push(license);
+                continue;
+            }
+
+            names.push(license.license_name.clone());
+            let mut active: ActiveModel = license.into();
+            active.user_id = Set(to.get() as i64);
+            // 原用户的排序对目标用户没有意义，接在目标用户已排序的协议之后
+            active.sort_order = Set(sort_order);
+            if sort_order > 0 {
+                sort_order += 1;
+            }
+            result.moved.push(active.update(&txn).await?);
+        }
+
+        txn.commit().await?;
+        Ok(result)
+    }
+
     /// Get license count for a user
     pub async fn get_user_license_count(&self, user_id: UserId) -> Result<u64, BotError> {
         Ok(Entity::find()
             .filter(Column::UserId.eq(user_id.get() as i64))
-            .count(self.0)
+            .count(self.conn)
             .await?)
     }
 
@@ -149,12 +515,78 @@ impl<'a> LicenseService<'a> {
                     .eq(license_id)
                     .and(Column::UserId.eq(user_id.get() as i64)),
             )
-            .exec(self.0)
+            .exec(self.conn)
             .await?;
 
         Ok(())
     }
 
+    /// Decrement usage count for a license (atomic operation), never going below zero
+    pub async fn decrement_usage(&self, license_id: i32, user_id: UserId) -> Result<(), BotError> {
+        Entity::update_many()
+            .col_expr(Column::UsageCount, Expr::col(Column::UsageCount).sub(1))
+            .filter(
+                Column::Id
+                    .eq(license_id)
+                    .and(Column::UserId.eq(user_id.get() as i64))
+                    .and(Column::UsageCount.gt(0)),
+            )
+            .exec(self.conn)
+            .await?;
+
+        Ok(())
+    }
+
+    /// Set usage count for a license, clamping negative values to zero
+    ///
+    /// Returns `false` if the license does not exist or belongs to another user.
+    pub async fn set_usage(
+        &self,
+        license_id: i32,
+        user_id: UserId,
+        usage_count: i32,
+    ) -> Result<bool, BotError> {
+        let result = Entity::update_many()
+            .col_expr(Column::UsageCount, Expr::value(usage_count.max(0)))
+            .filter(
+                Column::Id
+                    .eq(license_id)
+                    .and(Column::UserId.eq(user_id.get() as i64)),
+            )
+            .exec(self.conn)
+            .await?;
+
+        Ok(result.rows_affected > 0)
+    }
+
+    /// Reset usage count of a license to zero (atomic operation)
+    ///
+    /// Returns `false` if the license does not exist or belongs to another user.
+    pub async fn reset_usage(&self, license_id: i32, user_id: UserId) -> Result<bool, BotError> {
+        let result = Entity::update_many()
+            .col_expr(Column::UsageCount, Expr::value(0))
+            .filter(
+                Column::Id
+                    .eq(license_id)
+                    .and(Column::UserId.eq(user_id.get() as i64)),
+            )
+            .exec(self.conn)
+            .await?;
+
+        Ok(result.rows_affected > 0)
+    }
+
+    /// Reset usage count of all licenses of a user to zero, returning how many were reset
+    pub async fn reset_all_usage(&self, user_id: UserId) -> Result<u64, BotError> {
+        let result = Entity::update_many()
+            .col_expr(Column::UsageCount, Expr::value(0))
+            .filter(Column::UserId.eq(user_id.get() as i64))
+            .exec(self.conn)
+            .await?;
+
+        Ok(result.rows_affected)
+    }
+
     /// Get licenses sorted by usage count (most used first)
     pub async fn get_user_licenses_by_usage(
         &self,
@@ -164,7 +596,7 @@ impl<'a> LicenseService<'a> {
             .filter(Column::UserId.eq(user_id.get() as i64))
             .order_by_desc(Column::UsageCount)
             .order_by_desc(Column::CreatedAt)
-            .all(self.0)
+            .all(self.conn)
             .await?)
     }
 
@@ -177,7 +609,7 @@ impl<'a> LicenseService<'a> {
             .select_only()
             .column_as(Expr::col(Column::UsageCount).sum(), "total_usage")
             .into_tuple::<Option<i32>>()
-            .one(self.0)
+            .one(self.conn)
             .await?;
 
         Ok(result.flatten().unwrap_or(0))
@@ -200,14 +632,14 @@ impl<'a> LicenseService<'a> {
             query = query.filter(Column::Id.ne(exclude_id));
         }
 
-        Ok(query.one(self.0).await?.is_some())
+        Ok(query.one(self.conn).await?.is_some())
     }
 
     /// Clear all licenses for a user (dangerous operation)
     pub async fn clear_user_licenses(&self, user_id: UserId) -> Result<u64, BotError> {
         let result = Entity::delete_many()
             .filter(Column::UserId.eq(user_id.get() as i64))
-            .exec(self.0)
+            .exec(self.conn)
             .await?;
 
         Ok(result.rows_affected)
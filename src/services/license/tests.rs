@@ -1,6 +1,8 @@
 use migration::{Migrator, MigratorTrait, SchemaManager};
 use serenity::all::*;
 
+use super::{LicenseFields, LicenseSource};
+
 #[cfg(test)]
 use crate::database::BotDatabase;
 
@@ -23,11 +25,12 @@ async fn test_create_license() {
     let license = service
         .create(
             user_id,
-            "Test License".to_string(),
-            true,
-            false,
-            Some("Test restrictions".to_string()),
-            true,
+            LicenseFields {
+                allow_redistribution: true,
+                restrictions_note: Some("Test restrictions".to_string()),
+                allow_backup: true,
+                ..LicenseFields::new("Test License")
+            },
         )
         .await
         .unwrap();
@@ -40,6 +43,8 @@ async fn test_create_license() {
         Some("Test restrictions".to_string())
     );
     assert!(license.allow_backup);
+    assert!(!license.allow_commercial);
+    assert!(!license.require_attribution);
     assert_eq!(license.usage_count, 0);
 }
 
@@ -51,18 +56,25 @@ async fn test_get_user_licenses() {
 
     // Create two licenses
     service
-        .create(user_id, "License 1".to_string(), true, true, None, false)
+        .create(
+            user_id,
+            LicenseFields {
+                allow_redistribution: true,
+                allow_modification: true,
+                ..LicenseFields::new("License 1")
+            },
+        )
         .await
         .unwrap();
 
     service
         .create(
             user_id,
-            "License 2".to_string(),
-            false,
-            false,
-            Some("Restrictions".to_string()),
-            true,
+            LicenseFields {
+                restrictions_note: Some("Restrictions".to_string()),
+                allow_backup: true,
+                ..LicenseFields::new("License 2")
+            },
         )
         .await
         .unwrap();
@@ -82,7 +94,13 @@ async fn test_update_license() {
     let user_id = UserId::new(123);
 
     let license = service
-        .create(user_id, "Original".to_string(), true, false, None, false)
+        .create(
+            user_id,
+            LicenseFields {
+                allow_redistribution: true,
+                ..LicenseFields::new("Original")
+            },
+        )
         .await
         .unwrap();
 
@@ -90,11 +108,14 @@ async fn test_update_license() {
         .update(
             license.id,
             user_id,
-            "Updated".to_string(),
-            false,
-            true,
-            Some("New restrictions".to_string()),
-            true,
+            LicenseFields {
+                allow_modification: true,
+                restrictions_note: Some("New restrictions".to_string()),
+                allow_backup: true,
+                allow_commercial: true,
+                require_attribution: true,
+                ..LicenseFields::new("Updated")
+            },
         )
         .await
         .unwrap();
@@ -109,6 +130,8 @@ async fn test_update_license() {
         Some("New restrictions".to_string())
     );
     assert!(updated.allow_backup);
+    assert!(updated.allow_commercial);
+    assert!(updated.require_attribution);
 }
 
 #[tokio::test]
@@ -118,7 +141,13 @@ async fn test_delete_license() {
     let user_id = UserId::new(123);
 
     let license = service
-        .create(user_id, "Test".to_string(), true, false, None, false)
+        .create(
+            user_id,
+            LicenseFields {
+                allow_redistribution: true,
+                ..LicenseFields::new("Test")
+            },
+        )
         .await
         .unwrap();
 
@@ -136,7 +165,13 @@ async fn test_increment_usage() {
     let user_id = UserId::new(123);
 
     let license = service
-        .create(user_id, "Test".to_string(), true, false, None, false)
+        .create(
+            user_id,
+            LicenseFields {
+                allow_redistribution: true,
+                ..LicenseFields::new("Test")
+            },
+        )
         .await
         .unwrap();
 
@@ -151,6 +186,54 @@ async fn test_increment_usage() {
     assert_eq!(updated_license.usage_count, 2);
 }
 
+#[tokio::test]
+async fn test_reset_usage() {
+    let db = setup_test_db().await;
+    let service = db.license();
+    let owner = UserId::new(123);
+    let other = UserId::new(456);
+
+    let mut ids = Vec::new();
+    for (user_id, name) in [(owner, "A"), (owner, "B"), (other, "C")] {
+        let license = service
+            .create(
+                user_id,
+                LicenseFields {
+                    allow_redistribution: true,
+                    ..LicenseFields::new(name)
+                },
+            )
+            .await
+            .unwrap();
+        service.increment_usage(license.id, user_id).await.unwrap();
+        service.increment_usage(license.id, user_id).await.unwrap();
+        ids.push(license.id);
+    }
+    let usage = |id, user_id| {
+        let service = db.license();
+        async move {
+            service
+                .get_license(id, user_id)
+                .await
+                .unwrap()
+                .unwrap()
+                .usage_count
+        }
+    };
+
+    // 只能重置自己的协议
+    assert!(!service.reset_usage(ids[2], owner).await.unwrap());
+    assert_eq!(usage(ids[2], other).await, 2);
+
+    assert!(service.reset_usage(ids[0], owner).await.unwrap());
+    assert_eq!(usage(ids[0], owner).await, 0);
+    assert_eq!(usage(ids[1], owner).await, 2);
+
+    assert_eq!(service.reset_all_usage(owner).await.unwrap(), 2);
+    assert_eq!(usage(ids[1], owner).await, 0);
+    assert_eq!(usage(ids[2], other).await, 2);
+}
+
 #[tokio::test]
 async fn test_license_name_exists() {
     let db = setup_test_db().await;
@@ -158,7 +241,13 @@ async fn test_license_name_exists() {
     let user_id = UserId::new(123);
 
     service
-        .create(user_id, "Existing".to_string(), true, false, None, false)
+        .create(
+            user_id,
+            LicenseFields {
+                allow_redistribution: true,
+                ..LicenseFields::new("Existing")
+            },
+        )
         .await
         .unwrap();
 
@@ -185,9 +274,460 @@ async fn test_get_user_license_count() {
     assert_eq!(service.get_user_license_count(user_id).await.unwrap(), 0);
 
     service
-        .create(user_id, "License 1".to_string(), true, false, None, false)
+        .create(
+            user_id,
+            LicenseFields {
+                allow_redistribution: true,
+                ..LicenseFields::new("License 1")
+            },
+        )
         .await
         .unwrap();
 
     assert_eq!(service.get_user_license_count(user_id).await.unwrap(), 1);
 }
+
+#[tokio::test]
+async fn test_license_source_counts() {
+    let db = setup_test_db().await;
+    let service = db.license();
+
+    let sources = [
+        LicenseSource::Command,
+        LicenseSource::Interactive,
+        LicenseSource::Interactive,
+        LicenseSource::Grpc,
+    ];
+    for (i, source) in sources.into_iter().enumerate() {
+        let license = service
+            .create_with_source(
+                UserId::new(i as u64 + 1),
+                LicenseFields {
+                    allow_redistribution: true,
+                    allow_modification: true,
+                    ..LicenseFields::new(format!("License {i}"))
+                },
+                source,
+            )
+            .await
+            .unwrap();
+        assert_eq!(license.source, source.as_str());
+    }
+    // 未指定来源时记录为未知
+    let license = service
+        .create(
+            UserId::new(10),
+            LicenseFields {
+                allow_redistribution: true,
+                allow_modification: true,
+                ..LicenseFields::new("Legacy")
+            },
+        )
+        .await
+        .unwrap();
+    assert_eq!(
+        LicenseSource::from_db(&license.source),
+        LicenseSource::Unknown
+    );
+
+    let counts = service.get_source_counts().await.unwrap();
+    assert_eq!(counts[0], (LicenseSource::Interactive, 2));
+    assert_eq!(counts.len(), 4);
+    assert!(counts.contains(&(LicenseSource::Command, 1)));
+    assert!(counts.contains(&(LicenseSource::Grpc, 1)));
+    assert!(counts.contains(&(LicenseSource::Unknown, 1)));
+    assert!(!counts.iter().any(|(s, _)| *s == LicenseSource::AutoPublish));
+}
+
+#[tokio::test]
+async fn test_create_or_get_returns_existing() {
+    let db = setup_test_db().await;
+    let service = db.license();
+    let user_id = UserId::new(123);
+
+    let create = |name: &str, allow_backup: bool| {
+        service.create_or_get(
+            user_id,
+            LicenseFields {
+                allow_redistribution: true,
+                allow_backup,
+                ..LicenseFields::new(name)
+            },
+            LicenseSource::Command,
+        )
+    };
+
+    let (first, created) = create("Same", false).await.unwrap();
+    assert!(created);
+
+    // 同名时返回已有协议，不会重复创建
+    let (second, created) = create("Same", true).await.unwrap();
+    assert!(!created);
+    assert_eq!(second.id, first.id);
+    assert!(!second.allow_backup);
+    assert_eq!(service.get_user_license_count(user_id).await.unwrap(), 1);
+
+    let (_, created) = create("Other", false).await.unwrap();
+    assert!(created);
+    assert_eq!(service.get_user_license_count(user_id).await.unwrap(), 2);
+}
+
+#[tokio::test]
+async fn test_set_sort_order() {
+    let db = setup_test_db().await;
+    let service = db.license();
+    let user_id = UserId::new(123);
+    let other_user = UserId::new(456);
+
+    let mut ids = Vec::new();
+    for name in ["A", "B", "C"] {
+        let license = service
+            .create(user_id, LicenseFields::new(name))
+            .await
+            .unwrap();
+        ids.push(license.id);
+    }
+    let other = service
+        .create(other_user, LicenseFields::new("Other"))
+        .await
+        .unwrap();
+
+    // Custom order: B, C, A; another user's license is ignored
+    service
+        .set_sort_order(user_id, &[ids[1], ids[2], ids[0], other.id])
+        .await
+        .unwrap();
+    let names: Vec<String> = service
+        .get_user_licenses(user_id)
+        .await
+        .unwrap()
+        .into_iter()
+        .map(|l| l.license_name)
+        .collect();
+    assert_eq!(names, vec!["B", "C", "A"]);
+
+    let other = service
+        .get_license(other.id, other_user)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(other.sort_order, 0);
+
+    // New licenses go after the ordered ones
+    service
+        .create(user_id, LicenseFields::new("D"))
+        .await
+        .unwrap();
+    let names: Vec<String> = service
+        .get_user_licenses(user_id)
+        .await
+        .unwrap()
+        .into_iter()
+        .map(|l| l.license_name)
+        .collect();
+    assert_eq!(names, vec!["B", "C", "A", "D"]);
+
+    // A transferred license drops its old position
+    let moved = service
+        .transfer(ids[0], user_id, other_user)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(moved.sort_order, 0);
+}
+
+#[tokio::test]
+async fn test_create_respects_configured_limit() {
+    let db = setup_test_db().await;
+    let service = db.license().with_max_licenses(2);
+    let user_id = UserId::new(123);
+
+    let create = |name: &str| service.create(user_id, LicenseFields::new(name));
+    create("A").await.unwrap();
+    create("B").await.unwrap();
+    let err = create("C").await.unwrap_err();
+    assert!(matches!(
+        err,
+        crate::error::BotError::LicenseLimit { max: 2, .. }
+    ));
+    assert!(err.to_string().contains("最多只能创建2个协议"));
+
+    // 默认上限不受其他服务实例的设置影响
+    db.license()
+        .create(user_id, LicenseFields::new("C"))
+        .await
+        .unwrap();
+}
+
+#[tokio::test]
+async fn test_transfer_license() {
+    let db = setup_test_db().await;
+    let service = db.license().with_max_licenses(2);
+    let from = UserId::new(1);
+    let to = UserId::new(2);
+
+    let create = |user_id, name: &str| service.create(user_id, LicenseFields::new(name));
+    let license = create(from, "A").await.unwrap();
+    let duplicate = create(from, "B").await.unwrap();
+    create(to, "B").await.unwrap();
+
+    // 只有协议所有者才能转出
+    assert!(
+        service
+            .transfer(license.id, to, from)
+            .await
+            .unwrap()
+            .is_none()
+    );
+
+    // 目标用户已有同名协议时拒绝转移
+    assert!(matches!(
+        service.transfer(duplicate.id, from, to).await,
+        Err(crate::error::BotError::ValidationError { .. })
+    ));
+
+    let transferred = service
+        .transfer(license.id, from, to)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(transferred.user_id, to.get() as i64);
+    assert!(
+        service
+            .get_license(license.id, from)
+            .await
+            .unwrap()
+            .is_none()
+    );
+    assert!(service.get_license(license.id, to).await.unwrap().is_some());
+
+    // 目标用户的协议数量已达上限
+    let other = create(UserId::new(3), "C").await.unwrap();
+    let err = service
+        .transfer(other.id, UserId::new(3), to)
+        .await
+        .unwrap_err();
+    assert!(matches!(err, crate::error::BotError::LicenseLimit { .. }));
+    assert!(
+        service
+            .get_license(other.id, UserId::new(3))
+            .await
+            .unwrap()
+            .is_some()
+    );
+}
+
+#[tokio::test]
+async fn test_find_by_name_across_users() {
+    let db = setup_test_db().await;
+    let service = db.license();
+
+    for (user_id, name) in [(2, "共享协议"), (1, "共享协议"), (1, "其他协议")] {
+        service
+            .create(UserId::new(user_id), LicenseFields::new(name))
+            .await
+            .unwrap();
+    }
+
+    let found = service.find_by_name("共享协议").await.unwrap();
+    assert_eq!(
+        found.iter().map(|l| l.user_id).collect::<Vec<_>>(),
+        vec![1, 2]
+    );
+    assert!(found.iter().all(|l| l.license_name == "共享协议"));
+    assert!(service.find_by_name("不存在").await.unwrap().is_empty());
+}
+
+#[tokio::test]
+async fn test_transfer_all_licenses() {
+    let db = setup_test_db().await;
+    let service = db.license().with_max_licenses(3);
+    let from = UserId::new(1);
+    let to = UserId::new(2);
+
+    let create = |user_id, name: &str| service.create(user_id, LicenseFields::new(name));
+    for name in ["A", "B", "C"] {
+        create(from, name).await.unwrap();
+    }
+    create(to, "B").await.unwrap();
+
+    // 转给自己不做任何修改
+    let result = service.transfer_licenses(from, from).await.unwrap();
+    assert_eq!(result, super::LicenseTransfer::default());
+
+    let result = service.transfer_licenses(from, to).await.unwrap();
+    // 同名协议保留在原用户名下，目标用户只剩一个空位
+    let names = |licenses: &[super::UserLicense]| {
+        licenses
+            .iter()
+            .map(|l| l.license_name.clone())
+            .collect::<Vec<_>>()
+    };
+    assert_eq!(names(&result.name_conflicts), vec!["B"]);
+    assert_eq!(result.moved.len(), 2);
+    assert_eq!(result.over_limit.len(), 0);
+    assert!(result.moved.iter().all(|l| l.user_id == 2));
+
+    let remaining = names(&service.get_user_licenses(from).await.unwrap());
+    assert_eq!(remaining, vec!["B"]);
+    assert_eq!(service.get_user_license_count(to).await.unwrap(), 3);
+
+    // 目标用户已满时全部保留
+    create(from, "D").await.unwrap();
+    let result = service.transfer_licenses(from, to).await.unwrap();
+    assert!(result.moved.is_empty());
+    assert_eq!(names(&result.name_conflicts), vec!["B"]);
+    assert_eq!(names(&result.over_limit), vec!["D"]);
+}
+
+#[tokio::test]
+async fn test_rejects_out_of_range_color() {
+    let db = setup_test_db().await;
+    let service = db.license();
+    let user_id = UserId::new(123);
+
+    for color in [-1, 0x1000000] {
+        let result = service
+            .create(
+                user_id,
+                LicenseFields {
+                    allow_redistribution: true,
+                    allow_backup: true,
+                    color: Some(color),
+                    ..LicenseFields::new("Colored")
+                },
+            )
+            .await;
+        assert!(matches!(
+            result,
+            Err(crate::error::BotError::ValidationError { .. })
+        ));
+    }
+    assert_eq!(service.get_user_license_count(user_id).await.unwrap(), 0);
+
+    let license = service
+        .create(
+            user_id,
+            LicenseFields {
+                allow_redistribution: true,
+                allow_backup: true,
+                color: Some(0xFFFFFF),
+                ..LicenseFields::new("Colored")
+            },
+        )
+        .await
+        .unwrap();
+    let result = service
+        .update(
+            license.id,
+            user_id,
+            LicenseFields {
+                allow_redistribution: true,
+                allow_backup: true,
+                color: Some(-5),
+                ..LicenseFields::new("Colored")
+            },
+        )
+        .await;
+    assert!(matches!(
+        result,
+        Err(crate::error::BotError::ValidationError { .. })
+    ));
+    let unchanged = service.get_license(license.id, user_id).await.unwrap();
+    assert_eq!(unchanged.unwrap().color, Some(0xFFFFFF));
+}
+
+#[tokio::test]
+async fn test_license_names_unique_per_user() {
+    let db = setup_test_db().await;
+    let service = db.license();
+    let user_id = UserId::new(123);
+    let create = |user_id: UserId, name: &str| {
+        service.create(
+            user_id,
+            LicenseFields {
+                allow_redistribution: true,
+                allow_backup: true,
+                ..LicenseFields::new(name)
+            },
+        )
+    };
+
+    create(user_id, "A").await.unwrap();
+    let other = create(user_id, "B").await.unwrap();
+    // 其他用户可以使用相同的名称
+    create(UserId::new(456), "A").await.unwrap();
+
+    // 唯一索引拒绝绕过名称检查的重复写入
+    assert!(matches!(
+        create(user_id, "A").await,
+        Err(crate::error::BotError::ValidationError { .. })
+    ));
+    let result = service
+        .update(
+            other.id,
+            user_id,
+            LicenseFields {
+                allow_redistribution: true,
+                allow_backup: true,
+                ..LicenseFields::new("A")
+            },
+        )
+        .await;
+    assert!(matches!(
+        result,
+        Err(crate::error::BotError::ValidationError { .. })
+    ));
+    assert_eq!(service.get_user_license_count(user_id).await.unwrap(), 2);
+}
+
+#[tokio::test]
+async fn test_unique_name_migration_renames_duplicates() {
+    use entities::user_licenses;
+    use sea_orm::{ActiveModelTrait, Set};
+
+    let db = BotDatabase::new_memory().await.unwrap();
+    let migrations = Migrator::migrations();
+    let manager = SchemaManager::new(db.inner());
+    let unique_index = migrations
+        .iter()
+        .position(|m| m.name() == "m20250816_000001_unique_user_license_name")
+        .unwrap();
+    for migration in &migrations[..unique_index] {
+        migration.up(&manager).await.unwrap();
+    }
+
+    // 加入唯一索引之前已存在的重复协议
+    for _ in 0..2 {
+        user_licenses::ActiveModel {
+            user_id: Set(1),
+            license_name: Set("重复".to_string()),
+            allow_redistribution: Set(false),
+            allow_modification: Set(false),
+            allow_backup: Set(false),
+            allow_commercial: Set(false),
+            require_attribution: Set(false),
+            usage_count: Set(0),
+            created_at: Set(chrono::Utc::now()),
+            ..Default::default()
+        }
+        .insert(db.inner())
+        .await
+        .unwrap();
+    }
+    for migration in &migrations[unique_index..] {
+        migration.up(&manager).await.unwrap();
+    }
+
+    let names: Vec<String> = db
+        .license()
+        .get_user_licenses(UserId::new(1))
+        .await
+        .unwrap()
+        .into_iter()
+        .map(|l| l.license_name)
+        .collect();
+    assert_eq!(names.len(), 2);
+    assert!(names.contains(&"重复".to_string()));
+    assert!(names.iter().any(|n| n.starts_with("重复 (")));
+}
@@ -1,3 +1,85 @@
 use entities::user_licenses::Model;
 
 pub type UserLicense = Model;
+
+/// 协议的可编辑字段，创建和更新协议时使用
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct LicenseFields {
+    pub license_name: String,
+    pub allow_redistribution: bool,
+    pub allow_modification: bool,
+    pub restrictions_note: Option<String>,
+    pub allow_backup: bool,
+    pub allow_commercial: bool,
+    pub require_attribution: bool,
+    pub color: Option<i32>,
+}
+
+impl LicenseFields {
+    /// 指定名称、其余权限均为默认（不允许）的协议字段
+    pub fn new(license_name: impl Into<String>) -> Self {
+        Self {
+            license_name: license_name.into(),
+            ..Default::default()
+        }
+    }
+}
+
+impl From<&UserLicense> for LicenseFields {
+    fn from(license: &UserLicense) -> Self {
+        Self {
+            license_name: license.license_name.clone(),
+            allow_redistribution: license.allow_redistribution,
+            allow_modification: license.allow_modification,
+            restrictions_note: license.restrictions_note.clone(),
+            allow_backup: license.allow_backup,
+            allow_commercial: license.allow_commercial,
+            require_attribution: license.require_attribution,
+            color: license.color,
+        }
+    }
+}
+
+/// 协议的创建来源，以字符串形式存储在 `user_licenses.source` 列中
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum LicenseSource {
+    /// `/创建协议` 参数式命令
+    Command,
+    /// 交互式协议编辑器
+    Interactive,
+    /// 自动发布的新用户引导流程
+    AutoPublish,
+    /// gRPC 接口
+    Grpc,
+    /// 来源未知（迁移前创建的协议）
+    Unknown,
+}
+
+impl LicenseSource {
+    pub const ALL: [LicenseSource; 5] = [
+        LicenseSource::Command,
+        LicenseSource::Interactive,
+        LicenseSource::AutoPublish,
+        LicenseSource::Grpc,
+        LicenseSource::Unknown,
+    ];
+
+    /// 数据库中存储的值
+    pub fn as_str(self) -> &'static str {
+        match self {
+            LicenseSource::Command => "command",
+            LicenseSource::Interactive => "interactive",
+            LicenseSource::AutoPublish => "auto_publish",
+            LicenseSource::Grpc => "grpc",
+            LicenseSource::Unknown => "unknown",
+        }
+    }
+
+    /// 解析数据库中的值，无法识别时视为未知来源
+    pub fn from_db(value: &str) -> Self {
+        Self::ALL
+            .into_iter()
+            .find(|source| source.as_str() == value)
+            .unwrap_or(LicenseSource::Unknown)
+    }
+}
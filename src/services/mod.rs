@@ -1,9 +1,15 @@
 // mod flush;
 // mod messages;
+pub mod audit_log;
+pub mod discord_health;
 pub mod gateway;
+pub mod health_server;
 pub mod license;
 pub mod notification_service;
+pub mod post_sweeper;
 pub mod published_posts;
+pub mod self_check;
+pub mod spdx;
 pub mod status_monitor;
 pub mod system_license;
 pub mod user_settings;
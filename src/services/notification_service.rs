@@ -1,15 +1,38 @@
-use std::sync::Arc;
+use std::{
+    sync::Arc,
+    time::{Duration, Instant},
+};
 
 use arc_swap::ArcSwap;
-use reqwest::Client;
+use hmac::{Hmac, Mac};
+use reqwest::{Client, Response, StatusCode, Url, header::HeaderMap};
 use serde::Serialize;
+use sha2::Sha256;
 use snafu::ResultExt;
 use tracing;
 
-use crate::{config::BotCfg, error::BotError};
+use crate::{
+    config::{BotCfg, NotificationAckCfg},
+    error::BotError,
+};
+
+/// 通知载荷的格式版本，字段发生不兼容的变化时递增
+pub const NOTIFICATION_SCHEMA_VERSION: u32 = 1;
+/// 测试通知的事件类型
+pub const TEST_EVENT_TYPE: &str = "test";
+/// 端点验证探测的事件类型
+pub const PROBE_EVENT_TYPE: &str = "probe";
+/// 幂等键的时间窗口（秒），同一窗口内的重复通知使用相同的键
+pub const IDEMPOTENCY_WINDOW_SECS: i64 = 300;
 
 #[derive(Serialize, Debug)]
 pub struct NotificationPayload {
+    /// 载荷格式版本，接收方可据此选择解析方式
+    pub schema_version: u32,
+    /// 发送通知的机器人版本
+    pub bot_version: String,
+    /// 幂等键，接收方可据此丢弃重复的通知，生成方式见 [`idempotency_key`]
+    pub idempotency_key: String,
     pub event_type: String,
     pub timestamp: String,
     pub guild_id: String,
@@ -28,6 +51,47 @@ pub struct Author {
     pub display_name: String,
 }
 
+impl Author {
+    /// 通知中的作者信息，提供 `secret` 时匿名化
+    fn new(user: &serenity::all::User, secret: Option<&str>) -> Self {
+        match secret {
+            Some(secret) => Self::anonymized(user.id, secret),
+            None => Self::from_user(user),
+        }
+    }
+
+    /// 作者的原始 Discord 信息
+    fn from_user(user: &serenity::all::User) -> Self {
+        Self {
+            discord_user_id: user.id.to_string(),
+            username: user.name.clone(),
+            display_name: user.display_name().to_string(),
+        }
+    }
+
+    /// 匿名化的作者信息
+    ///
+    /// 用户ID替换为以 `secret` 为密钥的 HMAC-SHA256（十六进制），同一用户在同一密钥下
+    /// 始终得到相同的标识，接收方可据此关联而无法还原用户ID；用户名和显示名不再发送
+    fn anonymized(user_id: serenity::all::UserId, secret: &str) -> Self {
+        let mut mac = Hmac::<Sha256>::new_from_slice(secret.as_bytes())
+            .expect("HMAC accepts keys of any length");
+        mac.update(user_id.to_string().as_bytes());
+        let digest: String = mac
+            .finalize()
+            .into_bytes()
+            .iter()
+            .map(|byte| format!("{byte:02x}"))
+            .collect();
+        let name = format!("匿名作者-{}", &digest[..8]);
+        Self {
+            discord_user_id: digest,
+            username: name.clone(),
+            display_name: name,
+        }
+    }
+}
+
 #[derive(Serialize, Debug)]
 pub struct WorkInfo {
     pub title: String,
@@ -42,6 +106,15 @@ pub struct Urls {
     pub direct_message: String,
 }
 
+/// 端点验证的结果
+#[derive(Debug)]
+pub struct ProbeResult {
+    pub status: StatusCode,
+    pub elapsed: Duration,
+    /// 响应与确认格式不符的地方，为空表示验证通过
+    pub mismatches: Vec<String>,
+}
+
 #[derive(Debug)]
 pub struct NotificationService {
     client: Client,
@@ -74,13 +147,7 @@ impl NotificationService {
         tracing::info!("正在向 {} 发送备份通知...", endpoint);
 
         // 2. 发送 POST 请求
-        let response = self
-            .client
-            .post(endpoint.clone())
-            .json(payload)
-            .send()
-            .await
-            .whatever_context::<&str, BotError>("发送通知请求时发生网络错误")?;
+        let response = self.post(endpoint, payload).await?;
 
         // 3. 处理响应
         if response.status().is_success() {
@@ -99,18 +166,237 @@ impl NotificationService {
             })
         }
     }
+
+    /// 发送测试通知，返回响应状态码和耗时
+    ///
+    /// 与备份通知走相同的请求路径，但不受 `backup_enabled` 开关限制，
+    /// 非 2xx 的响应也会正常返回，由调用方展示给用户
+    pub async fn send_test_notification(
+        &self,
+        payload: &NotificationPayload,
+    ) -> Result<(StatusCode, Duration), BotError> {
+        let endpoint = self.config.load().endpoint.clone();
+        tracing::info!("正在向 {} 发送测试通知...", endpoint);
+
+        let started = Instant::now();
+        let response = self.post(&endpoint, payload).await?;
+        let elapsed = started.elapsed();
+        tracing::info!(
+            "测试通知已发送到 {}，状态码: {}，耗时 {:?}",
+            endpoint,
+            response.status(),
+            elapsed
+        );
+        Ok((response.status(), elapsed))
+    }
+
+    /// 发送探测通知，并按配置的确认格式检查端点的响应
+    ///
+    /// 与测试通知一样不受 `backup_enabled` 开关限制
+    pub async fn probe_endpoint(
+        &self,
+        payload: &NotificationPayload,
+    ) -> Result<ProbeResult, BotError> {
+        let config = self.config.load();
+        tracing::info!("正在验证通知端点 {}...", config.endpoint);
+
+        let started = Instant::now();
+        let response = self.post(&config.endpoint, payload).await?;
+        let elapsed = started.elapsed();
+        let status = response.status();
+        let headers = response.headers().clone();
+        let body = response
+            .text()
+            .await
+            .whatever_context::<&str, BotError>("读取端点响应时发生网络错误")?;
+
+        let mismatches = validate_ack(&config.notification_ack, status, &headers, &body);
+        tracing::info!(
+            "通知端点 {} 验证完成，状态码: {}，不符合项: {:?}",
+            config.endpoint,
+            status,
+            mismatches
+        );
+        Ok(ProbeResult {
+            status,
+            elapsed,
+            mismatches,
+        })
+    }
+
+    async fn post(
+        &self,
+        endpoint: &Url,
+        payload: &NotificationPayload,
+    ) -> Result<Response, BotError> {
+        let body = match &self.config.load().notification_template {
+            Some(template) => render_template(template, payload)?,
+            None => serde_json::to_value(payload)?,
+        };
+        self.client
+            .post(endpoint.clone())
+            .json(&body)
+            .send()
+            .await
+            .whatever_context::<&str, BotError>("发送通知请求时发生网络错误")
+    }
+}
+
+/// 检查端点的响应是否符合确认格式，返回所有不符合的地方
+pub fn validate_ack(
+    ack: &NotificationAckCfg,
+    status: StatusCode,
+    headers: &HeaderMap,
+    body: &str,
+) -> Vec<String> {
+    let mut mismatches = Vec::new();
+    if !status.is_success() {
+        mismatches.push(format!("状态码为 {status}，期望 2xx"));
+    }
+    if let Some(name) = &ack.header {
+        match (headers.get(name), &ack.header_value) {
+            (None, _) => mismatches.push(format!("缺少响应头 `{name}`")),
+            (Some(actual), Some(expected)) if actual.to_str().ok() != Some(expected.as_str()) => {
+                mismatches.push(format!(
+                    "响应头 `{name}` 为 `{}`，期望 `{expected}`",
+                    String::from_utf8_lossy(actual.as_bytes())
+                ))
+            }
+            _ => {}
+        }
+    }
+    if let Some(expected) = &ack.body_contains
+        && !body.contains(expected.as_str())
+    {
+        mismatches.push(format!("响应体中没有 `{expected}`"));
+    }
+    mismatches
+}
+
+/// 按模板渲染通知载荷
+///
+/// 模板为 JSON，其中字符串里的 `{{路径}}` 占位符会被替换为载荷中对应的字段，
+/// 路径以 `.` 分隔，如 `{{author.display_name}}`。字符串只包含一个占位符时保留字段原本的类型，
+/// 否则将字段值拼接为文本。模板无效或引用了不存在的字段时返回错误。
+pub fn render_template(
+    template: &str,
+    payload: &NotificationPayload,
+) -> Result<serde_json::Value, BotError> {
+    let template: serde_json::Value = serde_json::from_str(template)
+        .map_err(|e| template_error(format!("模板不是有效的 JSON: {e}")))?;
+    render_value(template, &serde_json::to_value(payload)?)
+}
+
+fn template_error(message: String) -> BotError {
+    BotError::ValidationError {
+        message: format!("通知载荷模板错误: {message}"),
+        loc: snafu::location!(),
+    }
+}
+
+fn render_value(
+    value: serde_json::Value,
+    payload: &serde_json::Value,
+) -> Result<serde_json::Value, BotError> {
+    use serde_json::Value;
+
+    Ok(match value {
+        Value::String(text) => render_string(&text, payload)?,
+        Value::Array(items) => Value::Array(
+            items
+                .into_iter()
+                .map(|item| render_value(item, payload))
+                .collect::<Result<_, _>>()?,
+        ),
+        Value::Object(map) => Value::Object(
+            map.into_iter()
+                .map(|(key, item)| Ok((key, render_value(item, payload)?)))
+                .collect::<Result<_, BotError>>()?,
+        ),
+        other => other,
+    })
+}
+
+fn render_string(text: &str, payload: &serde_json::Value) -> Result<serde_json::Value, BotError> {
+    let lookup = |path: &str| {
+        path.trim()
+            .split('.')
+            .try_fold(payload, |value, key| value.get(key))
+            .ok_or_else(|| template_error(format!("载荷中不存在字段 `{}`", path.trim())))
+    };
+
+    // 整个字符串就是一个占位符时保留原本的类型
+    if let Some(path) = text
+        .strip_prefix("{{")
+        .and_then(|rest| rest.strip_suffix("}}"))
+        .filter(|path| !path.contains("{{") && !path.contains("}}"))
+    {
+        return lookup(path).cloned();
+    }
+
+    let mut rendered = String::new();
+    let mut rest = text;
+    while let Some(start) = rest.find("{{") {
+        let Some(end) = rest[start..].find("}}") else {
+            break;
+        };
+        rendered.push_str(&rest[..start]);
+        match lookup(&rest[start + 2..start + end])? {
+            serde_json::Value::String(value) => rendered.push_str(value),
+            serde_json::Value::Null => {}
+            value => rendered.push_str(&value.to_string()),
+        }
+        rest = &rest[start + end + 2..];
+    }
+    rendered.push_str(rest);
+    Ok(serde_json::Value::String(rendered))
+}
+
+/// 生成通知的幂等键
+///
+/// 格式为 `{thread_id}:{message_id}:{backup_allowed}:{bucket}`，其中 `backup_allowed`
+/// 为 `1` 或 `0`，`bucket` 为 Unix 时间戳除以 [`IDEMPOTENCY_WINDOW_SECS`] 的商。
+/// 同一协议消息、同一备份权限在同一时间窗口内的通知得到相同的键；
+/// 跨越窗口边界的重复通知会得到不同的键。
+pub fn idempotency_key(
+    thread_id: serenity::all::ChannelId,
+    message_id: serenity::all::MessageId,
+    backup_allowed: bool,
+    at: chrono::DateTime<chrono::Utc>,
+) -> String {
+    let bucket = at.timestamp().div_euclid(IDEMPOTENCY_WINDOW_SECS);
+    format!(
+        "{thread_id}:{message_id}:{}:{bucket}",
+        u8::from(backup_allowed)
+    )
+}
+
+/// 截断内容预览，超出 `max_chars` 个字符时以省略号结尾（省略号计入长度）
+fn truncate_preview(content: &str, max_chars: usize) -> String {
+    if content.chars().count() <= max_chars {
+        return content.to_string();
+    }
+    let mut preview: String = content.chars().take(max_chars.saturating_sub(1)).collect();
+    preview.push('…');
+    preview
 }
 
 // 通知载荷构造辅助函数
 impl NotificationPayload {
     /// 从Discord上下文创建通知载荷
+    ///
+    /// 内容预览最多保留 `preview_chars` 个字符，超出时以省略号结尾。
+    /// 提供 `author_secret` 时作者信息会被匿名化，见 [`Author::anonymized`]
+    #[allow(clippy::too_many_arguments)]
     pub async fn from_discord_context(
         thread: &serenity::all::GuildChannel,
         message_id: serenity::all::MessageId,
         author: serenity::all::User,
         content_preview: String,
+        preview_chars: usize,
         license_type: String,
         backup_allowed: bool,
+        author_secret: Option<&str>,
     ) -> Self {
         let guild_id_str = thread.guild_id.to_string();
         let channel_id_str = thread.parent_id.unwrap_or_default().to_string();
@@ -123,21 +409,21 @@ impl NotificationPayload {
         let direct_message_url =
             format!("https://discord.com/channels/{guild_id_str}/{thread_id_str}/{message_id_str}");
 
+        let now = chrono::Utc::now();
         Self {
+            schema_version: NOTIFICATION_SCHEMA_VERSION,
+            bot_version: env!("CARGO_PKG_VERSION").to_string(),
+            idempotency_key: idempotency_key(thread.id, message_id, backup_allowed, now),
             event_type: "backup_permission_update".to_string(),
-            timestamp: chrono::Utc::now().to_rfc3339(),
+            timestamp: now.to_rfc3339(),
             guild_id: guild_id_str,
             channel_id: channel_id_str,
             thread_id: thread_id_str,
             message_id: message_id_str,
-            author: Author {
-                discord_user_id: author.id.to_string(),
-                username: author.name.clone(),
-                display_name: author.display_name().to_string(),
-            },
+            author: Author::new(&author, author_secret),
             work_info: WorkInfo {
                 title: thread.name.clone(),
-                content_preview: content_preview.chars().take(100).collect(),
+                content_preview: truncate_preview(&content_preview, preview_chars),
                 license_type,
                 backup_allowed,
             },
@@ -147,4 +433,336 @@ impl NotificationPayload {
             },
         }
     }
+    /// 创建端点验证用的探测载荷，除 `event_type` 为 [`PROBE_EVENT_TYPE`] 外与测试载荷相同
+    pub fn probe(
+        guild_id: serenity::all::GuildId,
+        channel_id: serenity::all::ChannelId,
+        author: &serenity::all::User,
+        author_secret: Option<&str>,
+    ) -> Self {
+        Self {
+            event_type: PROBE_EVENT_TYPE.to_string(),
+            ..Self::test(guild_id, channel_id, author, author_secret)
+        }
+    }
+
+    /// 创建测试通知载荷
+    ///
+    /// `event_type` 为 [`TEST_EVENT_TYPE`]，以发起测试的用户为作者，其余字段填入示例值，
+    /// 接收方应据此忽略该通知。与实际通知一样，提供 `author_secret` 时作者信息会被匿名化
+    pub fn test(
+        guild_id: serenity::all::GuildId,
+        channel_id: serenity::all::ChannelId,
+        author: &serenity::all::User,
+        author_secret: Option<&str>,
+    ) -> Self {
+        let now = chrono::Utc::now();
+        let message_id = serenity::all::MessageId::new(1);
+        Self {
+            schema_version: NOTIFICATION_SCHEMA_VERSION,
+            bot_version: env!("CARGO_PKG_VERSION").to_string(),
+            idempotency_key: format!("test:{}", now.timestamp_millis()),
+            event_type: TEST_EVENT_TYPE.to_string(),
+            timestamp: now.to_rfc3339(),
+            guild_id: guild_id.to_string(),
+            channel_id: channel_id.to_string(),
+            thread_id: channel_id.to_string(),
+            message_id: message_id.to_string(),
+            author: Author::new(author, author_secret),
+            work_info: WorkInfo {
+                title: "测试通知".to_string(),
+                content_preview: "这是一条测试通知，用于检查通知端点配置，请忽略。".to_string(),
+                license_type: "测试".to_string(),
+                backup_allowed: true,
+            },
+            urls: Urls {
+                discord_thread: format!("https://discord.com/channels/{guild_id}/{channel_id}"),
+                direct_message: format!(
+                    "https://discord.com/channels/{guild_id}/{channel_id}/{message_id}"
+                ),
+            },
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serenity::all::{ChannelId, GuildChannel, GuildId, MessageId, User, UserId};
+
+    use super::*;
+
+    #[tokio::test]
+    async fn test_payload_includes_versions() {
+        let mut thread = GuildChannel::default();
+        thread.id = ChannelId::new(30);
+        thread.guild_id = GuildId::new(10);
+        thread.parent_id = Some(ChannelId::new(20));
+        thread.name = "作品".to_string();
+        let mut author = User::default();
+        author.id = UserId::new(40);
+
+        let payload = NotificationPayload::from_discord_context(
+            &thread,
+            MessageId::new(50),
+            author,
+            "内容".to_string(),
+            100,
+            "CC-BY-4.0".to_string(),
+            true,
+            None,
+        )
+        .await;
+        let json = serde_json::to_value(&payload).unwrap();
+
+        assert_eq!(json["schema_version"], NOTIFICATION_SCHEMA_VERSION);
+        assert_eq!(json["bot_version"], env!("CARGO_PKG_VERSION"));
+        assert_eq!(json["event_type"], "backup_permission_update");
+        assert!(
+            json["idempotency_key"]
+                .as_str()
+                .unwrap()
+                .starts_with("30:50:1:")
+        );
+        assert_eq!(
+            json["urls"]["direct_message"],
+            "https://discord.com/channels/10/30/50"
+        );
+        assert_eq!(json["work_info"]["content_preview"], "内容");
+    }
+
+    #[tokio::test]
+    async fn test_anonymized_author() {
+        let mut thread = GuildChannel::default();
+        thread.id = ChannelId::new(30);
+        let author = |id| {
+            let mut user = User::default();
+            user.id = UserId::new(id);
+            user.name = "writer".to_string();
+            user
+        };
+        let payload = |user, secret| {
+            NotificationPayload::from_discord_context(
+                &thread,
+                MessageId::new(50),
+                user,
+                "内容".to_string(),
+                100,
+                "CC-BY-4.0".to_string(),
+                true,
+                secret,
+            )
+        };
+
+        let first = payload(author(40), Some("secret")).await.author;
+        let again = payload(author(40), Some("secret")).await.author;
+        let other_user = payload(author(41), Some("secret")).await.author;
+        let other_secret = payload(author(40), Some("rotated")).await.author;
+
+        // 同一用户、同一密钥得到稳定的标识，不同用户或密钥得到不同的标识
+        assert_eq!(first.discord_user_id, again.discord_user_id);
+        assert_ne!(first.discord_user_id, other_user.discord_user_id);
+        assert_ne!(first.discord_user_id, other_secret.discord_user_id);
+
+        // 不包含原始用户ID和用户名
+        assert_eq!(first.discord_user_id.len(), 64);
+        assert!(first.discord_user_id.chars().all(|c| c.is_ascii_hexdigit()));
+        assert_ne!(first.discord_user_id, "40");
+        assert!(first.username.starts_with("匿名作者-"));
+        assert!(!first.username.contains("writer"));
+        assert_eq!(first.username, first.display_name);
+
+        let plain = payload(author(40), None).await.author;
+        assert_eq!(plain.discord_user_id, "40");
+        assert_eq!(plain.username, "writer");
+    }
+
+    fn sample_payload() -> NotificationPayload {
+        let mut author = User::default();
+        author.id = UserId::new(40);
+        author.name = "writer".to_string();
+        NotificationPayload::test(GuildId::new(10), ChannelId::new(20), &author, None)
+    }
+
+    #[test]
+    fn test_render_template() {
+        let payload = sample_payload();
+        let template = r#"{
+            "type": "{{ event_type }}",
+            "user": "{{author.discord_user_id}}",
+            "backup": "{{work_info.backup_allowed}}",
+            "version": "{{schema_version}}",
+            "text": "{{author.username}} 更新了《{{work_info.title}}》（{{work_info.backup_allowed}}）",
+            "links": ["{{urls.discord_thread}}"],
+            "author": "{{author}}",
+            "fixed": 1
+        }"#;
+
+        let rendered = render_template(template, &payload).unwrap();
+        assert_eq!(
+            rendered,
+            serde_json::json!({
+                "type": "test",
+                "user": "40",
+                "backup": true,
+                "version": NOTIFICATION_SCHEMA_VERSION,
+                "text": "writer 更新了《测试通知》（true）",
+                "links": ["https://discord.com/channels/10/20"],
+                "author": serde_json::to_value(&payload.author).unwrap(),
+                "fixed": 1
+            })
+        );
+    }
+
+    #[test]
+    fn test_render_template_errors() {
+        let payload = sample_payload();
+        let message = |template| match render_template(template, &payload) {
+            Err(BotError::ValidationError { message, .. }) => message,
+            other => panic!("expected validation error, got {other:?}"),
+        };
+
+        assert!(message(r#"{"a": "{{author.nickname}}"}"#).contains("`author.nickname`"));
+        assert!(message(r#"{"a": "前缀 {{missing}}"}"#).contains("`missing`"));
+        assert!(message(r#"{"a": "#).contains("不是有效的 JSON"));
+
+        // 未闭合的占位符按原文保留
+        let rendered = render_template(r#"{"a": "{{event_type"}"#, &payload).unwrap();
+        assert_eq!(rendered["a"], "{{event_type");
+    }
+
+    #[test]
+    fn test_validate_ack() {
+        let headers = |pairs: &[(&'static str, &'static str)]| {
+            let mut map = HeaderMap::new();
+            for (name, value) in pairs {
+                map.insert(*name, value.parse().unwrap());
+            }
+            map
+        };
+
+        // 未配置确认格式时只检查状态码
+        let default = NotificationAckCfg::default();
+        assert!(validate_ack(&default, StatusCode::NO_CONTENT, &HeaderMap::new(), "").is_empty());
+        assert_eq!(
+            validate_ack(&default, StatusCode::NOT_FOUND, &HeaderMap::new(), ""),
+            vec!["状态码为 404 Not Found，期望 2xx"]
+        );
+
+        let ack = NotificationAckCfg {
+            header: Some("X-Ack".to_string()),
+            header_value: Some("ok".to_string()),
+            body_contains: Some("received".to_string()),
+        };
+        let ok_headers = headers(&[("x-ack", "ok")]);
+        assert!(
+            validate_ack(
+                &ack,
+                StatusCode::OK,
+                &ok_headers,
+                r#"{"status":"received"}"#
+            )
+            .is_empty()
+        );
+
+        let mismatches = validate_ack(
+            &ack,
+            StatusCode::OK,
+            &headers(&[("x-ack", "queued")]),
+            "accepted",
+        );
+        assert_eq!(
+            mismatches,
+            vec![
+                "响应头 `X-Ack` 为 `queued`，期望 `ok`",
+                "响应体中没有 `received`"
+            ]
+        );
+        assert_eq!(
+            validate_ack(&ack, StatusCode::OK, &HeaderMap::new(), "received"),
+            vec!["缺少响应头 `X-Ack`"]
+        );
+
+        // 只配置响应头名称时不检查值
+        let header_only = NotificationAckCfg {
+            header: Some("X-Ack".to_string()),
+            ..Default::default()
+        };
+        assert!(validate_ack(&header_only, StatusCode::OK, &ok_headers, "").is_empty());
+    }
+
+    #[test]
+    fn test_test_payload_is_marked() {
+        let mut author = User::default();
+        author.id = UserId::new(40);
+        let payload =
+            NotificationPayload::test(GuildId::new(10), ChannelId::new(20), &author, None);
+        let json = serde_json::to_value(&payload).unwrap();
+
+        assert_eq!(json["event_type"], TEST_EVENT_TYPE);
+        assert_eq!(json["schema_version"], NOTIFICATION_SCHEMA_VERSION);
+        assert_eq!(json["author"]["discord_user_id"], "40");
+        assert!(
+            json["idempotency_key"]
+                .as_str()
+                .unwrap()
+                .starts_with("test:")
+        );
+
+        // 测试和探测载荷与实际通知一样遵循匿名化设置
+        let secret = Some("secret");
+        let anonymized =
+            NotificationPayload::test(GuildId::new(10), ChannelId::new(20), &author, secret);
+        let probe =
+            NotificationPayload::probe(GuildId::new(10), ChannelId::new(20), &author, secret);
+        assert_eq!(
+            anonymized.author.discord_user_id,
+            Author::anonymized(author.id, "secret").discord_user_id
+        );
+        assert_eq!(
+            probe.author.discord_user_id,
+            anonymized.author.discord_user_id
+        );
+    }
+
+    #[test]
+    fn test_idempotency_key() {
+        let thread = ChannelId::new(30);
+        let message = MessageId::new(50);
+        let at = |secs| chrono::DateTime::from_timestamp(secs, 0).unwrap();
+
+        // 同一事件在同一时间窗口内的键相同
+        let key = idempotency_key(thread, message, true, at(1_700_000_100));
+        assert_eq!(key, "30:50:1:5666667");
+        assert_eq!(
+            key,
+            idempotency_key(thread, message, true, at(1_700_000_100 + 60))
+        );
+
+        // 备份权限、消息或时间窗口不同时键不同
+        assert_ne!(
+            key,
+            idempotency_key(thread, message, false, at(1_700_000_100))
+        );
+        assert_ne!(
+            key,
+            idempotency_key(thread, MessageId::new(51), true, at(1_700_000_100))
+        );
+        assert_ne!(
+            key,
+            idempotency_key(
+                thread,
+                message,
+                true,
+                at(1_700_000_100 + IDEMPOTENCY_WINDOW_SECS)
+            )
+        );
+    }
+
+    #[test]
+    fn test_truncate_preview() {
+        assert_eq!(truncate_preview("短内容", 3), "短内容");
+        // 按字符截断，省略号计入长度
+        assert_eq!(truncate_preview("这是一段较长的内容", 5), "这是一段…");
+        assert_eq!(truncate_preview("abc", 0), "…");
+    }
 }
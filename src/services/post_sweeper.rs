@@ -0,0 +1,119 @@
+use std::{sync::Arc, time::Duration};
+
+use arc_swap::ArcSwap;
+use chrono::Utc;
+use serenity::all::{ChannelId, Http, HttpError};
+use tokio::time;
+use tracing::{error, info, warn};
+
+use crate::{
+    config::BotCfg,
+    database::BotDatabase,
+    error::BotError,
+    services::discord_health::{DiscordHealth, is_outage_error},
+};
+
+/// 每轮扫描检查的帖子数
+const SWEEP_BATCH_SIZE: u64 = 50;
+/// 只扫描超过该时间未更新的帖子，新帖子的删除由 ThreadDelete 事件处理
+const SWEEP_MIN_AGE: chrono::Duration = chrono::Duration::days(1);
+/// 两次频道请求之间的间隔，避免触发速率限制
+const SWEEP_REQUEST_INTERVAL: Duration = Duration::from_millis(500);
+/// Discord 的 "Unknown Channel" 错误码
+const UNKNOWN_CHANNEL_CODE: isize = 10003;
+
+/// 判断频道请求失败是否因为频道（帖子）已不存在
+pub fn is_unknown_channel(err: &serenity::Error) -> bool {
+    matches!(
+        err,
+        serenity::Error::Http(HttpError::UnsuccessfulRequest(response))
+            if response.error.code == UNKNOWN_CHANNEL_CODE
+    )
+}
+
+/// 启动已发布帖子的清理后台任务
+///
+/// 定期分批检查旧的发布记录，删除帖子已不存在的记录，
+/// 用于处理机器人离线期间被删除的帖子。间隔为 0 时不启动，Discord 服务故障期间跳过。
+pub fn start_post_sweeper(
+    http: Arc<Http>,
+    db: Arc<BotDatabase>,
+    cfg: Arc<ArcSwap<BotCfg>>,
+    health: Arc<DiscordHealth>,
+) {
+    let interval_secs = cfg.load().post_sweep_interval_secs;
+    if interval_secs == 0 {
+        info!("已发布帖子清理任务已禁用");
+        return;
+    }
+
+    info!("启动已发布帖子清理任务，间隔: {} 秒", interval_secs);
+    tokio::spawn(async move {
+        let mut interval = time::interval(Duration::from_secs(interval_secs));
+        // 游标记录上一轮检查到的帖子，扫描到末尾后从头开始
+        let mut cursor = None;
+        loop {
+            interval.tick().await;
+            if health.is_outage() {
+                info!("Discord 服务故障中，跳过本轮已发布帖子清理");
+                continue;
+            }
+            match sweep_batch(&http, &db, &health, cursor).await {
+                Ok(next) => cursor = next,
+                Err(e) => error!("清理已删除帖子的发布记录失败: {}", e),
+            }
+        }
+    });
+}
+
+/// 检查一批旧的发布记录，返回下一轮的游标
+async fn sweep_batch(
+    http: &Http,
+    db: &BotDatabase,
+    health: &DiscordHealth,
+    cursor: Option<ChannelId>,
+) -> Result<Option<ChannelId>, BotError> {
+    let posts = db
+        .published_posts()
+        .get_posts_for_sweep(Utc::now() - SWEEP_MIN_AGE, cursor, SWEEP_BATCH_SIZE)
+        .await?;
+
+    let mut removed = 0;
+    for post in &posts {
+        let thread_id = ChannelId::new(post.thread_id as u64);
+        match http.get_channel(thread_id).await {
+            Ok(_) => {
+                health.record_success();
+            }
+            Err(e) if is_outage_error(&e) => {
+                warn!("检查帖子 {} 失败: {}", thread_id, e);
+                // Discord 故障时中止本轮，下一轮从同一位置继续
+                if health.record_failure() || health.is_outage() {
+                    warn!("Discord 服务故障，中止本轮已发布帖子清理");
+                    return Ok(cursor);
+                }
+            }
+            Err(e) if is_unknown_channel(&e) => {
+                if db.published_posts().delete(thread_id).await? {
+                    removed += 1;
+                }
+            }
+            // 其他错误（如无权限、网络问题）无法确认帖子已删除，保留记录
+            Err(e) => warn!("检查帖子 {} 失败: {}", thread_id, e),
+        }
+        time::sleep(SWEEP_REQUEST_INTERVAL).await;
+    }
+
+    if removed > 0 {
+        info!("已清理 {} 条已删除帖子的发布记录", removed);
+    }
+
+    // 不足一批说明已扫描到末尾，下一轮从头开始
+    Ok(if (posts.len() as u64) < SWEEP_BATCH_SIZE {
+        None
+    } else {
+        posts
+            .last()
+            .map(|post| ChannelId::new(post.thread_id as u64))
+    })
+}
@@ -1,12 +1,87 @@
+use std::collections::HashMap;
+
 use chrono::Utc;
 use entities::published_posts::*;
-use sea_orm::{QueryOrder, QuerySelect, Set, prelude::*};
+use sea_orm::{QueryOrder, QuerySelect, Set, prelude::*, sea_query::OnConflict};
+use serde::{Deserialize, Serialize};
 use serenity::all::*;
 
 use crate::{database::BotDatabase, error::BotError};
 
 pub type PublishedPost = Model;
 
+/// 分页读取用户帖子时每页的默认条数
+pub const USER_POSTS_PAGE_SIZE: u64 = 100;
+
+/// 用户帖子分页的游标，指向上一页的最后一条记录
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct UserPostsCursor {
+    pub updated_at: chrono::DateTime<Utc>,
+    pub thread_id: ChannelId,
+}
+
+impl From<&PublishedPost> for UserPostsCursor {
+    fn from(post: &PublishedPost) -> Self {
+        Self {
+            updated_at: post.updated_at,
+            thread_id: ChannelId::new(post.thread_id as u64),
+        }
+    }
+}
+
+/// 重新发布协议时对旧协议消息的处理方式
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, Default, Deserialize, Serialize, poise::ChoiceParameter,
+)]
+#[serde(rename_all = "snake_case")]
+pub enum ObsoleteLicenseAction {
+    /// 保留旧消息，标记为作废并取消置顶
+    #[default]
+    #[name = "Keep"]
+    #[name_localized("zh-CN", "保留并标记作废")]
+    Keep,
+    /// 删除旧消息
+    #[name = "Delete"]
+    #[name_localized("zh-CN", "删除")]
+    Delete,
+}
+
+impl ObsoleteLicenseAction {
+    /// 数据库中存储的值
+    pub fn as_str(self) -> &'static str {
+        match self {
+            ObsoleteLicenseAction::Keep => "keep",
+            ObsoleteLicenseAction::Delete => "delete",
+        }
+    }
+
+    /// 解析数据库中的值，无法识别时返回 `None`
+    pub fn from_db(value: &str) -> Option<Self> {
+        [ObsoleteLicenseAction::Keep, ObsoleteLicenseAction::Delete]
+            .into_iter()
+            .find(|action| action.as_str() == value)
+    }
+}
+
+/// 帖子实际使用的旧协议处理方式，帖子设置优先于全局配置
+pub fn effective_obsolete_action(
+    post: &PublishedPost,
+    default: ObsoleteLicenseAction,
+) -> ObsoleteLicenseAction {
+    post.obsolete_action
+        .as_deref()
+        .and_then(ObsoleteLicenseAction::from_db)
+        .unwrap_or(default)
+}
+
+/// 帖子的备份权限是否与所发布协议声明的不同（发布时或之后被覆盖）
+///
+/// 旧帖子没有记录协议声明的备份权限，视为未覆盖
+pub fn is_backup_overridden(post: &PublishedPost) -> bool {
+    post.license_backup_allowed
+        .is_some_and(|declared| declared != post.backup_allowed)
+}
+
 pub struct PublishedPostsService<'a>(&'a BotDatabase);
 
 impl BotDatabase {
@@ -31,6 +106,9 @@ impl PublishedPostsService<'_> {
             user_id: Set(user_id.get() as i64),
             backup_allowed: Set(backup_allowed),
             updated_at: Set(Utc::now()),
+            obsolete_action: Set(None),
+            system_license_name: Set(None),
+            license_backup_allowed: Set(None),
         };
 
         let result = post.insert(self.0.inner()).await?;
@@ -98,6 +176,46 @@ impl PublishedPostsService<'_> {
             .await?)
     }
 
+    /// Get one page of a user's posts, newest first
+    ///
+    /// Pass the last post of the previous page as `after` to continue. Posts are ordered
+    /// by `updated_at` then thread ID, so ties are never skipped or repeated. A post
+    /// updated while iterating moves before the cursor and is not visited again.
+    pub async fn get_user_posts_page(
+        &self,
+        user_id: UserId,
+        after: Option<UserPostsCursor>,
+        limit: u64,
+    ) -> Result<Vec<PublishedPost>, BotError> {
+        let mut query = Entity::find().filter(Column::UserId.eq(user_id.get() as i64));
+        if let Some(cursor) = after {
+            query = query.filter(
+                Column::UpdatedAt.lt(cursor.updated_at).or(Column::UpdatedAt
+                    .eq(cursor.updated_at)
+                    .and(Column::ThreadId.lt(cursor.thread_id.get() as i64))),
+            );
+        }
+        Ok(query
+            .order_by_desc(Column::UpdatedAt)
+            .order_by_desc(Column::ThreadId)
+            .limit(limit)
+            .all(self.0.inner())
+            .await?)
+    }
+
+    /// Count a user's posts whose backup permission differs from `backup_allowed`
+    pub async fn count_user_posts_with_other_backup(
+        &self,
+        user_id: UserId,
+        backup_allowed: bool,
+    ) -> Result<u64, BotError> {
+        Ok(Entity::find()
+            .filter(Column::UserId.eq(user_id.get() as i64))
+            .filter(Column::BackupAllowed.ne(backup_allowed))
+            .count(self.0.inner())
+            .await?)
+    }
+
     /// Get posts with backup allowed
     pub async fn get_backup_allowed_posts(&self) -> Result<Vec<PublishedPost>, BotError> {
         Ok(Entity::find()
@@ -132,6 +250,28 @@ impl PublishedPostsService<'_> {
             .await?)
     }
 
+    /// Get posts not updated since `before`, ordered by thread ID and starting after `after_thread`
+    ///
+    /// Used by the background sweep to page through old posts in batches.
+    pub async fn get_posts_for_sweep(
+        &self,
+        before: chrono::DateTime<Utc>,
+        after_thread: Option<ChannelId>,
+        limit: u64,
+    ) -> Result<Vec<PublishedPost>, BotError> {
+        let after_thread = after_thread.map_or(0, |id| id.get() as i64);
+        Ok(Entity::find()
+            .filter(
+                Column::UpdatedAt
+                    .lt(before)
+                    .and(Column::ThreadId.gt(after_thread)),
+            )
+            .order_by_asc(Column::ThreadId)
+            .limit(limit)
+            .all(self.0.inner())
+            .await?)
+    }
+
     /// Update backup permission for a post
     pub async fn update_backup_permission(
         &self,
@@ -155,6 +295,97 @@ impl PublishedPostsService<'_> {
         }
     }
 
+    /// Override backup permission for a post
+    ///
+    /// Returns the post and whether the permission actually changed,
+    /// so callers only re-render and notify on a real change.
+    pub async fn override_backup_permission(
+        &self,
+        thread_id: ChannelId,
+        backup_allowed: bool,
+    ) -> Result<Option<(PublishedPost, bool)>, BotError> {
+        let Some(post) = self.get_by_thread(thread_id).await? else {
+            return Ok(None);
+        };
+
+        if post.backup_allowed == backup_allowed {
+            return Ok(Some((post, false)));
+        }
+
+        Ok(self
+            .update_backup_permission(thread_id, backup_allowed)
+            .await?
+            .map(|updated| (updated, true)))
+    }
+
+    /// Set the per-thread obsolete license action, `None` restores the global setting
+    pub async fn set_obsolete_action(
+        &self,
+        thread_id: ChannelId,
+        action: Option<ObsoleteLicenseAction>,
+    ) -> Result<Option<PublishedPost>, BotError> {
+        let Some(post) = self.get_by_thread(thread_id).await? else {
+            return Ok(None);
+        };
+
+        let mut active_post: ActiveModel = post.into();
+        active_post.obsolete_action = Set(action.map(|a| a.as_str().to_string()));
+
+        Ok(Some(active_post.update(self.0.inner()).await?))
+    }
+
+    /// Record the system license a post was published with, `None` for user licenses
+    pub async fn set_system_license(
+        &self,
+        thread_id: ChannelId,
+        license_name: Option<&str>,
+    ) -> Result<Option<PublishedPost>, BotError> {
+        let Some(post) = self.get_by_thread(thread_id).await? else {
+            return Ok(None);
+        };
+
+        let mut active_post: ActiveModel = post.into();
+        active_post.system_license_name = Set(license_name.map(str::to_string));
+
+        Ok(Some(active_post.update(self.0.inner()).await?))
+    }
+
+    /// Record the backup permission declared by the published license
+    pub async fn set_license_backup(
+        &self,
+        thread_id: ChannelId,
+        license_backup_allowed: bool,
+    ) -> Result<Option<PublishedPost>, BotError> {
+        let Some(post) = self.get_by_thread(thread_id).await? else {
+            return Ok(None);
+        };
+
+        let mut active_post: ActiveModel = post.into();
+        active_post.license_backup_allowed = Set(Some(license_backup_allowed));
+
+        Ok(Some(active_post.update(self.0.inner()).await?))
+    }
+
+    /// Count published posts per system license
+    ///
+    /// Posts published before the license name was recorded are not counted.
+    pub async fn count_by_system_license(&self) -> Result<HashMap<String, u64>, BotError> {
+        let rows: Vec<(String, i64)> = Entity::find()
+            .select_only()
+            .column(Column::SystemLicenseName)
+            .column_as(Column::ThreadId.count(), "count")
+            .filter(Column::SystemLicenseName.is_not_null())
+            .group_by(Column::SystemLicenseName)
+            .into_tuple()
+            .all(self.0.inner())
+            .await?;
+
+        Ok(rows
+            .into_iter()
+            .map(|(name, count)| (name, count as u64))
+            .collect())
+    }
+
     /// Delete a published post
     pub async fn delete(&self, thread_id: ChannelId) -> Result<bool, BotError> {
         let result = Entity::delete_many()
@@ -197,21 +428,44 @@ impl PublishedPostsService<'_> {
     }
 
     /// Record or update a published post (upsert operation)
+    ///
+    /// All publish columns are written in a single statement, so the row never
+    /// mixes a new message with the previous license's system name or backup flag.
+    /// The owner and the per-thread obsolete action of an existing post are kept.
     pub async fn record_or_update(
         &self,
         thread_id: ChannelId,
         message_id: MessageId,
         user_id: UserId,
         backup_allowed: bool,
+        license_backup_allowed: bool,
+        system_license: Option<&str>,
     ) -> Result<PublishedPost, BotError> {
-        // Try to update existing post first
-        if let Some(updated) = self.update(thread_id, message_id, backup_allowed).await? {
-            Ok(updated)
-        } else {
-            // Create new post if doesn't exist
-            self.record(thread_id, message_id, user_id, backup_allowed)
-                .await
-        }
+        let post = ActiveModel {
+            thread_id: Set(thread_id.get() as i64),
+            message_id: Set(message_id.get() as i64),
+            user_id: Set(user_id.get() as i64),
+            backup_allowed: Set(backup_allowed),
+            updated_at: Set(Utc::now()),
+            obsolete_action: Set(None),
+            system_license_name: Set(system_license.map(str::to_string)),
+            license_backup_allowed: Set(Some(license_backup_allowed)),
+        };
+
+        Ok(Entity::insert(post)
+            .on_conflict(
+                OnConflict::column(Column::ThreadId)
+                    .update_columns([
+                        Column::MessageId,
+                        Column::BackupAllowed,
+                        Column::UpdatedAt,
+                        Column::SystemLicenseName,
+                        Column::LicenseBackupAllowed,
+                    ])
+                    .to_owned(),
+            )
+            .exec_with_returning(self.0.inner())
+            .await?)
     }
 
     /// Check if backup permission has changed for a thread
@@ -332,6 +586,197 @@ mod tests {
         assert!(!updated.backup_allowed);
     }
 
+    #[tokio::test]
+    async fn test_override_backup_permission() {
+        let db = setup_test_db().await;
+        let service = db.published_posts();
+        let thread_id = ChannelId::new(123);
+        let message_id = MessageId::new(456);
+        let user_id = UserId::new(789);
+
+        // No post yet
+        assert!(
+            service
+                .override_backup_permission(thread_id, true)
+                .await
+                .unwrap()
+                .is_none()
+        );
+
+        service
+            .record(thread_id, message_id, user_id, false)
+            .await
+            .unwrap();
+
+        // Same value: no change, no renotify
+        let (post, changed) = service
+            .override_backup_permission(thread_id, false)
+            .await
+            .unwrap()
+            .unwrap();
+        assert!(!changed);
+        assert!(!post.backup_allowed);
+
+        // Different value: updated and should renotify
+        let (post, changed) = service
+            .override_backup_permission(thread_id, true)
+            .await
+            .unwrap()
+            .unwrap();
+        assert!(changed);
+        assert!(post.backup_allowed);
+        assert_eq!(post.message_id, 456);
+    }
+
+    #[tokio::test]
+    async fn test_count_by_system_license() {
+        let db = setup_test_db().await;
+        let service = db.published_posts();
+        let user_id = UserId::new(789);
+
+        for (thread, license) in [
+            (101, Some("MIT")),
+            (102, Some("MIT")),
+            (103, Some("CC-BY-4.0")),
+            (104, None),
+        ] {
+            let thread_id = ChannelId::new(thread);
+            service
+                .record(thread_id, MessageId::new(thread), user_id, true)
+                .await
+                .unwrap();
+            service
+                .set_system_license(thread_id, license)
+                .await
+                .unwrap();
+        }
+
+        let counts = service.count_by_system_license().await.unwrap();
+        assert_eq!(counts.len(), 2);
+        assert_eq!(counts["MIT"], 2);
+        assert_eq!(counts["CC-BY-4.0"], 1);
+
+        // Republishing with a user license clears the record
+        service
+            .set_system_license(ChannelId::new(103), None)
+            .await
+            .unwrap();
+        let counts = service.count_by_system_license().await.unwrap();
+        assert!(!counts.contains_key("CC-BY-4.0"));
+    }
+
+    #[tokio::test]
+    async fn test_license_backup_override() {
+        let db = setup_test_db().await;
+        let service = db.published_posts();
+        let thread_id = ChannelId::new(123);
+
+        // Older posts without a declared permission are never reported as overridden
+        let post = service
+            .record(thread_id, MessageId::new(456), UserId::new(789), false)
+            .await
+            .unwrap();
+        assert_eq!(post.license_backup_allowed, None);
+        assert!(!is_backup_overridden(&post));
+
+        let post = service
+            .set_license_backup(thread_id, true)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(post.license_backup_allowed, Some(true));
+        assert!(is_backup_overridden(&post));
+
+        let (post, _) = service
+            .override_backup_permission(thread_id, true)
+            .await
+            .unwrap()
+            .unwrap();
+        assert!(!is_backup_overridden(&post));
+
+        assert!(
+            service
+                .set_license_backup(ChannelId::new(999), true)
+                .await
+                .unwrap()
+                .is_none()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_obsolete_action_override() {
+        let db = setup_test_db().await;
+        let service = db.published_posts();
+        let thread_id = ChannelId::new(123);
+
+        // No post yet
+        assert!(
+            service
+                .set_obsolete_action(thread_id, Some(ObsoleteLicenseAction::Delete))
+                .await
+                .unwrap()
+                .is_none()
+        );
+
+        let post = service
+            .record(thread_id, MessageId::new(456), UserId::new(789), true)
+            .await
+            .unwrap();
+
+        // Without an override the global setting applies
+        assert_eq!(
+            effective_obsolete_action(&post, ObsoleteLicenseAction::Keep),
+            ObsoleteLicenseAction::Keep
+        );
+        assert_eq!(
+            effective_obsolete_action(&post, ObsoleteLicenseAction::Delete),
+            ObsoleteLicenseAction::Delete
+        );
+
+        // The per-thread override takes precedence and survives republishing
+        service
+            .set_obsolete_action(thread_id, Some(ObsoleteLicenseAction::Delete))
+            .await
+            .unwrap();
+        let post = service
+            .record_or_update(
+                thread_id,
+                MessageId::new(999),
+                UserId::new(789),
+                false,
+                false,
+                None,
+            )
+            .await
+            .unwrap();
+        assert_eq!(
+            effective_obsolete_action(&post, ObsoleteLicenseAction::Keep),
+            ObsoleteLicenseAction::Delete
+        );
+
+        let post = service
+            .set_obsolete_action(thread_id, Some(ObsoleteLicenseAction::Keep))
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(
+            effective_obsolete_action(&post, ObsoleteLicenseAction::Delete),
+            ObsoleteLicenseAction::Keep
+        );
+
+        // Clearing the override restores the global setting
+        let post = service
+            .set_obsolete_action(thread_id, None)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(post.obsolete_action, None);
+        assert_eq!(
+            effective_obsolete_action(&post, ObsoleteLicenseAction::Delete),
+            ObsoleteLicenseAction::Delete
+        );
+    }
+
     #[tokio::test]
     async fn test_has_published_post() {
         let db = setup_test_db().await;
@@ -386,6 +831,84 @@ mod tests {
         assert_eq!(other_posts.len(), 1);
     }
 
+    #[tokio::test]
+    async fn test_get_user_posts_page() {
+        let db = setup_test_db().await;
+        let service = db.published_posts();
+        let user_id = UserId::new(789);
+
+        // 101 最新，102-104 更新时间相同，105 最旧；另有其他用户的帖子
+        let base = Utc::now();
+        let times = [
+            (101, base),
+            (102, base - Duration::minutes(1)),
+            (103, base - Duration::minutes(1)),
+            (104, base - Duration::minutes(1)),
+            (105, base - Duration::minutes(2)),
+        ];
+        for (thread, updated_at) in times {
+            let post = service
+                .record(
+                    ChannelId::new(thread),
+                    MessageId::new(thread),
+                    user_id,
+                    true,
+                )
+                .await
+                .unwrap();
+            let mut post: ActiveModel = post.into();
+            post.updated_at = Set(updated_at);
+            post.update(db.inner()).await.unwrap();
+        }
+        service
+            .record(
+                ChannelId::new(200),
+                MessageId::new(200),
+                UserId::new(1),
+                true,
+            )
+            .await
+            .unwrap();
+
+        // 按页读取，同一时间的帖子跨页时不重复也不遗漏
+        let mut cursor = None;
+        let mut pages = Vec::new();
+        loop {
+            let page = service
+                .get_user_posts_page(user_id, cursor, 2)
+                .await
+                .unwrap();
+            let Some(last) = page.last() else {
+                break;
+            };
+            cursor = Some(UserPostsCursor::from(last));
+            pages.push(page.iter().map(|p| p.thread_id).collect::<Vec<_>>());
+        }
+        assert_eq!(pages, vec![vec![101, 104], vec![103, 102], vec![105]]);
+
+        // 遍历过程中被更新的帖子移到游标之前，不会再次出现
+        let first = service.get_user_posts_page(user_id, None, 2).await.unwrap();
+        service
+            .update_backup_permission(ChannelId::new(103), false)
+            .await
+            .unwrap();
+        let rest = service
+            .get_user_posts_page(user_id, first.last().map(UserPostsCursor::from), 10)
+            .await
+            .unwrap();
+        assert_eq!(
+            rest.iter().map(|p| p.thread_id).collect::<Vec<_>>(),
+            vec![102, 105]
+        );
+        assert_eq!(
+            service
+                .count_user_posts_with_other_backup(user_id, true)
+                .await
+                .unwrap(),
+            1
+        );
+    }
+
     #[tokio::test]
     async fn test_get_backup_allowed_posts() {
         let db = setup_test_db().await;
@@ -491,18 +1014,23 @@ mod tests {
 
         // First call should create
         let post1 = service
-            .record_or_update(thread_id, message_id, user_id, true)
+            .record_or_update(thread_id, message_id, user_id, true, true, Some("MIT"))
             .await
             .unwrap();
         assert_eq!(post1.message_id, 456);
+        assert_eq!(post1.system_license_name.as_deref(), Some("MIT"));
+        assert_eq!(post1.license_backup_allowed, Some(true));
 
-        // Second call should update
+        // Second call should update every publish column together
         let post2 = service
-            .record_or_update(thread_id, new_message_id, user_id, false)
+            .record_or_update(thread_id, new_message_id, UserId::new(1), false, true, None)
             .await
             .unwrap();
         assert_eq!(post2.message_id, 999);
+        assert_eq!(post2.user_id, 789);
         assert!(!post2.backup_allowed);
+        assert_eq!(post2.system_license_name, None);
+        assert_eq!(post2.license_backup_allowed, Some(true));
 
         // Should only have one post
         assert_eq!(service.get_total_count().await.unwrap(), 1);
@@ -555,4 +1083,49 @@ mod tests {
         let old_posts = service.get_posts_in_range(from_old, to_old).await.unwrap();
         assert_eq!(old_posts.len(), 0);
     }
+
+    #[tokio::test]
+    async fn test_get_posts_for_sweep() {
+        let db = setup_test_db().await;
+        let service = db.published_posts();
+        let user_id = UserId::new(789);
+
+        for thread in [103, 101, 102] {
+            service
+                .record(
+                    ChannelId::new(thread),
+                    MessageId::new(thread + 1000),
+                    user_id,
+                    false,
+                )
+                .await
+                .unwrap();
+        }
+
+        // 尚未过期的帖子不会被扫描
+        let before = Utc::now() - Duration::hours(1);
+        assert!(
+            service
+                .get_posts_for_sweep(before, None, 10)
+                .await
+                .unwrap()
+                .is_empty()
+        );
+
+        // 按帖子ID分批翻页
+        let before = Utc::now() + Duration::hours(1);
+        let first = service.get_posts_for_sweep(before, None, 2).await.unwrap();
+        assert_eq!(
+            first.iter().map(|p| p.thread_id).collect::<Vec<_>>(),
+            vec![101, 102]
+        );
+        let rest = service
+            .get_posts_for_sweep(before, Some(ChannelId::new(102)), 2)
+            .await
+            .unwrap();
+        assert_eq!(
+            rest.iter().map(|p| p.thread_id).collect::<Vec<_>>(),
+            vec![103]
+        );
+    }
 }
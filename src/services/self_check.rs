@@ -0,0 +1,340 @@
+use std::{fmt, sync::OnceLock, time::Duration};
+
+use moka::future::Cache;
+use reqwest::Url;
+use serenity::all::{ChannelId, Http, Permissions};
+use tracing::{error, info, warn};
+
+use crate::{config::BotCfg, error::BotError};
+
+/// 在论坛频道中发布协议所需的各项权限及其说明
+pub const FORUM_PERMISSIONS: [(Permissions, &str); 6] = [
+    (Permissions::VIEW_CHANNEL, "查看频道"),
+    (Permissions::READ_MESSAGE_HISTORY, "读取消息历史"),
+    (Permissions::SEND_MESSAGES_IN_THREADS, "在帖子中发送消息"),
+    (Permissions::EMBED_LINKS, "嵌入链接（用于显示协议卡片）"),
+    (
+        Permissions::ATTACH_FILES,
+        "附加文件（用于上传完整协议文本）",
+    ),
+    (Permissions::MANAGE_MESSAGES, "管理消息（用于置顶协议）"),
+];
+
+/// 在论坛频道中发布协议所需的权限（发送、置顶协议消息及上传完整协议文本）
+pub const REQUIRED_FORUM_PERMISSIONS: Permissions = {
+    let mut permissions = Permissions::empty();
+    let mut i = 0;
+    while i < FORUM_PERMISSIONS.len() {
+        permissions = permissions.union(FORUM_PERMISSIONS[i].0);
+        i += 1;
+    }
+    permissions
+};
+
+/// 论坛权限缓存的有效期，权限修改后最多在此时间后生效
+const FORUM_PERMISSIONS_TTL: Duration = Duration::from_secs(60);
+
+// 论坛权限缓存，避免每次发布都重新请求频道、服务器和成员信息
+static FORUM_PERMISSIONS_CACHE: OnceLock<Cache<ChannelId, Permissions>> = OnceLock::new();
+
+/// 自检项的结果等级，按严重程度排序
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum CheckLevel {
+    Ok,
+    Warning,
+    Critical,
+}
+
+impl fmt::Display for CheckLevel {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            CheckLevel::Ok => "OK",
+            CheckLevel::Warning => "WARN",
+            CheckLevel::Critical => "FAIL",
+        })
+    }
+}
+
+/// 单个自检项的结果
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CheckResult {
+    pub name: &'static str,
+    pub level: CheckLevel,
+    pub message: String,
+}
+
+impl CheckResult {
+    fn new(name: &'static str, level: CheckLevel, message: impl Into<String>) -> Self {
+        Self {
+            name,
+            level,
+            message: message.into(),
+        }
+    }
+}
+
+/// 启动自检的汇总报告
+#[derive(Debug, Default)]
+pub struct SelfCheckReport {
+    pub results: Vec<CheckResult>,
+}
+
+impl SelfCheckReport {
+    pub fn push(&mut self, result: CheckResult) {
+        self.results.push(result);
+    }
+
+    /// 所有自检项中最严重的等级
+    pub fn level(&self) -> CheckLevel {
+        self.results
+            .iter()
+            .map(|r| r.level)
+            .max()
+            .unwrap_or(CheckLevel::Ok)
+    }
+
+    /// 输出每个自检项的结果及整体就绪状态
+    pub fn log_summary(&self) {
+        for result in &self.results {
+            match result.level {
+                CheckLevel::Ok => info!("[{}] {}: {}", result.level, result.name, result.message),
+                CheckLevel::Warning => {
+                    warn!("[{}] {}: {}", result.level, result.name, result.message)
+                }
+                CheckLevel::Critical => {
+                    error!("[{}] {}: {}", result.level, result.name, result.message)
+                }
+            }
+        }
+
+        let count = |level| self.results.iter().filter(|r| r.level == level).count();
+        info!(
+            "启动自检完成: {} 项通过, {} 项警告, {} 项失败",
+            count(CheckLevel::Ok),
+            count(CheckLevel::Warning),
+            count(CheckLevel::Critical)
+        );
+    }
+}
+
+/// 检查数据库迁移是否已全部应用
+pub fn check_migrations(pending: &[String]) -> CheckResult {
+    const NAME: &str = "数据库迁移";
+    if pending.is_empty() {
+        CheckResult::new(NAME, CheckLevel::Ok, "数据库结构已是最新")
+    } else {
+        CheckResult::new(
+            NAME,
+            CheckLevel::Critical,
+            format!(
+                "存在 {} 个未应用的迁移: {}",
+                pending.len(),
+                pending.join(", ")
+            ),
+        )
+    }
+}
+
+/// 检查系统协议是否已加载
+pub fn check_system_licenses(count: usize) -> CheckResult {
+    const NAME: &str = "系统协议";
+    if count == 0 {
+        CheckResult::new(
+            NAME,
+            CheckLevel::Critical,
+            "未加载任何系统协议，请检查系统协议文件",
+        )
+    } else {
+        CheckResult::new(NAME, CheckLevel::Ok, format!("已加载 {count} 个系统协议"))
+    }
+}
+
+/// 检查配置项之间的一致性
+///
+/// `system_license_names` 用于校验社区默认协议是否存在
+pub fn check_config(cfg: &BotCfg, system_license_names: &[String]) -> CheckResult {
+    const NAME: &str = "配置";
+    let mut critical = Vec::new();
+    let mut warnings = Vec::new();
+
+    if cfg.token.trim().is_empty() {
+        critical.push("未配置 Discord token".to_string());
+    }
+    if cfg.gateway_enabled.unwrap_or(false)
+        && (cfg.gateway_address.is_none() || cfg.gateway_api_key.is_none())
+    {
+        warnings.push("已启用 gRPC 网关但缺少地址或 API 密钥，网关不会启动".to_string());
+    }
+    if cfg.allowed_forum_channels.is_empty() {
+        warnings.push("未配置自动发布论坛频道，自动发布不会生效".to_string());
+    }
+    if let Some(name) = &cfg.default_guild_license_name
+        && !system_license_names.contains(name)
+    {
+        warnings.push(format!("社区默认协议「{name}」不在系统协议中"));
+    }
+    if let Some(url) = &cfg.community_rules_url
+        && !matches!(Url::parse(url), Ok(u) if matches!(u.scheme(), "http" | "https"))
+    {
+        warnings.push(format!("社区规则链接无效: {url}"));
+    }
+    if let Some(template) = &cfg.notification_template
+        && let Err(e) = serde_json::from_str::<serde_json::Value>(template)
+    {
+        warnings.push(format!("通知载荷模板不是有效的 JSON: {e}"));
+    }
+
+    let (level, issues) = if !critical.is_empty() {
+        critical.extend(warnings);
+        (CheckLevel::Critical, critical)
+    } else if !warnings.is_empty() {
+        (CheckLevel::Warning, warnings)
+    } else {
+        return CheckResult::new(NAME, CheckLevel::Ok, "配置有效");
+    };
+    CheckResult::new(NAME, level, issues.join("; "))
+}
+
+/// 计算机器人在论坛频道中的权限
+pub async fn forum_permissions(http: &Http, forum_id: ChannelId) -> Result<Permissions, BotError> {
+    let channel = forum_id
+        .to_channel(http)
+        .await?
+        .guild()
+        .ok_or(BotError::GenericError {
+            message: "不是服务器频道".to_string(),
+            source: None,
+        })?;
+    let guild = channel.guild_id.to_partial_guild(http).await?;
+    let member = channel.guild_id.current_user_member(http).await?;
+    Ok(guild.user_permissions_in(&channel, &member))
+}
+
+/// 获取机器人在论坛频道中的权限，短时间内重复查询时使用缓存结果
+///
+/// 只缓存成功获取的权限，请求失败时下次重新查询。
+pub async fn cached_forum_permissions(
+    http: &Http,
+    forum_id: ChannelId,
+) -> Result<Permissions, BotError> {
+    let cache = FORUM_PERMISSIONS_CACHE.get_or_init(|| {
+        Cache::builder()
+            .time_to_live(FORUM_PERMISSIONS_TTL)
+            .max_capacity(1_000)
+            .build()
+    });
+    if let Some(permissions) = cache.get(&forum_id).await {
+        return Ok(permissions);
+    }
+    let permissions = forum_permissions(http, forum_id).await?;
+    cache.insert(forum_id, permissions).await;
+    Ok(permissions)
+}
+
+/// 检查机器人在论坛频道中是否拥有发布协议所需的权限
+pub fn check_forum_permissions(channel_id: ChannelId, permissions: Permissions) -> CheckResult {
+    const NAME: &str = "论坛权限";
+    let missing = REQUIRED_FORUM_PERMISSIONS - permissions;
+    if missing.is_empty() {
+        CheckResult::new(NAME, CheckLevel::Ok, format!("<#{channel_id}> 权限完整"))
+    } else {
+        CheckResult::new(
+            NAME,
+            CheckLevel::Warning,
+            format!("<#{channel_id}> 缺少权限: {missing}"),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+
+    use super::*;
+
+    fn test_cfg() -> BotCfg {
+        toml::from_str(
+            r#"
+            time_offset = 0
+            token = "token"
+            admin_role_ids = []
+            backup_enabled = false
+            endpoint = "http://localhost"
+            extra_admins_ids = []
+            allowed_forum_channels = [1]
+            "#,
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_check_migrations() {
+        assert_eq!(check_migrations(&[]).level, CheckLevel::Ok);
+        let result = check_migrations(&["m1".to_string(), "m2".to_string()]);
+        assert_eq!(result.level, CheckLevel::Critical);
+        assert!(result.message.contains("m1, m2"));
+    }
+
+    #[test]
+    fn test_check_system_licenses() {
+        assert_eq!(check_system_licenses(0).level, CheckLevel::Critical);
+        assert_eq!(check_system_licenses(3).level, CheckLevel::Ok);
+    }
+
+    #[test]
+    fn test_check_config() {
+        let names = vec!["MIT".to_string()];
+        let mut cfg = test_cfg();
+        assert_eq!(check_config(&cfg, &names).level, CheckLevel::Ok);
+
+        cfg.default_guild_license_name = Some("不存在".to_string());
+        cfg.allowed_forum_channels = HashSet::new();
+        let result = check_config(&cfg, &names);
+        assert_eq!(result.level, CheckLevel::Warning);
+        assert!(result.message.contains("不存在"));
+        assert!(result.message.contains("论坛频道"));
+
+        cfg.community_rules_url = Some("not a url".to_string());
+        assert!(check_config(&cfg, &names).message.contains("not a url"));
+
+        cfg.notification_template = Some("{\"a\": ".to_string());
+        assert!(check_config(&cfg, &names).message.contains("通知载荷模板"));
+
+        cfg.token = String::new();
+        assert_eq!(check_config(&cfg, &names).level, CheckLevel::Critical);
+    }
+
+    #[test]
+    fn test_check_forum_permissions() {
+        let channel = ChannelId::new(1);
+        assert_eq!(
+            check_forum_permissions(
+                channel,
+                REQUIRED_FORUM_PERMISSIONS | Permissions::ADD_REACTIONS
+            )
+            .level,
+            CheckLevel::Ok
+        );
+
+        let result = check_forum_permissions(
+            channel,
+            REQUIRED_FORUM_PERMISSIONS - Permissions::MANAGE_MESSAGES,
+        );
+        assert_eq!(result.level, CheckLevel::Warning);
+        assert!(result.message.contains("Manage Messages"));
+    }
+
+    #[test]
+    fn test_report_level() {
+        let mut report = SelfCheckReport::default();
+        assert_eq!(report.level(), CheckLevel::Ok);
+        report.push(check_system_licenses(1));
+        report.push(check_forum_permissions(
+            ChannelId::new(1),
+            Permissions::empty(),
+        ));
+        assert_eq!(report.level(), CheckLevel::Warning);
+        report.push(check_migrations(&["m1".to_string()]));
+        assert_eq!(report.level(), CheckLevel::Critical);
+    }
+}
@@ -0,0 +1,196 @@
+use crate::types::license::SystemLicense;
+
+/// 常见 SPDX 协议到本机器人权限模型的映射
+///
+/// | SPDX | 二次传播 | 二次修改 | 商业使用 | 署名 | 附加说明 |
+/// |------|---------|---------|---------|------|---------|
+/// | MIT / ISC / BSD-2-Clause / BSD-3-Clause / Apache-2.0 | ✅ | ✅ | ✅ | ✅ | — |
+/// | GPL-2.0 / GPL-3.0 / LGPL-3.0 / AGPL-3.0 / MPL-2.0 | ✅ | ✅ | ✅ | ✅ | 衍生作品须以相同协议发布 |
+/// | CC0-1.0 / Unlicense | ✅ | ✅ | ✅ | ❌ | — |
+/// | CC-BY-4.0 | ✅ | ✅ | ✅ | ✅ | — |
+/// | CC-BY-SA-4.0 | ✅ | ✅ | ✅ | ✅ | 相同方式共享 |
+/// | CC-BY-NC-4.0 | ✅ | ✅ | ❌ | ✅ | — |
+/// | CC-BY-NC-SA-4.0 | ✅ | ✅ | ❌ | ✅ | 相同方式共享 |
+/// | CC-BY-ND-4.0 | ✅ | ❌ | ✅ | ✅ | — |
+/// | CC-BY-NC-ND-4.0 | ✅ | ❌ | ❌ | ✅ | — |
+///
+/// GPL 系列同时接受 `-only` / `-or-later` 写法和已弃用的简写（如 `GPL-3.0`），简写按 `-only` 处理。
+///
+/// SPDX 协议不涉及本服务器的备份权限，导入的协议一律不允许备份，需要时由管理员手动调整。
+struct SpdxMapping {
+    ids: &'static [&'static str],
+    allow_redistribution: bool,
+    allow_modification: bool,
+    allow_commercial: bool,
+    require_attribution: bool,
+    restrictions_note: Option<&'static str>,
+}
+
+/// 已弃用的 SPDX 简写及其规范标识符
+const DEPRECATED_IDS: &[(&str, &str)] = &[
+    ("GPL-2.0", "GPL-2.0-only"),
+    ("GPL-3.0", "GPL-3.0-only"),
+    ("LGPL-3.0", "LGPL-3.0-only"),
+    ("AGPL-3.0", "AGPL-3.0-only"),
+];
+
+const COPYLEFT_NOTE: &str = "衍生作品须以相同协议发布";
+const SHARE_ALIKE_NOTE: &str = "修改后的作品须以相同协议共享";
+
+const SPDX_MAPPINGS: &[SpdxMapping] = &[
+    SpdxMapping {
+        ids: &["MIT", "ISC", "BSD-2-Clause", "BSD-3-Clause", "Apache-2.0"],
+        allow_redistribution: true,
+        allow_modification: true,
+        allow_commercial: true,
+        require_attribution: true,
+        restrictions_note: None,
+    },
+    SpdxMapping {
+        ids: &[
+            "GPL-2.0-only",
+            "GPL-2.0-or-later",
+            "GPL-3.0-only",
+            "GPL-3.0-or-later",
+            "LGPL-3.0-only",
+            "LGPL-3.0-or-later",
+            "AGPL-3.0-only",
+            "AGPL-3.0-or-later",
+            "MPL-2.0",
+        ],
+        allow_redistribution: true,
+        allow_modification: true,
+        allow_commercial: true,
+        require_attribution: true,
+        restrictions_note: Some(COPYLEFT_NOTE),
+    },
+    SpdxMapping {
+        ids: &["CC0-1.0", "Unlicense"],
+        allow_redistribution: true,
+        allow_modification: true,
+        allow_commercial: true,
+        require_attribution: false,
+        restrictions_note: None,
+    },
+    SpdxMapping {
+        ids: &["CC-BY-4.0"],
+        allow_redistribution: true,
+        allow_modification: true,
+        allow_commercial: true,
+        require_attribution: true,
+        restrictions_note: None,
+    },
+    SpdxMapping {
+        ids: &["CC-BY-SA-4.0"],
+        allow_redistribution: true,
+        allow_modification: true,
+        allow_commercial: true,
+        require_attribution: true,
+        restrictions_note: Some(SHARE_ALIKE_NOTE),
+    },
+    SpdxMapping {
+        ids: &["CC-BY-NC-4.0"],
+        allow_redistribution: true,
+        allow_modification: true,
+        allow_commercial: false,
+        require_attribution: true,
+        restrictions_note: None,
+    },
+    SpdxMapping {
+        ids: &["CC-BY-NC-SA-4.0"],
+        allow_redistribution: true,
+        allow_modification: true,
+        allow_commercial: false,
+        require_attribution: true,
+        restrictions_note: Some(SHARE_ALIKE_NOTE),
+    },
+    SpdxMapping {
+        ids: &["CC-BY-ND-4.0"],
+        allow_redistribution: true,
+        allow_modification: false,
+        allow_commercial: true,
+        require_attribution: true,
+        restrictions_note: None,
+    },
+    SpdxMapping {
+        ids: &["CC-BY-NC-ND-4.0"],
+        allow_redistribution: true,
+        allow_modification: false,
+        allow_commercial: false,
+        require_attribution: true,
+        restrictions_note: None,
+    },
+];
+
+/// 将 SPDX 标识符转换为系统协议，标识符不区分大小写，未知标识符返回 `None`
+///
+/// 协议名使用 SPDX 的规范写法
+pub fn spdx_to_system_license(identifier: &str) -> Option<SystemLicense> {
+    let identifier = identifier.trim();
+    let identifier = DEPRECATED_IDS
+        .iter()
+        .find(|(deprecated, _)| deprecated.eq_ignore_ascii_case(identifier))
+        .map_or(identifier, |(_, canonical)| canonical);
+    SPDX_MAPPINGS.iter().find_map(|mapping| {
+        let id = mapping
+            .ids
+            .iter()
+            .find(|id| id.eq_ignore_ascii_case(identifier))?;
+        Some(SystemLicense {
+            license_name: id.to_string(),
+            allow_redistribution: mapping.allow_redistribution,
+            allow_modification: mapping.allow_modification,
+            restrictions_note: mapping.restrictions_note.map(str::to_string),
+            allow_backup: false,
+            allow_commercial: mapping.allow_commercial,
+            require_attribution: mapping.require_attribution,
+            color: None,
+            allowed_publisher_ids: Vec::new(),
+            allowed_publisher_roles: Vec::new(),
+            full_text_url: None,
+            full_text_file: None,
+            category: None,
+        })
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_spdx_mapping() {
+        let mit = spdx_to_system_license("mit").unwrap();
+        assert_eq!(mit.license_name, "MIT");
+        assert!(mit.allow_redistribution && mit.allow_modification && mit.allow_commercial);
+        assert!(mit.require_attribution);
+        assert!(!mit.allow_backup);
+
+        let nc_nd = spdx_to_system_license(" CC-BY-NC-ND-4.0 ").unwrap();
+        assert!(nc_nd.allow_redistribution);
+        assert!(!nc_nd.allow_modification);
+        assert!(!nc_nd.allow_commercial);
+
+        let gpl = spdx_to_system_license("GPL-3.0-only").unwrap();
+        assert_eq!(gpl.restrictions_note.as_deref(), Some(COPYLEFT_NOTE));
+
+        // 已弃用的简写映射到 -only 的规范写法
+        let gpl2 = spdx_to_system_license("gpl-2.0").unwrap();
+        assert_eq!(gpl2.license_name, "GPL-2.0-only");
+        assert_eq!(
+            spdx_to_system_license("AGPL-3.0").unwrap().license_name,
+            "AGPL-3.0-only"
+        );
+        assert_eq!(
+            spdx_to_system_license("GPL-3.0-or-later")
+                .unwrap()
+                .license_name,
+            "GPL-3.0-or-later"
+        );
+
+        let cc0 = spdx_to_system_license("CC0-1.0").unwrap();
+        assert!(!cc0.require_attribution);
+
+        assert!(spdx_to_system_license("Not-A-License").is_none());
+    }
+}
@@ -5,7 +5,7 @@ use serenity::all::{ChannelId, Http, MessageId};
 use tokio::{sync::RwLock, task::JoinHandle, time};
 use tracing::{error, info, warn};
 
-use crate::{config::BotCfg, database::BotDatabase};
+use crate::{config::BotCfg, database::BotDatabase, services::gateway::GatewayStatus};
 
 /// 全局的状态监控任务 handle
 static STATUS_MONITOR_HANDLE: tokio::sync::OnceCell<RwLock<Option<JoinHandle<()>>>> =
@@ -19,6 +19,7 @@ pub async fn start_status_monitor(
     db: Arc<BotDatabase>,
     cfg: Arc<ArcSwap<BotCfg>>,
     cache: Arc<serenity::cache::Cache>,
+    gateway_status: Arc<ArcSwap<GatewayStatus>>,
 ) {
     // 检查配置中是否有状态消息信息
     let config = cfg.load();
@@ -46,6 +47,7 @@ pub async fn start_status_monitor(
             db,
             cfg,
             cache,
+            gateway_status,
             channel_id,
             message_id,
             update_interval_secs,
@@ -68,6 +70,7 @@ pub async fn restart_status_monitor(
     db: Arc<BotDatabase>,
     cfg: Arc<ArcSwap<BotCfg>>,
     cache: Arc<serenity::cache::Cache>,
+    gateway_status: Arc<ArcSwap<GatewayStatus>>,
 ) {
     // 停止旧任务
     if let Some(handle_lock) = STATUS_MONITOR_HANDLE.get() {
@@ -79,15 +82,17 @@ pub async fn restart_status_monitor(
     }
 
     // 启动新任务
-    start_status_monitor(http, db, cfg, cache).await;
+    start_status_monitor(http, db, cfg, cache, gateway_status).await;
 }
 
 /// 状态监控后台任务
+#[allow(clippy::too_many_arguments)]
 async fn status_monitor_task(
     http: Arc<Http>,
     db: Arc<BotDatabase>,
     _cfg: Arc<ArcSwap<BotCfg>>,
     cache: Arc<serenity::cache::Cache>,
+    gateway_status: Arc<ArcSwap<GatewayStatus>>,
     channel_id: ChannelId,
     message_id: MessageId,
     update_interval_secs: u64,
@@ -96,7 +101,14 @@ async fn status_monitor_task(
         // 执行状态更新
         let latency = Duration::from_millis(100);
 
-        match crate::commands::system::create_system_info_embed(&db, &cache, latency).await {
+        match crate::commands::system::create_system_info_embed(
+            &db,
+            &cache,
+            latency,
+            &gateway_status.load(),
+        )
+        .await
+        {
             Ok(embed) => {
                 if let Err(e) = http
                     .edit_message(
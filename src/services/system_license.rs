@@ -1,47 +1,399 @@
 use std::{
+    collections::HashMap,
     path::{Path, PathBuf},
-    sync::Arc,
+    sync::{
+        Arc, Mutex,
+        atomic::{AtomicU64, Ordering},
+    },
+    time::{Duration, SystemTime},
 };
 
 use arc_swap::ArcSwap;
+use tokio::time;
+use tracing::{error, info};
 
-use crate::{error::BotError, types::license::SystemLicense};
+use crate::{config::BotCfg, error::BotError, types::license::SystemLicense};
+
+/// 自动重载时检查协议文件修改时间的间隔
+const AUTO_RELOAD_INTERVAL: Duration = Duration::from_secs(10);
+/// 每个协议必须提供的权限字段
+const REQUIRED_BOOL_FIELDS: &[&str] =
+    &["allow_redistribution", "allow_modification", "allow_backup"];
+/// 可省略但提供时必须为布尔值的字段
+const OPTIONAL_BOOL_FIELDS: &[&str] = &["allow_commercial", "require_attribution"];
+/// 错误消息中最多列出的问题数，避免超出 Discord 消息长度限制
+const MAX_REPORTED_ERRORS: usize = 10;
+
+/// 校验单个协议条目，返回发现的所有问题
+fn validate_entry(entry: &serde_json::Value) -> Vec<String> {
+    let Some(object) = entry.as_object() else {
+        return vec!["应为对象".to_string()];
+    };
+
+    let mut problems = Vec::new();
+    match object.get("license_name") {
+        Some(serde_json::Value::String(name)) if !name.trim().is_empty() => {}
+        Some(serde_json::Value::String(_)) => problems.push("license_name 不能为空".to_string()),
+        Some(_) => problems.push("license_name 必须为字符串".to_string()),
+        None => problems.push("缺少 license_name".to_string()),
+    }
+    for field in REQUIRED_BOOL_FIELDS {
+        match object.get(*field) {
+            Some(serde_json::Value::Bool(_)) => {}
+            Some(_) => problems.push(format!("{field} 必须为布尔值")),
+            None => problems.push(format!("缺少 {field}（布尔值）")),
+        }
+    }
+    for field in OPTIONAL_BOOL_FIELDS {
+        if object.get(*field).is_some_and(|v| !v.is_boolean()) {
+            problems.push(format!("{field} 必须为布尔值"));
+        }
+    }
+    if object
+        .get("restrictions_note")
+        .is_some_and(|v| !v.is_null() && !v.is_string())
+    {
+        problems.push("restrictions_note 必须为字符串或 null".to_string());
+    }
+    problems
+}
+
+/// 解析并校验协议文件内容
+///
+/// 收集所有条目的问题后一并返回，每个问题都标明条目序号（从 0 开始）和协议名
+fn parse_licenses(content: &str) -> Result<Vec<SystemLicense>, BotError> {
+    let invalid = |message: String| BotError::ValidationError {
+        message,
+        loc: snafu::location!(),
+    };
+    let value: serde_json::Value = serde_json::from_str(content)
+        .map_err(|e| invalid(format!("协议文件不是有效的 JSON: {e}")))?;
+    let serde_json::Value::Array(entries) = value else {
+        return Err(invalid("协议文件的顶层应为数组".to_string()));
+    };
+
+    let mut licenses = Vec::with_capacity(entries.len());
+    let mut errors = Vec::new();
+    for (index, entry) in entries.into_iter().enumerate() {
+        let label = match entry.get("license_name").and_then(|v| v.as_str()) {
+            Some(name) if !name.trim().is_empty() => format!("#{index} ({name})"),
+            _ => format!("#{index}"),
+        };
+        let problems = validate_entry(&entry);
+        if !problems.is_empty() {
+            errors.push(format!("{label}: {}", problems.join("；")));
+            continue;
+        }
+        // 其余字段（如发布名单、完整文本）交给 serde 检查
+        match serde_json::from_value(entry) {
+            Ok(license) => licenses.push(license),
+            Err(e) => errors.push(format!("{label}: {e}")),
+        }
+    }
+
+    if errors.is_empty() {
+        return Ok(licenses);
+    }
+    let mut lines = vec![format!("协议文件中有 {} 个协议存在问题：", errors.len())];
+    lines.extend(errors.iter().take(MAX_REPORTED_ERRORS).cloned());
+    if errors.len() > MAX_REPORTED_ERRORS {
+        lines.push(format!(
+            "……另有 {} 个问题未列出",
+            errors.len() - MAX_REPORTED_ERRORS
+        ));
+    }
+    Err(invalid(lines.join("\n")))
+}
+
+/// 已加载的系统协议及按名称建立的索引，两者随重载一起替换
+#[derive(Debug)]
+struct LicenseCatalog {
+    licenses: Vec<SystemLicense>,
+    by_name: HashMap<String, usize>,
+}
+
+impl From<Vec<SystemLicense>> for LicenseCatalog {
+    fn from(licenses: Vec<SystemLicense>) -> Self {
+        let mut by_name = HashMap::with_capacity(licenses.len());
+        for (index, license) in licenses.iter().enumerate() {
+            // 同名协议以文件中第一个为准
+            by_name.entry(license.license_name.clone()).or_insert(index);
+        }
+        Self { licenses, by_name }
+    }
+}
 
 #[derive(Debug)]
 pub struct SystemLicenseCache {
-    licenses: ArcSwap<Vec<SystemLicense>>,
+    catalog: ArcSwap<LicenseCatalog>,
     path: PathBuf,
+    /// 每次成功加载后递增，用于判断协议列表是否变化
+    version: AtomicU64,
+    /// 最近一次加载时协议文件的修改时间
+    modified: Mutex<Option<SystemTime>>,
+}
+
+/// 获取文件的修改时间，无法获取时返回 `None`
+async fn file_modified(path: &Path) -> Option<SystemTime> {
+    tokio::fs::metadata(path).await.ok()?.modified().ok()
 }
 
 impl SystemLicenseCache {
     pub async fn new(path: &Path) -> Result<Self, BotError> {
+        let modified = file_modified(path).await;
         let content = tokio::fs::read_to_string(path).await?;
-        let licenses: Vec<SystemLicense> = serde_json::from_str(&content)?;
+        let licenses = parse_licenses(&content)?;
 
         Ok(Self {
-            licenses: ArcSwap::from_pointee(licenses),
+            catalog: ArcSwap::from_pointee(licenses.into()),
             path: path.to_path_buf(),
+            version: AtomicU64::new(0),
+            modified: Mutex::new(modified),
         })
     }
 
+    /// 当前协议列表的版本号，初始为 0，每次重载成功后加 1
+    pub fn version(&self) -> u64 {
+        self.version.load(Ordering::Acquire)
+    }
+
+    /// 协议文件所在目录，用于解析协议中的相对文件路径
+    pub fn base_dir(&self) -> &Path {
+        self.path.parent().unwrap_or(Path::new("."))
+    }
+
+    /// 获取全部系统协议，用于构建选择菜单等需要完整列表的场景
     pub async fn get_all(&self) -> Vec<SystemLicense> {
-        Vec::clone(self.licenses.load().as_ref())
+        self.catalog.load().licenses.clone()
     }
 
+    /// 按名称查找系统协议
     pub async fn get_by_name(&self, name: &str) -> Option<SystemLicense> {
-        self.licenses
-            .load()
-            .iter()
-            .find(|l| l.license_name == name)
-            .cloned()
+        let catalog = self.catalog.load();
+        catalog
+            .by_name
+            .get(name)
+            .map(|&index| catalog.licenses[index].clone())
+    }
+
+    /// 将协议追加到协议文件并重新加载，跳过已存在的同名协议
+    ///
+    /// 返回实际新增的协议名
+    pub async fn import(&self, licenses: Vec<SystemLicense>) -> Result<Vec<String>, BotError> {
+        let content = tokio::fs::read_to_string(&self.path).await?;
+        let mut current = parse_licenses(&content)?;
+
+        let mut added = Vec::new();
+        for license in licenses {
+            if current
+                .iter()
+                .any(|l| l.license_name == license.license_name)
+            {
+                continue;
+            }
+            added.push(license.license_name.clone());
+            current.push(license);
+        }
+
+        if !added.is_empty() {
+            tokio::fs::write(&self.path, serde_json::to_string_pretty(&current)?).await?;
+        }
+        self.reload().await?;
+
+        Ok(added)
     }
 
+    /// 从文件重新加载协议，解析失败时保留当前的协议列表
     pub async fn reload(&self) -> Result<(), BotError> {
+        let modified = file_modified(&self.path).await;
         let content = tokio::fs::read_to_string(&self.path).await?;
-        let new_licenses: Vec<SystemLicense> = serde_json::from_str(&content)?;
+        let new_licenses = parse_licenses(&content)?;
 
-        self.licenses.store(Arc::new(new_licenses));
+        self.catalog.store(Arc::new(new_licenses.into()));
+        *self.modified.lock().unwrap() = modified;
+        self.version.fetch_add(1, Ordering::AcqRel);
 
         Ok(())
     }
+
+    /// 协议文件的修改时间变化时重新加载，返回是否已重载
+    ///
+    /// 加载失败时同样记录新的修改时间，同一次修改只报告一次错误
+    pub async fn reload_if_changed(&self) -> Result<bool, BotError> {
+        let modified = file_modified(&self.path).await;
+        {
+            let mut last = self.modified.lock().unwrap();
+            if *last == modified {
+                return Ok(false);
+            }
+            *last = modified;
+        }
+        self.reload().await?;
+        Ok(true)
+    }
+}
+
+/// 启动系统协议文件的自动重载任务
+///
+/// 定期检查协议文件的修改时间，文件变化后自动重载。未启用 `auto_reload_system_licenses` 时不启动。
+pub fn start_auto_reload(cache: Arc<SystemLicenseCache>, cfg: Arc<ArcSwap<BotCfg>>) {
+    if !cfg.load().auto_reload_system_licenses {
+        return;
+    }
+
+    info!(
+        "启动系统协议自动重载任务，检查间隔: {} 秒",
+        AUTO_RELOAD_INTERVAL.as_secs()
+    );
+    tokio::spawn(async move {
+        let mut interval = time::interval(AUTO_RELOAD_INTERVAL);
+        loop {
+            interval.tick().await;
+            match cache.reload_if_changed().await {
+                Ok(true) => info!("系统协议文件已变化，已自动重载（版本 {}）", cache.version()),
+                Ok(false) => {}
+                Err(e) => error!("自动重载系统协议失败，继续使用之前的协议: {}", e),
+            }
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn licenses_json(names: &[&str]) -> String {
+        let licenses: Vec<_> = names
+            .iter()
+            .map(|name| {
+                serde_json::json!({
+                    "license_name": name,
+                    "allow_redistribution": true,
+                    "allow_modification": false,
+                    "restrictions_note": null,
+                    "allow_backup": false,
+                })
+            })
+            .collect();
+        serde_json::to_string(&licenses).unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_get_by_name_after_reload() {
+        let path = std::env::temp_dir().join(format!(
+            "dc-bot-system-licenses-{}.json",
+            std::process::id()
+        ));
+        tokio::fs::write(&path, licenses_json(&["MIT", "CC-BY-4.0"]))
+            .await
+            .unwrap();
+
+        let cache = SystemLicenseCache::new(&path).await.unwrap();
+        assert_eq!(cache.get_by_name("MIT").await.unwrap().license_name, "MIT");
+        assert!(cache.get_by_name("GPL-3.0").await.is_none());
+
+        // 重载后索引随协议列表一起更新
+        tokio::fs::write(&path, licenses_json(&["CC-BY-4.0", "GPL-3.0"]))
+            .await
+            .unwrap();
+        cache.reload().await.unwrap();
+        assert!(cache.get_by_name("MIT").await.is_none());
+        assert_eq!(
+            cache.get_by_name("GPL-3.0").await.unwrap().license_name,
+            "GPL-3.0"
+        );
+        assert_eq!(cache.get_all().await.len(), 2);
+
+        tokio::fs::remove_file(&path).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_reload_if_changed_keeps_previous_on_error() {
+        let path = std::env::temp_dir().join(format!(
+            "dc-bot-system-licenses-watch-{}.json",
+            std::process::id()
+        ));
+        let touch = |secs: u64| {
+            std::fs::File::options()
+                .write(true)
+                .open(&path)
+                .unwrap()
+                .set_modified(SystemTime::UNIX_EPOCH + Duration::from_secs(secs))
+                .unwrap();
+        };
+        tokio::fs::write(&path, licenses_json(&["MIT"]))
+            .await
+            .unwrap();
+        touch(1);
+
+        let cache = SystemLicenseCache::new(&path).await.unwrap();
+        assert_eq!(cache.version(), 0);
+        assert!(!cache.reload_if_changed().await.unwrap());
+
+        // 文件变化后重载并递增版本号
+        tokio::fs::write(&path, licenses_json(&["MIT", "GPL-3.0"]))
+            .await
+            .unwrap();
+        touch(2);
+        assert!(cache.reload_if_changed().await.unwrap());
+        assert_eq!(cache.version(), 1);
+        assert!(cache.get_by_name("GPL-3.0").await.is_some());
+
+        // 解析失败时保留之前的协议，同一次修改不会重复报错
+        tokio::fs::write(&path, "not json").await.unwrap();
+        touch(3);
+        assert!(cache.reload_if_changed().await.is_err());
+        assert!(!cache.reload_if_changed().await.unwrap());
+        assert_eq!(cache.version(), 1);
+        assert_eq!(cache.get_all().await.len(), 2);
+
+        tokio::fs::remove_file(&path).await.unwrap();
+    }
+
+    #[test]
+    fn test_parse_licenses_reports_every_broken_entry() {
+        let content = serde_json::json!([
+            {
+                "license_name": "MIT",
+                "allow_redistribution": true,
+                "allow_modification": true,
+                "restrictions_note": null,
+                "allow_backup": true,
+            },
+            {
+                "license_name": "",
+                "allow_redistribution": "yes",
+                "allow_modification": true,
+            },
+            {
+                "license_name": "CC-BY-4.0",
+                "allow_redistribution": true,
+                "allow_modification": true,
+                "allow_backup": false,
+                "color": "red",
+            },
+            "not an object",
+        ])
+        .to_string();
+
+        let err = parse_licenses(&content).unwrap_err();
+        let BotError::ValidationError { message, .. } = err else {
+            panic!("expected validation error, got {err:?}");
+        };
+        assert!(message.contains("3 个协议存在问题"));
+        assert!(message.contains("#1: license_name 不能为空"));
+        assert!(message.contains("allow_redistribution 必须为布尔值"));
+        assert!(message.contains("缺少 allow_backup（布尔值）"));
+        assert!(message.contains("#2 (CC-BY-4.0):"));
+        assert!(message.contains("#3: 应为对象"));
+        assert!(!message.contains("#0"));
+
+        // 有效的文件正常解析，缺省的可选字段使用默认值
+        let valid = licenses_json(&["MIT", "GPL-3.0"]);
+        assert_eq!(parse_licenses(&valid).unwrap().len(), 2);
+        assert!(matches!(
+            parse_licenses("{}"),
+            Err(BotError::ValidationError { .. })
+        ));
+    }
 }
@@ -1,28 +1,39 @@
+use std::collections::HashMap;
+
+use chrono::{DateTime, Utc};
 use entities::user_settings::*;
-use sea_orm::{Set, prelude::*};
+use sea_orm::{QueryOrder, QuerySelect, Set, TransactionTrait, prelude::*};
 use serenity::all::*;
 
-use crate::{database::BotDatabase, error::BotError, types::license::DefaultLicenseIdentifier};
+use crate::{
+    database::BotDatabase, error::BotError, services::license::LicenseService,
+    types::license::DefaultLicenseIdentifier,
+};
 
 pub type UserSettings = Model;
 
-pub struct UserSettingsService<'a>(&'a BotDatabase);
+/// 用户设置服务，可在数据库连接或事务上使用
+pub struct UserSettingsService<'a, C = DatabaseConnection>(&'a C);
 
 impl BotDatabase {
     /// Get a reference to the user settings service
     pub fn user_settings(&self) -> UserSettingsService<'_> {
-        UserSettingsService(self)
+        UserSettingsService(self.inner())
     }
 }
 
-impl UserSettingsService<'_> {
+impl<'a, C: ConnectionTrait + TransactionTrait> UserSettingsService<'a, C> {
+    pub fn new(conn: &'a C) -> Self {
+        UserSettingsService(conn)
+    }
+
     /// Get user settings, create default if not exists
     pub async fn get_or_create(&self, user_id: UserId) -> Result<UserSettings, BotError> {
         let user_id_i64 = user_id.get() as i64;
 
         if let Some(settings) = Entity::find()
             .filter(Column::UserId.eq(user_id_i64))
-            .one(self.0.inner())
+            .one(self.0)
             .await?
         {
             Ok(settings)
@@ -37,7 +48,7 @@ impl UserSettingsService<'_> {
                 default_system_license_backup: Set(None),
             };
 
-            let created = default_settings.insert(self.0.inner()).await?;
+            let created = default_settings.insert(self.0).await?;
             Ok(created)
         }
     }
@@ -46,7 +57,7 @@ impl UserSettingsService<'_> {
     pub async fn get(&self, user_id: UserId) -> Result<Option<UserSettings>, BotError> {
         Ok(Entity::find()
             .filter(Column::UserId.eq(user_id.get() as i64))
-            .one(self.0.inner())
+            .one(self.0)
             .await?)
     }
 
@@ -60,7 +71,7 @@ impl UserSettingsService<'_> {
         let mut active_settings: ActiveModel = settings.into();
         active_settings.auto_publish_enabled = Set(enabled);
 
-        let updated = active_settings.update(self.0.inner()).await?;
+        let updated = active_settings.update(self.0).await?;
         Ok(updated)
     }
 
@@ -92,7 +103,7 @@ impl UserSettingsService<'_> {
             }
         }
 
-        let updated = active_settings.update(self.0.inner()).await?;
+        let updated = active_settings.update(self.0).await?;
         Ok(updated)
     }
 
@@ -104,7 +115,7 @@ impl UserSettingsService<'_> {
         let mut active_settings: ActiveModel = settings.into();
         active_settings.auto_publish_enabled = Set(new_enabled);
 
-        let updated = active_settings.update(self.0.inner()).await?;
+        let updated = active_settings.update(self.0).await?;
         Ok(updated)
     }
 
@@ -119,7 +130,7 @@ impl UserSettingsService<'_> {
         let mut active_settings: ActiveModel = settings.into();
         active_settings.skip_auto_publish_confirmation = Set(new_skip);
 
-        let updated = active_settings.update(self.0.inner()).await?;
+        let updated = active_settings.update(self.0).await?;
         Ok(updated)
     }
 
@@ -130,12 +141,26 @@ impl UserSettingsService<'_> {
     }
 
     /// Get default license for user
+    ///
+    /// A user license always takes precedence over a system license with the same name.
     pub async fn get_default_license(
         &self,
         user_id: UserId,
     ) -> Result<Option<DefaultLicenseIdentifier>, BotError> {
         let settings = self.get_or_create(user_id).await?;
 
+        if let (Some(id), Some(name)) = (
+            settings.default_user_license_id,
+            settings.default_system_license_name.as_ref(),
+        ) {
+            tracing::warn!(
+                "User {} has both user license {} and system license {} as default, using the user license",
+                user_id,
+                id,
+                name
+            );
+        }
+
         if let Some(user_license_id) = settings.default_user_license_id {
             Ok(Some(DefaultLicenseIdentifier::User(user_license_id)))
         } else if let Some(system_license_name) = settings.default_system_license_name {
@@ -150,11 +175,35 @@ impl UserSettingsService<'_> {
         self.set_default_license(user_id, None, None).await
     }
 
+    /// Clear the default user license if it no longer exists
+    ///
+    /// Not every deletion path clears the default (e.g. gRPC deletes), so callers
+    /// showing the default can use this to heal stale references. Returns whether
+    /// the default was reset.
+    pub async fn clear_stale_default_license(&self, user_id: UserId) -> Result<bool, BotError> {
+        let Some(settings) = self.get(user_id).await? else {
+            return Ok(false);
+        };
+        let Some(license_id) = settings.default_user_license_id else {
+            return Ok(false);
+        };
+        if LicenseService::new(self.0)
+            .get_license(license_id, user_id)
+            .await?
+            .is_some()
+        {
+            return Ok(false);
+        }
+
+        self.clear_default_license(user_id).await?;
+        Ok(true)
+    }
+
     /// Delete user settings
     pub async fn delete(&self, user_id: UserId) -> Result<bool, BotError> {
         let result = Entity::delete_many()
             .filter(Column::UserId.eq(user_id.get() as i64))
-            .exec(self.0.inner())
+            .exec(self.0)
             .await?;
 
         Ok(result.rows_affected > 0)
@@ -162,25 +211,97 @@ impl UserSettingsService<'_> {
 
     /// Get all users with auto publish enabled
     pub async fn get_auto_publish_users(&self) -> Result<Vec<UserId>, BotError> {
-        let settings = Entity::find()
-            .filter(Column::AutoPublishEnabled.eq(true))
-            .all(self.0.inner())
-            .await?;
-
-        Ok(settings
+        Ok(self
+            .get_auto_publish_settings()
+            .await?
             .into_iter()
             .map(|s| UserId::new(s.user_id as u64))
             .collect())
     }
 
+    /// Get settings of all users with auto publish enabled
+    pub async fn get_auto_publish_settings(&self) -> Result<Vec<UserSettings>, BotError> {
+        Ok(Entity::find()
+            .filter(Column::AutoPublishEnabled.eq(true))
+            .all(self.0)
+            .await?)
+    }
+
     /// Get count of users with auto publish enabled
     pub async fn get_auto_publish_count(&self) -> Result<u64, BotError> {
         Ok(Entity::find()
             .filter(Column::AutoPublishEnabled.eq(true))
-            .count(self.0.inner())
+            .count(self.0)
             .await?)
     }
 
+    /// Get users with auto publish enabled who have not published since `since`
+    ///
+    /// A user is active if any of their published posts was recorded or updated at or after
+    /// `since`; users who never published are considered inactive.
+    pub async fn get_inactive_auto_publish_users(
+        &self,
+        since: DateTime<Utc>,
+    ) -> Result<Vec<UserId>, BotError> {
+        use entities::published_posts;
+        use sea_orm::sea_query::Query;
+
+        let active_users = Query::select()
+            .column(published_posts::Column::UserId)
+            .from(published_posts::Entity)
+            .and_where(published_posts::Column::UpdatedAt.gte(since))
+            .to_owned();
+        let user_ids: Vec<i64> = Entity::find()
+            .select_only()
+            .column(Column::UserId)
+            .filter(Column::AutoPublishEnabled.eq(true))
+            .filter(Column::UserId.not_in_subquery(active_users))
+            .order_by_asc(Column::UserId)
+            .into_tuple()
+            .all(self.0)
+            .await?;
+
+        Ok(user_ids
+            .into_iter()
+            .map(|id| UserId::new(id as u64))
+            .collect())
+    }
+
+    /// Disable auto publish for the given users, returning how many were changed
+    pub async fn disable_auto_publish(&self, user_ids: &[UserId]) -> Result<u64, BotError> {
+        use sea_orm::sea_query::Expr;
+
+        let result = Entity::update_many()
+            .col_expr(Column::AutoPublishEnabled, Expr::value(false))
+            .filter(Column::AutoPublishEnabled.eq(true))
+            .filter(Column::UserId.is_in(user_ids.iter().map(|id| id.get() as i64)))
+            .exec(self.0)
+            .await?;
+
+        Ok(result.rows_affected)
+    }
+
+    /// Count users per default system license
+    ///
+    /// Users whose default is a user license are not counted, matching `get_default_license`.
+    pub async fn count_default_system_licenses(&self) -> Result<HashMap<String, u64>, BotError> {
+        let rows: Vec<(String, i64)> = Entity::find()
+            .select_only()
+            .column(Column::DefaultSystemLicenseName)
+            .column_as(Column::UserId.count(), "count")
+            .filter(Column::DefaultSystemLicenseName.is_not_null())
+            .filter(Column::DefaultUserLicenseId.is_null())
+            .group_by(Column::DefaultSystemLicenseName)
+            .into_tuple()
+            .all(self.0)
+            .await?;
+
+        Ok(rows
+            .into_iter()
+            .map(|(name, count)| (name, count as u64))
+            .collect())
+    }
+
     /// Update settings with validation
     pub async fn update_settings(
         &self,
@@ -212,7 +333,7 @@ impl UserSettingsService<'_> {
             }
         }
 
-        let updated = active_settings.update(self.0.inner()).await?;
+        let updated = active_settings.update(self.0).await?;
         Ok(updated)
     }
 }
@@ -222,7 +343,10 @@ mod tests {
     use migration::{Migrator, MigratorTrait, SchemaManager};
 
     use super::*;
-    use crate::{database::BotDatabase, types::license::DefaultLicenseIdentifier};
+    use crate::{
+        database::BotDatabase, services::license::LicenseFields,
+        types::license::DefaultLicenseIdentifier,
+    };
 
     async fn setup_test_db() -> BotDatabase {
         let db = BotDatabase::new_memory().await.unwrap();
@@ -278,11 +402,10 @@ mod tests {
                 .license()
                 .create(
                     user_id,
-                    format!("Test License {}", i),
-                    true,
-                    false,
-                    None,
-                    false,
+                    LicenseFields {
+                        allow_redistribution: true,
+                        ..LicenseFields::new(format!("Test License {}", i))
+                    },
                 )
                 .await
                 .unwrap();
@@ -327,6 +450,77 @@ mod tests {
         assert_eq!(settings.default_system_license_name, None);
     }
 
+    #[tokio::test]
+    async fn test_clear_stale_default_license() {
+        let db = setup_test_db().await;
+        let service = db.user_settings();
+        let user_id = UserId::new(123);
+
+        // No settings, nothing to clear
+        assert!(!service.clear_stale_default_license(user_id).await.unwrap());
+
+        let license = db
+            .license()
+            .create(
+                user_id,
+                LicenseFields {
+                    allow_redistribution: true,
+                    ..LicenseFields::new("Test License")
+                },
+            )
+            .await
+            .unwrap();
+        service
+            .set_default_license(
+                user_id,
+                Some(DefaultLicenseIdentifier::User(license.id)),
+                None,
+            )
+            .await
+            .unwrap();
+
+        // License still exists, default is kept
+        assert!(!service.clear_stale_default_license(user_id).await.unwrap());
+        assert_eq!(
+            service.get_default_license(user_id).await.unwrap(),
+            Some(DefaultLicenseIdentifier::User(license.id))
+        );
+
+        // A default that no longer resolves for the user is reset
+        let other_license = db
+            .license()
+            .create(
+                UserId::new(456),
+                LicenseFields {
+                    allow_redistribution: true,
+                    ..LicenseFields::new("Other License")
+                },
+            )
+            .await
+            .unwrap();
+        service
+            .set_default_license(
+                user_id,
+                Some(DefaultLicenseIdentifier::User(other_license.id)),
+                None,
+            )
+            .await
+            .unwrap();
+        assert!(service.clear_stale_default_license(user_id).await.unwrap());
+        assert_eq!(service.get_default_license(user_id).await.unwrap(), None);
+
+        // A system license default is never treated as stale
+        service
+            .set_default_license(
+                user_id,
+                Some(DefaultLicenseIdentifier::System("MIT".to_string())),
+                None,
+            )
+            .await
+            .unwrap();
+        assert!(!service.clear_stale_default_license(user_id).await.unwrap());
+    }
+
     #[tokio::test]
     async fn test_toggle_auto_publish() {
         let db = setup_test_db().await;
@@ -374,11 +568,10 @@ mod tests {
             .license()
             .create(
                 user_id,
-                "Test License".to_string(),
-                true,
-                false,
-                None,
-                false,
+                LicenseFields {
+                    allow_redistribution: true,
+                    ..LicenseFields::new("Test License")
+                },
             )
             .await
             .unwrap();
@@ -411,6 +604,35 @@ mod tests {
         );
     }
 
+    #[tokio::test]
+    async fn test_count_default_system_licenses() {
+        let db = setup_test_db().await;
+        let service = db.user_settings();
+
+        for (user, license) in [(1, "MIT"), (2, "MIT"), (3, "CC-BY-4.0")] {
+            service
+                .set_default_license(
+                    UserId::new(user),
+                    Some(DefaultLicenseIdentifier::System(license.to_string())),
+                    None,
+                )
+                .await
+                .unwrap();
+        }
+        // Users without a default are not counted
+        service.get_or_create(UserId::new(4)).await.unwrap();
+
+        let counts = service.count_default_system_licenses().await.unwrap();
+        assert_eq!(counts.len(), 2);
+        assert_eq!(counts["MIT"], 2);
+        assert_eq!(counts["CC-BY-4.0"], 1);
+
+        // Switching to no default removes the user from the count
+        service.clear_default_license(UserId::new(3)).await.unwrap();
+        let counts = service.count_default_system_licenses().await.unwrap();
+        assert_eq!(counts.get("CC-BY-4.0"), None);
+    }
+
     #[tokio::test]
     async fn test_system_license_backup_override() {
         let db = setup_test_db().await;
@@ -469,11 +691,10 @@ mod tests {
                 .license()
                 .create(
                     user_id,
-                    format!("Test License {}", i),
-                    true,
-                    false,
-                    None,
-                    false,
+                    LicenseFields {
+                        allow_redistribution: true,
+                        ..LicenseFields::new(format!("Test License {}", i))
+                    },
                 )
                 .await
                 .unwrap();
@@ -563,4 +784,81 @@ mod tests {
         service.set_auto_publish(user1, false).await.unwrap();
         assert_eq!(service.get_auto_publish_count().await.unwrap(), 1);
     }
+
+    #[tokio::test]
+    async fn test_get_default_license_prefers_user_license() {
+        let db = setup_test_db().await;
+        let service = db.user_settings();
+        let user_id = UserId::new(123);
+
+        let license = db
+            .license()
+            .create(
+                user_id,
+                LicenseFields {
+                    allow_redistribution: true,
+                    ..LicenseFields::new("MIT")
+                },
+            )
+            .await
+            .unwrap();
+
+        // 模拟旧数据中同时存在同名的用户协议和系统协议默认值
+        let mut settings: ActiveModel = service.get_or_create(user_id).await.unwrap().into();
+        settings.default_user_license_id = Set(Some(license.id));
+        settings.default_system_license_name = Set(Some("MIT".to_string()));
+        settings.update(db.inner()).await.unwrap();
+
+        assert_eq!(
+            service.get_default_license(user_id).await.unwrap(),
+            Some(DefaultLicenseIdentifier::User(license.id))
+        );
+    }
+
+    #[tokio::test]
+    async fn test_inactive_auto_publish_users() {
+        let db = setup_test_db().await;
+        let service = db.user_settings();
+        let active = UserId::new(1);
+        let never_published = UserId::new(2);
+        let disabled = UserId::new(3);
+
+        service.set_auto_publish(active, true).await.unwrap();
+        service
+            .set_auto_publish(never_published, true)
+            .await
+            .unwrap();
+        service.set_auto_publish(disabled, false).await.unwrap();
+        for (thread, user) in [(10, active), (30, disabled)] {
+            db.published_posts()
+                .record(
+                    ChannelId::new(thread),
+                    MessageId::new(thread + 1),
+                    user,
+                    true,
+                )
+                .await
+                .unwrap();
+        }
+
+        // 最近发布过的用户不算不活跃，未开启自动发布的用户不在结果中
+        let since = chrono::Utc::now() - chrono::Duration::hours(1);
+        let inactive = service
+            .get_inactive_auto_publish_users(since)
+            .await
+            .unwrap();
+        assert_eq!(inactive, vec![never_published]);
+
+        // 截止时间之后没有发布记录时，所有开启自动发布的用户都不活跃
+        let since = chrono::Utc::now() + chrono::Duration::hours(1);
+        let inactive = service
+            .get_inactive_auto_publish_users(since)
+            .await
+            .unwrap();
+        assert_eq!(inactive, vec![active, never_published]);
+
+        assert_eq!(service.disable_auto_publish(&inactive).await.unwrap(), 2);
+        assert!(!service.is_auto_publish_enabled(active).await.unwrap());
+        assert!(service.get_auto_publish_users().await.unwrap().is_empty());
+    }
 }
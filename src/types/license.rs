@@ -1,3 +1,5 @@
+use std::path::PathBuf;
+
 use entities::user_licenses::Model as LicenseModel;
 use serde::{Deserialize, Serialize};
 use serenity::all::*;
@@ -14,8 +16,32 @@ pub struct SystemLicense {
     pub allow_modification: bool,
     pub restrictions_note: Option<String>,
     pub allow_backup: bool,
+    #[serde(default)]
+    pub allow_commercial: bool,
+    #[serde(default)]
+    pub require_attribution: bool,
+    #[serde(default)]
+    pub color: Option<i32>,
+    /// 允许发布该协议的用户；与 `allowed_publisher_roles` 都为空时所有人都可以发布
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub allowed_publisher_ids: Vec<UserId>,
+    /// 允许发布该协议的身份组
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub allowed_publisher_roles: Vec<RoleId>,
+    /// 完整协议文本的链接，发布时以按钮形式附在协议消息下方
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub full_text_url: Option<String>,
+    /// 完整协议文本文件（PDF、Markdown 等），相对于协议文件所在目录，发布时作为附件上传
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub full_text_file: Option<PathBuf>,
+    /// 协议分类（如"开源"、"创作"），用于在选择菜单中分组显示
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub category: Option<String>,
 }
 
+/// 未设置分类的系统协议所在的分组
+pub const DEFAULT_LICENSE_CATEGORY: &str = "其他";
+
 impl From<LicenseModel> for SystemLicense {
     fn from(model: LicenseModel) -> Self {
         SystemLicense {
@@ -24,11 +50,44 @@ impl From<LicenseModel> for SystemLicense {
             allow_modification: model.allow_modification,
             restrictions_note: model.restrictions_note,
             allow_backup: model.allow_backup,
+            allow_commercial: model.allow_commercial,
+            require_attribution: model.require_attribution,
+            color: model.color,
+            allowed_publisher_ids: Vec::new(),
+            allowed_publisher_roles: Vec::new(),
+            full_text_url: None,
+            full_text_file: None,
+            category: None,
         }
     }
 }
 
 impl SystemLicense {
+    /// 是否配置了发布名单
+    pub fn is_restricted(&self) -> bool {
+        !self.allowed_publisher_ids.is_empty() || !self.allowed_publisher_roles.is_empty()
+    }
+
+    /// 检查用户是否可以发布该协议
+    ///
+    /// 未配置发布名单时所有人可发布，否则用户需在名单中或拥有名单中的身份组
+    pub fn can_publish(&self, user_id: UserId, roles: &[RoleId]) -> bool {
+        !self.is_restricted()
+            || self.allowed_publisher_ids.contains(&user_id)
+            || roles
+                .iter()
+                .any(|role| self.allowed_publisher_roles.contains(role))
+    }
+
+    /// 协议所属分类，未设置或为空时归入默认分组
+    pub fn category_name(&self) -> &str {
+        self.category
+            .as_deref()
+            .map(str::trim)
+            .filter(|c| !c.is_empty())
+            .unwrap_or(DEFAULT_LICENSE_CATEGORY)
+    }
+
     pub fn to_user_license(&self, user_id: UserId, index: i32) -> LicenseModel {
         LicenseModel {
             id: index,
@@ -40,6 +99,154 @@ impl SystemLicense {
             allow_backup: self.allow_backup,
             usage_count: 0,
             created_at: chrono::Utc::now(),
+            allow_commercial: self.allow_commercial,
+            require_attribution: self.require_attribution,
+            color: self.color,
+            // 系统协议不写入数据库，没有创建来源
+            source: crate::services::license::LicenseSource::Unknown
+                .as_str()
+                .to_string(),
+            sort_order: 0,
         }
     }
 }
+
+/// 按分类对系统协议分组
+///
+/// 分组按分类在列表中首次出现的顺序排列，默认分组总在最后；组内保持原有顺序
+pub fn group_by_category(licenses: &[SystemLicense]) -> Vec<(&str, Vec<&SystemLicense>)> {
+    let mut groups: Vec<(&str, Vec<&SystemLicense>)> = Vec::new();
+    for license in licenses {
+        let category = license.category_name();
+        match groups.iter_mut().find(|(name, _)| *name == category) {
+            Some((_, members)) => members.push(license),
+            None => groups.push((category, vec![license])),
+        }
+    }
+    // 稳定排序，只把默认分组移到最后
+    groups.sort_by_key(|(name, _)| *name == DEFAULT_LICENSE_CATEGORY);
+    groups
+}
+
+/// 生成能区分来源的协议展示名
+///
+/// 系统协议总是带"(系统)"后缀；用户协议与某个系统协议同名时带"(个人)"后缀
+pub fn license_display_name(
+    license_name: &str,
+    is_system: bool,
+    system_licenses: &[SystemLicense],
+) -> String {
+    if is_system {
+        format!("{license_name} (系统)")
+    } else if system_licenses
+        .iter()
+        .any(|l| l.license_name == license_name)
+    {
+        format!("{license_name} (个人)")
+    } else {
+        license_name.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn system_license(name: &str) -> SystemLicense {
+        SystemLicense {
+            license_name: name.to_string(),
+            allow_redistribution: true,
+            allow_modification: true,
+            restrictions_note: None,
+            allow_backup: true,
+            allow_commercial: false,
+            require_attribution: true,
+            color: None,
+            allowed_publisher_ids: Vec::new(),
+            allowed_publisher_roles: Vec::new(),
+            full_text_url: None,
+            full_text_file: None,
+            category: None,
+        }
+    }
+
+    #[test]
+    fn test_license_display_name_disambiguates_collisions() {
+        let system_licenses = vec![system_license("MIT")];
+
+        assert_eq!(
+            license_display_name("MIT", false, &system_licenses),
+            "MIT (个人)"
+        );
+        assert_eq!(
+            license_display_name("MIT", true, &system_licenses),
+            "MIT (系统)"
+        );
+        assert_eq!(
+            license_display_name("我的协议", false, &system_licenses),
+            "我的协议"
+        );
+    }
+
+    #[test]
+    fn test_system_license_publish_permission() {
+        let open = system_license("MIT");
+        assert!(!open.is_restricted());
+        assert!(open.can_publish(UserId::new(1), &[]));
+
+        let official = SystemLicense {
+            allowed_publisher_ids: vec![UserId::new(1)],
+            allowed_publisher_roles: vec![RoleId::new(100)],
+            ..system_license("社区官方协议")
+        };
+        assert!(official.is_restricted());
+        assert!(official.can_publish(UserId::new(1), &[]));
+        assert!(official.can_publish(UserId::new(2), &[RoleId::new(100)]));
+        assert!(!official.can_publish(UserId::new(2), &[RoleId::new(200)]));
+        assert!(!official.can_publish(UserId::new(3), &[]));
+    }
+
+    #[test]
+    fn test_group_by_category() {
+        let categorized = |name: &str, category: Option<&str>| SystemLicense {
+            category: category.map(str::to_string),
+            ..system_license(name)
+        };
+        let licenses = vec![
+            categorized("自定义A", None),
+            categorized("MIT", Some("开源")),
+            categorized("CC-BY-4.0", Some("创作")),
+            categorized("GPL-3.0", Some("开源")),
+            categorized("空分类", Some("  ")),
+        ];
+
+        let groups: Vec<(&str, Vec<&str>)> = group_by_category(&licenses)
+            .into_iter()
+            .map(|(name, members)| {
+                (
+                    name,
+                    members.iter().map(|l| l.license_name.as_str()).collect(),
+                )
+            })
+            .collect();
+        assert_eq!(
+            groups,
+            vec![
+                ("开源", vec!["MIT", "GPL-3.0"]),
+                ("创作", vec!["CC-BY-4.0"]),
+                (DEFAULT_LICENSE_CATEGORY, vec!["自定义A", "空分类"]),
+            ]
+        );
+
+        // 旧的协议文件没有 category 字段
+        let legacy: SystemLicense = serde_json::from_value(serde_json::json!({
+            "license_name": "MIT",
+            "allow_redistribution": true,
+            "allow_modification": true,
+            "restrictions_note": null,
+            "allow_backup": true,
+        }))
+        .unwrap();
+        assert_eq!(legacy.category_name(), DEFAULT_LICENSE_CATEGORY);
+    }
+}
@@ -0,0 +1,119 @@
+use serde::de::DeserializeOwned;
+use serenity::all::Attachment;
+
+use crate::error::BotError;
+
+/// 导入用 JSON 附件的默认大小上限（字节）
+pub const MAX_JSON_ATTACHMENT_SIZE: u32 = 1024 * 1024;
+
+fn invalid(message: String) -> BotError {
+    BotError::ValidationError {
+        message,
+        loc: snafu::location!(),
+    }
+}
+
+/// 下载前检查附件的类型和大小
+///
+/// Discord 未提供内容类型时按 `.json` 扩展名判断
+fn validate_json_attachment(attachment: &Attachment, max_size: u32) -> Result<(), BotError> {
+    let is_json = match &attachment.content_type {
+        Some(content_type) => content_type
+            .split(';')
+            .next()
+            .is_some_and(|mime| mime.trim().eq_ignore_ascii_case("application/json")),
+        None => attachment.filename.to_lowercase().ends_with(".json"),
+    };
+    if !is_json {
+        return Err(invalid(format!(
+            "文件 {} 不是 JSON 文件，请上传 .json 文件",
+            attachment.filename
+        )));
+    }
+
+    if attachment.size > max_size {
+        return Err(invalid(format!(
+            "文件 {} 过大（{} KB），最大允许 {} KB",
+            attachment.filename,
+            attachment.size.div_ceil(1024),
+            max_size / 1024
+        )));
+    }
+
+    Ok(())
+}
+
+/// 校验并下载 JSON 附件，解析为 `T`
+///
+/// 类型不符或超过 `max_size` 的附件在下载前即被拒绝，返回可直接展示给用户的错误
+pub async fn download_json_attachment<T: DeserializeOwned>(
+    attachment: &Attachment,
+    max_size: u32,
+) -> Result<T, BotError> {
+    validate_json_attachment(attachment, max_size)?;
+
+    let bytes = attachment.download().await?;
+    // 附件信息中的大小与实际内容不符时同样拒绝
+    if bytes.len() > max_size as usize {
+        return Err(invalid(format!(
+            "文件 {} 过大，最大允许 {} KB",
+            attachment.filename,
+            max_size / 1024
+        )));
+    }
+
+    serde_json::from_slice(&bytes)
+        .map_err(|e| invalid(format!("文件 {} 不是有效的 JSON: {e}", attachment.filename)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn attachment(filename: &str, content_type: Option<&str>, size: u32) -> Attachment {
+        serde_json::from_value(serde_json::json!({
+            "id": "1",
+            "filename": filename,
+            "size": size,
+            "url": "https://cdn.example.com/file",
+            "proxy_url": "https://media.example.com/file",
+            "content_type": content_type,
+        }))
+        .unwrap()
+    }
+
+    fn rejection(result: Result<(), BotError>) -> String {
+        match result {
+            Err(BotError::ValidationError { message, .. }) => message,
+            other => panic!("expected validation error, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn test_rejects_oversized_attachment() {
+        let limit = 1024;
+        let at_limit = attachment("licenses.json", Some("application/json"), limit);
+        assert!(validate_json_attachment(&at_limit, limit).is_ok());
+
+        let oversized = attachment("licenses.json", Some("application/json"), 5 * 1024 + 1);
+        let message = rejection(validate_json_attachment(&oversized, limit));
+        assert!(message.contains("过大（6 KB）"));
+        assert!(message.contains("最大允许 1 KB"));
+    }
+
+    #[test]
+    fn test_rejects_non_json_attachment() {
+        let limit = MAX_JSON_ATTACHMENT_SIZE;
+        let with_charset = attachment("a.json", Some("application/json; charset=utf-8"), 10);
+        assert!(validate_json_attachment(&with_charset, limit).is_ok());
+
+        let image = attachment("licenses.json", Some("image/png"), 10);
+        assert!(rejection(validate_json_attachment(&image, limit)).contains("不是 JSON 文件"));
+
+        // 没有内容类型时按扩展名判断
+        let untyped_json = attachment("licenses.JSON", None, 10);
+        assert!(validate_json_attachment(&untyped_json, limit).is_ok());
+        let untyped_text = attachment("licenses.txt", None, 10);
+        assert!(validate_json_attachment(&untyped_text, limit).is_err());
+    }
+}
@@ -1,5 +1,8 @@
 use crate::services::license::UserLicense;
-use crate::utils::LicenseEmbedBuilder;
+use crate::types::license::group_by_category;
+use crate::utils::{
+    LicenseEmbedBuilder, Locale, Paginator, SELECT_MENU_PAGE_SIZE, paged_select_rows,
+};
 use serenity::all::*;
 
 /// 自动发布流程的UI构建器
@@ -20,80 +23,105 @@ impl AutoPublishUI {
             ])])
     }
 
-    /// 构建协议选择菜单
-    pub fn build_license_selection_menu(
+    /// 协议选择菜单的全部选项
+    fn license_selection_options(
         system_licenses: &[crate::types::license::SystemLicense],
-    ) -> CreateSelectMenu {
+    ) -> Vec<CreateSelectMenuOption> {
         let mut select_options = vec![
             CreateSelectMenuOption::new("创建新协议", "new_license")
                 .description("创建一个全新的协议"),
         ];
 
-        for license in system_licenses {
-            select_options.push(
-                CreateSelectMenuOption::new(
-                    &license.license_name,
-                    format!("system_{}", license.license_name),
-                )
-                .description("基于系统协议创建"),
-            );
+        // 按分类分组排列，分类名显示在选项描述中
+        for (category, licenses) in group_by_category(system_licenses) {
+            for license in licenses {
+                select_options.push(
+                    CreateSelectMenuOption::new(
+                        &license.license_name,
+                        format!("system_{}", license.license_name),
+                    )
+                    .description(format!("📂 {category} · 基于系统协议创建")),
+                );
+            }
         }
 
-        CreateSelectMenu::new(
-            "license_selection",
-            CreateSelectMenuKind::String {
-                options: select_options,
-            },
-        )
-        .placeholder("请选择协议类型")
-        .max_values(1)
+        select_options
     }
 
-    /// 构建重新选择协议菜单的followup消息
-    pub fn build_license_reselection_menu(
+    /// 构建协议选择菜单，选项超过一页时附带翻页按钮
+    pub fn build_license_selection_menu(
         system_licenses: &[crate::types::license::SystemLicense],
-    ) -> CreateInteractionResponseFollowup {
-        let mut select_options = vec![
-            CreateSelectMenuOption::new("创建新协议", "new_license")
-                .description("创建一个全新的协议"),
-        ];
+        paginator: &Paginator,
+    ) -> Vec<CreateActionRow> {
+        let select_options = Self::license_selection_options(system_licenses);
+        paged_select_rows(&select_options, paginator, |options| {
+            CreateSelectMenu::new(
+                "license_selection",
+                CreateSelectMenuKind::String { options },
+            )
+            .placeholder("请选择协议类型")
+            .max_values(1)
+        })
+    }
 
-        for license in system_licenses {
-            select_options.push(
-                CreateSelectMenuOption::new(
-                    &license.license_name,
-                    format!("system_{}", license.license_name),
-                )
-                .description("基于系统协议创建"),
-            );
-        }
+    /// 构建重新选择协议菜单，末尾附带退出选项
+    pub fn build_license_reselection_rows(
+        system_licenses: &[crate::types::license::SystemLicense],
+        paginator: &Paginator,
+    ) -> Vec<CreateActionRow> {
+        let mut select_options = Self::license_selection_options(system_licenses);
 
         // 添加退出选项
         select_options.push(
             CreateSelectMenuOption::new("不再设置", "exit_setup").description("退出协议设置流程"),
         );
 
-        let select_menu = CreateSelectMenu::new(
-            "license_reselection",
-            CreateSelectMenuKind::String {
-                options: select_options,
-            },
-        )
-        .placeholder("请重新选择协议类型或退出")
-        .max_values(1);
+        paged_select_rows(&select_options, paginator, |options| {
+            CreateSelectMenu::new(
+                "license_reselection",
+                CreateSelectMenuKind::String { options },
+            )
+            .placeholder("请重新选择协议类型或退出")
+            .max_values(1)
+        })
+    }
+
+    /// 协议选择菜单的翻页状态
+    pub fn license_selection_paginator(
+        system_licenses: &[crate::types::license::SystemLicense],
+        with_exit_option: bool,
+    ) -> Paginator {
+        // 选项包括"创建新协议"，重新选择时还有"不再设置"
+        let fixed_options = 1 + usize::from(with_exit_option);
+        Paginator::for_items(system_licenses.len() + fixed_options, SELECT_MENU_PAGE_SIZE)
+    }
 
+    /// 构建重新选择协议菜单的followup消息
+    pub fn build_license_reselection_menu(
+        system_licenses: &[crate::types::license::SystemLicense],
+        paginator: &Paginator,
+    ) -> CreateInteractionResponseFollowup {
         CreateInteractionResponseFollowup::new()
             .content("你取消了之前的协议编辑。请重新选择一个协议类型，或选择\"不再设置\"退出流程：")
-            .components(vec![CreateActionRow::SelectMenu(select_menu)])
+            .components(Self::build_license_reselection_rows(
+                system_licenses,
+                paginator,
+            ))
             .ephemeral(true)
     }
 
     /// 构建自动发布确认面板
+    ///
+    /// `replaces_existing` 表示帖子中已发布协议，确认后会被替换
     pub fn build_auto_publish_confirmation(
         license: &UserLicense,
         display_name: &str,
+        locale: Locale,
+        thread: &GuildChannel,
+        replaces_existing: bool,
     ) -> CreateMessage {
-        let embed = LicenseEmbedBuilder::create_auto_publish_preview_embed(license, display_name);
+        let embed =
+            Self::build_preview_embed(license, display_name, locale, thread, replaces_existing);
 
         CreateMessage::new()
             .embed(embed)
@@ -107,6 +135,28 @@ impl AutoPublishUI {
             ])])
     }
 
+    /// 构建带有目标帖子信息的协议预览embed
+    fn build_preview_embed(
+        license: &UserLicense,
+        display_name: &str,
+        locale: Locale,
+        thread: &GuildChannel,
+        replaces_existing: bool,
+    ) -> CreateEmbed {
+        let thread_url = format!(
+            "https://discord.com/channels/{}/{}",
+            thread.guild_id, thread.id
+        );
+        LicenseEmbedBuilder::create_auto_publish_preview_embed(
+            license,
+            display_name,
+            locale,
+            &thread.name,
+            &thread_url,
+            replaces_existing,
+        )
+    }
+
     /// 构建发布确认按钮
     pub fn build_publish_confirmation_button() -> CreateButton {
         CreateButton::new("confirm_new_user_publish")
@@ -116,11 +166,11 @@ impl AutoPublishUI {
 
     /// 创建启用功能的回复消息
     pub fn create_enable_response(
-        select_menu: CreateSelectMenu,
+        selection_rows: Vec<CreateActionRow>,
     ) -> CreateInteractionResponseMessage {
         CreateInteractionResponseMessage::new()
             .content("✅ 自动发布功能已启用！\n\n请选择你要使用的协议：")
-            .components(vec![CreateActionRow::SelectMenu(select_menu)])
+            .components(selection_rows)
             .ephemeral(true)
     }
 
@@ -153,8 +203,12 @@ impl AutoPublishUI {
     pub fn create_new_user_publish_confirmation(
         license: &UserLicense,
         display_name: &str,
+        locale: Locale,
+        thread: &GuildChannel,
+        replaces_existing: bool,
     ) -> CreateInteractionResponseFollowup {
-        let embed = LicenseEmbedBuilder::create_auto_publish_preview_embed(license, display_name);
+        let embed =
+            Self::build_preview_embed(license, display_name, locale, thread, replaces_existing);
 
         CreateInteractionResponseFollowup::new()
             .content("✅ 协议创建成功！\n\n📝 现在请确认是否要将其发布到这个帖子中：")
@@ -172,12 +226,43 @@ impl AutoPublishUI {
             .components(Vec::new())
     }
 
+    /// 创建协议数量达到上限时的提示，可从已有协议中选择一个作为默认协议
+    pub fn create_license_limit_followup(
+        licenses: &[UserLicense],
+    ) -> CreateInteractionResponseFollowup {
+        let content = format!(
+            "❌ 你已有 {} 个协议，达到上限，无法保存新协议。\n\n你可以在下方选择一个已有协议作为默认协议继续，或使用 `/协议管理` 删除不需要的协议后重新设置。",
+            licenses.len()
+        );
+        let options: Vec<CreateSelectMenuOption> = licenses
+            .iter()
+            .take(25)
+            .map(|license| {
+                CreateSelectMenuOption::new(&license.license_name, license.id.to_string())
+            })
+            .collect();
+
+        let mut followup = CreateInteractionResponseFollowup::new()
+            .content(content)
+            .ephemeral(true);
+        if !options.is_empty() {
+            followup = followup.components(vec![CreateActionRow::SelectMenu(
+                CreateSelectMenu::new(
+                    "reuse_existing_license",
+                    CreateSelectMenuKind::String { options },
+                )
+                .placeholder("选择已有协议作为默认协议"),
+            )]);
+        }
+        followup
+    }
+
     /// 创建新协议发布确认的followup消息
     pub fn create_new_license_publish_confirmation(
         license_name: &str,
     ) -> CreateInteractionResponseFollowup {
         let confirm_message = format!(
-            "✅ 协议「{license_name}」已创建并设置为默认协议！\n\n是否要在当前帖子中发布此协议？"
+            "✅ 协议「{license_name}」已设置为默认协议！\n\n是否要在当前帖子中发布此协议？"
         );
 
         CreateInteractionResponseFollowup::new()
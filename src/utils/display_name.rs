@@ -0,0 +1,182 @@
+use std::{future::Future, sync::OnceLock, time::Duration};
+
+use moka::future::Cache;
+use serenity::all::{GuildId, Http, HttpError, User, UserId};
+
+/// 显示名缓存的有效期，成员改名后最多在此时间后生效
+const DISPLAY_NAME_TTL: Duration = Duration::from_secs(300);
+/// 无法获取用户信息时使用的名称
+const UNKNOWN_USER_NAME: &str = "未知用户";
+/// Discord 的 "Unknown Member" 错误码
+const UNKNOWN_MEMBER_CODE: isize = 10007;
+/// Discord 的 "Unknown User" 错误码
+const UNKNOWN_USER_CODE: isize = 10013;
+
+// 成员显示名缓存，减少重复的成员查询请求
+static DISPLAY_NAMES: OnceLock<Cache<(GuildId, UserId), String>> = OnceLock::new();
+
+fn display_names() -> &'static Cache<(GuildId, UserId), String> {
+    DISPLAY_NAMES.get_or_init(|| {
+        Cache::builder()
+            .time_to_live(DISPLAY_NAME_TTL)
+            .max_capacity(10_000) // 限制最大条目数
+            .build()
+    })
+}
+
+/// 查询显示名的结果
+enum NameLookup {
+    Found(String),
+    /// 成员或用户确定不存在
+    Missing,
+    /// 请求失败，可能只是暂时的网络或服务问题
+    Failed,
+}
+
+/// 判断请求是否因 Discord 返回指定错误码而失败
+fn has_error_code(err: &serenity::Error, code: isize) -> bool {
+    matches!(
+        err,
+        serenity::Error::Http(HttpError::UnsuccessfulRequest(response))
+            if response.error.code == code
+    )
+}
+
+/// 在缓存中查找显示名，未命中时调用 `fetch`
+///
+/// 找不到名称时使用 `fallback`。确定不存在时回退的名称同样会被缓存，
+/// 避免已退出服务器的成员在有效期内被反复查询；请求失败时不缓存，下次重新查询。
+async fn resolve_in<F, Fut>(
+    cache: &Cache<(GuildId, UserId), String>,
+    key: (GuildId, UserId),
+    fetch: F,
+    fallback: impl FnOnce() -> String,
+) -> String
+where
+    F: FnOnce() -> Fut,
+    Fut: Future<Output = NameLookup>,
+{
+    if let Some(name) = cache.get(&key).await {
+        return name;
+    }
+    let name = match fetch().await {
+        NameLookup::Found(name) => name,
+        NameLookup::Missing => fallback(),
+        NameLookup::Failed => return fallback(),
+    };
+    cache.insert(key, name.clone()).await;
+    name
+}
+
+/// 获取已缓存的显示名，不发起请求
+pub async fn cached_display_name(guild_id: GuildId, user_id: UserId) -> Option<String> {
+    display_names().get(&(guild_id, user_id)).await
+}
+
+/// 获取用户在服务器中的显示名，找不到成员时回退到用户的全局名
+pub async fn member_display_name(http: &Http, guild_id: GuildId, user: &User) -> String {
+    resolve_in(
+        display_names(),
+        (guild_id, user.id),
+        || async {
+            match guild_id.member(http, user.id).await {
+                Ok(member) => NameLookup::Found(member.display_name().to_string()),
+                Err(e) if has_error_code(&e, UNKNOWN_MEMBER_CODE) => NameLookup::Missing,
+                Err(_) => NameLookup::Failed,
+            }
+        },
+        || user.display_name().to_string(),
+    )
+    .await
+}
+
+/// 根据用户ID获取显示名，找不到成员时请求用户信息获取全局名
+pub async fn member_display_name_by_id(http: &Http, guild_id: GuildId, user_id: UserId) -> String {
+    resolve_in(
+        display_names(),
+        (guild_id, user_id),
+        || async {
+            match guild_id.member(http, user_id).await {
+                Ok(member) => NameLookup::Found(member.display_name().to_string()),
+                Err(e) if has_error_code(&e, UNKNOWN_MEMBER_CODE) => {
+                    match user_id.to_user(http).await {
+                        Ok(user) => NameLookup::Found(user.display_name().to_string()),
+                        Err(e) if has_error_code(&e, UNKNOWN_USER_CODE) => NameLookup::Missing,
+                        Err(_) => NameLookup::Failed,
+                    }
+                }
+                Err(_) => NameLookup::Failed,
+            }
+        },
+        || UNKNOWN_USER_NAME.to_string(),
+    )
+    .await
+}
+
+/// 跳过缓存重新获取用户在服务器中的显示名，并更新缓存
+pub async fn refresh_member_display_name(
+    http: &Http,
+    guild_id: GuildId,
+    user_id: UserId,
+) -> String {
+    display_names().invalidate(&(guild_id, user_id)).await;
+    member_display_name_by_id(http, guild_id, user_id).await
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    use super::*;
+
+    #[tokio::test]
+    async fn test_display_name_cache_and_fallback() {
+        let cache = Cache::new(100);
+        let fetches = AtomicUsize::new(0);
+        let key = (GuildId::new(1), UserId::new(10));
+        let fetch = |name: Option<&'static str>| {
+            let fetches = &fetches;
+            move || async move {
+                fetches.fetch_add(1, Ordering::SeqCst);
+                name.map_or(NameLookup::Missing, |n| NameLookup::Found(n.to_string()))
+            }
+        };
+
+        // 未命中时请求，命中后不再请求
+        let name = resolve_in(&cache, key, fetch(Some("成员名")), || "全局名".into()).await;
+        assert_eq!(name, "成员名");
+        let name = resolve_in(&cache, key, fetch(Some("新名字")), || "全局名".into()).await;
+        assert_eq!(name, "成员名");
+        assert_eq!(fetches.load(Ordering::SeqCst), 1);
+
+        // 找不到成员时回退到全局名，回退值同样被缓存
+        let other = (GuildId::new(1), UserId::new(20));
+        let name = resolve_in(&cache, other, fetch(None), || "全局名".into()).await;
+        assert_eq!(name, "全局名");
+        let name = resolve_in(&cache, other, fetch(Some("成员名")), || "全局名".into()).await;
+        assert_eq!(name, "全局名");
+        assert_eq!(fetches.load(Ordering::SeqCst), 2);
+
+        // 不同服务器分别缓存
+        let other_guild = (GuildId::new(2), UserId::new(10));
+        let name = resolve_in(&cache, other_guild, fetch(Some("另一个名字")), || {
+            "全局名".into()
+        })
+        .await;
+        assert_eq!(name, "另一个名字");
+        assert_eq!(fetches.load(Ordering::SeqCst), 3);
+
+        // 请求失败时使用回退值但不缓存，下次重新查询
+        let failed = (GuildId::new(1), UserId::new(30));
+        let name = resolve_in(
+            &cache,
+            failed,
+            || async { NameLookup::Failed },
+            || UNKNOWN_USER_NAME.into(),
+        )
+        .await;
+        assert_eq!(name, UNKNOWN_USER_NAME);
+        let name = resolve_in(&cache, failed, fetch(Some("成员名")), || "全局名".into()).await;
+        assert_eq!(name, "成员名");
+    }
+}
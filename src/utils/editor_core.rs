@@ -1,4 +1,8 @@
-use crate::{error::BotError, types::license::SystemLicense, utils::LicenseEmbedBuilder};
+use super::{editor_session::encode_custom_id, locale::Locale};
+use crate::{
+    config::NewLicenseDefaults, error::BotError, services::license::LicenseFields,
+    types::license::SystemLicense, utils::LicenseEmbedBuilder,
+};
 use serenity::all::*;
 
 /// 协议编辑状态，包含协议的所有可编辑字段
@@ -9,6 +13,9 @@ pub struct LicenseEditState {
     pub allow_modification: bool,
     pub restrictions_note: Option<String>,
     pub allow_backup: bool,
+    pub allow_commercial: bool,
+    pub require_attribution: bool,
+    pub color: Option<i32>,
 }
 
 impl LicenseEditState {
@@ -20,23 +27,34 @@ impl LicenseEditState {
             allow_modification: false,
             restrictions_note: None,
             allow_backup: false,
+            allow_commercial: false,
+            require_attribution: false,
+            color: None,
+        }
+    }
+
+    /// 使用配置的默认权限创建新的协议编辑状态
+    pub fn with_defaults(name: String, defaults: &NewLicenseDefaults) -> Self {
+        Self {
+            allow_redistribution: defaults.allow_redistribution,
+            allow_modification: defaults.allow_modification,
+            restrictions_note: defaults.restrictions_note.clone(),
+            allow_backup: defaults.allow_backup,
+            ..Self::new(name)
         }
     }
 
     /// 从现有协议创建编辑状态
-    pub fn from_existing(
-        name: String,
-        allow_redistribution: bool,
-        allow_modification: bool,
-        restrictions_note: Option<String>,
-        allow_backup: bool,
-    ) -> Self {
+    pub fn from_existing(fields: LicenseFields) -> Self {
         Self {
-            license_name: name,
-            allow_redistribution,
-            allow_modification,
-            restrictions_note,
-            allow_backup,
+            license_name: fields.license_name,
+            allow_redistribution: fields.allow_redistribution,
+            allow_modification: fields.allow_modification,
+            restrictions_note: fields.restrictions_note,
+            allow_backup: fields.allow_backup,
+            allow_commercial: fields.allow_commercial,
+            require_attribution: fields.require_attribution,
+            color: fields.color,
         }
     }
 
@@ -48,21 +66,39 @@ impl LicenseEditState {
             allow_modification: system_license.allow_modification,
             restrictions_note: system_license.restrictions_note.clone(),
             allow_backup: system_license.allow_backup,
+            allow_commercial: system_license.allow_commercial,
+            require_attribution: system_license.require_attribution,
+            color: system_license.color,
         }
     }
 
     /// 转换为用户协议的字段
-    pub fn to_user_license_fields(&self) -> (String, bool, bool, Option<String>, bool) {
-        (
-            self.license_name.clone(),
-            self.allow_redistribution,
-            self.allow_modification,
-            self.restrictions_note.clone(),
-            self.allow_backup,
-        )
+    pub fn to_user_license_fields(&self) -> LicenseFields {
+        LicenseFields {
+            license_name: self.license_name.clone(),
+            allow_redistribution: self.allow_redistribution,
+            allow_modification: self.allow_modification,
+            restrictions_note: self.restrictions_note.clone(),
+            allow_backup: self.allow_backup,
+            allow_commercial: self.allow_commercial,
+            require_attribution: self.require_attribution,
+            color: self.color,
+        }
     }
 }
 
+/// 解析十六进制颜色字符串（如 `#5865F2` 或 `5865F2`）为RGB整数
+pub fn parse_hex_color(input: &str) -> Result<i32, BotError> {
+    let hex = input.trim().trim_start_matches('#');
+    if hex.len() != 6 || !hex.chars().all(|c| c.is_ascii_hexdigit()) {
+        return Err(BotError::ValidationError {
+            message: format!("无效的颜色值 '{}'，请使用 #RRGGBB 格式", input.trim()),
+            loc: snafu::location!(),
+        });
+    }
+    Ok(i32::from_str_radix(hex, 16)?)
+}
+
 /// UI提供者trait，抽象不同框架的UI操作
 #[async_trait::async_trait]
 pub trait UIProvider {
@@ -99,8 +135,8 @@ impl EditorCore {
         &mut self.state
     }
 
-    /// 构建UI界面
-    pub fn build_ui(&self) -> (CreateEmbed, Vec<CreateActionRow>) {
+    /// 构建UI界面，按钮 custom_id 中编码了会话ID
+    pub fn build_ui(&self, session_id: u64, locale: Locale) -> (CreateEmbed, Vec<CreateActionRow>) {
         // 创建协议预览嵌入
         let embed = LicenseEmbedBuilder::create_license_preview_embed(
             &self.state.license_name,
@@ -108,42 +144,53 @@ impl EditorCore {
             self.state.allow_modification,
             self.state.restrictions_note.as_deref(),
             Some(self.state.allow_backup),
+            self.state.allow_commercial,
+            self.state.require_attribution,
+            self.state.color,
+            locale,
         );
 
         // 创建按钮
-        let edit_name_btn = CreateButton::new("edit_name")
+        let edit_name_btn = CreateButton::new(encode_custom_id("edit_name", session_id))
             .label("编辑名称")
             .style(ButtonStyle::Secondary);
 
-        let edit_restrictions_btn = CreateButton::new("edit_restrictions")
-            .label("编辑限制条件")
+        let edit_restrictions_btn =
+            CreateButton::new(encode_custom_id("edit_restrictions", session_id))
+                .label("编辑限制条件")
+                .style(ButtonStyle::Secondary);
+
+        let edit_color_btn = CreateButton::new(encode_custom_id("edit_color", session_id))
+            .label("编辑颜色")
             .style(ButtonStyle::Secondary);
 
-        let toggle_redistribution_btn = CreateButton::new("toggle_redistribution")
-            .label(if self.state.allow_redistribution {
-                "关闭二传"
-            } else {
-                "开启二传"
-            })
-            .style(if self.state.allow_redistribution {
-                ButtonStyle::Success
-            } else {
-                ButtonStyle::Secondary
-            });
+        let toggle_redistribution_btn =
+            CreateButton::new(encode_custom_id("toggle_redistribution", session_id))
+                .label(if self.state.allow_redistribution {
+                    "关闭二传"
+                } else {
+                    "开启二传"
+                })
+                .style(if self.state.allow_redistribution {
+                    ButtonStyle::Success
+                } else {
+                    ButtonStyle::Secondary
+                });
 
-        let toggle_modification_btn = CreateButton::new("toggle_modification")
-            .label(if self.state.allow_modification {
-                "关闭二改"
-            } else {
-                "开启二改"
-            })
-            .style(if self.state.allow_modification {
-                ButtonStyle::Success
-            } else {
-                ButtonStyle::Secondary
-            });
+        let toggle_modification_btn =
+            CreateButton::new(encode_custom_id("toggle_modification", session_id))
+                .label(if self.state.allow_modification {
+                    "关闭二改"
+                } else {
+                    "开启二改"
+                })
+                .style(if self.state.allow_modification {
+                    ButtonStyle::Success
+                } else {
+                    ButtonStyle::Secondary
+                });
 
-        let toggle_backup_btn = CreateButton::new("toggle_backup")
+        let toggle_backup_btn = CreateButton::new(encode_custom_id("toggle_backup", session_id))
             .label(if self.state.allow_backup {
                 "关闭备份"
             } else {
@@ -155,20 +202,49 @@ impl EditorCore {
                 ButtonStyle::Secondary
             });
 
-        let save_btn = CreateButton::new("save_license")
+        let toggle_commercial_btn =
+            CreateButton::new(encode_custom_id("toggle_commercial", session_id))
+                .label(if self.state.allow_commercial {
+                    "关闭商用"
+                } else {
+                    "开启商用"
+                })
+                .style(if self.state.allow_commercial {
+                    ButtonStyle::Success
+                } else {
+                    ButtonStyle::Secondary
+                });
+
+        let toggle_attribution_btn =
+            CreateButton::new(encode_custom_id("toggle_attribution", session_id))
+                .label(if self.state.require_attribution {
+                    "关闭署名"
+                } else {
+                    "开启署名"
+                })
+                .style(if self.state.require_attribution {
+                    ButtonStyle::Success
+                } else {
+                    ButtonStyle::Secondary
+                });
+
+        let save_btn = CreateButton::new(encode_custom_id("save_license", session_id))
             .label("保存")
             .style(ButtonStyle::Primary);
 
-        let cancel_btn = CreateButton::new("cancel_license")
+        let cancel_btn = CreateButton::new(encode_custom_id("cancel_license", session_id))
             .label("取消")
             .style(ButtonStyle::Danger);
 
         // 组装按钮行
-        let row1 = CreateActionRow::Buttons(vec![edit_name_btn, edit_restrictions_btn]);
+        let row1 =
+            CreateActionRow::Buttons(vec![edit_name_btn, edit_restrictions_btn, edit_color_btn]);
         let row2 = CreateActionRow::Buttons(vec![
             toggle_redistribution_btn,
             toggle_modification_btn,
             toggle_backup_btn,
+            toggle_commercial_btn,
+            toggle_attribution_btn,
         ]);
         let row3 = CreateActionRow::Buttons(vec![save_btn, cancel_btn]);
 
@@ -188,17 +264,52 @@ mod tests {
         assert!(!state.allow_modification);
         assert!(state.restrictions_note.is_none());
         assert!(!state.allow_backup);
+        assert!(!state.allow_commercial);
+        assert!(!state.require_attribution);
+        assert!(state.color.is_none());
     }
 
     #[test]
-    fn test_license_edit_state_from_existing() {
-        let state = LicenseEditState::from_existing(
-            "Existing License".to_string(),
-            true,
-            false,
-            Some("Some restrictions".to_string()),
-            true,
+    fn test_license_edit_state_with_defaults() {
+        let defaults = NewLicenseDefaults {
+            allow_redistribution: true,
+            allow_modification: false,
+            allow_backup: true,
+            restrictions_note: Some("请注明原作者".to_string()),
+        };
+        let state = LicenseEditState::with_defaults("我的协议1".to_string(), &defaults);
+        assert_eq!(state.license_name, "我的协议1");
+        assert!(state.allow_redistribution);
+        assert!(!state.allow_modification);
+        assert!(state.allow_backup);
+        assert_eq!(state.restrictions_note, Some("请注明原作者".to_string()));
+        // 未配置的字段保持关闭
+        assert!(!state.allow_commercial);
+        assert!(!state.require_attribution);
+        assert!(state.color.is_none());
+
+        // 默认配置与 `new` 一致
+        let state = LicenseEditState::with_defaults(
+            "Test License".to_string(),
+            &NewLicenseDefaults::default(),
         );
+        assert!(!state.allow_redistribution);
+        assert!(!state.allow_backup);
+        assert!(state.restrictions_note.is_none());
+    }
+
+    #[test]
+    fn test_license_edit_state_from_existing() {
+        let state = LicenseEditState::from_existing(LicenseFields {
+            license_name: "Existing License".to_string(),
+            allow_redistribution: true,
+            allow_modification: false,
+            restrictions_note: Some("Some restrictions".to_string()),
+            allow_backup: true,
+            allow_commercial: true,
+            require_attribution: false,
+            color: Some(0x5865F2),
+        });
         assert_eq!(state.license_name, "Existing License");
         assert!(state.allow_redistribution);
         assert!(!state.allow_modification);
@@ -207,13 +318,35 @@ mod tests {
             Some("Some restrictions".to_string())
         );
         assert!(state.allow_backup);
+        assert!(state.allow_commercial);
+        assert!(!state.require_attribution);
+        assert_eq!(state.color, Some(0x5865F2));
+    }
+
+    #[test]
+    fn test_parse_hex_color() {
+        assert_eq!(parse_hex_color("#5865F2").unwrap(), 0x5865F2);
+        assert_eq!(parse_hex_color("ff0000").unwrap(), 0xFF0000);
+        assert_eq!(parse_hex_color(" #00ff00 ").unwrap(), 0x00FF00);
+        assert!(matches!(
+            parse_hex_color("#12345"),
+            Err(BotError::ValidationError { .. })
+        ));
+        assert!(matches!(
+            parse_hex_color("#GGGGGG"),
+            Err(BotError::ValidationError { .. })
+        ));
+        assert!(matches!(
+            parse_hex_color("+12345"),
+            Err(BotError::ValidationError { .. })
+        ));
     }
 
     #[test]
     fn test_editor_core_build_ui() {
         let state = LicenseEditState::new("Test License".to_string());
         let core = EditorCore::new(state);
-        let (_embed, components) = core.build_ui();
+        let (_embed, components) = core.build_ui(1, Locale::default());
 
         assert_eq!(components.len(), 3); // 3 rows of buttons
         // 验证embed已创建，无需检查内部字段
@@ -0,0 +1,125 @@
+use std::{sync::OnceLock, time::Duration};
+
+use moka::future::Cache;
+
+use super::editor_core::LicenseEditState;
+
+/// 编辑会话存活时间，与编辑器交互超时保持一致
+const SESSION_TTL_SECS: u64 = 600;
+/// custom_id 中动作与会话ID之间的分隔符
+const SESSION_SEPARATOR: char = ':';
+
+/// 编辑器会话存储，按会话ID保存协议编辑状态
+///
+/// 会话ID编码在组件和Modal的 custom_id 中，
+/// 使Modal提交等跨交互操作可以准确取回编辑状态。
+#[derive(Clone)]
+pub struct EditorSessionStore {
+    sessions: Cache<u64, LicenseEditState>,
+}
+
+impl EditorSessionStore {
+    /// 创建指定存活时间的会话存储
+    pub fn new(ttl: Duration) -> Self {
+        Self {
+            sessions: Cache::builder()
+                .time_to_live(ttl)
+                .max_capacity(10_000)
+                .build(),
+        }
+    }
+
+    /// 获取全局会话存储
+    pub fn global() -> &'static Self {
+        static STORE: OnceLock<EditorSessionStore> = OnceLock::new();
+        STORE.get_or_init(|| Self::new(Duration::from_secs(SESSION_TTL_SECS)))
+    }
+
+    /// 写入（或覆盖）会话状态
+    pub async fn insert(&self, session_id: u64, state: LicenseEditState) {
+        self.sessions.insert(session_id, state).await;
+    }
+
+    /// 读取会话状态，过期或不存在时返回 `None`
+    pub async fn get(&self, session_id: u64) -> Option<LicenseEditState> {
+        self.sessions.get(&session_id).await
+    }
+
+    /// 移除会话
+    pub async fn remove(&self, session_id: u64) {
+        self.sessions.invalidate(&session_id).await;
+    }
+}
+
+/// 将会话ID编码进 custom_id，格式为 `{action}:{session_id}`
+pub fn encode_custom_id(action: &str, session_id: u64) -> String {
+    format!("{action}{SESSION_SEPARATOR}{session_id}")
+}
+
+/// 从 custom_id 中解析动作和会话ID
+///
+/// 会话ID始终是最后一个分隔符之后的数字段，动作名本身可以包含下划线。
+pub fn decode_custom_id(custom_id: &str) -> Option<(&str, u64)> {
+    let (action, session_id) = custom_id.rsplit_once(SESSION_SEPARATOR)?;
+    if action.is_empty() {
+        return None;
+    }
+    Some((action, session_id.parse().ok()?))
+}
+
+/// 获取 custom_id 对应的动作名，未编码会话ID时原样返回
+pub fn custom_id_action(custom_id: &str) -> &str {
+    decode_custom_id(custom_id).map_or(custom_id, |(action, _)| action)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_session_store_insert_get_remove() {
+        let store = EditorSessionStore::new(Duration::from_secs(60));
+        let mut state = LicenseEditState::new("Test License".to_string());
+        state.allow_backup = true;
+
+        store.insert(42, state).await;
+        let loaded = store.get(42).await.expect("session should exist");
+        assert_eq!(loaded.license_name, "Test License");
+        assert!(loaded.allow_backup);
+        assert!(store.get(43).await.is_none());
+
+        store.remove(42).await;
+        assert!(store.get(42).await.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_session_store_expiry() {
+        let store = EditorSessionStore::new(Duration::from_millis(50));
+        store
+            .insert(1, LicenseEditState::new("Expiring".to_string()))
+            .await;
+        assert!(store.get(1).await.is_some());
+
+        tokio::time::sleep(Duration::from_millis(120)).await;
+        assert!(store.get(1).await.is_none());
+    }
+
+    #[test]
+    fn test_custom_id_round_trip() {
+        let custom_id = encode_custom_id("edit_restrictions_modal", 1_234_567_890_123_456_789);
+        assert_eq!(
+            decode_custom_id(&custom_id),
+            Some(("edit_restrictions_modal", 1_234_567_890_123_456_789))
+        );
+        assert_eq!(custom_id_action(&custom_id), "edit_restrictions_modal");
+    }
+
+    #[test]
+    fn test_decode_custom_id_malformed() {
+        assert_eq!(decode_custom_id("save_license"), None);
+        assert_eq!(decode_custom_id("save_license:abc"), None);
+        assert_eq!(decode_custom_id(":123"), None);
+        assert_eq!(decode_custom_id("save_license:"), None);
+        assert_eq!(custom_id_action("save_license"), "save_license");
+    }
+}
@@ -1,57 +1,207 @@
 use entities::user_licenses::Model as UserLicense;
-use serenity::all::{Colour, CreateEmbed, CreateEmbedFooter, Timestamp};
-
-// 常用字符串常量
-const PERMISSION_ALLOWED: &str = "✅ 允许";
-const PERMISSION_DENIED: &str = "❌ 不允许";
-const COMMERCIAL_USE_DENIED: &str = "❌ 社区不允许任何作品用于商业化";
-const NO_RESTRICTIONS: &str = "无特殊限制";
-const LICENSE_PROTECTION_TEXT: &str = "本作品内容受以下授权协议保护：";
-const REDISTRIBUTION_FIELD: &str = "社区内二次传播";
-const MODIFICATION_FIELD: &str = "社区内二次修改";
-const BACKUP_FIELD: &str = "管理组备份";
-const COMMERCIAL_FIELD: &str = "商业化使用";
-const RESTRICTIONS_FIELD: &str = "限制条件";
+use serenity::all::{Colour, CreateEmbed, CreateEmbedFooter, Embed, Timestamp};
+
+use super::locale::{LicenseLabels, Locale};
+use crate::config::Branding;
+
+// Discord embed 长度限制
+const EMBED_MAX_FIELDS: usize = 25;
+const EMBED_MAX_TOTAL_CHARS: usize = 6000;
+const EMBED_TITLE_MAX_CHARS: usize = 256;
+const EMBED_DESCRIPTION_MAX_CHARS: usize = 4096;
+const EMBED_FIELD_NAME_MAX_CHARS: usize = 256;
+const EMBED_FIELD_VALUE_MAX_CHARS: usize = 1024;
+const EMBED_FOOTER_MAX_CHARS: usize = 2048;
+const OVERFLOW_FIELD_NAME: &str = "其余字段";
+
+/// 按字符数截断文本，超出时以省略号结尾
+fn truncate_chars(text: &str, max_chars: usize) -> String {
+    if text.chars().count() <= max_chars {
+        return text.to_string();
+    }
+    let mut truncated: String = text.chars().take(max_chars.saturating_sub(1)).collect();
+    truncated.push('…');
+    truncated
+}
+
+/// 将字段裁剪到 Discord 限制之内
+///
+/// 超出数量上限的字段会合并为一个"其余字段"，
+/// `reserved_chars` 为标题、描述、页脚已占用的字符数。
+fn fit_embed_fields(
+    fields: &[(String, String, bool)],
+    reserved_chars: usize,
+) -> Vec<(String, String, bool)> {
+    let mut fitted: Vec<(String, String, bool)> = fields
+        .iter()
+        .take(if fields.len() > EMBED_MAX_FIELDS {
+            EMBED_MAX_FIELDS - 1
+        } else {
+            EMBED_MAX_FIELDS
+        })
+        .map(|(name, value, inline)| {
+            (
+                truncate_chars(name, EMBED_FIELD_NAME_MAX_CHARS),
+                truncate_chars(value, EMBED_FIELD_VALUE_MAX_CHARS),
+                *inline,
+            )
+        })
+        .collect();
+
+    if fields.len() > EMBED_MAX_FIELDS {
+        let merged = fields[EMBED_MAX_FIELDS - 1..]
+            .iter()
+            .map(|(name, value, _)| format!("{name}: {value}"))
+            .collect::<Vec<_>>()
+            .join("\n");
+        fitted.push((
+            OVERFLOW_FIELD_NAME.to_string(),
+            truncate_chars(&merged, EMBED_FIELD_VALUE_MAX_CHARS),
+            false,
+        ));
+    }
+
+    // 控制总字符数，超出预算的字段截断或丢弃
+    let mut budget = EMBED_MAX_TOTAL_CHARS.saturating_sub(reserved_chars);
+    let mut result = Vec::with_capacity(fitted.len());
+    for (name, value, inline) in fitted {
+        let name_len = name.chars().count();
+        let value_len = value.chars().count();
+        if name_len + value_len <= budget {
+            budget -= name_len + value_len;
+            result.push((name, value, inline));
+        } else {
+            if budget > name_len + 1 {
+                let value = truncate_chars(&value, budget - name_len);
+                result.push((name, value, inline));
+            }
+            break;
+        }
+    }
+    result
+}
+
+/// 已发布协议中与协议当前内容不一致的字段
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StaleField {
+    /// 字段名
+    pub name: String,
+    /// 已发布的值，字段不存在时为 `None`
+    pub published: Option<String>,
+    /// 当前协议对应的值，当前布局中没有该字段时为 `None`
+    pub current: Option<String>,
+}
 
 /// 协议相关的嵌入消息构建工具
 pub struct LicenseEmbedBuilder;
 
 impl LicenseEmbedBuilder {
     /// 格式化权限值
-    fn format_permission(allowed: bool) -> &'static str {
+    fn format_permission(labels: &LicenseLabels, allowed: bool) -> &'static str {
         if allowed {
-            PERMISSION_ALLOWED
+            labels.permission_allowed
         } else {
-            PERMISSION_DENIED
+            labels.permission_denied
+        }
+    }
+
+    /// 协议卡片颜色，未设置时使用蓝色
+    fn license_colour(color: Option<i32>) -> Colour {
+        color.map_or(Colour::BLUE, |rgb| Colour::new(rgb as u32))
+    }
+
+    /// 已发布协议的颜色：协议自身颜色 > 服务器品牌颜色 > 蓝色
+    fn branded_colour(color: Option<i32>, branding: Option<&Branding>) -> Colour {
+        match (color, branding.and_then(|b| b.color)) {
+            (None, Some(rgb)) => Colour::new(rgb),
+            _ => Self::license_colour(color),
+        }
+    }
+
+    /// 已发布协议的页脚，服务器配置了页脚文本时附加在作者之后
+    fn branded_footer(
+        labels: &LicenseLabels,
+        display_name: &str,
+        branding: Option<&Branding>,
+    ) -> CreateEmbedFooter {
+        CreateEmbedFooter::new(Self::branded_footer_text(labels, display_name, branding))
+    }
+
+    fn branded_footer_text(
+        labels: &LicenseLabels,
+        display_name: &str,
+        branding: Option<&Branding>,
+    ) -> String {
+        let author = format!("{}: {display_name}", labels.author_prefix);
+        let text = match branding.and_then(|b| b.footer_text.as_deref()) {
+            Some(footer_text) => format!("{author} | {footer_text}"),
+            None => author,
+        };
+        truncate_chars(&text, EMBED_FOOTER_MAX_CHARS)
+    }
+
+    /// 应用服务器品牌的缩略图
+    fn apply_branding_thumbnail(embed: CreateEmbed, branding: Option<&Branding>) -> CreateEmbed {
+        match branding.and_then(|b| b.thumbnail_url.as_deref()) {
+            Some(url) => embed.thumbnail(url),
+            None => embed,
         }
     }
 
     /// 添加协议权限字段到embed
+    #[allow(clippy::too_many_arguments)]
     fn add_license_fields(
         embed: CreateEmbed,
+        locale: Locale,
         allow_redistribution: bool,
         allow_modification: bool,
         allow_backup: bool,
+        allow_commercial: bool,
+        require_attribution: bool,
         restrictions_note: Option<&str>,
     ) -> CreateEmbed {
-        embed
+        let labels = locale.labels();
+        let embed = embed
             .field(
-                REDISTRIBUTION_FIELD,
-                Self::format_permission(allow_redistribution),
+                labels.redistribution_field,
+                Self::format_permission(labels, allow_redistribution),
                 true,
             )
             .field(
-                MODIFICATION_FIELD,
-                Self::format_permission(allow_modification),
+                labels.modification_field,
+                Self::format_permission(labels, allow_modification),
                 true,
             )
-            .field(BACKUP_FIELD, Self::format_permission(allow_backup), true)
-            .field(COMMERCIAL_FIELD, COMMERCIAL_USE_DENIED, true)
             .field(
-                RESTRICTIONS_FIELD,
-                restrictions_note.unwrap_or(NO_RESTRICTIONS),
-                false,
+                labels.backup_field,
+                Self::format_permission(labels, allow_backup),
+                true,
             )
+            .field(
+                labels.commercial_field,
+                if allow_commercial {
+                    labels.permission_allowed
+                } else {
+                    labels.commercial_use_denied
+                },
+                true,
+            );
+
+        // 仅在需要署名时显示，避免打乱现有字段布局
+        let embed = if require_attribution {
+            embed.field(labels.attribution_field, labels.attribution_required, true)
+        } else {
+            embed
+        };
+
+        embed.field(
+            labels.restrictions_field,
+            truncate_chars(
+                restrictions_note.unwrap_or(labels.no_restrictions),
+                EMBED_FIELD_VALUE_MAX_CHARS,
+            ),
+            false,
+        )
     }
     /// 创建协议管理主菜单embed
     pub fn create_license_manager_embed() -> CreateEmbed {
@@ -62,21 +212,41 @@ impl LicenseEmbedBuilder {
     }
 
     /// 创建协议详情展示embed
-    pub fn create_license_detail_embed(license: &UserLicense) -> CreateEmbed {
+    pub fn create_license_detail_embed(license: &UserLicense, locale: Locale) -> CreateEmbed {
+        let labels = locale.labels();
         let embed = CreateEmbed::new()
-            .title(format!("📜 授权协议: {}", license.license_name))
-            .description(LICENSE_PROTECTION_TEXT)
-            .colour(Colour::BLUE);
+            .title(truncate_chars(
+                &format!("{}: {}", labels.license_title, license.license_name),
+                EMBED_TITLE_MAX_CHARS,
+            ))
+            .description(labels.license_protection_text)
+            .colour(Self::license_colour(license.color));
 
         Self::add_license_fields(
             embed,
+            locale,
             license.allow_redistribution,
             license.allow_modification,
             license.allow_backup,
+            license.allow_commercial,
+            license.require_attribution,
             license.restrictions_note.as_deref(),
         )
     }
 
+    /// 创建只读的协议详情embed，footer中附带使用次数与创建时间
+    pub fn create_license_inspection_embed(license: &UserLicense, locale: Locale) -> CreateEmbed {
+        let labels = locale.labels();
+        Self::create_license_detail_embed(license, locale).footer(CreateEmbedFooter::new(format!(
+            "ID: {} | {}: {} | {}: {}",
+            license.id,
+            labels.usage_count_label,
+            license.usage_count,
+            labels.created_at_label,
+            license.created_at.format("%Y-%m-%d %H:%M UTC")
+        )))
+    }
+
     /// 创建协议删除成功embed
     pub fn create_license_deleted_embed(license_name: &str) -> CreateEmbed {
         CreateEmbed::new()
@@ -85,20 +255,46 @@ impl LicenseEmbedBuilder {
             .colour(serenity::all::colours::branding::GREEN)
     }
 
+    /// 创建批量删除成功embed
+    pub fn create_licenses_bulk_deleted_embed(count: usize) -> CreateEmbed {
+        CreateEmbed::new()
+            .title("✅ 协议已删除")
+            .description(format!("已成功删除 {count} 个协议。"))
+            .colour(serenity::all::colours::branding::GREEN)
+    }
+
     /// 创建协议预览embed
+    #[allow(clippy::too_many_arguments)]
     pub fn create_license_preview_embed(
         name: &str,
         redis: bool,
         modify: bool,
         rest: Option<&str>,
         backup: Option<bool>,
+        commercial: bool,
+        attribution: bool,
+        color: Option<i32>,
+        locale: Locale,
     ) -> CreateEmbed {
+        let labels = locale.labels();
         let embed = CreateEmbed::new()
-            .title(format!("📜 授权协议: {name}"))
-            .description(LICENSE_PROTECTION_TEXT)
-            .colour(Colour::BLUE);
+            .title(truncate_chars(
+                &format!("{}: {name}", labels.license_title),
+                EMBED_TITLE_MAX_CHARS,
+            ))
+            .description(labels.license_protection_text)
+            .colour(Self::license_colour(color));
 
-        Self::add_license_fields(embed, redis, modify, backup.unwrap_or(false), rest)
+        Self::add_license_fields(
+            embed,
+            locale,
+            redis,
+            modify,
+            backup.unwrap_or(false),
+            commercial,
+            attribution,
+            rest,
+        )
     }
 
     /// 创建协议发布成功embed
@@ -110,12 +306,15 @@ impl LicenseEmbedBuilder {
     }
 
     /// 创建自动发布设置embed
+    ///
+    /// `default_license_reset` 表示默认协议因已失效被自动重置
     pub fn create_auto_publish_settings_embed(
         auto_copyright: bool,
         license_name: String,
         skip_confirmation: bool,
         is_system_license: bool,
         default_system_license_backup: Option<bool>,
+        default_license_reset: bool,
     ) -> CreateEmbed {
         let status_icon = if auto_copyright { "🟢" } else { "🔴" };
         let status_text = if auto_copyright {
@@ -134,7 +333,9 @@ impl LicenseEmbedBuilder {
             )
             .field(
                 "📜 默认协议",
-                if license_name == "未设置" {
+                if default_license_reset {
+                    "⚠️ 默认协议已失效，已重置".to_string()
+                } else if license_name == "未设置" {
                     "❌ 未设置".to_string()
                 } else {
                     format!("✅ {license_name}")
@@ -176,21 +377,277 @@ impl LicenseEmbedBuilder {
         license: &UserLicense,
         backup_allowed: bool,
         display_name: &str,
+        locale: Locale,
+        community_rules_url: Option<&str>,
+        branding: Option<&Branding>,
     ) -> CreateEmbed {
+        let labels = locale.labels();
         let embed = CreateEmbed::new()
-            .title("📜 授权协议")
-            .description(LICENSE_PROTECTION_TEXT)
-            .colour(Colour::BLUE);
+            .title(labels.license_title)
+            .description(labels.license_protection_text)
+            .colour(Self::branded_colour(license.color, branding));
 
-        Self::add_license_fields(
+        let mut embed = Self::add_license_fields(
             embed,
+            locale,
             license.allow_redistribution,
             license.allow_modification,
             backup_allowed,
+            license.allow_commercial,
+            license.require_attribution,
             license.restrictions_note.as_deref(),
-        )
-        .footer(CreateEmbedFooter::new(format!("作者: {display_name}")))
-        .timestamp(Timestamp::now())
+        );
+
+        // 社区规则链接（仅在配置后显示）
+        if let Some(url) = community_rules_url {
+            embed = embed.field(
+                labels.community_rules_field,
+                truncate_chars(url, EMBED_FIELD_VALUE_MAX_CHARS),
+                false,
+            );
+        }
+
+        Self::apply_branding_thumbnail(embed, branding)
+            .footer(Self::branded_footer(labels, display_name, branding))
+            .timestamp(Timestamp::now())
+    }
+
+    /// 创建双重授权协议embed
+    ///
+    /// 每个协议汇总为一个字段，备份权限属于帖子本身，只显示一次，
+    /// 字段名与单协议embed保持一致以便管理员覆盖时定位。
+    pub fn create_dual_license_embed(
+        primary: &UserLicense,
+        secondary: &UserLicense,
+        backup_allowed: bool,
+        display_name: &str,
+        locale: Locale,
+        community_rules_url: Option<&str>,
+        branding: Option<&Branding>,
+    ) -> CreateEmbed {
+        let labels = locale.labels();
+        let mut embed = CreateEmbed::new()
+            .title(labels.dual_license_title)
+            .description(labels.dual_license_text)
+            .colour(Self::branded_colour(primary.color, branding));
+
+        for (index, license) in [primary, secondary].into_iter().enumerate() {
+            embed = embed.field(
+                truncate_chars(
+                    &format!(
+                        "{} {}: {}",
+                        labels.dual_license_option,
+                        index + 1,
+                        license.license_name
+                    ),
+                    EMBED_FIELD_NAME_MAX_CHARS,
+                ),
+                truncate_chars(
+                    &Self::license_summary(license, labels),
+                    EMBED_FIELD_VALUE_MAX_CHARS,
+                ),
+                false,
+            );
+        }
+
+        embed = embed.field(
+            labels.backup_field,
+            Self::format_permission(labels, backup_allowed),
+            true,
+        );
+
+        // 社区规则链接（仅在配置后显示）
+        if let Some(url) = community_rules_url {
+            embed = embed.field(
+                labels.community_rules_field,
+                truncate_chars(url, EMBED_FIELD_VALUE_MAX_CHARS),
+                false,
+            );
+        }
+
+        Self::apply_branding_thumbnail(embed, branding)
+            .footer(Self::branded_footer(labels, display_name, branding))
+            .timestamp(Timestamp::now())
+    }
+
+    /// 单协议embed中由协议内容决定的字段（不含帖子级别的备份权限）
+    ///
+    /// 不需要署名时署名字段为 `None`，与 `add_license_fields` 的布局一致。
+    fn license_content_fields(
+        license: &UserLicense,
+        labels: &LicenseLabels,
+    ) -> [(&'static str, Option<String>); 5] {
+        [
+            (
+                labels.redistribution_field,
+                Some(Self::format_permission(labels, license.allow_redistribution).to_string()),
+            ),
+            (
+                labels.modification_field,
+                Some(Self::format_permission(labels, license.allow_modification).to_string()),
+            ),
+            (
+                labels.commercial_field,
+                Some(
+                    if license.allow_commercial {
+                        labels.permission_allowed
+                    } else {
+                        labels.commercial_use_denied
+                    }
+                    .to_string(),
+                ),
+            ),
+            (
+                labels.attribution_field,
+                license
+                    .require_attribution
+                    .then(|| labels.attribution_required.to_string()),
+            ),
+            (
+                labels.restrictions_field,
+                Some(truncate_chars(
+                    license
+                        .restrictions_note
+                        .as_deref()
+                        .unwrap_or(labels.no_restrictions),
+                    EMBED_FIELD_VALUE_MAX_CHARS,
+                )),
+            ),
+        ]
+    }
+
+    /// 将单个协议的权限汇总为多行文本（不含帖子级别的备份权限）
+    fn license_summary(license: &UserLicense, labels: &LicenseLabels) -> String {
+        Self::license_content_fields(license, labels)
+            .into_iter()
+            .filter_map(|(name, value)| Some(format!("{name}: {}", value?)))
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    /// 判断已发布的embed是否为双重授权协议
+    pub fn is_dual_license_embed(embed: &Embed) -> bool {
+        [Locale::ZhCn, Locale::EnUs]
+            .into_iter()
+            .any(|locale| embed.title.as_deref() == Some(locale.labels().dual_license_title))
+    }
+
+    /// 比较已发布的单协议embed与协议当前内容，返回过期的字段
+    ///
+    /// 按字段名匹配（兼容所有语言），备份权限属于帖子本身，不参与比较。
+    pub fn stale_fields(embed: &Embed, license: &UserLicense) -> Vec<StaleField> {
+        let locale = [Locale::ZhCn, Locale::EnUs]
+            .into_iter()
+            .find(|locale| {
+                embed
+                    .fields
+                    .iter()
+                    .any(|f| f.name == locale.labels().redistribution_field)
+            })
+            .unwrap_or_default();
+
+        Self::license_content_fields(license, locale.labels())
+            .into_iter()
+            .filter_map(|(name, current)| {
+                let published = embed
+                    .fields
+                    .iter()
+                    .find(|f| f.name == name)
+                    .map(|f| f.value.clone());
+                (published != current).then(|| StaleField {
+                    name: name.to_string(),
+                    published,
+                    current,
+                })
+            })
+            .collect()
+    }
+
+    /// 创建已发布协议对比结果embed
+    pub fn create_stale_fields_embed(license_name: &str, stale: &[StaleField]) -> CreateEmbed {
+        let embed = CreateEmbed::new().title(truncate_chars(
+            &format!("🔍 对比已发布协议: {license_name}"),
+            EMBED_TITLE_MAX_CHARS,
+        ));
+        if stale.is_empty() {
+            return embed
+                .description("已发布的协议与当前协议一致，无需更新。")
+                .colour(Colour::DARK_GREEN);
+        }
+
+        let fields: Vec<(String, String, bool)> = stale
+            .iter()
+            .map(|field| {
+                (
+                    format!("⚠️ {}", field.name),
+                    format!(
+                        "已发布: {}\n当前: {}",
+                        field.published.as_deref().unwrap_or("（无）"),
+                        field.current.as_deref().unwrap_or("（无）")
+                    ),
+                    false,
+                )
+            })
+            .collect();
+        let description = "以下字段与当前协议不一致，可点击下方按钮重新发布。";
+        let mut embed = embed.description(description).colour(Colour::GOLD);
+        for (name, value, inline) in fit_embed_fields(&fields, description.chars().count()) {
+            embed = embed.field(name, value, inline);
+        }
+        embed
+    }
+
+    /// 重新渲染已发布协议embed中的备份权限字段
+    pub fn create_backup_overridden_embed(original: &Embed, backup_allowed: bool) -> CreateEmbed {
+        let mut embed = CreateEmbed::new();
+        if let Some(title) = &original.title {
+            embed = embed.title(title);
+        }
+        if let Some(description) = &original.description {
+            embed = embed.description(description);
+        }
+        if let Some(colour) = original.colour {
+            embed = embed.colour(colour);
+        }
+        if let Some(footer) = &original.footer {
+            embed = embed.footer(CreateEmbedFooter::new(&footer.text));
+        }
+        if let Some(timestamp) = original.timestamp {
+            embed = embed.timestamp(timestamp);
+        }
+
+        // 按字段名匹配备份字段（兼容所有语言），其余字段原样保留
+        for field in &original.fields {
+            let backup_locale = [Locale::ZhCn, Locale::EnUs]
+                .into_iter()
+                .find(|locale| locale.labels().backup_field == field.name);
+            let value = match backup_locale {
+                Some(locale) => Self::format_permission(locale.labels(), backup_allowed),
+                None => field.value.as_str(),
+            };
+            embed = embed.field(&field.name, value, field.inline);
+        }
+
+        embed
+    }
+
+    /// 以新的作者名和当前服务器品牌重新生成已发布协议的页脚
+    ///
+    /// 根据原页脚的作者前缀识别协议语言，不以任何语言的作者前缀开头时返回 `None`
+    pub fn rename_footer_author(
+        footer: &str,
+        display_name: &str,
+        branding: Option<&Branding>,
+    ) -> Option<String> {
+        let labels = [Locale::ZhCn, Locale::EnUs]
+            .into_iter()
+            .map(Locale::labels)
+            .find(|labels| {
+                footer
+                    .strip_prefix(labels.author_prefix)
+                    .is_some_and(|rest| rest.starts_with(": "))
+            })?;
+        Some(Self::branded_footer_text(labels, display_name, branding))
     }
 
     /// 创建作废协议embed
@@ -200,21 +657,39 @@ impl LicenseEmbedBuilder {
         original_fields: &[(String, String, bool)],
         original_footer: Option<&str>,
     ) -> CreateEmbed {
+        let title = truncate_chars(
+            &format!("⚠️ [已作废] {original_title}"),
+            EMBED_TITLE_MAX_CHARS,
+        );
+        let footer = original_footer
+            .map(|text| truncate_chars(&format!("{text} | 已作废"), EMBED_FOOTER_MAX_CHARS));
+        // 描述最多使用标题和footer之外的剩余总长度
+        let description_budget = EMBED_MAX_TOTAL_CHARS
+            .saturating_sub(title.chars().count())
+            .saturating_sub(footer.as_ref().map_or(0, |f| f.chars().count()))
+            .min(EMBED_DESCRIPTION_MAX_CHARS);
+        let description = truncate_chars(
+            &format!("**此协议已被新协议替换**\n\n{original_description}"),
+            description_budget,
+        );
+
+        let reserved_chars = title.chars().count()
+            + description.chars().count()
+            + footer.as_ref().map_or(0, |f| f.chars().count());
+
         let mut embed = CreateEmbed::new()
-            .title(format!("⚠️ [已作废] {original_title}"))
-            .description(format!(
-                "**此协议已被新协议替换**\n\n{original_description}"
-            ))
+            .title(title)
+            .description(description)
             .colour(Colour::from_rgb(128, 128, 128)); // 灰色表示已作废
 
-        // 添加原有字段
-        for (name, value, inline) in original_fields {
-            embed = embed.field(name, value, *inline);
+        // 添加原有字段（裁剪到Discord限制之内）
+        for (name, value, inline) in fit_embed_fields(original_fields, reserved_chars) {
+            embed = embed.field(name, value, inline);
         }
 
         // 添加footer和时间戳
-        if let Some(footer_text) = original_footer {
-            embed = embed.footer(CreateEmbedFooter::new(format!("{footer_text} | 已作废")));
+        if let Some(footer_text) = footer {
+            embed = embed.footer(CreateEmbedFooter::new(footer_text));
         }
 
         embed.timestamp(Timestamp::now())
@@ -225,6 +700,104 @@ impl LicenseEmbedBuilder {
         Self::create_license_manager_embed().field("无协议", "您还没有创建任何协议。", false)
     }
 
+    /// 创建两个协议的对比embed
+    ///
+    /// 每项权限一个字段，并排列出两个协议的取值，取值不同的字段以 🔸 标出
+    pub fn create_license_comparison_embed(
+        a: &UserLicense,
+        b: &UserLicense,
+        locale: Locale,
+    ) -> CreateEmbed {
+        let labels = locale.labels();
+        let commercial = |allowed: bool| {
+            if allowed {
+                labels.permission_allowed
+            } else {
+                labels.commercial_use_denied
+            }
+        };
+        let attribution = |required: bool| {
+            if required {
+                labels.attribution_required
+            } else {
+                labels.attribution_not_required
+            }
+        };
+        let restrictions = |license: &UserLicense| -> String {
+            // 两个取值共用一个字段，各占一半长度
+            truncate_chars(
+                license
+                    .restrictions_note
+                    .as_deref()
+                    .unwrap_or(labels.no_restrictions),
+                EMBED_FIELD_VALUE_MAX_CHARS / 2 - 8,
+            )
+        };
+
+        let rows = [
+            (
+                labels.redistribution_field,
+                Self::format_permission(labels, a.allow_redistribution).to_string(),
+                Self::format_permission(labels, b.allow_redistribution).to_string(),
+                true,
+            ),
+            (
+                labels.modification_field,
+                Self::format_permission(labels, a.allow_modification).to_string(),
+                Self::format_permission(labels, b.allow_modification).to_string(),
+                true,
+            ),
+            (
+                labels.backup_field,
+                Self::format_permission(labels, a.allow_backup).to_string(),
+                Self::format_permission(labels, b.allow_backup).to_string(),
+                true,
+            ),
+            (
+                labels.commercial_field,
+                commercial(a.allow_commercial).to_string(),
+                commercial(b.allow_commercial).to_string(),
+                true,
+            ),
+            (
+                labels.attribution_field,
+                attribution(a.require_attribution).to_string(),
+                attribution(b.require_attribution).to_string(),
+                true,
+            ),
+            (
+                labels.restrictions_field,
+                restrictions(a),
+                restrictions(b),
+                false,
+            ),
+        ];
+
+        let mut differences = 0;
+        let mut embed = CreateEmbed::new()
+            .title("⚖️ 协议对比")
+            .description(truncate_chars(
+                &format!("**A**: {}\n**B**: {}", a.license_name, b.license_name),
+                EMBED_FIELD_VALUE_MAX_CHARS,
+            ));
+        for (label, value_a, value_b, inline) in rows {
+            let name = if value_a == value_b {
+                label.to_string()
+            } else {
+                differences += 1;
+                format!("🔸 {label}")
+            };
+            embed = embed.field(name, format!("A: {value_a}\nB: {value_b}"), inline);
+        }
+
+        let (footer, colour) = if differences == 0 {
+            ("两个协议的权限完全相同".to_string(), Colour::DARK_GREEN)
+        } else {
+            (format!("🔸 标记的 {differences} 项不同"), Colour::ORANGE)
+        };
+        embed.footer(CreateEmbedFooter::new(footer)).colour(colour)
+    }
+
     /// 创建设置页面无协议embed
     pub fn create_settings_no_license_embed() -> CreateEmbed {
         CreateEmbed::new()
@@ -234,23 +807,476 @@ impl LicenseEmbedBuilder {
     }
 
     /// 创建自动发布预览embed
+    ///
+    /// 显示发布的目标帖子，帖子中已有协议时提示将被替换
     pub fn create_auto_publish_preview_embed(
         license: &UserLicense,
         display_name: &str,
+        locale: Locale,
+        thread_name: &str,
+        thread_url: &str,
+        replaces_existing: bool,
     ) -> CreateEmbed {
+        let mut description = format!(
+            "检测到您启用了自动发布功能，是否要为此帖子发布以下协议？\n\n📍 发布到：[{}]({thread_url})",
+            // 转义方括号，避免帖子名破坏链接格式
+            thread_name.replace('[', "\\[").replace(']', "\\]")
+        );
+        if replaces_existing {
+            description.push_str("\n⚠️ 此帖子已发布协议，将替换现有协议");
+        }
         let embed = CreateEmbed::new()
             .title("📜 准备发布协议")
-            .description("检测到您启用了自动发布功能，是否要为此帖子发布以下协议？")
+            .description(description)
             .colour(Colour::GOLD);
 
         Self::add_license_fields(
             embed,
+            locale,
             license.allow_redistribution,
             license.allow_modification,
             license.allow_backup,
+            license.allow_commercial,
+            license.require_attribution,
             license.restrictions_note.as_deref(),
         )
-        .footer(CreateEmbedFooter::new(format!("作者: {display_name}")))
+        .footer(CreateEmbedFooter::new(truncate_chars(
+            &format!("{}: {display_name}", locale.labels().author_prefix),
+            EMBED_FOOTER_MAX_CHARS,
+        )))
         .timestamp(Timestamp::now())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use chrono::Utc;
+    use serde_json::Value;
+
+    use super::*;
+
+    /// 统计embed中计入Discord总长度限制的字符数
+    fn embed_total_chars(embed: &Value) -> usize {
+        let text_len = |v: &Value| v.as_str().map_or(0, |s| s.chars().count());
+        let fields_len: usize = embed["fields"]
+            .as_array()
+            .map(|fields| {
+                fields
+                    .iter()
+                    .map(|f| text_len(&f["name"]) + text_len(&f["value"]))
+                    .sum()
+            })
+            .unwrap_or(0);
+        text_len(&embed["title"])
+            + text_len(&embed["description"])
+            + text_len(&embed["footer"]["text"])
+            + fields_len
+    }
+
+    fn assert_within_limits(embed: &Value) {
+        let fields = embed["fields"].as_array().cloned().unwrap_or_default();
+        assert!(fields.len() <= EMBED_MAX_FIELDS);
+        for field in &fields {
+            assert!(field["name"].as_str().unwrap().chars().count() <= EMBED_FIELD_NAME_MAX_CHARS);
+            assert!(
+                field["value"].as_str().unwrap().chars().count() <= EMBED_FIELD_VALUE_MAX_CHARS
+            );
+        }
+        assert!(embed_total_chars(embed) <= EMBED_MAX_TOTAL_CHARS);
+    }
+
+    #[test]
+    fn test_obsolete_embed_stays_within_limits() {
+        let fields: Vec<(String, String, bool)> = (0..40)
+            .map(|i| {
+                (
+                    format!("字段{i}{}", "名".repeat(300)),
+                    "值".repeat(1500),
+                    true,
+                )
+            })
+            .collect();
+
+        let embed = LicenseEmbedBuilder::create_obsolete_license_embed(
+            &"标题".repeat(200),
+            &"描述".repeat(3000),
+            &fields,
+            Some(&"页脚".repeat(1500)),
+        );
+        let embed = serde_json::to_value(&embed).unwrap();
+
+        assert_within_limits(&embed);
+        assert!(embed["title"].as_str().unwrap().chars().count() <= EMBED_TITLE_MAX_CHARS);
+    }
+
+    #[test]
+    fn test_fit_embed_fields_merges_overflow() {
+        let fields: Vec<(String, String, bool)> = (0..30)
+            .map(|i| (format!("f{i}"), format!("v{i}"), true))
+            .collect();
+
+        let fitted = fit_embed_fields(&fields, 0);
+        assert_eq!(fitted.len(), EMBED_MAX_FIELDS);
+        let last = fitted.last().unwrap();
+        assert_eq!(last.0, OVERFLOW_FIELD_NAME);
+        assert!(last.1.contains("f24: v24"));
+        assert!(last.1.contains("f29: v29"));
+    }
+
+    fn test_license(restrictions_note: Option<String>) -> UserLicense {
+        UserLicense {
+            id: 1,
+            user_id: 1,
+            license_name: "Test".to_string(),
+            allow_redistribution: true,
+            allow_modification: false,
+            restrictions_note,
+            allow_backup: false,
+            usage_count: 0,
+            created_at: Utc::now(),
+            allow_commercial: false,
+            require_attribution: true,
+            color: None,
+            source: "command".to_string(),
+            sort_order: 0,
+        }
+    }
+
+    #[test]
+    fn test_license_embed_truncates_long_restrictions() {
+        let license = test_license(Some("限".repeat(5000)));
+
+        let embed = LicenseEmbedBuilder::create_license_embed(
+            &license,
+            false,
+            &"名".repeat(3000),
+            Locale::ZhCn,
+            None,
+            None,
+        );
+        let embed = serde_json::to_value(&embed).unwrap();
+
+        assert_within_limits(&embed);
+    }
+
+    #[test]
+    fn test_community_rules_field_only_when_configured() {
+        let license = test_license(None);
+        let has_rules_field = |embed: &Value| {
+            embed["fields"]
+                .as_array()
+                .unwrap()
+                .iter()
+                .any(|f| f["name"] == "社区规则")
+        };
+
+        let embed = LicenseEmbedBuilder::create_license_embed(
+            &license,
+            false,
+            "作者",
+            Locale::ZhCn,
+            None,
+            None,
+        );
+        assert!(!has_rules_field(&serde_json::to_value(&embed).unwrap()));
+
+        let embed = LicenseEmbedBuilder::create_license_embed(
+            &license,
+            false,
+            "作者",
+            Locale::ZhCn,
+            Some("https://example.com/rules"),
+            None,
+        );
+        let embed = serde_json::to_value(&embed).unwrap();
+        assert!(has_rules_field(&embed));
+        assert!(
+            embed["fields"]
+                .as_array()
+                .unwrap()
+                .iter()
+                .any(|f| f["value"] == "https://example.com/rules")
+        );
+    }
+
+    #[test]
+    fn test_dual_license_embed_combines_both_licenses() {
+        let primary = test_license(None);
+        let secondary = UserLicense {
+            id: 2,
+            license_name: "Second".to_string(),
+            allow_commercial: true,
+            require_attribution: false,
+            ..test_license(Some("仅限非盈利".to_string()))
+        };
+
+        let embed = LicenseEmbedBuilder::create_dual_license_embed(
+            &primary,
+            &secondary,
+            true,
+            "作者",
+            Locale::ZhCn,
+            None,
+            None,
+        );
+        let embed = serde_json::to_value(&embed).unwrap();
+        assert_within_limits(&embed);
+        assert_eq!(embed["title"], "📜 双重授权协议");
+
+        let fields = embed["fields"].as_array().unwrap();
+        let names: Vec<&str> = fields.iter().map(|f| f["name"].as_str().unwrap()).collect();
+        assert_eq!(names, ["选项 1: Test", "选项 2: Second", "管理组备份"]);
+
+        // 每个协议的权限分别汇总
+        let first = fields[0]["value"].as_str().unwrap();
+        assert!(first.contains("署名要求: ✅ 需要署名"));
+        assert!(first.contains("限制条件: 无特殊限制"));
+        let second = fields[1]["value"].as_str().unwrap();
+        assert!(second.contains("商业化使用: ✅ 允许"));
+        assert!(second.contains("限制条件: 仅限非盈利"));
+        assert!(!second.contains("署名要求"));
+
+        // 备份权限只显示一次
+        assert_eq!(fields[2]["value"], "✅ 允许");
+    }
+
+    #[test]
+    fn test_license_comparison_marks_differences() {
+        let a = test_license(None);
+        let b = UserLicense {
+            id: 2,
+            license_name: "Second".to_string(),
+            allow_modification: true,
+            require_attribution: false,
+            ..test_license(Some("限".repeat(5000)))
+        };
+
+        let embed = LicenseEmbedBuilder::create_license_comparison_embed(&a, &b, Locale::ZhCn);
+        let embed = serde_json::to_value(&embed).unwrap();
+        assert_within_limits(&embed);
+        assert_eq!(embed["description"], "**A**: Test\n**B**: Second");
+
+        let fields = embed["fields"].as_array().unwrap();
+        let names: Vec<&str> = fields.iter().map(|f| f["name"].as_str().unwrap()).collect();
+        assert_eq!(
+            names,
+            [
+                "社区内二次传播",
+                "🔸 社区内二次修改",
+                "管理组备份",
+                "商业化使用",
+                "🔸 署名要求",
+                "🔸 限制条件"
+            ]
+        );
+        assert_eq!(fields[1]["value"], "A: ❌ 不允许\nB: ✅ 允许");
+        assert_eq!(fields[4]["value"], "A: ✅ 需要署名\nB: ➖ 无需署名");
+        assert_eq!(embed["footer"]["text"], "🔸 标记的 3 项不同");
+
+        // 与自身对比时没有差异
+        let embed = LicenseEmbedBuilder::create_license_comparison_embed(&a, &a, Locale::ZhCn);
+        let embed = serde_json::to_value(&embed).unwrap();
+        assert_eq!(embed["footer"]["text"], "两个协议的权限完全相同");
+    }
+
+    #[test]
+    fn test_auto_publish_preview_shows_target_thread() {
+        let license = test_license(None);
+        let url = "https://discord.com/channels/1/2";
+
+        let embed = LicenseEmbedBuilder::create_auto_publish_preview_embed(
+            &license,
+            "作者",
+            Locale::ZhCn,
+            "我的[新]作品",
+            url,
+            false,
+        );
+        let description = serde_json::to_value(&embed).unwrap()["description"]
+            .as_str()
+            .unwrap()
+            .to_string();
+        assert!(description.contains("[我的\\[新\\]作品](https://discord.com/channels/1/2)"));
+        assert!(!description.contains("将替换现有协议"));
+
+        let embed = LicenseEmbedBuilder::create_auto_publish_preview_embed(
+            &license,
+            "作者",
+            Locale::ZhCn,
+            "我的作品",
+            url,
+            true,
+        );
+        let json = serde_json::to_value(&embed).unwrap();
+        assert!(
+            json["description"]
+                .as_str()
+                .unwrap()
+                .contains("将替换现有协议")
+        );
+    }
+
+    #[test]
+    fn test_inspection_embed_footer() {
+        let license = UserLicense {
+            id: 7,
+            usage_count: 3,
+            created_at: chrono::DateTime::parse_from_rfc3339("2025-08-01T12:30:00Z")
+                .unwrap()
+                .with_timezone(&Utc),
+            ..test_license(None)
+        };
+
+        let embed = LicenseEmbedBuilder::create_license_inspection_embed(&license, Locale::ZhCn);
+        let embed = serde_json::to_value(&embed).unwrap();
+        assert_eq!(
+            embed["footer"]["text"],
+            "ID: 7 | 使用次数: 3 | 创建于: 2025-08-01 12:30 UTC"
+        );
+    }
+
+    #[test]
+    fn test_guild_branding_overrides_defaults() {
+        let license = test_license(None);
+        let branding = Branding {
+            color: Some(0x123456),
+            footer_text: Some("示例社区".to_string()),
+            thumbnail_url: Some("https://example.com/icon.png".to_string()),
+        };
+
+        let default_embed = serde_json::to_value(LicenseEmbedBuilder::create_license_embed(
+            &license,
+            false,
+            "作者",
+            Locale::ZhCn,
+            None,
+            None,
+        ))
+        .unwrap();
+        assert_eq!(default_embed["color"], Colour::BLUE.0);
+        assert_eq!(default_embed["footer"]["text"], "作者: 作者");
+        assert!(default_embed.get("thumbnail").is_none());
+
+        let branded_embed = serde_json::to_value(LicenseEmbedBuilder::create_license_embed(
+            &license,
+            false,
+            "作者",
+            Locale::ZhCn,
+            None,
+            Some(&branding),
+        ))
+        .unwrap();
+        assert_eq!(branded_embed["color"], 0x123456);
+        assert_eq!(branded_embed["footer"]["text"], "作者: 作者 | 示例社区");
+        assert_eq!(
+            branded_embed["thumbnail"]["url"],
+            "https://example.com/icon.png"
+        );
+
+        // 协议自身的颜色优先于服务器品牌颜色
+        let coloured = UserLicense {
+            color: Some(0xABCDEF),
+            ..test_license(None)
+        };
+        let embed = serde_json::to_value(LicenseEmbedBuilder::create_license_embed(
+            &coloured,
+            false,
+            "作者",
+            Locale::ZhCn,
+            None,
+            Some(&branding),
+        ))
+        .unwrap();
+        assert_eq!(embed["color"], 0xABCDEF);
+    }
+
+    #[test]
+    fn test_refresh_footer_author() {
+        let branding = Branding {
+            footer_text: Some("示例社区".to_string()),
+            ..Default::default()
+        };
+
+        // 按当前服务器品牌重新生成页脚
+        assert_eq!(
+            LicenseEmbedBuilder::rename_footer_author(
+                "作者: 旧昵称 | 示例社区",
+                "新昵称",
+                Some(&branding)
+            )
+            .as_deref(),
+            Some("作者: 新昵称 | 示例社区")
+        );
+        assert_eq!(
+            LicenseEmbedBuilder::rename_footer_author("Author: Old Name", "New Name", None)
+                .as_deref(),
+            Some("Author: New Name")
+        );
+        // 名称中包含分隔符时不会破坏页脚
+        assert_eq!(
+            LicenseEmbedBuilder::rename_footer_author(
+                "作者: A | B | 示例社区",
+                "C | D",
+                Some(&branding)
+            )
+            .as_deref(),
+            Some("作者: C | D | 示例社区")
+        );
+        assert_eq!(
+            LicenseEmbedBuilder::rename_footer_author("ID: 7 | 使用次数: 3", "新昵称", None),
+            None
+        );
+    }
+
+    #[test]
+    fn test_stale_fields_between_embed_and_license() {
+        let published = test_license(None);
+        let embed: Embed = serde_json::from_value(
+            serde_json::to_value(LicenseEmbedBuilder::create_license_embed(
+                &published,
+                true,
+                "作者",
+                Locale::ZhCn,
+                None,
+                None,
+            ))
+            .unwrap(),
+        )
+        .unwrap();
+
+        // 内容未变化时没有过期字段，帖子级别的备份权限不参与比较
+        let unchanged = UserLicense {
+            allow_backup: true,
+            ..test_license(None)
+        };
+        assert!(LicenseEmbedBuilder::stale_fields(&embed, &unchanged).is_empty());
+        assert!(!LicenseEmbedBuilder::is_dual_license_embed(&embed));
+
+        let updated = UserLicense {
+            allow_modification: true,
+            require_attribution: false,
+            ..test_license(Some("禁止转载".to_string()))
+        };
+        let stale = LicenseEmbedBuilder::stale_fields(&embed, &updated);
+        assert_eq!(
+            stale,
+            vec![
+                StaleField {
+                    name: "社区内二次修改".to_string(),
+                    published: Some("❌ 不允许".to_string()),
+                    current: Some("✅ 允许".to_string()),
+                },
+                StaleField {
+                    name: "署名要求".to_string(),
+                    published: Some("✅ 需要署名".to_string()),
+                    current: None,
+                },
+                StaleField {
+                    name: "限制条件".to_string(),
+                    published: Some("无特殊限制".to_string()),
+                    current: Some("禁止转载".to_string()),
+                },
+            ]
+        );
+    }
+}
@@ -0,0 +1,67 @@
+/// 首个匹配字符位于开头时的加分
+const PREFIX_BONUS: u32 = 10;
+/// 连续匹配时每个字符额外的加分
+const CONSECUTIVE_BONUS: u32 = 2;
+
+/// 计算 `query` 作为子序列在 `candidate` 中的匹配得分（忽略大小写）
+///
+/// 不匹配时返回 `None`；空查询匹配一切，得分为 0。
+/// 连续匹配和靠前的匹配得分更高，例如 "mt" 可以匹配 "MIT"，
+/// 但 "mit" 对 "MIT" 的得分高于对 "My Inline Text"。
+/// 逐字符比较，不分配内存，适合在每次按键触发的自动补全中使用。
+pub fn fuzzy_score(candidate: &str, query: &str) -> Option<u32> {
+    let mut query_chars = query.chars().flat_map(char::to_lowercase).peekable();
+    if query_chars.peek().is_none() {
+        return Some(0);
+    }
+
+    let mut score = 0;
+    let mut consecutive = 0;
+    let mut first_match = None;
+    for (index, c) in candidate.chars().flat_map(char::to_lowercase).enumerate() {
+        let Some(&expected) = query_chars.peek() else {
+            break;
+        };
+        if c == expected {
+            query_chars.next();
+            first_match.get_or_insert(index);
+            score += 1 + consecutive * CONSECUTIVE_BONUS;
+            consecutive += 1;
+        } else {
+            consecutive = 0;
+        }
+    }
+
+    if query_chars.peek().is_some() {
+        return None;
+    }
+
+    let position_bonus = first_match.map_or(0, |index| PREFIX_BONUS.saturating_sub(index as u32));
+    Some(score + position_bonus)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fuzzy_subsequence_match() {
+        assert!(fuzzy_score("MIT", "mt").is_some());
+        assert!(fuzzy_score("MIT", "mit").is_some());
+        assert!(fuzzy_score("我的协议1", "协议").is_some());
+        assert_eq!(fuzzy_score("MIT", ""), Some(0));
+        assert_eq!(fuzzy_score("MIT", "tm"), None);
+        assert_eq!(fuzzy_score("MIT", "mitx"), None);
+    }
+
+    #[test]
+    fn test_fuzzy_ranking() {
+        let exact = fuzzy_score("MIT", "mit").unwrap();
+        let scattered = fuzzy_score("My Inline Text", "mit").unwrap();
+        assert!(exact > scattered);
+
+        let prefix = fuzzy_score("协议A", "协议").unwrap();
+        let inner = fuzzy_score("我的协议", "协议").unwrap();
+        assert!(prefix > inner);
+    }
+}
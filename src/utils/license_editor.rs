@@ -1,10 +1,18 @@
 use serenity::all::*;
 use tracing::{debug, warn};
 
-use super::editor_core::{EditorCore, LicenseEditState, UIProvider};
+use super::{
+    editor_core::{EditorCore, LicenseEditState, UIProvider, parse_hex_color},
+    editor_session::{EditorSessionStore, custom_id_action, decode_custom_id, encode_custom_id},
+    locale::Locale,
+};
 use crate::{commands::Data, error::BotError};
 
 const INTERACTION_TIMEOUT_SECS: u64 = 600;
+/// 在超时前多少秒提示用户
+const TIMEOUT_WARNING_SECS: u64 = 60;
+/// 编辑器消息的标题文本
+const EDITOR_HEADER: &str = "📝 **协议编辑器** - 点击按钮修改设置";
 
 /// 协议编辑面板
 ///
@@ -34,124 +42,127 @@ pub async fn present_license_editing_panel(
     interaction: &ComponentInteraction,
     initial_state: LicenseEditState,
 ) -> Result<LicenseEditorOutcome, BotError> {
-    // 创建编辑器状态
-    let mut editor_state = LicenseEditor::new(serenity_ctx, data, initial_state);
+    // 创建编辑器状态，以初始交互ID作为会话ID
+    let session_id = interaction.id.get();
+    let mut editor_state = LicenseEditor::new(serenity_ctx, data, session_id, initial_state)
+        .with_locale(Locale::from_discord(&interaction.locale));
+    editor_state.sync_session().await;
+
+    // 发送初始编辑界面并进入编辑循环
+    let result = match editor_state.send_initial_ui(interaction).await {
+        Ok(()) => run_editing_loop(serenity_ctx, interaction, &mut editor_state).await,
+        Err(e) => Err(e),
+    };
+
+    // 会话结束，清理会话存储
+    EditorSessionStore::global().remove(session_id).await;
+    result
+}
+
+/// 编辑器主循环中等待到的事件
+enum EditorEvent {
+    Button(Option<ComponentInteraction>),
+    Modal(Option<ModalInteraction>),
+}
 
-    // 发送初始编辑界面
-    editor_state.send_initial_ui(interaction).await?;
+/// 编辑器主循环
+async fn run_editing_loop(
+    serenity_ctx: &serenity::all::Context,
+    interaction: &ComponentInteraction,
+    editor_state: &mut LicenseEditor<'_>,
+) -> Result<LicenseEditorOutcome, BotError> {
+    let timeout = std::time::Duration::from_secs(INTERACTION_TIMEOUT_SECS);
+    let warning_delay =
+        std::time::Duration::from_secs(INTERACTION_TIMEOUT_SECS - TIMEOUT_WARNING_SECS);
+    // 最近一次以延迟更新确认的交互，超时提示通过它编辑面板
+    let mut latest_interaction = interaction.clone();
 
-    // 主编辑循环 - 使用 tokio::select! 智能处理Modal和按钮交互
+    // 主编辑循环 - 使用 tokio::select! 同时处理Modal、按钮交互和超时提示
     loop {
         // 获取response对象用于监听交互
         let response = interaction.get_response(&serenity_ctx.http).await?;
 
-        // 根据当前Modal状态决定监听策略
-        match editor_state.modal_waiting {
-            ModalWaitingState::None => {
-                // 没有等待中的Modal，只等待按钮交互
-                let Some(edit_interaction) = response
-                    .await_component_interaction(&serenity_ctx.shard)
-                    .author_id(interaction.user.id)
-                    .timeout(std::time::Duration::from_secs(INTERACTION_TIMEOUT_SECS))
-                    .await
-                else {
-                    // 超时，清理UI
-                    editor_state.cleanup_ui(interaction).await?;
-                    return Ok(LicenseEditorOutcome {
-                        state: None,
-                        interaction: None,
-                    });
-                };
+        // 每轮等待重新计时，用户有新操作时超时提示随之重置
+        let warning_at = tokio::time::Instant::now() + warning_delay;
+        let mut warned = false;
+        let waiting_modal = !matches!(editor_state.modal_waiting, ModalWaitingState::None);
+
+        let button_wait = response
+            .await_component_interaction(&serenity_ctx.shard)
+            .author_id(interaction.user.id)
+            .timeout(timeout)
+            .into_future();
+        let modal_wait = response
+            .await_modal_interaction(&serenity_ctx.shard)
+            .into_future();
+        tokio::pin!(button_wait, modal_wait);
+
+        let event = loop {
+            tokio::select! {
+                // 等待Modal提交（仅在有等待中的Modal时）
+                modal_result = &mut modal_wait, if waiting_modal => {
+                    break EditorEvent::Modal(modal_result);
+                }
+
+                // 等待按钮交互
+                button_result = &mut button_wait => {
+                    break EditorEvent::Button(button_result);
+                }
+
+                // 超时前提示用户
+                _ = tokio::time::sleep_until(warning_at), if !warned => {
+                    warned = true;
+                    editor_state.show_timeout_warning(&latest_interaction).await;
+                }
+            }
+        };
+
+        match event {
+            EditorEvent::Modal(Some(modal_interaction)) => {
+                // 处理Modal提交
+                editor_state.handle_modal_submit(&modal_interaction).await?;
+                editor_state.modal_waiting = ModalWaitingState::None;
+
+                // 更新UI显示 - 使用原始interaction编辑响应
+                editor_state.update_ui(interaction).await?;
+            }
+            EditorEvent::Modal(None) => {
+                // Modal被取消，重置状态
+                editor_state.modal_waiting = ModalWaitingState::None;
+            }
+            EditorEvent::Button(None) => {
+                // 超时，清理UI
+                editor_state.cleanup_ui(interaction).await?;
+                return Ok(LicenseEditorOutcome {
+                    state: None,
+                    interaction: None,
+                });
+            }
+            EditorEvent::Button(Some(edit_interaction)) => {
+                // 新的按钮交互到达，放弃Modal等待
+                if waiting_modal {
+                    tracing::info!("New button interaction received, abandoning modal wait");
+                    editor_state.modal_waiting = ModalWaitingState::None;
+                }
 
                 // 处理按钮交互
                 let should_exit = editor_state.handle_interaction(&edit_interaction).await?;
 
                 if should_exit {
                     // 检查是否是保存操作
-                    if edit_interaction.data.custom_id == "save_license" {
-                        editor_state.cleanup_ui(&edit_interaction).await?;
-                        return Ok(LicenseEditorOutcome {
-                            state: Some(editor_state.get_state().clone()),
-                            interaction: Some(edit_interaction.clone()),
-                        });
-                    } else {
-                        editor_state.cleanup_ui(&edit_interaction).await?;
-                        return Ok(LicenseEditorOutcome {
-                            state: None,
-                            interaction: Some(edit_interaction.clone()),
-                        });
-                    }
-                } else {
-                    // 更新UI显示（如果不是Modal操作）
-                    if !matches!(editor_state.modal_waiting, ModalWaitingState::None) {
-                        // Modal已发送，不更新UI，等待Modal处理
-                    } else {
-                        editor_state.update_ui(&edit_interaction).await?;
-                    }
+                    let saved =
+                        custom_id_action(&edit_interaction.data.custom_id) == "save_license";
+                    editor_state.cleanup_ui(&edit_interaction).await?;
+                    return Ok(LicenseEditorOutcome {
+                        state: saved.then(|| editor_state.get_state().clone()),
+                        interaction: Some(edit_interaction),
+                    });
                 }
-            }
-            _ => {
-                // 有等待中的Modal，同时等待Modal提交和新的按钮交互
-                tokio::select! {
-                    // 等待Modal提交
-                    modal_result = response.await_modal_interaction(&serenity_ctx.shard) => {
-                        if let Some(modal_interaction) = modal_result {
-                            // 处理Modal提交
-                            editor_state.handle_modal_submit(&modal_interaction).await?;
-                            editor_state.modal_waiting = ModalWaitingState::None;
-
-                            // 更新UI显示 - 使用原始interaction编辑响应
-                            editor_state.update_ui(interaction).await?;
-                        } else {
-                            // Modal被取消，重置状态
-                            editor_state.modal_waiting = ModalWaitingState::None;
-                        }
-                    }
-
-                    // 等待新的按钮交互
-                    button_result = response.await_component_interaction(&serenity_ctx.shard)
-                        .author_id(interaction.user.id)
-                        .timeout(std::time::Duration::from_secs(INTERACTION_TIMEOUT_SECS)) => {
-
-                        if let Some(edit_interaction) = button_result {
-                            // 新的按钮交互到达，放弃Modal等待
-                            if !matches!(editor_state.modal_waiting, ModalWaitingState::None) {
-                                tracing::info!("New button interaction received, abandoning modal wait");
-                                editor_state.modal_waiting = ModalWaitingState::None;
-                            }
 
-                            // 处理按钮交互
-                            let should_exit = editor_state.handle_interaction(&edit_interaction).await?;
-
-                            if should_exit {
-                                if edit_interaction.data.custom_id == "save_license" {
-                                    editor_state.cleanup_ui(&edit_interaction).await?;
-                                    return Ok(LicenseEditorOutcome {
-                                        state: Some(editor_state.get_state().clone()),
-                                        interaction: Some(edit_interaction.clone()),
-                                    });
-                                } else {
-                                    editor_state.cleanup_ui(&edit_interaction).await?;
-                                    return Ok(LicenseEditorOutcome {
-                                        state: None,
-                                        interaction: Some(edit_interaction.clone()),
-                                    });
-                                }
-                            } else {
-                                // 更新UI显示（如果不是Modal操作）
-                                if matches!(editor_state.modal_waiting, ModalWaitingState::None) {
-                                    editor_state.update_ui(&edit_interaction).await?;
-                                }
-                            }
-                        } else {
-                            // 超时，清理UI
-                            editor_state.cleanup_ui(interaction).await?;
-                            return Ok(LicenseEditorOutcome {
-                                state: None,
-                                interaction: None,
-                            });
-                        }
-                    }
+                // 更新UI显示（如果不是Modal操作）
+                if matches!(editor_state.modal_waiting, ModalWaitingState::None) {
+                    editor_state.update_ui(&edit_interaction).await?;
+                    latest_interaction = edit_interaction;
                 }
             }
         }
@@ -164,11 +175,14 @@ pub enum ModalWaitingState {
     None,
     WaitingForName,
     WaitingForRestrictions,
+    WaitingForColor,
 }
 
 /// 协议编辑器
 pub struct LicenseEditor<'a> {
     serenity_ctx: &'a serenity::all::Context,
+    session_id: u64,
+    locale: Locale,
     core: EditorCore,
     modal_waiting: ModalWaitingState,
 }
@@ -177,19 +191,35 @@ impl<'a> LicenseEditor<'a> {
     pub fn new(
         serenity_ctx: &'a serenity::all::Context,
         _data: &'a Data,
+        session_id: u64,
         state: LicenseEditState,
     ) -> Self {
         Self {
             serenity_ctx,
+            session_id,
+            locale: Locale::default(),
             core: EditorCore::new(state),
             modal_waiting: ModalWaitingState::None,
         }
     }
 
+    /// 设置编辑器预览使用的语言
+    pub fn with_locale(mut self, locale: Locale) -> Self {
+        self.locale = locale;
+        self
+    }
+
     pub fn get_state(&self) -> &LicenseEditState {
         self.core.get_state()
     }
 
+    /// 将当前编辑状态写入会话存储
+    pub async fn sync_session(&self) {
+        EditorSessionStore::global()
+            .insert(self.session_id, self.core.get_state().clone())
+            .await;
+    }
+
     /// 处理Modal提交
     pub async fn handle_modal_submit(
         &mut self,
@@ -203,6 +233,13 @@ impl<'a> LicenseEditor<'a> {
             )
             .await?;
 
+        // 按 custom_id 中的会话ID取回编辑状态
+        if let Some((_, session_id)) = decode_custom_id(&modal_interaction.data.custom_id)
+            && let Some(state) = EditorSessionStore::global().get(session_id).await
+        {
+            *self.core.get_state_mut() = state;
+        }
+
         // 根据等待状态处理不同类型的Modal
         match &self.modal_waiting {
             ModalWaitingState::WaitingForName => {
@@ -241,11 +278,44 @@ impl<'a> LicenseEditor<'a> {
                     );
                 }
             }
+            ModalWaitingState::WaitingForColor => {
+                // 处理颜色编辑，留空恢复默认颜色
+                if let Some(ActionRowComponent::InputText(input)) = modal_interaction
+                    .data
+                    .components
+                    .first()
+                    .and_then(|row| row.components.first())
+                {
+                    let value = input.value.clone().unwrap_or_default();
+                    if value.trim().is_empty() {
+                        self.core.get_state_mut().color = None;
+                    } else {
+                        match parse_hex_color(&value) {
+                            Ok(color) => self.core.get_state_mut().color = Some(color),
+                            Err(e) => {
+                                modal_interaction
+                                    .create_followup(
+                                        &self.serenity_ctx.http,
+                                        CreateInteractionResponseFollowup::new()
+                                            .content(format!("❌ {}", e.user_message()))
+                                            .ephemeral(true),
+                                    )
+                                    .await?;
+                            }
+                        }
+                    }
+                    tracing::info!(
+                        "License color updated to: {:?}",
+                        self.core.get_state().color
+                    );
+                }
+            }
             ModalWaitingState::None => {
                 warn!("Received modal submission but not waiting for any modal");
             }
         }
 
+        self.sync_session().await;
         Ok(())
     }
 }
@@ -285,14 +355,14 @@ impl<'a> LicenseEditor<'a> {
         &self,
         interaction: &ComponentInteraction,
     ) -> Result<(), BotError> {
-        let (embed, components) = self.core.build_ui();
+        let (embed, components) = self.core.build_ui(self.session_id, self.locale);
 
         interaction
             .create_response(
                 &self.serenity_ctx.http,
                 CreateInteractionResponse::Message(
                     CreateInteractionResponseMessage::new()
-                        .content("📝 **协议编辑器** - 点击按钮修改设置")
+                        .content(EDITOR_HEADER)
                         .embed(embed)
                         .components(components)
                         .ephemeral(true),
@@ -305,12 +375,13 @@ impl<'a> LicenseEditor<'a> {
 
     /// 更新编辑界面
     pub async fn update_ui(&self, interaction: &ComponentInteraction) -> Result<(), BotError> {
-        let (embed, components) = self.core.build_ui();
+        let (embed, components) = self.core.build_ui(self.session_id, self.locale);
 
         interaction
             .edit_response(
                 &self.serenity_ctx.http,
                 EditInteractionResponse::default()
+                    .content(EDITOR_HEADER)
                     .embed(embed)
                     .components(components),
             )
@@ -319,6 +390,19 @@ impl<'a> LicenseEditor<'a> {
         Ok(())
     }
 
+    /// 在编辑器消息中追加即将超时的提示，下一次更新界面时会被清除
+    pub async fn show_timeout_warning(&self, interaction: &ComponentInteraction) {
+        if let Err(e) = interaction
+            .edit_response(
+                &self.serenity_ctx.http,
+                EditInteractionResponse::new().content(format!("{EDITOR_HEADER}\n⚠️ 面板即将超时")),
+            )
+            .await
+        {
+            debug!("Failed to show editor timeout warning: {}", e);
+        }
+    }
+
     /// 清理UI - 删除编辑器消息
     pub async fn cleanup_ui(&self, interaction: &ComponentInteraction) -> Result<(), BotError> {
         match interaction.delete_response(&self.serenity_ctx.http).await {
@@ -347,19 +431,21 @@ impl<'a> LicenseEditor<'a> {
         &mut self,
         interaction: &ComponentInteraction,
     ) -> Result<bool, BotError> {
-        match interaction.data.custom_id.as_str() {
+        let result = match custom_id_action(&interaction.data.custom_id) {
             "edit_name" => {
                 // 处理编辑名称 - 发送Modal但不等待结果
-                let modal = CreateModal::new("edit_name_modal", "编辑协议名称").components(vec![
-                    CreateActionRow::InputText(
-                        CreateInputText::new(InputTextStyle::Short, "协议名称", "name_input")
-                            .placeholder("输入协议名称")
-                            .value(&self.core.get_state().license_name)
-                            .min_length(1)
-                            .max_length(50)
-                            .required(true),
-                    ),
-                ]);
+                let modal = CreateModal::new(
+                    encode_custom_id("edit_name_modal", self.session_id),
+                    "编辑协议名称",
+                )
+                .components(vec![CreateActionRow::InputText(
+                    CreateInputText::new(InputTextStyle::Short, "协议名称", "name_input")
+                        .placeholder("输入协议名称")
+                        .value(&self.core.get_state().license_name)
+                        .min_length(1)
+                        .max_length(50)
+                        .required(true),
+                )]);
 
                 // 发送Modal
                 interaction
@@ -379,26 +465,27 @@ impl<'a> LicenseEditor<'a> {
             }
             "edit_restrictions" => {
                 // 处理编辑限制条件 - 发送Modal但不等待结果
-                let modal =
-                    CreateModal::new("edit_restrictions_modal", "编辑限制条件").components(vec![
-                        CreateActionRow::InputText(
-                            CreateInputText::new(
-                                InputTextStyle::Paragraph,
-                                "限制条件",
-                                "restrictions_input",
-                            )
-                            .placeholder("输入限制条件（可选）")
-                            .value(
-                                self.core
-                                    .get_state()
-                                    .restrictions_note
-                                    .clone()
-                                    .unwrap_or_default(),
-                            )
-                            .max_length(1000)
-                            .required(false),
-                        ),
-                    ]);
+                let modal = CreateModal::new(
+                    encode_custom_id("edit_restrictions_modal", self.session_id),
+                    "编辑限制条件",
+                )
+                .components(vec![CreateActionRow::InputText(
+                    CreateInputText::new(
+                        InputTextStyle::Paragraph,
+                        "限制条件",
+                        "restrictions_input",
+                    )
+                    .placeholder("输入限制条件（可选）")
+                    .value(
+                        self.core
+                            .get_state()
+                            .restrictions_note
+                            .clone()
+                            .unwrap_or_default(),
+                    )
+                    .max_length(1000)
+                    .required(false),
+                )]);
 
                 // 发送Modal
                 interaction
@@ -416,6 +503,42 @@ impl<'a> LicenseEditor<'a> {
 
                 Ok(false) // 继续编辑，但现在处于Modal等待状态
             }
+            "edit_color" => {
+                // 处理编辑颜色 - 发送Modal但不等待结果
+                let current = self
+                    .core
+                    .get_state()
+                    .color
+                    .map(|rgb| format!("#{rgb:06X}"))
+                    .unwrap_or_default();
+                let modal = CreateModal::new(
+                    encode_custom_id("edit_color_modal", self.session_id),
+                    "编辑协议颜色",
+                )
+                .components(vec![CreateActionRow::InputText(
+                    CreateInputText::new(InputTextStyle::Short, "颜色", "color_input")
+                        .placeholder("#RRGGBB（留空使用默认蓝色）")
+                        .value(current)
+                        .max_length(7)
+                        .required(false),
+                )]);
+
+                // 发送Modal
+                interaction
+                    .create_response(
+                        &self.serenity_ctx.http,
+                        CreateInteractionResponse::Modal(modal),
+                    )
+                    .await?;
+
+                // 设置Modal等待状态
+                self.modal_waiting = ModalWaitingState::WaitingForColor;
+                tracing::info!(
+                    "Modal sent for color editing, waiting for submission or new interaction"
+                );
+
+                Ok(false) // 继续编辑，但现在处于Modal等待状态
+            }
             "toggle_redistribution" => {
                 self.acknowledge(interaction).await?;
                 self.core.get_state_mut().allow_redistribution =
@@ -433,6 +556,18 @@ impl<'a> LicenseEditor<'a> {
                 self.core.get_state_mut().allow_backup = !self.core.get_state().allow_backup;
                 Ok(false) // 继续编辑
             }
+            "toggle_commercial" => {
+                self.acknowledge(interaction).await?;
+                self.core.get_state_mut().allow_commercial =
+                    !self.core.get_state().allow_commercial;
+                Ok(false) // 继续编辑
+            }
+            "toggle_attribution" => {
+                self.acknowledge(interaction).await?;
+                self.core.get_state_mut().require_attribution =
+                    !self.core.get_state().require_attribution;
+                Ok(false) // 继续编辑
+            }
             "save_license" => {
                 self.acknowledge(interaction).await?;
                 Ok(true) // 保存并退出
@@ -445,6 +580,9 @@ impl<'a> LicenseEditor<'a> {
                 warn!("Unknown interaction: {}", interaction.data.custom_id);
                 Ok(false)
             }
-        }
+        };
+
+        self.sync_session().await;
+        result
     }
 }
@@ -0,0 +1,125 @@
+use serde::{Deserialize, Serialize};
+
+/// 协议嵌入消息使用的语言
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Locale {
+    #[default]
+    #[serde(rename = "zh-CN")]
+    ZhCn,
+    #[serde(rename = "en-US")]
+    EnUs,
+}
+
+/// 协议嵌入消息中的字段标签与权限文本
+#[derive(Debug)]
+pub struct LicenseLabels {
+    pub permission_allowed: &'static str,
+    pub permission_denied: &'static str,
+    pub commercial_use_denied: &'static str,
+    pub no_restrictions: &'static str,
+    pub license_protection_text: &'static str,
+    pub license_title: &'static str,
+    pub author_prefix: &'static str,
+    pub redistribution_field: &'static str,
+    pub modification_field: &'static str,
+    pub backup_field: &'static str,
+    pub commercial_field: &'static str,
+    pub attribution_field: &'static str,
+    pub attribution_required: &'static str,
+    pub attribution_not_required: &'static str,
+    pub restrictions_field: &'static str,
+    pub community_rules_field: &'static str,
+    pub dual_license_title: &'static str,
+    pub dual_license_text: &'static str,
+    pub dual_license_option: &'static str,
+    pub usage_count_label: &'static str,
+    pub created_at_label: &'static str,
+}
+
+const ZH_CN_LABELS: LicenseLabels = LicenseLabels {
+    permission_allowed: "✅ 允许",
+    permission_denied: "❌ 不允许",
+    commercial_use_denied: "❌ 社区不允许任何作品用于商业化",
+    no_restrictions: "无特殊限制",
+    license_protection_text: "本作品内容受以下授权协议保护：",
+    license_title: "📜 授权协议",
+    author_prefix: "作者",
+    redistribution_field: "社区内二次传播",
+    modification_field: "社区内二次修改",
+    backup_field: "管理组备份",
+    commercial_field: "商业化使用",
+    attribution_field: "署名要求",
+    attribution_required: "✅ 需要署名",
+    attribution_not_required: "➖ 无需署名",
+    restrictions_field: "限制条件",
+    community_rules_field: "社区规则",
+    dual_license_title: "📜 双重授权协议",
+    dual_license_text: "本作品可任选以下任一协议使用：",
+    dual_license_option: "选项",
+    usage_count_label: "使用次数",
+    created_at_label: "创建于",
+};
+
+const EN_US_LABELS: LicenseLabels = LicenseLabels {
+    permission_allowed: "✅ Allowed",
+    permission_denied: "❌ Not allowed",
+    commercial_use_denied: "❌ The community does not allow any commercial use",
+    no_restrictions: "No special restrictions",
+    license_protection_text: "This work is protected by the following license:",
+    license_title: "📜 License",
+    author_prefix: "Author",
+    redistribution_field: "Redistribution in community",
+    modification_field: "Modification in community",
+    backup_field: "Moderator backup",
+    commercial_field: "Commercial use",
+    attribution_field: "Attribution",
+    attribution_required: "✅ Credit required",
+    attribution_not_required: "➖ No credit required",
+    restrictions_field: "Restrictions",
+    community_rules_field: "Community rules",
+    dual_license_title: "📜 Dual License",
+    dual_license_text: "This work may be used under either of the following licenses:",
+    dual_license_option: "Option",
+    usage_count_label: "Uses",
+    created_at_label: "Created",
+};
+
+impl Locale {
+    /// 根据 Discord 交互的 locale 字段选择语言，未支持的语言回退到简体中文
+    pub fn from_discord(locale: &str) -> Self {
+        if locale.starts_with("en") {
+            Locale::EnUs
+        } else {
+            Locale::ZhCn
+        }
+    }
+
+    /// 获取该语言的字段标签
+    pub fn labels(self) -> &'static LicenseLabels {
+        match self {
+            Locale::ZhCn => &ZH_CN_LABELS,
+            Locale::EnUs => &EN_US_LABELS,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_from_discord_locale() {
+        assert_eq!(Locale::from_discord("en-US"), Locale::EnUs);
+        assert_eq!(Locale::from_discord("en-GB"), Locale::EnUs);
+        assert_eq!(Locale::from_discord("zh-CN"), Locale::ZhCn);
+        assert_eq!(Locale::from_discord("ja"), Locale::ZhCn);
+        assert_eq!(Locale::from_discord(""), Locale::ZhCn);
+    }
+
+    #[test]
+    fn test_labels_lookup() {
+        assert_eq!(Locale::ZhCn.labels().backup_field, "管理组备份");
+        assert_eq!(Locale::EnUs.labels().backup_field, "Moderator backup");
+        assert_eq!(Locale::default(), Locale::ZhCn);
+    }
+}
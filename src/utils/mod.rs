@@ -1,11 +1,30 @@
+mod attachment;
 mod auto_publish_ui;
 mod children;
+mod display_name;
 mod editor_core;
+mod editor_session;
 mod embed;
+mod fuzzy;
 mod license_editor;
+mod locale;
+mod pagination;
 
+pub use attachment::{MAX_JSON_ATTACHMENT_SIZE, download_json_attachment};
 pub use auto_publish_ui::AutoPublishUI;
 pub use children::get_all_children_channels;
-pub use editor_core::{EditorCore, LicenseEditState, UIProvider};
-pub use embed::LicenseEmbedBuilder;
+pub use display_name::{
+    cached_display_name, member_display_name, member_display_name_by_id,
+    refresh_member_display_name,
+};
+pub use editor_core::{EditorCore, LicenseEditState, UIProvider, parse_hex_color};
+pub use editor_session::{
+    EditorSessionStore, custom_id_action, decode_custom_id, encode_custom_id,
+};
+pub use embed::{LicenseEmbedBuilder, StaleField};
+pub use fuzzy::fuzzy_score;
 pub use license_editor::{LicenseEditorOutcome, present_license_editing_panel};
+pub use locale::{LicenseLabels, Locale};
+pub use pagination::{
+    PAGE_NEXT_ID, PAGE_PREV_ID, Paginator, SELECT_MENU_PAGE_SIZE, paged_select_rows,
+};
@@ -0,0 +1,218 @@
+use serenity::all::{
+    ButtonStyle, CreateActionRow, CreateButton, CreateSelectMenu, CreateSelectMenuOption,
+};
+
+/// 上一页按钮的 custom_id
+pub const PAGE_PREV_ID: &str = "page_prev";
+/// 下一页按钮的 custom_id
+pub const PAGE_NEXT_ID: &str = "page_next";
+/// 页码指示按钮的 custom_id（始终禁用）
+const PAGE_INDICATOR_ID: &str = "page_indicator";
+/// 下拉菜单每页的选项数，Discord 上限为 25，预留一个位置
+pub const SELECT_MENU_PAGE_SIZE: usize = 24;
+
+/// 按钮翻页的状态
+///
+/// 页码从 0 开始，翻页不会越过首页和末页
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Paginator {
+    page: usize,
+    total: usize,
+}
+
+impl Paginator {
+    /// 创建翻页状态，页数至少为 1
+    pub fn new(total: usize) -> Self {
+        Self {
+            page: 0,
+            total: total.max(1),
+        }
+    }
+
+    /// 按每页 `page_size` 个条目为 `count` 个条目创建翻页状态
+    pub fn for_items(count: usize, page_size: usize) -> Self {
+        Self::new(count.div_ceil(page_size.max(1)))
+    }
+
+    /// 取出当前页的条目
+    pub fn page_items<'a, T>(&self, items: &'a [T], page_size: usize) -> &'a [T] {
+        let start = (self.page * page_size).min(items.len());
+        let end = (start + page_size).min(items.len());
+        &items[start..end]
+    }
+
+    pub fn page(&self) -> usize {
+        self.page
+    }
+
+    pub fn total(&self) -> usize {
+        self.total
+    }
+
+    pub fn is_first(&self) -> bool {
+        self.page == 0
+    }
+
+    pub fn is_last(&self) -> bool {
+        self.page + 1 == self.total
+    }
+
+    /// 翻到上一页，已在首页时返回 `false`
+    pub fn prev_page(&mut self) -> bool {
+        if self.is_first() {
+            return false;
+        }
+        self.page -= 1;
+        true
+    }
+
+    /// 翻到下一页，已在末页时返回 `false`
+    pub fn next_page(&mut self) -> bool {
+        if self.is_last() {
+            return false;
+        }
+        self.page += 1;
+        true
+    }
+
+    /// 跳转到指定页，页码越界时返回 `false`
+    pub fn go_to(&mut self, page: usize) -> bool {
+        if page >= self.total {
+            return false;
+        }
+        self.page = page;
+        true
+    }
+
+    /// 处理翻页按钮，返回该按钮是否属于翻页按钮
+    pub fn handle_action(&mut self, custom_id: &str) -> bool {
+        match custom_id {
+            PAGE_PREV_ID => {
+                self.prev_page();
+                true
+            }
+            PAGE_NEXT_ID => {
+                self.next_page();
+                true
+            }
+            _ => false,
+        }
+    }
+
+    /// 构建翻页按钮行：上一页、页码、下一页
+    pub fn nav_row(&self) -> CreateActionRow {
+        self.nav_row_labeled("◀", "▶")
+    }
+
+    /// 使用自定义标签构建翻页按钮行
+    pub fn nav_row_labeled(&self, prev_label: &str, next_label: &str) -> CreateActionRow {
+        CreateActionRow::Buttons(vec![
+            CreateButton::new(PAGE_PREV_ID)
+                .label(prev_label)
+                .style(ButtonStyle::Secondary)
+                .disabled(self.is_first()),
+            CreateButton::new(PAGE_INDICATOR_ID)
+                .label(format!("{}/{}", self.page + 1, self.total))
+                .style(ButtonStyle::Secondary)
+                .disabled(true),
+            CreateButton::new(PAGE_NEXT_ID)
+                .label(next_label)
+                .style(ButtonStyle::Secondary)
+                .disabled(self.is_last()),
+        ])
+    }
+}
+
+/// 构建分页下拉菜单的组件行
+///
+/// 只放入当前页的选项，超过一页时追加"上一页"/"下一页"按钮行
+pub fn paged_select_rows(
+    options: &[CreateSelectMenuOption],
+    paginator: &Paginator,
+    build_menu: impl FnOnce(Vec<CreateSelectMenuOption>) -> CreateSelectMenu,
+) -> Vec<CreateActionRow> {
+    let page_options = paginator
+        .page_items(options, SELECT_MENU_PAGE_SIZE)
+        .to_vec();
+    let mut rows = vec![CreateActionRow::SelectMenu(build_menu(page_options))];
+    if paginator.total() > 1 {
+        rows.push(paginator.nav_row_labeled("上一页", "下一页"));
+    }
+    rows
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_paginator_bounds() {
+        let mut paginator = Paginator::new(3);
+        assert!(paginator.is_first());
+        assert!(!paginator.prev_page());
+
+        assert!(paginator.next_page());
+        assert!(paginator.next_page());
+        assert!(paginator.is_last());
+        assert!(!paginator.next_page());
+        assert_eq!(paginator.page(), 2);
+
+        assert!(!paginator.go_to(3));
+        assert!(paginator.go_to(0));
+        assert_eq!(paginator.page(), 0);
+    }
+
+    #[test]
+    fn test_paginator_handle_action() {
+        let mut paginator = Paginator::new(2);
+        assert!(paginator.handle_action(PAGE_NEXT_ID));
+        assert_eq!(paginator.page(), 1);
+        assert!(paginator.handle_action(PAGE_PREV_ID));
+        assert_eq!(paginator.page(), 0);
+        assert!(!paginator.handle_action("other"));
+    }
+
+    #[test]
+    fn test_paginator_empty_has_one_page() {
+        let paginator = Paginator::new(0);
+        assert_eq!(paginator.total(), 1);
+        assert!(paginator.is_first() && paginator.is_last());
+    }
+
+    #[test]
+    fn test_paginator_page_items() {
+        let items: Vec<usize> = (0..25).collect();
+        let mut paginator = Paginator::for_items(items.len(), SELECT_MENU_PAGE_SIZE);
+        assert_eq!(paginator.total(), 2);
+        assert_eq!(
+            paginator.page_items(&items, SELECT_MENU_PAGE_SIZE).len(),
+            24
+        );
+
+        assert!(paginator.next_page());
+        assert_eq!(paginator.page_items(&items, SELECT_MENU_PAGE_SIZE), &[24]);
+
+        let paginator = Paginator::for_items(24, SELECT_MENU_PAGE_SIZE);
+        assert_eq!(paginator.total(), 1);
+    }
+
+    #[test]
+    fn test_paged_select_rows() {
+        use serenity::all::CreateSelectMenuKind;
+
+        let build =
+            |options| CreateSelectMenu::new("menu", CreateSelectMenuKind::String { options });
+        let options: Vec<_> = (0..30)
+            .map(|i| CreateSelectMenuOption::new(i.to_string(), i.to_string()))
+            .collect();
+
+        let paginator = Paginator::for_items(options.len(), SELECT_MENU_PAGE_SIZE);
+        assert_eq!(paged_select_rows(&options, &paginator, build).len(), 2);
+
+        let paginator = Paginator::for_items(10, SELECT_MENU_PAGE_SIZE);
+        assert_eq!(
+            paged_select_rows(&options[..10], &paginator, build).len(),
+            1
+        );
+    }
+}